@@ -493,11 +493,12 @@ impl IntoResponse for ApiError {
         let (status, error_message) = match self {
             ApiError::Automation(err) => {
                 tracing::error!("Automation error: {:?}", err);
-                let code = match err {
-                    AutomationError::ElementNotFound(_) => StatusCode::NOT_FOUND, // 404
+                let code = match err.inner() {
+                    AutomationError::ElementNotFound(_) | AutomationError::StaleElement(_) => StatusCode::NOT_FOUND, // 404
                     AutomationError::Timeout(_) => StatusCode::REQUEST_TIMEOUT,   // 408
                     AutomationError::UnsupportedOperation(_) => StatusCode::NOT_IMPLEMENTED, // 501
                     AutomationError::InvalidArgument(_) => StatusCode::BAD_REQUEST, // 400
+                    AutomationError::TargetBusy(_) => StatusCode::SERVICE_UNAVAILABLE, // 503
                     _ => StatusCode::INTERNAL_SERVER_ERROR,                       // 500 for others
                 };
                 (code, format!("Automation error: {}", err))
@@ -948,14 +949,14 @@ async fn is_element_visible(
         }
         Err(e) => {
             // Distinguish between element not found during wait vs. error calling is_visible
-            if matches!(e, AutomationError::Timeout(_)) {
+            if matches!(e.inner(), AutomationError::Timeout(_)) {
                 info!(
                     "Element not found or timed out while checking visibility: {}",
                     e
                 );
                 // Return false if the element wasn't found or visible within timeout
                 Ok(Json(BooleanResponse { result: false }))
-            } else if matches!(e, AutomationError::ElementNotFound(_)) {
+            } else if matches!(e.inner(), AutomationError::ElementNotFound(_) | AutomationError::StaleElement(_)) {
                 // This case might occur if the element disappears *after* being found but *before* visibility check
                 info!("Element disappeared while checking visibility: {}", e);
                 Ok(Json(BooleanResponse { result: false })) // Treat disappeared as not visible
@@ -0,0 +1,114 @@
+//! Session-wide emergency stop
+//!
+//! Once triggered, every input action and every wait loop fails with
+//! [`AutomationError::Aborted`] until the stop is cleared, so a misbehaving
+//! unattended run stops clicking and typing within one poll interval. The
+//! flag is process-wide: it stops every [`crate::Desktop`], not just the one
+//! it was triggered through.
+
+use crate::AutomationError;
+use crate::platforms::AccessibilityEngine;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+// How often the kill switch hotkey is checked
+const HOTKEY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+static ABORTED: AtomicBool = AtomicBool::new(false);
+
+/// Fail with `Aborted` if the kill switch has been triggered
+pub(crate) fn check() -> Result<(), AutomationError> {
+    if is_aborted() {
+        Err(AutomationError::Aborted(
+            "automation was stopped by the kill switch".to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn is_aborted() -> bool {
+    ABORTED.load(Ordering::SeqCst)
+}
+
+pub(crate) fn reset() {
+    ABORTED.store(false, Ordering::SeqCst);
+}
+
+/// Stop all automation, then release held keys and buttons, lift any input block and
+/// clear the overlay. Cleanup failures are logged, not returned: the stop itself
+/// has already taken effect.
+pub(crate) fn trigger(
+    engine: &dyn AccessibilityEngine,
+    clear_overlay: Option<&(dyn Fn() + Send + Sync)>,
+) {
+    ABORTED.store(true, Ordering::SeqCst);
+    error!("Kill switch triggered, stopping all automation");
+    if let Err(e) = engine.release_input() {
+        warn!(error = %e, "Failed to release input");
+    }
+    if let Some(clear_overlay) = clear_overlay {
+        clear_overlay();
+    }
+}
+
+/// Triggers the kill switch when its hotkey is held, see
+/// [`crate::Desktop::register_kill_switch`]. Stops watching when dropped.
+pub struct KillSwitch {
+    hotkey: String,
+    task: JoinHandle<()>,
+}
+
+impl KillSwitch {
+    pub(crate) fn spawn(
+        engine: Arc<dyn AccessibilityEngine>,
+        hotkey: &str,
+        clear_overlay: Option<Arc<dyn Fn() + Send + Sync>>,
+    ) -> Result<Self, AutomationError> {
+        let keys: Vec<String> = hotkey.split('+').map(|key| key.trim().to_string()).collect();
+        if keys.iter().any(|key| key.is_empty()) {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Invalid kill switch hotkey '{}'",
+                hotkey
+            )));
+        }
+        // Surface unknown key names and unsupported platforms now, not on the first poll
+        for key in &keys {
+            engine.is_key_pressed(key)?;
+        }
+
+        let task = tokio::spawn(async move {
+            let mut was_pressed = false;
+            loop {
+                let pressed = keys
+                    .iter()
+                    .all(|key| engine.is_key_pressed(key).unwrap_or(false));
+                // Trigger on the press, not on every poll while it is held
+                if pressed && !was_pressed {
+                    trigger(engine.as_ref(), clear_overlay.as_deref());
+                }
+                was_pressed = pressed;
+                tokio::time::sleep(HOTKEY_POLL_INTERVAL).await;
+            }
+        });
+
+        Ok(Self {
+            hotkey: hotkey.to_string(),
+            task,
+        })
+    }
+
+    /// The hotkey being watched, e.g. `ctrl+alt+f12`
+    pub fn hotkey(&self) -> &str {
+        &self.hotkey
+    }
+}
+
+impl Drop for KillSwitch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,224 @@
+//! Options for locator actions, including post-action verification
+//!
+//! Apps sometimes swallow input (a click lands while the window is still
+//! repainting, a keystroke arrives before the field has focus). With a
+//! [`Verify`] condition set, the action re-checks the UI afterwards and
+//! retries or fails with [`AutomationError::VerificationFailed`] instead of
+//! silently carrying on.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, UIElement};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+// How deep to look for text when checking `Verify::TextEquals`
+const VERIFY_TEXT_DEPTH: usize = 5;
+
+// Key presses that select everything in a field and delete it
+#[cfg(target_os = "windows")]
+const CLEAR_FIELD_KEYS: &[&str] = &["{ctrl}a", "{delete}"];
+#[cfg(not(target_os = "windows"))]
+const CLEAR_FIELD_KEYS: &[&str] = &["cmd+a", "delete"];
+
+/// A condition checked after an action to confirm it took effect
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verify {
+    /// The target's text (or value) equals the given string, ignoring surrounding whitespace
+    TextEquals(String),
+    /// Keyboard focus is on a different element than before the action
+    FocusMoved,
+    /// The target's value differs from what it was before the action
+    ValueChanged,
+}
+
+/// Options controlling how a locator action is performed
+#[derive(Debug, Clone)]
+pub struct ActionOptions {
+    /// How long to wait for the element; falls back to the locator's default timeout
+    pub timeout: Option<Duration>,
+    /// Condition to check after the action, if any
+    pub verify: Option<Verify>,
+    /// How many extra attempts to make when verification fails
+    pub retries: usize,
+    /// How long to let the UI settle before verifying
+    pub settle_time: Duration,
+}
+
+impl Default for ActionOptions {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            verify: None,
+            retries: 2,
+            settle_time: Duration::from_millis(250),
+        }
+    }
+}
+
+impl ActionOptions {
+    /// Options that verify the action with the given condition
+    pub fn verify(verify: Verify) -> Self {
+        Self {
+            verify: Some(verify),
+            ..Default::default()
+        }
+    }
+}
+
+/// Checks a locator action waits on before acting, like Playwright's actionability
+/// checks: an element that is still animating in, greyed out or behind a dialog
+/// would swallow the input. Checks the platform can't make are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionabilityOptions {
+    /// The element reports itself visible
+    pub visible: bool,
+    /// The element reports itself enabled
+    pub enabled: bool,
+    /// The element's center is on a connected monitor
+    pub on_screen: bool,
+    /// No other element, e.g. a dialog or overlay, is on top of the element's center
+    pub unobscured: bool,
+}
+
+impl Default for ActionabilityOptions {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            enabled: true,
+            on_screen: true,
+            unobscured: true,
+        }
+    }
+}
+
+impl ActionabilityOptions {
+    /// Act as soon as the element is found, without any checks
+    pub fn none() -> Self {
+        Self {
+            visible: false,
+            enabled: false,
+            on_screen: false,
+            unobscured: false,
+        }
+    }
+}
+
+/// Where input was going right after an action, to show afterwards that it reached the
+/// intended application
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionContext {
+    /// The window holding keyboard focus, e.g. `Window 'Untitled - Notepad'`
+    pub foreground_window: Option<String>,
+    /// The element holding keyboard focus
+    pub focused_element: Option<String>,
+    pub timestamp: SystemTime,
+}
+
+impl ActionContext {
+    pub(crate) fn capture(engine: &dyn AccessibilityEngine) -> Self {
+        let focused = engine.get_focused_element().ok();
+        Self {
+            foreground_window: focused
+                .as_ref()
+                .map(|element| crate::artifacts::containing_window(element).describe()),
+            focused_element: focused.as_ref().map(UIElement::describe),
+            timestamp: SystemTime::now(),
+        }
+    }
+}
+
+/// UI state captured before an action so the verification can compare against it
+#[derive(Debug, Default)]
+pub(crate) struct ActionSnapshot {
+    focused: Option<UIElement>,
+    value: Option<String>,
+}
+
+impl Verify {
+    /// Capture whatever this condition needs to compare against after the action
+    pub(crate) fn snapshot(
+        &self,
+        engine: &Arc<dyn AccessibilityEngine>,
+        element: &UIElement,
+    ) -> ActionSnapshot {
+        match self {
+            Verify::TextEquals(_) => ActionSnapshot::default(),
+            Verify::FocusMoved => ActionSnapshot {
+                focused: engine.get_focused_element().ok(),
+                value: None,
+            },
+            Verify::ValueChanged => ActionSnapshot {
+                focused: None,
+                value: element.attributes().value,
+            },
+        }
+    }
+
+    /// Check the condition, returning a description of the mismatch if it does not hold
+    pub(crate) fn check(
+        &self,
+        engine: &Arc<dyn AccessibilityEngine>,
+        element: &UIElement,
+        before: &ActionSnapshot,
+    ) -> Result<(), String> {
+        match self {
+            Verify::TextEquals(expected) => {
+                let text = element.text(VERIFY_TEXT_DEPTH).unwrap_or_default();
+                let value = element.attributes().value.unwrap_or_default();
+                if text.trim() == expected.trim() || value.trim() == expected.trim() {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected text '{}', found text '{}' / value '{}'",
+                        expected, text, value
+                    ))
+                }
+            }
+            Verify::FocusMoved => {
+                // Focus appearing where there was none, or leaving, also counts as moving
+                match (engine.get_focused_element().ok(), &before.focused) {
+                    (Some(now), Some(before)) if now == *before => {
+                        Err(format!("focus stayed on {}", now.describe()))
+                    }
+                    (None, None) => Err("no element has focus".to_string()),
+                    _ => Ok(()),
+                }
+            }
+            Verify::ValueChanged => {
+                let now = element.attributes().value;
+                if now != before.value {
+                    Ok(())
+                } else {
+                    Err(format!("value stayed {:?}", now))
+                }
+            }
+        }
+    }
+}
+
+/// Build the error returned when every attempt failed verification
+pub(crate) fn verification_failed(verify: &Verify, attempts: usize, last: &str) -> AutomationError {
+    AutomationError::VerificationFailed(format!(
+        "{:?} did not hold after {} attempt(s): {}",
+        verify, attempts, last
+    ))
+}
+
+/// Read the current contents of an input field
+pub(crate) fn read_field(element: &UIElement) -> Option<String> {
+    element
+        .value()
+        .ok()
+        .or_else(|| element.text(VERIFY_TEXT_DEPTH).ok())
+}
+
+/// Empty an input field, through its value if settable and the keyboard otherwise
+pub(crate) fn clear_field(element: &UIElement) -> Result<(), AutomationError> {
+    if element.set_value("").is_ok() {
+        return Ok(());
+    }
+    for key in CLEAR_FIELD_KEYS {
+        element.press_key(key)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Localized equivalents of common UI strings
+//!
+//! A script written against an English install looks for "OK" and "Cancel",
+//! which a Spanish one labels "Aceptar" and "Cancelar". With an [`AliasTable`]
+//! set on the desktop (see [`crate::Desktop::set_locale_tolerant`]), name
+//! selectors also match the other words in the same group.
+
+use crate::{Selector, SelectorRegex};
+
+// Built-in groups: English, Spanish, French, German, Italian, Portuguese,
+// Simplified Chinese, Traditional Chinese and Japanese where they differ
+const BUILTIN_ALIASES: &[&[&str]] = &[
+    &["OK", "Aceptar", "确定", "確定"],
+    &["Cancel", "Cancelar", "Annuler", "Abbrechen", "Annulla", "取消", "キャンセル"],
+    &["Yes", "Sí", "Oui", "Ja", "Sì", "Sim", "是", "はい"],
+    &["No", "Non", "Nein", "Não", "否", "いいえ"],
+    &["Save", "Guardar", "Enregistrer", "Speichern", "Salva", "Salvar", "保存", "儲存"],
+    &["Open", "Abrir", "Ouvrir", "Öffnen", "Apri", "打开", "開啟", "開く"],
+    &["Close", "Cerrar", "Fermer", "Schließen", "Chiudi", "Fechar", "关闭", "關閉", "閉じる"],
+    &["Apply", "Aplicar", "Appliquer", "Übernehmen", "Applica", "应用", "套用", "適用"],
+    &["Delete", "Eliminar", "Supprimer", "Löschen", "Elimina", "Excluir", "删除", "刪除", "削除"],
+    &["Next", "Siguiente", "Suivant", "Weiter", "Avanti", "Avançar", "下一步", "次へ"],
+    &["Back", "Atrás", "Précédent", "Zurück", "Indietro", "Voltar", "上一步", "戻る"],
+    &["Finish", "Finalizar", "Terminer", "Fertig stellen", "Fine", "Concluir", "完成", "完了"],
+    &["Help", "Ayuda", "Aide", "Hilfe", "Guida", "Ajuda", "帮助", "說明", "ヘルプ"],
+    &["Print", "Imprimir", "Imprimer", "Drucken", "Stampa", "打印", "列印", "印刷"],
+    &["Edit", "Edición", "Édition", "Bearbeiten", "Modifica", "Editar", "编辑", "編輯", "編集"],
+    &["File", "Archivo", "Fichier", "Datei", "Arquivo", "文件", "檔案", "ファイル"],
+];
+
+/// Groups of words that mean the same on different locales' UIs. `Default` is the
+/// built-in table of common dialog buttons and menus; extend it with [`AliasTable::add`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasTable {
+    groups: Vec<Vec<String>>,
+}
+
+impl Default for AliasTable {
+    fn default() -> Self {
+        Self {
+            groups: BUILTIN_ALIASES
+                .iter()
+                .map(|group| group.iter().map(|word| word.to_string()).collect())
+                .collect(),
+        }
+    }
+}
+
+impl AliasTable {
+    /// A table without the built-in groups
+    pub fn empty() -> Self {
+        Self { groups: Vec::new() }
+    }
+
+    /// Declare `words` equivalent. They join the group of any word already in the table,
+    /// so `add(&["Save", "Spara"])` adds Swedish to the built-in "Save" group.
+    pub fn add(&mut self, words: &[&str]) -> &mut Self {
+        let position = words.iter().find_map(|word| self.group_of(word));
+        let group = match position {
+            Some(position) => &mut self.groups[position],
+            None => {
+                self.groups.push(Vec::new());
+                self.groups.last_mut().unwrap()
+            }
+        };
+        for word in words {
+            if !group.iter().any(|known| known.to_lowercase() == word.to_lowercase()) {
+                group.push(word.to_string());
+            }
+        }
+        self
+    }
+
+    /// The words equivalent to `word`, including itself; empty if it has no aliases
+    pub fn aliases(&self, word: &str) -> &[String] {
+        self.group_of(word).map_or(&[], |position| &self.groups[position])
+    }
+
+    fn group_of(&self, word: &str) -> Option<usize> {
+        let word = word.trim().to_lowercase();
+        self.groups
+            .iter()
+            .position(|group| group.iter().any(|known| known.to_lowercase() == word))
+    }
+
+    /// Rewrite the name selectors inside `selector` to match any of their aliases as a
+    /// whole word, ignoring case, so a short alias like "Ja" doesn't match "Java".
+    /// Everything else is left as it is.
+    pub(crate) fn expand(&self, selector: Selector) -> Selector {
+        match selector {
+            Selector::Name(name) => {
+                let aliases = self.aliases(&name);
+                if aliases.is_empty() {
+                    return Selector::Name(name);
+                }
+                let alternatives: Vec<String> = aliases.iter().map(|alias| regex::escape(alias)).collect();
+                Selector::Regex {
+                    key: "name".to_string(),
+                    pattern: SelectorRegex::new(format!(r"(?i)\b(?:{})\b", alternatives.join("|"))),
+                }
+            }
+            Selector::Chain(stages) => {
+                Selector::Chain(stages.into_iter().map(|stage| self.expand(stage)).collect())
+            }
+            Selector::Nth { base, index } => Selector::Nth {
+                base: Box::new(self.expand(*base)),
+                index,
+            },
+            Selector::State { base, filters } => Selector::State {
+                base: Box::new(self.expand(*base)),
+                filters,
+            },
+            Selector::Filter { base, predicate } => Selector::Filter {
+                base: Box::new(self.expand(*base)),
+                predicate,
+            },
+            Selector::Relative {
+                base,
+                relation,
+                anchor,
+            } => Selector::Relative {
+                base: Box::new(self.expand(*base)),
+                relation,
+                anchor: Box::new(self.expand(*anchor)),
+            },
+            other => other,
+        }
+    }
+}
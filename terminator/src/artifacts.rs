@@ -0,0 +1,151 @@
+//! Diagnostics captured automatically when an action fails
+//!
+//! Unattended runs are hard to debug from an error message alone. When a
+//! [`FailureArtifactsConfig`] is set on the [`crate::Desktop`] (or directly on a
+//! [`crate::Locator`]), failed locator actions capture a screenshot, the focused
+//! element and a shallow dump of the target window and attach them to the error.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{ScreenshotResult, UIElement};
+use image::{ImageBuffer, Rgba};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// Controls what gets captured when an action fails
+#[derive(Debug, Clone)]
+pub struct FailureArtifactsConfig {
+    /// Directory to write artifacts into. When `None` they are only attached to the error.
+    pub output_dir: Option<PathBuf>,
+    /// Whether to capture a screenshot of the primary monitor
+    pub capture_screenshot: bool,
+    /// How many levels of the target window to include in the tree dump
+    pub tree_depth: usize,
+}
+
+impl Default for FailureArtifactsConfig {
+    fn default() -> Self {
+        Self {
+            output_dir: None,
+            capture_screenshot: true,
+            tree_depth: 2,
+        }
+    }
+}
+
+/// Diagnostics captured at the moment an action failed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FailureArtifacts {
+    /// Screenshot of the primary monitor
+    pub screenshot: Option<ScreenshotResult>,
+    /// Description of the element that had keyboard focus
+    pub focused_element: Option<String>,
+    /// Indented dump of the target window (or the focused window if there was no target)
+    pub tree_dump: Option<String>,
+    /// Directory the artifacts were written to, if an output dir was configured
+    pub saved_to: Option<PathBuf>,
+}
+
+/// Capture failure artifacts for an action that targeted `target` (if it was resolved)
+pub(crate) async fn capture(
+    engine: &Arc<dyn AccessibilityEngine>,
+    target: Option<&UIElement>,
+    config: &FailureArtifactsConfig,
+    error_message: &str,
+) -> FailureArtifacts {
+    let mut artifacts = FailureArtifacts::default();
+
+    if config.capture_screenshot {
+        match engine.capture_screen().await {
+            Ok(screenshot) => artifacts.screenshot = Some(screenshot),
+            Err(e) => warn!(error = %e, "Failed to capture failure screenshot"),
+        }
+    }
+
+    let focused = engine.get_focused_element().ok();
+    artifacts.focused_element = focused.as_ref().map(|e| e.describe());
+
+    if let Some(window) = target.or(focused.as_ref()).map(containing_window) {
+        let mut dump = String::new();
+        dump_tree(&window, 0, config.tree_depth, &mut dump);
+        artifacts.tree_dump = Some(dump);
+    }
+
+    if let Some(dir) = &config.output_dir {
+        match write_to_dir(dir, &artifacts, error_message) {
+            Ok(path) => {
+                info!(path = %path.display(), "Failure artifacts written");
+                artifacts.saved_to = Some(path);
+            }
+            Err(e) => warn!(error = %e, "Failed to write failure artifacts"),
+        }
+    }
+
+    artifacts
+}
+
+/// Walk up from `element` to the nearest window, or the topmost ancestor if there is none
+pub(crate) fn containing_window(element: &UIElement) -> UIElement {
+    let mut current = element.clone();
+    loop {
+        if current.role().to_lowercase().contains("window") {
+            return current;
+        }
+        match current.parent() {
+            Ok(Some(parent)) => current = parent,
+            _ => return current,
+        }
+    }
+}
+
+pub(crate) fn dump_tree(element: &UIElement, depth: usize, max_depth: usize, out: &mut String) {
+    out.push_str(&"  ".repeat(depth));
+    out.push_str(&element.describe());
+    out.push('\n');
+
+    if depth >= max_depth {
+        return;
+    }
+    if let Ok(children) = element.children() {
+        for child in children {
+            dump_tree(&child, depth + 1, max_depth, out);
+        }
+    }
+}
+
+fn write_to_dir(
+    dir: &Path,
+    artifacts: &FailureArtifacts,
+    error_message: &str,
+) -> std::io::Result<PathBuf> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let path = dir.join(format!("failure-{}", millis));
+    std::fs::create_dir_all(&path)?;
+
+    std::fs::write(path.join("error.txt"), error_message)?;
+    if let Some(focused) = &artifacts.focused_element {
+        std::fs::write(path.join("focused_element.txt"), focused)?;
+    }
+    if let Some(dump) = &artifacts.tree_dump {
+        std::fs::write(path.join("tree.txt"), dump)?;
+    }
+    if let Some(screenshot) = &artifacts.screenshot {
+        let buffer: Option<ImageBuffer<Rgba<u8>, Vec<u8>>> = ImageBuffer::from_raw(
+            screenshot.width,
+            screenshot.height,
+            screenshot.image_data.clone(),
+        );
+        match buffer {
+            Some(buffer) => buffer
+                .save(path.join("screenshot.png"))
+                .map_err(std::io::Error::other)?,
+            None => warn!("Screenshot data does not match its dimensions, skipping"),
+        }
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,285 @@
+//! Reading and switching browser state through the accessibility tree
+//!
+//! Chrome, Edge, Firefox and Safari all expose their address bar and tab strip
+//! as ordinary controls, so the current URL and open tabs can be read without
+//! a debugging protocol connection.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, Locator, Selector, UIElement};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+// How long to search the browser window for its controls
+const FIND_TIMEOUT: Duration = Duration::from_secs(3);
+// Browser chrome is shallow compared to page content, no need to walk the whole document
+const CHROME_SEARCH_DEPTH: usize = 15;
+
+#[cfg(target_os = "windows")]
+mod platform {
+    pub const ADDRESS_BAR: &str = "edit";
+    pub const TAB: &str = "tabitem";
+    /// Attribute property set on the active tab
+    pub const ACTIVE_TAB_PROPERTY: &str = "IsSelected";
+    pub const FOCUS_ADDRESS_BAR: &str = "{ctrl}l";
+    pub const SUBMIT: &str = "{enter}";
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    pub const ADDRESS_BAR: &str = "AXTextField";
+    /// Safari's tab bar is a group of radio buttons
+    pub const TAB: &str = "AXRadioButton";
+    pub const ACTIVE_TAB_PROPERTY: &str = "AXValue";
+    pub const FOCUS_ADDRESS_BAR: &str = "cmd+l";
+    pub const SUBMIT: &str = "return";
+}
+
+// How often the window title and address bar are checked while waiting for a page to load
+const TITLE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// Accessible names browsers give their address bar (Chrome/Edge, Firefox, Safari)
+const ADDRESS_BAR_NAMES: &[&str] = &[
+    "address and search bar",
+    "search or enter address",
+    "search with google or enter address",
+    "smart search field",
+    "address and search",
+];
+
+/// A tab in a browser window
+#[derive(Debug, Clone)]
+pub struct BrowserTab {
+    pub title: String,
+    /// Position in the tab strip, starting at 0
+    pub index: usize,
+    pub is_active: bool,
+    /// The tab strip entry, clicking it switches to the tab
+    pub element: UIElement,
+}
+
+/// Identifies a tab for [`crate::Desktop::select_tab`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TabTarget {
+    /// The first tab whose title contains this text (case-insensitive)
+    Title(String),
+    /// Position in the tab strip, starting at 0
+    Index(usize),
+}
+
+impl From<&str> for TabTarget {
+    fn from(title: &str) -> Self {
+        TabTarget::Title(title.to_string())
+    }
+}
+
+impl From<usize> for TabTarget {
+    fn from(index: usize) -> Self {
+        TabTarget::Index(index)
+    }
+}
+
+/// Walk up from `element` to its top-level window
+pub(crate) fn top_level_window(element: &UIElement) -> UIElement {
+    let mut current = element.clone();
+    // A top-level window is a child of the desktop root, which itself has no parent
+    while let Ok(Some(parent)) = current.parent() {
+        match parent.parent() {
+            Ok(Some(_)) => current = parent,
+            _ => break,
+        }
+    }
+    current
+}
+
+/// Find the address bar in a browser window
+pub(crate) async fn address_bar(
+    engine: &Arc<dyn AccessibilityEngine>,
+    window: &UIElement,
+) -> Result<UIElement, AutomationError> {
+    let fields = find_all(engine, window, platform::ADDRESS_BAR).await?;
+
+    let named = fields.iter().find(|field| {
+        let attributes = field.attributes();
+        [attributes.name, attributes.description]
+            .into_iter()
+            .flatten()
+            .any(|text| ADDRESS_BAR_NAMES.contains(&text.trim().to_lowercase().as_str()))
+    });
+    // Unknown browser or locale: settle for the first field that holds something URL-shaped
+    let address_bar = named.cloned().or_else(|| {
+        fields
+            .into_iter()
+            .find(|field| field.attributes().value.is_some_and(|v| looks_like_url(&v)))
+    });
+
+    address_bar.ok_or_else(|| AutomationError::ElementNotFound("Browser address bar".to_string()))
+}
+
+/// Read the URL shown in a browser window's address bar
+pub(crate) async fn current_url(
+    engine: &Arc<dyn AccessibilityEngine>,
+    window: &UIElement,
+) -> Result<String, AutomationError> {
+    let address_bar = address_bar(engine, window).await?;
+    address_bar
+        .attributes()
+        .value
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| AutomationError::ElementNotFound("Address bar has no URL".to_string()))
+}
+
+/// List the tabs in a browser window, in tab strip order
+pub(crate) async fn tabs(
+    engine: &Arc<dyn AccessibilityEngine>,
+    window: &UIElement,
+) -> Result<Vec<BrowserTab>, AutomationError> {
+    let tabs = find_all(engine, window, platform::TAB)
+        .await?
+        .into_iter()
+        .enumerate()
+        .map(|(index, element)| {
+            let attributes = element.attributes();
+            let is_active = attributes
+                .properties
+                .get(platform::ACTIVE_TAB_PROPERTY)
+                .cloned()
+                .flatten()
+                .is_some_and(|v| v.as_bool() == Some(true) || v.as_i64() == Some(1));
+            BrowserTab {
+                title: attributes.name.unwrap_or_default(),
+                index,
+                is_active,
+                element,
+            }
+        })
+        .collect();
+    Ok(tabs)
+}
+
+/// Switch to the tab matching `target`
+pub(crate) async fn select_tab(
+    engine: &Arc<dyn AccessibilityEngine>,
+    window: &UIElement,
+    target: &TabTarget,
+) -> Result<BrowserTab, AutomationError> {
+    let tabs = tabs(engine, window).await?;
+    let tab = match target {
+        TabTarget::Index(index) => tabs.into_iter().nth(*index),
+        TabTarget::Title(title) => {
+            let title = title.to_lowercase();
+            tabs.into_iter().find(|tab| tab.title.to_lowercase().contains(&title))
+        }
+    }
+    .ok_or_else(|| AutomationError::ElementNotFound(format!("Browser tab {:?}", target)))?;
+
+    tab.element.click()?;
+    Ok(BrowserTab {
+        is_active: true,
+        ..tab
+    })
+}
+
+/// Load `url` in the active tab of `window` through its address bar.
+/// Returns the window title once it has changed from what it was before.
+pub(crate) async fn navigate(
+    engine: &Arc<dyn AccessibilityEngine>,
+    window: &UIElement,
+    url: &str,
+    timeout: Duration,
+) -> Result<String, AutomationError> {
+    let title_before = window.name().unwrap_or_default();
+    let address_bar = address_bar(engine, window).await?;
+
+    window.activate_window()?;
+    window.press_key(platform::FOCUS_ADDRESS_BAR)?;
+    // The shortcut can be eaten by the page (e.g. an editor capturing keys), fall back to focusing directly
+    if !address_bar.is_focused().unwrap_or(false) {
+        debug!("Address bar shortcut did not take focus, focusing directly");
+        address_bar.focus()?;
+        if !address_bar.is_focused().unwrap_or(false) {
+            return Err(AutomationError::VerificationFailed(
+                "Could not focus the browser address bar".to_string(),
+            ));
+        }
+    }
+
+    address_bar.set_value(url).or_else(|_| address_bar.type_text(url, false))?;
+    address_bar.press_key(platform::SUBMIT)?;
+
+    let start = Instant::now();
+    loop {
+        let title = window.name().unwrap_or_default();
+        if title != title_before {
+            return Ok(title);
+        }
+        // Reloading a page, or one with a fixed title, leaves the title alone; once focus
+        // has left the address bar it shows the URL that actually loaded
+        if !address_bar.is_focused().unwrap_or(true)
+            && address_bar.attributes().value.is_some_and(|shown| same_url(&shown, url))
+        {
+            return Ok(title);
+        }
+        if start.elapsed() >= timeout {
+            return Err(AutomationError::Timeout(format!(
+                "Browser title and address did not change within {:?} after navigating to {}",
+                timeout, url
+            )));
+        }
+        crate::abort::check()?;
+        tokio::time::sleep(TITLE_POLL_INTERVAL).await;
+    }
+}
+
+async fn find_all(
+    engine: &Arc<dyn AccessibilityEngine>,
+    window: &UIElement,
+    role: &str,
+) -> Result<Vec<UIElement>, AutomationError> {
+    Locator::new(
+        engine.clone(),
+        Selector::Role {
+            role: role.to_string(),
+            name: None,
+        },
+    )
+    .within(window.clone())
+    .all(Some(FIND_TIMEOUT), Some(CHROME_SEARCH_DEPTH))
+    .await
+}
+
+/// Whether the address bar shows the requested URL, allowing for the scheme, `www.` and
+/// trailing slash browsers add or hide
+fn same_url(shown: &str, requested: &str) -> bool {
+    fn normalize(url: &str) -> String {
+        let url = url.trim().to_lowercase();
+        let url = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .unwrap_or(&url);
+        let url = url.strip_prefix("www.").unwrap_or(url);
+        url.trim_end_matches('/').to_string()
+    }
+    !requested.trim().is_empty() && normalize(shown) == normalize(requested)
+}
+
+fn looks_like_url(text: &str) -> bool {
+    let text = text.trim();
+    !text.is_empty() && !text.contains(' ') && (text.contains("://") || text.contains('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_url_ignores_what_browsers_add_or_hide() {
+        assert!(same_url("https://www.example.com/", "example.com"));
+        assert!(same_url("example.com/orders?id=7", "http://example.com/orders?id=7"));
+        assert!(same_url("  HTTPS://Example.com ", "https://example.com/"));
+
+        assert!(!same_url("https://example.com/orders", "example.com"));
+        assert!(!same_url("https://example.org", "example.com"));
+        assert!(!same_url("", ""));
+    }
+}
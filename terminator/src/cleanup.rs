@@ -0,0 +1,244 @@
+//! Leaving the desktop as the session found it
+//!
+//! Every app, URL or file the automation opens leaves a process or window
+//! behind, and on a CI desktop those pile up from run to run. The desktop
+//! notes the process it launched and the windows that process tree opened,
+//! and [`crate::Desktop::cleanup_session`] closes the windows and terminates
+//! the processes (with any children they started) at the end.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, ProcessInfo, UIElement, WindowPlacement};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+// How long terminated processes get to exit before cleanup reports them
+const EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What [`crate::Desktop::cleanup_session`] closed, and what it couldn't
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// Titles of the windows asked to close, in processes that were running before the session
+    pub closed_windows: Vec<String>,
+    /// Processes terminated, including children the session's processes started
+    pub terminated_processes: Vec<ProcessInfo>,
+    /// Why a window or process could not be cleaned up
+    pub failures: Vec<String>,
+}
+
+impl CleanupReport {
+    /// Whether everything the session created is gone
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Processes and windows present before a launch
+pub(crate) struct LaunchSnapshot {
+    /// `None` where processes can't be listed
+    pids: Option<HashSet<u32>>,
+    /// `None` where windows can't be listed
+    windows: Option<HashSet<isize>>,
+}
+
+/// Processes and windows created by the session so far
+#[derive(Default)]
+pub(crate) struct SessionTracker {
+    tracked: Mutex<Tracked>,
+}
+
+#[derive(Default)]
+struct Tracked {
+    processes: Vec<ProcessInfo>,
+    windows: Vec<WindowPlacement>,
+}
+
+impl SessionTracker {
+    /// Note what is running before launching something
+    pub(crate) fn snapshot(engine: &dyn AccessibilityEngine) -> LaunchSnapshot {
+        let pids = match engine.list_processes() {
+            Ok(processes) => Some(processes.into_iter().map(|process| process.pid).collect()),
+            Err(e) => {
+                debug!(error = %e, "Cannot list processes, launches won't be tracked");
+                None
+            }
+        };
+        let windows = engine
+            .get_window_placements()
+            .ok()
+            .map(|windows| windows.into_iter().map(|window| window.handle).collect());
+        LaunchSnapshot { pids, windows }
+    }
+
+    /// Track what a launch created: the process of `launched`, the element the launch
+    /// returned, with the windows that appeared since `before` in that process or the
+    /// processes it started. Nothing else that started meanwhile is tracked, and nothing
+    /// is when the launched process can't be told. If the launched process was already
+    /// running, e.g. a browser that opened a new window, it is left running and only its
+    /// new windows are tracked.
+    pub(crate) fn record_launch(
+        &self,
+        engine: &dyn AccessibilityEngine,
+        before: LaunchSnapshot,
+        launched: &UIElement,
+    ) {
+        let (Some(pids_before), Ok(launched_pid)) = (before.pids, launched.process_id()) else {
+            debug!("Launched process unknown, not tracking the launch");
+            return;
+        };
+        let Ok(running) = engine.list_processes() else {
+            return;
+        };
+        let Some(launched_process) = running.iter().find(|process| process.pid == launched_pid) else {
+            return;
+        };
+        let tree = with_children(std::slice::from_ref(launched_process), &running);
+        let new_windows: Vec<WindowPlacement> = match &before.windows {
+            Some(handles) => engine
+                .get_window_placements()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|window| !handles.contains(&window.handle))
+                .filter(|window| tree.iter().any(|process| process.pid == window.process_id))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut new_processes: Vec<ProcessInfo> = Vec::new();
+        let mut reused_windows = Vec::new();
+        let owners = std::iter::once(launched_pid).chain(new_windows.iter().map(|window| window.process_id));
+        for pid in owners {
+            if !pids_before.contains(&pid)
+                && !new_processes.iter().any(|known| known.pid == pid)
+                && let Some(process) = running.iter().find(|process| process.pid == pid)
+            {
+                new_processes.push(process.clone());
+            }
+        }
+        for window in new_windows {
+            if pids_before.contains(&window.process_id) {
+                reused_windows.push(window);
+            }
+        }
+        debug!(
+            processes = new_processes.len(),
+            windows = reused_windows.len(),
+            "Tracking launched processes and windows"
+        );
+
+        let Ok(mut tracked) = self.tracked.lock() else {
+            return;
+        };
+        for process in new_processes {
+            if !tracked.processes.iter().any(|known| known.pid == process.pid) {
+                tracked.processes.push(process);
+            }
+        }
+        tracked.windows.extend(reused_windows);
+    }
+
+    pub(crate) fn track_process(&self, process: ProcessInfo) {
+        if let Ok(mut tracked) = self.tracked.lock()
+            && !tracked.processes.iter().any(|known| known.pid == process.pid)
+        {
+            tracked.processes.push(process);
+        }
+    }
+
+    /// Close the tracked windows and terminate the tracked processes, then forget them
+    pub(crate) async fn cleanup(&self, engine: &dyn AccessibilityEngine) -> Result<CleanupReport, AutomationError> {
+        let Tracked { processes, windows } = self
+            .tracked
+            .lock()
+            .map(|mut tracked| std::mem::take(&mut *tracked))
+            .unwrap_or_default();
+        let running = engine.list_processes()?;
+        let targets = with_children(&processes, &running);
+        info!(
+            processes = targets.len(),
+            windows = windows.len(),
+            "Cleaning up session"
+        );
+
+        let mut report = CleanupReport::default();
+        for window in windows {
+            if targets.iter().any(|process| process.pid == window.process_id) {
+                continue;
+            }
+            match engine.close_window(&window) {
+                Ok(()) => report.closed_windows.push(window.title),
+                Err(e) if matches!(e.inner(), AutomationError::ElementNotFound(_)) => {}
+                Err(e) => report.failures.push(format!("Window '{}': {}", window.title, e)),
+            }
+        }
+
+        let mut terminated = Vec::new();
+        for process in targets {
+            match engine.terminate_process(process.pid) {
+                Ok(()) => terminated.push(process),
+                // Exited on its own, e.g. with its parent
+                Err(e) if matches!(e.inner(), AutomationError::ElementNotFound(_)) => {}
+                Err(e) => report
+                    .failures
+                    .push(format!("Process {} ({}): {}", process.pid, process.name, e)),
+            }
+        }
+
+        let start = Instant::now();
+        loop {
+            let running: HashSet<u32> = engine.list_processes()?.into_iter().map(|process| process.pid).collect();
+            let (alive, exited): (Vec<_>, Vec<_>) =
+                terminated.into_iter().partition(|process| running.contains(&process.pid));
+            report.terminated_processes.extend(exited);
+            terminated = alive;
+            if terminated.is_empty() || start.elapsed() >= EXIT_TIMEOUT {
+                break;
+            }
+            tokio::time::sleep(EXIT_POLL_INTERVAL).await;
+        }
+        for process in terminated {
+            warn!(pid = process.pid, name = %process.name, "Process still running after cleanup");
+            report.failures.push(format!(
+                "Process {} ({}) still running after {:?}",
+                process.pid, process.name, EXIT_TIMEOUT
+            ));
+        }
+
+        info!(
+            closed_windows = report.closed_windows.len(),
+            terminated_processes = report.terminated_processes.len(),
+            failures = report.failures.len(),
+            "Session cleaned up"
+        );
+        Ok(report)
+    }
+}
+
+/// The tracked processes still running, matched by name in case a pid was reused,
+/// and every process they started, children first
+fn with_children(tracked: &[ProcessInfo], running: &[ProcessInfo]) -> Vec<ProcessInfo> {
+    let mut found: Vec<ProcessInfo> = running
+        .iter()
+        .filter(|process| {
+            tracked
+                .iter()
+                .any(|known| known.pid == process.pid && known.name == process.name)
+        })
+        .cloned()
+        .collect();
+    let mut next = 0;
+    while next < found.len() {
+        let parent = found[next].pid;
+        for child in running.iter().filter(|process| process.parent_pid == Some(parent)) {
+            if !found.iter().any(|known| known.pid == child.pid) {
+                found.push(child.clone());
+            }
+        }
+        next += 1;
+    }
+    // Children go first so a parent can't restart them while it is being terminated
+    found.reverse();
+    found
+}
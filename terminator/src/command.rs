@@ -0,0 +1,252 @@
+//! Running shell commands with a working directory, environment, stdin,
+//! a hard timeout and streamed output
+//!
+//! [`crate::Desktop::run_command`] waits for the command to exit and can hang
+//! a workflow forever. [`CommandBuilder`] (from [`crate::Desktop::command`])
+//! adds the knobs a workflow needs to stay in control of the child process.
+
+use crate::{AutomationError, CommandOutput};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::Child;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+/// A line of output from a running command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+/// Configures a shell command; see [`crate::Desktop::command`]
+#[derive(Debug, Clone)]
+pub struct CommandBuilder {
+    command: String,
+    current_dir: Option<PathBuf>,
+    env: Vec<(String, String)>,
+    stdin: Option<String>,
+    timeout: Option<Duration>,
+}
+
+impl CommandBuilder {
+    /// Pick the command for this platform, like `run_command` does
+    pub(crate) fn new(
+        windows_command: Option<&str>,
+        unix_command: Option<&str>,
+    ) -> Result<Self, AutomationError> {
+        #[cfg(target_os = "windows")]
+        let command = windows_command.ok_or_else(|| {
+            AutomationError::InvalidArgument("Windows command must be provided".to_string())
+        })?;
+        #[cfg(not(target_os = "windows"))]
+        let command = unix_command.ok_or_else(|| {
+            AutomationError::InvalidArgument("Unix command must be provided".to_string())
+        })?;
+        #[cfg(target_os = "windows")]
+        let _ = unix_command;
+        #[cfg(not(target_os = "windows"))]
+        let _ = windows_command;
+
+        Ok(Self {
+            command: command.to_string(),
+            current_dir: None,
+            env: Vec::new(),
+            stdin: None,
+            timeout: None,
+        })
+    }
+
+    /// Run the command in `dir` instead of the current directory
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Set an environment variable for the command
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Write `input` to the command's stdin, then close it
+    pub fn stdin(mut self, input: impl Into<String>) -> Self {
+        self.stdin = Some(input.into());
+        self
+    }
+
+    /// Kill the command if it is still running after `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Start the command and stream its output line by line
+    pub fn spawn(self) -> Result<RunningCommand, AutomationError> {
+        #[cfg(target_os = "windows")]
+        let mut command = {
+            let mut command = tokio::process::Command::new("powershell");
+            command.args(["-NoProfile", "-WindowStyle", "hidden", "-Command", &self.command]);
+            command
+        };
+        #[cfg(not(target_os = "windows"))]
+        let mut command = {
+            let mut command = tokio::process::Command::new("/bin/sh");
+            command.arg("-c").arg(&self.command);
+            command
+        };
+
+        if let Some(dir) = &self.current_dir {
+            command.current_dir(dir);
+        }
+        command
+            .envs(self.env.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .stdin(if self.stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = command.spawn().map_err(|e| {
+            AutomationError::platform_with_source(format!("Failed to start '{}'", self.command), e)
+        })?;
+        debug!(pid = child.id(), command = %self.command, "Command started");
+
+        if let (Some(input), Some(mut stdin)) = (self.stdin, child.stdin.take()) {
+            tokio::spawn(async move {
+                if let Err(e) = stdin.write_all(input.as_bytes()).await {
+                    warn!(error = %e, "Failed to write command stdin");
+                }
+                // Dropping stdin closes it so the command sees EOF
+            });
+        }
+
+        let (sender, lines) = mpsc::unbounded_channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, sender.clone(), OutputLine::Stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, sender, OutputLine::Stderr);
+        }
+
+        Ok(RunningCommand {
+            child,
+            lines,
+            deadline: self.timeout.map(|timeout| Instant::now() + timeout),
+            timeout: self.timeout,
+            timed_out: false,
+        })
+    }
+
+    /// Run the command to completion and collect its output.
+    /// Fails with [`AutomationError::Timeout`] if the timeout elapses first.
+    pub async fn output(self) -> Result<CommandOutput, AutomationError> {
+        let mut running = self.spawn()?;
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        while let Some(line) = running.next_line().await {
+            let (buffer, text) = match line {
+                OutputLine::Stdout(text) => (&mut stdout, text),
+                OutputLine::Stderr(text) => (&mut stderr, text),
+            };
+            buffer.push_str(&text);
+            buffer.push('\n');
+        }
+        let exit_status = running.wait().await?;
+        Ok(CommandOutput {
+            exit_status,
+            stdout,
+            stderr,
+        })
+    }
+}
+
+/// A command started with [`CommandBuilder::spawn`]. Killed when dropped.
+pub struct RunningCommand {
+    child: Child,
+    lines: mpsc::UnboundedReceiver<OutputLine>,
+    deadline: Option<Instant>,
+    timeout: Option<Duration>,
+    timed_out: bool,
+}
+
+impl RunningCommand {
+    /// OS process id, `None` once the command has exited
+    pub fn pid(&self) -> Option<u32> {
+        self.child.id()
+    }
+
+    /// Wait for the next line of stdout or stderr. Returns `None` once both
+    /// streams are closed, or when the timeout elapses (the command is killed).
+    pub async fn next_line(&mut self) -> Option<OutputLine> {
+        let Some(deadline) = self.deadline else {
+            return self.lines.recv().await;
+        };
+        match tokio::time::timeout_at(deadline, self.lines.recv()).await {
+            Ok(line) => line,
+            Err(_) => {
+                self.kill_on_timeout().await;
+                None
+            }
+        }
+    }
+
+    /// Wait for the command to exit and return its exit code.
+    /// Fails with [`AutomationError::Timeout`] if the timeout elapses first.
+    pub async fn wait(&mut self) -> Result<Option<i32>, AutomationError> {
+        if !self.timed_out {
+            let status = match self.deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, self.child.wait()).await.ok(),
+                None => Some(self.child.wait().await),
+            };
+            match status {
+                Some(status) => {
+                    let status = status.map_err(|e| {
+                        AutomationError::platform_with_source("Failed to wait for command", e)
+                    })?;
+                    return Ok(status.code());
+                }
+                None => self.kill_on_timeout().await,
+            }
+        }
+        Err(AutomationError::Timeout(format!(
+            "Command did not finish within {:?} and was killed",
+            self.timeout.unwrap_or_default()
+        )))
+    }
+
+    /// Kill the command
+    pub async fn kill(&mut self) -> Result<(), AutomationError> {
+        self.child
+            .kill()
+            .await
+            .map_err(|e| AutomationError::platform_with_source("Failed to kill command", e))
+    }
+
+    async fn kill_on_timeout(&mut self) {
+        if !self.timed_out {
+            warn!(pid = self.child.id(), timeout = ?self.timeout, "Command timed out, killing it");
+            self.timed_out = true;
+            let _ = self.child.kill().await;
+        }
+    }
+}
+
+fn forward_lines<R>(
+    stream: R,
+    sender: mpsc::UnboundedSender<OutputLine>,
+    wrap: fn(String) -> OutputLine,
+) where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stream).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if sender.send(wrap(line)).is_err() {
+                return;
+            }
+        }
+    });
+}
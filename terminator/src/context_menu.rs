@@ -0,0 +1,125 @@
+//! Picking items from context menus
+//!
+//! Context menus are transient top-level windows, often owned by a different
+//! process than the element that was right-clicked (the shell, for example),
+//! so they can't be found by searching below that element. Instead the
+//! desktop's top-level windows are compared before and after the right-click.
+
+use crate::selector::descendants_of;
+use crate::{AutomationError, Role, UIElement};
+use std::time::{Duration, Instant};
+use tracing::{info, instrument};
+
+// How long to wait for a menu (or submenu) to appear by default
+const DEFAULT_MENU_TIMEOUT: Duration = Duration::from_secs(5);
+// How often the top-level windows are re-read while waiting for a menu
+const MENU_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The context menu of an element, see [`UIElement::context_menu`]
+#[derive(Debug, Clone)]
+pub struct ContextMenu {
+    element: UIElement,
+    timeout: Duration,
+}
+
+impl ContextMenu {
+    pub(crate) fn new(element: UIElement) -> Self {
+        Self {
+            element,
+            timeout: DEFAULT_MENU_TIMEOUT,
+        }
+    }
+
+    /// How long to wait for the menu, and each submenu, to appear (5 seconds by default)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Right-click the element and click the menu item `item`.
+    ///
+    /// Items are matched by name, ignoring case, `&` mnemonics, trailing `...`
+    /// and shortcut text. Separate submenu levels with `>`, e.g. `"New > Folder"`.
+    /// Returns the clicked item.
+    #[instrument(skip(self))]
+    pub async fn select(&self, item: &str) -> Result<UIElement, AutomationError> {
+        let start = Instant::now();
+        let desktop = desktop_root(&self.element)?;
+        let before = desktop.children()?;
+
+        self.element.right_click()?;
+
+        let mut clicked = None;
+        for label in item.split('>').map(str::trim) {
+            let menu_item = self.wait_for_item(&desktop, &before, label).await?;
+            menu_item.click()?;
+            clicked = Some(menu_item);
+        }
+
+        info!(
+            duration_ms = start.elapsed().as_millis(),
+            item,
+            "Context menu item selected"
+        );
+        clicked.ok_or_else(|| AutomationError::InvalidArgument("Empty menu item".to_string()))
+    }
+
+    /// Wait for a menu item labelled `label` in a window that appeared since `before`
+    /// was read, or in any top-level menu
+    async fn wait_for_item(
+        &self,
+        desktop: &UIElement,
+        before: &[UIElement],
+        label: &str,
+    ) -> Result<UIElement, AutomationError> {
+        let start = Instant::now();
+        let wanted = normalize_item(label);
+        loop {
+            let windows = desktop.children().unwrap_or_default();
+            // Newest windows last, and submenus open after their parent menu
+            for window in windows.iter().rev() {
+                if before.contains(window) && window.normalized_role() != Role::Menu {
+                    continue;
+                }
+                let found = std::iter::once(window.clone())
+                    .chain(descendants_of(window))
+                    .find(|e| {
+                        e.normalized_role() == Role::MenuItem
+                            && e.name().is_some_and(|name| normalize_item(&name) == wanted)
+                    });
+                if let Some(found) = found {
+                    return Ok(found);
+                }
+            }
+
+            if start.elapsed() >= self.timeout {
+                return Err(AutomationError::ElementNotFound(format!(
+                    "No context menu item '{}' appeared within {:?}",
+                    label, self.timeout
+                )));
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(MENU_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// The desktop root above `element`
+fn desktop_root(element: &UIElement) -> Result<UIElement, AutomationError> {
+    let mut current = element.clone();
+    while let Some(parent) = current.parent()? {
+        current = parent;
+    }
+    Ok(current)
+}
+
+/// Menu item text as it is compared: without mnemonics, ellipsis or shortcut, case-folded
+fn normalize_item(text: &str) -> String {
+    let text = text.split('\t').next().unwrap_or_default();
+    text.replace('&', "")
+        .trim()
+        .trim_end_matches("...")
+        .trim_end_matches('…')
+        .trim()
+        .to_lowercase()
+}
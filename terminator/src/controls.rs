@@ -0,0 +1,373 @@
+//! Setters for composite input controls
+//!
+//! Date pickers and spinners are made of several parts (per-field segments,
+//! up/down buttons, a buddy edit box) and usually reject a plain `type_text`
+//! of the whole value; drop-downs have to be opened before their items exist.
+//! These helpers try the control's native pattern first, then its value or a
+//! click, and only then the keyboard, reading the result back each time to
+//! confirm the control accepted it.
+
+use crate::action::{clear_field, read_field};
+use crate::utils::normalize;
+use crate::{AutomationError, Role, UIElement};
+use chrono::{Datelike, NaiveDate};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+// Relative tolerance when comparing a spinner's displayed value with the requested one
+const SPINNER_TOLERANCE: f64 = 1e-9;
+// Roles of the entries of lists, drop-downs and menus
+const OPTION_ROLES: &[Role] = &[Role::ListItem, Role::MenuItem, Role::TreeItem, Role::DataItem];
+// How many levels below the control (or its parent) options are looked for
+const OPTION_SEARCH_DEPTH: usize = 8;
+// How long an opened drop-down gets to show its items
+const OPTION_APPEAR_TIMEOUT: Duration = Duration::from_secs(2);
+const OPTION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// How long a checkbox gets to report its new state after being flipped
+const CHECK_SETTLE_TIMEOUT: Duration = Duration::from_secs(1);
+const CHECK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+// A tri-state checkbox cycles through three states, so any of them is at most two flips away
+const MAX_CHECK_FLIPS: usize = 2;
+
+/// One numeric segment of a displayed date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateField {
+    Year,
+    Month,
+    Day,
+}
+
+/// The order and separator of the numeric date a control displays, e.g. `03/15/2024`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DateLayout {
+    order: [DateField; 3],
+    separator: String,
+}
+
+impl Default for DateLayout {
+    fn default() -> Self {
+        Self {
+            order: [DateField::Year, DateField::Month, DateField::Day],
+            separator: "-".to_string(),
+        }
+    }
+}
+
+impl DateLayout {
+    /// Work out the layout from the date a control currently shows, falling back
+    /// to ISO 8601 when it shows nothing or a date with month names
+    pub(crate) fn detect(displayed: &str) -> Self {
+        let (numbers, separator) = split_numeric(displayed);
+        let Ok(values) = <[(usize, u32); 3]>::try_from(numbers) else {
+            return Self::default();
+        };
+
+        let year = values
+            .iter()
+            .position(|&(digits, value)| digits == 4 || value > 31)
+            .unwrap_or(2);
+        let others: Vec<usize> = (0..3).filter(|&i| i != year).collect();
+        let day_first = if values[others[0]].1 > 12 {
+            true
+        } else if values[others[1]].1 > 12 {
+            false
+        } else {
+            // Ambiguous, e.g. 03/04/2024: slashes mostly mean month first,
+            // dots and dashes day first, unless the year leads
+            year != 0 && separator != "/"
+        };
+
+        let mut order = [DateField::Year; 3];
+        let (first, second) = if day_first {
+            (DateField::Day, DateField::Month)
+        } else {
+            (DateField::Month, DateField::Day)
+        };
+        order[others[0]] = first;
+        order[others[1]] = second;
+        Self { order, separator }
+    }
+
+    fn format(&self, date: NaiveDate) -> String {
+        self.order
+            .iter()
+            .map(|field| match field {
+                DateField::Year => format!("{:04}", date.year()),
+                DateField::Month => format!("{:02}", date.month()),
+                DateField::Day => format!("{:02}", date.day()),
+            })
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+
+    pub(crate) fn parse(&self, displayed: &str) -> Option<NaiveDate> {
+        let (numbers, _) = split_numeric(displayed);
+        if numbers.len() != 3 {
+            return None;
+        }
+        let (mut year, mut month, mut day) = (0, 0, 0);
+        for (field, (digits, value)) in self.order.iter().zip(numbers) {
+            match field {
+                // Two-digit years are shown for this century
+                DateField::Year if digits <= 2 => year = 2000 + value,
+                DateField::Year => year = value,
+                DateField::Month => month = value,
+                DateField::Day => day = value,
+            }
+        }
+        NaiveDate::from_ymd_opt(year as i32, month, day)
+    }
+}
+
+/// The runs of digits in `text` as (digit count, value), and the text between the first two
+fn split_numeric(text: &str) -> (Vec<(usize, u32)>, String) {
+    let mut numbers = Vec::new();
+    let mut separator = String::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits > 0 {
+            numbers.push((digits, rest[..digits].parse().unwrap_or(u32::MAX)));
+            rest = &rest[digits..];
+        } else {
+            let gap = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+            if numbers.len() == 1 {
+                separator = rest[..gap].to_string();
+            }
+            rest = &rest[gap..];
+        }
+    }
+    (numbers, separator)
+}
+
+/// Set a date picker to `date`
+pub(crate) fn set_date(element: &UIElement, date: NaiveDate) -> Result<(), AutomationError> {
+    let current = read_field(element).unwrap_or_default();
+    let layout = DateLayout::detect(&current);
+    let text = layout.format(date);
+    let shows_date = |element: &UIElement| {
+        read_field(element).is_none_or(|shown| layout.parse(&shown) == Some(date))
+    };
+
+    if element.set_value(&text).is_ok() && shows_date(element) {
+        return Ok(());
+    }
+
+    // Typing the separator moves a segmented picker on to its next field,
+    // and a text-box picker simply takes the whole string
+    element.focus()?;
+    clear_field(element)?;
+    element.type_text(&text, false)?;
+    if shows_date(element) {
+        Ok(())
+    } else {
+        Err(AutomationError::VerificationFailed(format!(
+            "Date picker shows '{}' instead of {}",
+            read_field(element).unwrap_or_default(),
+            date
+        )))
+    }
+}
+
+/// Set a spinner (numeric up/down) to `value`
+pub(crate) fn set_spinner_value(element: &UIElement, value: f64) -> Result<(), AutomationError> {
+    if !value.is_finite() {
+        return Err(AutomationError::InvalidArgument(format!(
+            "Spinner value must be finite, got {}",
+            value
+        )));
+    }
+    match element.set_range_value(value) {
+        Ok(()) => return Ok(()),
+        // Out of the control's range: typing it would only be clamped or rejected
+        Err(e) if matches!(e.inner(), AutomationError::InvalidArgument(_)) => return Err(e),
+        Err(_) => {}
+    }
+
+    // The text lives in the spinner itself or in its buddy edit box
+    let field = element
+        .children()
+        .unwrap_or_default()
+        .into_iter()
+        .find(|child| child.normalized_role() == Role::Edit)
+        .unwrap_or_else(|| element.clone());
+    let text = value.to_string();
+    let shows_value = |field: &UIElement| {
+        read_field(field).is_none_or(|shown| {
+            shown
+                .trim()
+                .replace(',', "")
+                .parse::<f64>()
+                .is_ok_and(|shown| {
+                    (shown - value).abs() <= SPINNER_TOLERANCE * value.abs().max(1.0)
+                })
+        })
+    };
+
+    if field.set_value(&text).is_ok() && shows_value(&field) {
+        return Ok(());
+    }
+
+    field.focus()?;
+    clear_field(&field)?;
+    field.type_text(&text, false)?;
+    if shows_value(&field) {
+        Ok(())
+    } else {
+        Err(AutomationError::VerificationFailed(format!(
+            "Spinner shows '{}' instead of {}",
+            read_field(&field).unwrap_or_default(),
+            value
+        )))
+    }
+}
+
+/// Choose the item named `option` in a combo box, drop-down or list box
+pub(crate) fn select_option(element: &UIElement, option: &str) -> Result<(), AutomationError> {
+    let wanted = normalize(option);
+    if wanted.is_empty() {
+        return Err(AutomationError::InvalidArgument("Option to select is empty".to_string()));
+    }
+    let shows_option = |element: &UIElement| read_field(element).is_some_and(|shown| normalize(&shown) == wanted);
+    if shows_option(element) {
+        return Ok(());
+    }
+
+    // A list box shows its items already; a closed drop-down has to open first
+    let mut opened = false;
+    let mut item = find_option(element, &wanted);
+    if item.is_none() {
+        if element.attributes().state.expanded != Some(true) {
+            if element.set_expanded(true).is_err() {
+                element.click()?;
+            }
+            opened = true;
+        }
+        item = wait_for_option(element, &wanted)?;
+    }
+    let item = item.ok_or_else(|| AutomationError::ElementNotFound(format!("No option named '{}'", option)))?;
+
+    if item.select_item().is_err() {
+        // Scrolling only helps the click; a failure shows up as a failed click
+        let _ = item.scroll_into_view();
+        item.click()?;
+    }
+    // Selecting through the pattern leaves some drop-downs open
+    if opened && element.attributes().state.expanded == Some(true) {
+        let _ = element.set_expanded(false);
+    }
+
+    if shows_option(element) || item.attributes().state.selected == Some(true) {
+        Ok(())
+    } else {
+        Err(AutomationError::VerificationFailed(format!(
+            "Control shows '{}' instead of '{}'",
+            read_field(element).unwrap_or_default(),
+            option
+        )))
+    }
+}
+
+/// Poll for the option while an opened drop-down fills in its items
+fn wait_for_option(element: &UIElement, wanted: &str) -> Result<Option<UIElement>, AutomationError> {
+    let start = Instant::now();
+    loop {
+        if let Some(item) = find_option(element, wanted) {
+            return Ok(Some(item));
+        }
+        if start.elapsed() >= OPTION_APPEAR_TIMEOUT {
+            return Ok(None);
+        }
+        crate::abort::check()?;
+        sleep(OPTION_POLL_INTERVAL);
+    }
+}
+
+/// The item named `wanted` (normalized) inside the control or, for web combo boxes
+/// whose list is a sibling of the input, inside its parent
+fn find_option(element: &UIElement, wanted: &str) -> Option<UIElement> {
+    let is_option = |candidate: &UIElement| {
+        OPTION_ROLES.contains(&candidate.normalized_role()) && {
+            let attributes = candidate.attributes();
+            attributes
+                .name
+                .or(attributes.label)
+                .is_some_and(|name| normalize(&name) == wanted)
+        }
+    };
+    element
+        .descendants(OPTION_SEARCH_DEPTH)
+        .find(|candidate| is_option(candidate))
+        .or_else(|| {
+            let parent = element.parent().ok()??;
+            parent.descendants(OPTION_SEARCH_DEPTH).find(|candidate| is_option(candidate))
+        })
+}
+
+/// Whether a checkbox or toggle is on, or a radio button selected; `None` when mixed
+/// or not checkable
+pub(crate) fn checked_state(element: &UIElement) -> Option<bool> {
+    let state = element.attributes().state;
+    state.checked.or(state.selected)
+}
+
+/// Check or uncheck a checkbox, toggle button or radio button
+pub(crate) fn set_checked(element: &UIElement, checked: bool) -> Result<(), AutomationError> {
+    let current = checked_state(element);
+    if current == Some(checked) {
+        return Ok(());
+    }
+    let role = element.normalized_role();
+    if current.is_none() && !matches!(role, Role::CheckBox | Role::RadioButton) {
+        return Err(AutomationError::UnexpectedValue(format!(
+            "A {} element has no checked state",
+            role
+        )));
+    }
+    let is_radio = role == Role::RadioButton;
+    if !checked && is_radio {
+        return Err(AutomationError::InvalidArgument(
+            "A radio button can't be unchecked; check another one in its group".to_string(),
+        ));
+    }
+
+    let mut before = current;
+    for _ in 0..MAX_CHECK_FLIPS {
+        let flipped = if is_radio { element.select_item() } else { element.toggle() };
+        if flipped.is_err() {
+            element.click()?;
+        }
+        if wait_for_checked_state(element, checked)? {
+            return Ok(());
+        }
+        // Flip again only if this one landed on the third state of a tri-state checkbox
+        let after = checked_state(element);
+        if after == before {
+            break;
+        }
+        before = after;
+    }
+    Err(AutomationError::VerificationFailed(format!(
+        "Element is {} after being flipped",
+        match checked_state(element) {
+            Some(true) => "checked",
+            Some(false) => "unchecked",
+            None => "mixed",
+        }
+    )))
+}
+
+/// Poll until the element reports `checked`; whether it did before the timeout
+fn wait_for_checked_state(element: &UIElement, checked: bool) -> Result<bool, AutomationError> {
+    let start = Instant::now();
+    loop {
+        if checked_state(element) == Some(checked) {
+            return Ok(true);
+        }
+        if start.elapsed() >= CHECK_SETTLE_TIMEOUT {
+            return Ok(false);
+        }
+        crate::abort::check()?;
+        sleep(CHECK_POLL_INTERVAL);
+    }
+}
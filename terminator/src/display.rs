@@ -0,0 +1,143 @@
+//! Monitor enumeration and display configuration change events
+//!
+//! Element bounds are always read live from the accessibility tree, but
+//! anything a workflow keeps around in screen coordinates (a saved
+//! [`crate::WindowLayout`], click positions, capture regions) goes stale when
+//! a monitor is plugged in or the resolution or scaling changes. A
+//! [`DisplayWatcher`] reports those changes so such values can be refreshed.
+
+use crate::AutomationError;
+use crate::platforms::AccessibilityEngine;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// A connected monitor and its place on the virtual desktop
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// DPI scale, 1.0 being 96 DPI on Windows / non-Retina on macOS
+    pub scale_factor: f32,
+    pub is_primary: bool,
+}
+
+/// A change in the display configuration
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayChange {
+    /// A monitor was connected
+    Added(MonitorInfo),
+    /// A monitor was disconnected
+    Removed(MonitorInfo),
+    /// A monitor's position, resolution, scale or primary status changed
+    Changed {
+        before: MonitorInfo,
+        after: MonitorInfo,
+    },
+}
+
+/// Reports display configuration changes, see [`crate::Desktop::watch_displays`].
+/// Stops watching when dropped.
+pub struct DisplayWatcher {
+    receiver: mpsc::UnboundedReceiver<DisplayChange>,
+    task: JoinHandle<()>,
+}
+
+impl DisplayWatcher {
+    pub(crate) fn spawn(
+        engine: Arc<dyn AccessibilityEngine>,
+        poll_interval: Duration,
+    ) -> Result<Self, AutomationError> {
+        let mut current = engine.list_monitors()?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(poll_interval).await;
+                let monitors = match engine.list_monitors() {
+                    Ok(monitors) => monitors,
+                    Err(e) => {
+                        // Enumeration can fail transiently while the configuration is changing
+                        warn!(error = %e, "Failed to list monitors");
+                        continue;
+                    }
+                };
+                for change in diff_monitors(&current, &monitors) {
+                    info!(?change, "Display configuration changed");
+                    if sender.send(change).is_err() {
+                        return;
+                    }
+                }
+                current = monitors;
+            }
+        });
+
+        Ok(Self { receiver, task })
+    }
+
+    /// Wait for the next display change
+    pub async fn next(&mut self) -> Option<DisplayChange> {
+        self.receiver.recv().await
+    }
+
+    /// Return a pending display change without waiting, if there is one
+    pub fn try_next(&mut self) -> Option<DisplayChange> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for DisplayWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Compare two monitor lists, matching monitors by name
+fn diff_monitors(before: &[MonitorInfo], after: &[MonitorInfo]) -> Vec<DisplayChange> {
+    let mut changes = Vec::new();
+    for old in before {
+        match after.iter().find(|m| m.name == old.name) {
+            Some(new) if new != old => changes.push(DisplayChange::Changed {
+                before: old.clone(),
+                after: new.clone(),
+            }),
+            Some(_) => {}
+            None => changes.push(DisplayChange::Removed(old.clone())),
+        }
+    }
+    for new in after {
+        if !before.iter().any(|m| m.name == new.name) {
+            changes.push(DisplayChange::Added(new.clone()));
+        }
+    }
+    changes
+}
+
+/// List monitors through xcap, shared by the platforms that capture through it
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub(crate) fn xcap_monitors() -> Result<Vec<MonitorInfo>, AutomationError> {
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| AutomationError::platform(format!("Failed to get monitors: {}", e)))?;
+    monitors
+        .iter()
+        .map(|monitor| {
+            let info = || -> xcap::XCapResult<MonitorInfo> {
+                Ok(MonitorInfo {
+                    name: monitor.name()?,
+                    x: monitor.x()?,
+                    y: monitor.y()?,
+                    width: monitor.width()?,
+                    height: monitor.height()?,
+                    scale_factor: monitor.scale_factor()?,
+                    is_primary: monitor.is_primary()?,
+                })
+            };
+            info().map_err(|e| AutomationError::platform(format!("Failed to read monitor: {}", e)))
+        })
+        .collect()
+}
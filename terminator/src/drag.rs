@@ -0,0 +1,65 @@
+//! Dragging with the mouse
+//!
+//! Apps only treat a press-move-release as a drag if it looks like one: the
+//! button is held a moment before moving, the pointer passes through points
+//! in between (a single jump never leaves the drag threshold or fires the
+//! drag-over events drop targets highlight on) and it rests over the target
+//! before letting go. [`DragOptions`] controls that pacing.
+
+use crate::{AutomationError, UIElement};
+use std::thread::sleep;
+use std::time::Duration;
+use tracing::debug;
+
+/// How a drag moves the mouse, see [`UIElement::drag_to`] and [`crate::Desktop::drag`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DragOptions {
+    /// How many intermediate moves to make between the start and the end
+    pub steps: usize,
+    /// How long the moves take in total; longer is slower
+    pub duration: Duration,
+    /// How long to hold the button down before moving, so the app registers a press
+    pub hold_delay: Duration,
+    /// How long to rest over the target before releasing, so it can react to the drag
+    /// (highlight a drop zone, open a folder)
+    pub drop_delay: Duration,
+}
+
+impl Default for DragOptions {
+    fn default() -> Self {
+        Self {
+            steps: 20,
+            duration: Duration::from_millis(400),
+            hold_delay: Duration::from_millis(150),
+            drop_delay: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Drag from `from` to `to` in screen coordinates, injecting input through `input`
+pub(crate) fn drag(
+    input: &UIElement,
+    from: (f64, f64),
+    to: (f64, f64),
+    options: &DragOptions,
+) -> Result<(), AutomationError> {
+    debug!(?from, ?to, steps = options.steps, "Dragging");
+    input.mouse_click_and_hold(from.0, from.1)?;
+
+    let moved = (|| {
+        sleep(options.hold_delay);
+        let steps = options.steps.max(1);
+        let step_delay = options.duration / steps as u32;
+        for step in 1..=steps {
+            let progress = step as f64 / steps as f64;
+            input.mouse_move(from.0 + (to.0 - from.0) * progress, from.1 + (to.1 - from.1) * progress)?;
+            sleep(step_delay);
+        }
+        sleep(options.drop_delay);
+        Ok(())
+    })();
+
+    // Let go even if a move failed, so the button isn't left held down
+    let released = input.mouse_release();
+    moved.and(released)
+}
@@ -8,6 +8,12 @@ use crate::UIElement;
 
 use super::renderer::{Color, Corner, HighlightEffect, HighlightStyle, OverlayRenderer, PopupStyle, Rect};
 
+// Step annotation colors and legend layout
+const STEP_COLOR: Color = Color { r: 255, g: 140, b: 0, a: 255 };
+const LEGEND_WIDTH: f32 = 300.0;
+const LEGEND_GAP: f32 = 16.0;
+const LEGEND_LINE_HEIGHT: f32 = 22.0;
+
 /// Main engine for screen drawing and visualization
 pub struct OverlayEngine {
     renderer: Arc<Mutex<Box<dyn OverlayRenderer>>>,
@@ -128,6 +134,69 @@ impl OverlayEngine {
         Ok(())
     }
     
+    /// Number several elements as the steps of a walkthrough and list the step labels
+    /// in a legend popup next to them. Everything stays up until [`OverlayEngine::clear`].
+    pub fn annotate_steps<S: AsRef<str>>(
+        &self,
+        steps: &[(UIElement, S)],
+    ) -> Result<(), AutomationError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        
+        let mut renderer = self.renderer.lock().unwrap();
+        renderer.clear()?;
+        
+        let mut legend = Vec::with_capacity(steps.len());
+        let mut annotated: Option<Rect> = None;
+        for (index, (element, label)) in steps.iter().enumerate() {
+            let number = (index + 1).to_string();
+            legend.push(format!("{}. {}", number, label.as_ref()));
+            
+            let Ok((x, y, width, height)) = element.bounds() else {
+                continue; // still listed in the legend
+            };
+            let rect = Rect {
+                x: x as f32,
+                y: y as f32,
+                width: width as f32,
+                height: height as f32,
+            };
+            let style = HighlightStyle::Border {
+                thickness: 2.0,
+                color: STEP_COLOR,
+            }
+            .and(HighlightStyle::Badge {
+                text: number,
+                position: Corner::TopLeft,
+            });
+            renderer.draw_highlight(rect, style)?;
+            annotated = Some(match annotated {
+                Some(union) => union_rect(union, rect),
+                None => rect,
+            });
+        }
+        
+        // Beside the annotated elements rather than on top of them
+        let legend_area = annotated.map(|union| {
+            let x = if union.x >= LEGEND_WIDTH + LEGEND_GAP {
+                union.x - LEGEND_WIDTH - LEGEND_GAP
+            } else {
+                union.x + union.width + LEGEND_GAP
+            };
+            Rect {
+                x,
+                y: union.y,
+                width: LEGEND_WIDTH,
+                height: LEGEND_LINE_HEIGHT * legend.len() as f32 + LEGEND_GAP,
+            }
+        });
+        renderer.show_popup(&legend.join("\n"), Duration::MAX, PopupStyle::Info, legend_area)?;
+        
+        renderer.update()?;
+        Ok(())
+    }
+    
     /// Show a popup message
     pub fn show_popup(
         &self,
@@ -140,7 +209,25 @@ impl OverlayEngine {
         }
         
         let style = style.unwrap_or(PopupStyle::Info);
-        self.renderer.lock().unwrap().show_popup(message, duration, style)?;
+        self.renderer.lock().unwrap().show_popup(message, duration, style, None)?;
+        
+        Ok(())
+    }
+    
+    /// Show a popup message centered in `area`, e.g. the bounds of a specific monitor
+    pub fn show_popup_in(
+        &self,
+        message: &str,
+        duration: Duration,
+        style: Option<PopupStyle>,
+        area: Rect,
+    ) -> Result<(), AutomationError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        
+        let style = style.unwrap_or(PopupStyle::Info);
+        self.renderer.lock().unwrap().show_popup(message, duration, style, Some(area))?;
         
         Ok(())
     }
@@ -155,8 +242,29 @@ impl OverlayEngine {
         Ok(())
     }
     
+    /// A function that clears all drawings, callable from another task (e.g. the kill switch)
+    pub(crate) fn clearer(&self) -> impl Fn() + Send + Sync + 'static {
+        let renderer = self.renderer.clone();
+        move || {
+            if let Ok(mut renderer) = renderer.lock() {
+                let _ = renderer.clear();
+            }
+        }
+    }
+
     /// Check if the overlay engine is enabled
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
-}
\ No newline at end of file
+}
+
+fn union_rect(a: Rect, b: Rect) -> Rect {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+    Rect {
+        x,
+        y,
+        width: (a.x + a.width).max(b.x + b.width) - x,
+        height: (a.y + a.height).max(b.y + b.height) - y,
+    }
+}
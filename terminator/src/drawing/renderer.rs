@@ -12,7 +12,7 @@ pub struct Rect {
 }
 
 /// Represents a color with RGBA components
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -32,6 +32,26 @@ impl Color {
         color.a = alpha;
         color
     }
+
+    /// Relative luminance as defined by WCAG 2, from 0.0 (black) to 1.0 (white). Alpha is ignored.
+    pub fn relative_luminance(&self) -> f64 {
+        let linear = |channel: u8| {
+            let c = channel as f64 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// WCAG 2 contrast ratio against `other`, from 1.0 (identical) to 21.0 (black on white).
+    /// Normal text needs at least 4.5 to meet level AA, large text 3.0.
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
 }
 
 /// Style options for popup messages
@@ -44,6 +64,20 @@ pub enum PopupStyle {
     Custom(Color, Color), // bg, text
 }
 
+/// Which monitor a popup appears on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DisplayTarget {
+    /// The primary monitor
+    #[default]
+    Primary,
+    /// The monitor with this name, as listed by `Desktop::monitors`
+    Monitor(String),
+    /// The monitor showing the focused window
+    ActiveWindow,
+    /// The monitor under the mouse cursor
+    Cursor,
+}
+
 /// Animation effects for highlights
 #[derive(Debug, Clone)]
 pub enum HighlightEffect {
@@ -58,6 +92,27 @@ pub enum HighlightStyle {
     Border { thickness: f32, color: Color },
     Fill { color: Color, opacity: f32 },
     Badge { text: String, position: Corner },
+    /// Several styles drawn on the same element, in order (later layers on top)
+    Combined(Vec<HighlightStyle>),
+}
+
+impl HighlightStyle {
+    /// Draw `other` on top of this style, e.g. a fill, then a border, then a badge
+    pub fn and(self, other: HighlightStyle) -> HighlightStyle {
+        let mut layers = self.into_layers();
+        layers.extend(other.into_layers());
+        HighlightStyle::Combined(layers)
+    }
+
+    /// The individual styles to draw, bottom layer first
+    pub fn into_layers(self) -> Vec<HighlightStyle> {
+        match self {
+            HighlightStyle::Combined(styles) => {
+                styles.into_iter().flat_map(HighlightStyle::into_layers).collect()
+            }
+            style => vec![style],
+        }
+    }
 }
 
 /// Corner positions for badges
@@ -77,8 +132,8 @@ pub trait OverlayRenderer: Send + Sync {
     /// Draw a highlight around a UI element
     fn draw_highlight(&mut self, bounds: Rect, style: HighlightStyle) -> Result<(), crate::AutomationError>;
     
-    /// Show a popup message
-    fn show_popup(&mut self, text: &str, duration: Duration, style: PopupStyle) -> Result<(), crate::AutomationError>;
+    /// Show a popup message centered in `area` (screen coordinates), or on the primary monitor if `None`
+    fn show_popup(&mut self, text: &str, duration: Duration, style: PopupStyle, area: Option<Rect>) -> Result<(), crate::AutomationError>;
     
     /// Clear all drawings
     fn clear(&mut self) -> Result<(), crate::AutomationError>;
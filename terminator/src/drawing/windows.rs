@@ -28,7 +28,10 @@ pub struct WindowsOverlayRenderer {
     #[cfg(target_os = "windows")]
     highlights: Vec<(Rect, HighlightStyle)>,
     #[cfg(target_os = "windows")]
-    popups: Vec<(String, Instant, Duration, PopupStyle)>,
+    popups: Vec<(String, Instant, Duration, PopupStyle, Option<Rect>)>,
+    // Top-left of the virtual screen, i.e. where the overlay window's client area starts
+    #[cfg(target_os = "windows")]
+    origin: (i32, i32),
     #[cfg(not(target_os = "windows"))]
     _dummy: (), // Placeholder for non-Windows platforms
     active: bool,
@@ -54,9 +57,9 @@ extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM
                         
                         // Draw popups
                         let now = Instant::now();
-                        renderer_lock.popups.retain(|(text, start, duration, style)| {
+                        renderer_lock.popups.retain(|(text, start, duration, style, area)| {
                             if start.elapsed() < *duration {
-                                renderer_lock.draw_popup_internal(hdc, text, *style).ok();
+                                renderer_lock.draw_popup_internal(hdc, text, *style, *area).ok();
                                 true
                             } else {
                                 false
@@ -86,6 +89,7 @@ impl WindowsOverlayRenderer {
                 hwnd: HWND(0),
                 highlights: Vec::new(),
                 popups: Vec::new(),
+                origin: (0, 0),
                 active: false,
             })
         }
@@ -120,13 +124,16 @@ impl WindowsOverlayRenderer {
             
             RegisterClassExA(&wc);
             
-            // Get screen dimensions
-            let screen_width = windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics(
-                windows::Win32::UI::WindowsAndMessaging::SM_CXSCREEN
-            );
-            let screen_height = windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics(
-                windows::Win32::UI::WindowsAndMessaging::SM_CYSCREEN
-            );
+            // Cover the whole virtual screen so highlights show on every monitor
+            use windows::Win32::UI::WindowsAndMessaging::{
+                GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN,
+                SM_YVIRTUALSCREEN,
+            };
+            let screen_x = GetSystemMetrics(SM_XVIRTUALSCREEN);
+            let screen_y = GetSystemMetrics(SM_YVIRTUALSCREEN);
+            let screen_width = GetSystemMetrics(SM_CXVIRTUALSCREEN);
+            let screen_height = GetSystemMetrics(SM_CYVIRTUALSCREEN);
+            self.origin = (screen_x, screen_y);
             
             // Create layered, topmost, transparent window
             self.hwnd = CreateWindowExA(
@@ -134,7 +141,7 @@ impl WindowsOverlayRenderer {
                 class_name,
                 PCSTR(b"Terminator Overlay\0".as_ptr()),
                 WS_POPUP,
-                0, 0, screen_width, screen_height,
+                screen_x, screen_y, screen_width, screen_height,
                 HWND(0),
                 Default::default(),
                 HINSTANCE(0),
@@ -142,7 +149,7 @@ impl WindowsOverlayRenderer {
             );
             
             if self.hwnd.0 == 0 {
-                return Err(AutomationError::InternalError(
+                return Err(AutomationError::Internal(
                     "Failed to create overlay window".to_string(),
                 ));
             }
@@ -157,13 +164,15 @@ impl WindowsOverlayRenderer {
         }
     }
     
+    /// Convert a rect in screen coordinates to the overlay window's client coordinates
     #[cfg(target_os = "windows")]
     fn rect_to_win32_rect(&self, rect: Rect) -> RECT {
+        let (origin_x, origin_y) = self.origin;
         RECT {
-            left: rect.x as i32,
-            top: rect.y as i32,
-            right: (rect.x + rect.width) as i32,
-            bottom: (rect.y + rect.height) as i32,
+            left: rect.x as i32 - origin_x,
+            top: rect.y as i32 - origin_y,
+            right: (rect.x + rect.width) as i32 - origin_x,
+            bottom: (rect.y + rect.height) as i32 - origin_y,
         }
     }
     
@@ -233,6 +242,11 @@ impl WindowsOverlayRenderer {
                     SelectObject(hdc, old_font);
                     DeleteObject(font);
                 }
+                HighlightStyle::Combined(styles) => {
+                    for layer in styles {
+                        self.draw_highlight_internal(hdc, bounds, layer)?;
+                    }
+                }
             }
             
             Ok(())
@@ -240,7 +254,7 @@ impl WindowsOverlayRenderer {
     }
     
     #[cfg(target_os = "windows")]
-    fn draw_popup_internal(&self, hdc: HDC, text: &str, style: PopupStyle) -> Result<(), AutomationError> {
+    fn draw_popup_internal(&self, hdc: HDC, text: &str, style: PopupStyle, area: Option<Rect>) -> Result<(), AutomationError> {
         unsafe {
             // Map style to colors
             let (bg_color, text_color) = match style {
@@ -251,19 +265,24 @@ impl WindowsOverlayRenderer {
                 PopupStyle::Custom(bg, text) => (bg, text),
             };
             
-            // Get screen dimensions
-            let screen_width = windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics(
-                windows::Win32::UI::WindowsAndMessaging::SM_CXSCREEN
-            ) as f32;
-            let screen_height = windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics(
-                windows::Win32::UI::WindowsAndMessaging::SM_CYSCREEN
-            ) as f32;
-            
-            // Calculate popup dimensions and position
+            // Center on the requested area, or the primary monitor (which starts at 0,0)
+            let area = area.unwrap_or_else(|| Rect {
+                x: 0.0,
+                y: 0.0,
+                width: windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics(
+                    windows::Win32::UI::WindowsAndMessaging::SM_CXSCREEN
+                ) as f32,
+                height: windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics(
+                    windows::Win32::UI::WindowsAndMessaging::SM_CYSCREEN
+                ) as f32,
+            });
+            
+            // Calculate popup dimensions and position, one text row per line
+            let lines: Vec<&str> = text.lines().collect();
             let popup_width = 300.0;
-            let popup_height = 80.0;
-            let popup_x = (screen_width - popup_width) / 2.0;
-            let popup_y = (screen_height - popup_height) / 2.0;
+            let popup_height = (lines.len() as f32 * 22.0 + 20.0).max(80.0);
+            let popup_x = area.x + (area.width - popup_width) / 2.0;
+            let popup_y = area.y + (area.height - popup_height) / 2.0;
             
             let popup_rect = Rect {
                 x: popup_x,
@@ -290,10 +309,13 @@ impl WindowsOverlayRenderer {
             let old_font = SelectObject(hdc, font);
             
             // Center text in popup
-            let text_x = popup_x as i32 + 10;
-            let text_y = popup_y as i32 + (popup_height as i32 / 2) - 9;
+            let text_x = win32_rect.left + 10;
+            let text_top = win32_rect.top + (popup_height as i32 - lines.len() as i32 * 22) / 2;
             
-            TextOutA(hdc, text_x, text_y, PCSTR(text.as_ptr()), text.len() as i32);
+            for (row, line) in lines.iter().enumerate() {
+                let text_y = text_top + row as i32 * 22;
+                TextOutA(hdc, text_x, text_y, PCSTR(line.as_ptr()), line.len() as i32);
+            }
             
             // Clean up
             SelectObject(hdc, old_font);
@@ -309,6 +331,7 @@ impl WindowsOverlayRenderer {
             hwnd: self.hwnd,
             highlights: self.highlights.clone(),
             popups: self.popups.clone(),
+            origin: self.origin,
             active: self.active,
         }
     }
@@ -355,7 +378,7 @@ impl OverlayRenderer for WindowsOverlayRenderer {
         }
     }
     
-    fn show_popup(&mut self, text: &str, duration: Duration, style: PopupStyle) -> Result<(), AutomationError> {
+    fn show_popup(&mut self, text: &str, duration: Duration, style: PopupStyle, area: Option<Rect>) -> Result<(), AutomationError> {
         #[cfg(target_os = "windows")]
         {
             if !self.active {
@@ -363,7 +386,7 @@ impl OverlayRenderer for WindowsOverlayRenderer {
             }
             
             // Store popup for rendering
-            self.popups.push((text.to_string(), Instant::now(), duration, style));
+            self.popups.push((text.to_string(), Instant::now(), duration, style, area));
             
             // Trigger redraw
             unsafe {
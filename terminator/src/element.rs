@@ -1,30 +1,97 @@
+use crate::action::{ActionContext, ActionabilityOptions};
+use crate::drag::DragOptions;
+use crate::drawing::Color;
 use crate::errors::AutomationError;
+use crate::fingerprint::VisualFingerprint;
+use crate::hover::HoverOptions;
+use crate::keys::KeyChord;
+use crate::platforms::AccessibilityEngine;
+use crate::role::Role;
+use crate::secret::SecretString;
 use crate::selector::Selector;
+use crate::value::{NumericValue, RangeValue};
+use crate::visual_changes::VisualChangeWatcher;
+use chrono::NaiveDate;
+use futures::Stream;
+use image::RgbaImage;
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::time::Instant;
-use tracing::{info, instrument, warn};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, instrument, warn};
 
-use super::{ClickResult, Locator};
+use super::{ClickResult, ContextMenu, Locator};
+
+// How often bounds are re-read while waiting for them to settle
+const BOUNDS_POLL_INTERVAL: Duration = Duration::from_millis(50);
+// How long to wait for bounds to settle if no timeout is given
+const DEFAULT_STABLE_BOUNDS_TIMEOUT: Duration = Duration::from_secs(10);
+// How often readiness is re-checked while waiting for an application to respond
+const RESPONSIVE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// How often actionability is re-checked before an action
+const ACTIONABILITY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// How many ancestors to walk when relating the element under a point to this one
+const MAX_HIT_TEST_DEPTH: usize = 32;
+// How often a progress bar is read while waiting for it to finish
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// How close to the end a progress bar has to be to count as finished
+const PROGRESS_COMPLETE_TOLERANCE: f64 = 1e-3;
 
 /// Represents a UI element in a desktop application
-#[derive(Debug)]
 pub struct UIElement {
     inner: Box<dyn UIElementImpl>,
+    /// The engine the element was found through, reused by [`UIElement::locator`]
+    engine: Option<Arc<dyn AccessibilityEngine>>,
 }
 
 /// Attributes associated with a UI element
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct UIElementAttributes {
+    /// Role as reported by the platform, e.g. `Button` or `AXButton`
     pub role: String,
+    /// The platform role mapped to its canonical [`Role`]
+    pub normalized_role: Role,
     pub name: Option<String>,
     pub label: Option<String>,
     pub value: Option<String>,
     pub description: Option<String>,
     pub properties: HashMap<String, Option<serde_json::Value>>,
     pub is_keyboard_focusable: Option<bool>,
+    /// Extended help or tooltip text
+    pub help_text: Option<String>,
+    /// Mnemonic that activates the element, e.g. `Alt+F`
+    pub access_key: Option<String>,
+    /// Shortcut that triggers the element's action, e.g. `Ctrl+S`
+    pub accelerator_key: Option<String>,
+    /// Hint text shown in an empty input field
+    pub placeholder: Option<String>,
+    /// Name of the element that labels this one
+    pub labeled_by: Option<String>,
+    /// Text of the elements that describe this one
+    pub described_by: Option<String>,
+    pub state: ElementState,
+}
+
+/// State flags of a UI element; `None` where the platform doesn't report a flag
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElementState {
+    pub enabled: Option<bool>,
+    pub focused: Option<bool>,
+    pub offscreen: Option<bool>,
+    pub read_only: Option<bool>,
+    /// Marked as required for form submission
+    pub required: Option<bool>,
+    /// A password field that hides its contents
+    pub password: Option<bool>,
+    /// Checked state of checkboxes and toggles; `None` when indeterminate
+    pub checked: Option<bool>,
+    pub expanded: Option<bool>,
+    pub selected: Option<bool>,
 }
 
+/// An element's children, read one at a time
+pub(crate) type ChildIter = Box<dyn Iterator<Item = Result<UIElement, AutomationError>> + Send>;
+
 /// Interface for platform-specific element implementations
 pub(crate) trait UIElementImpl: Send + Sync + Debug {
     fn object_id(&self) -> usize;
@@ -35,17 +102,86 @@ pub(crate) trait UIElementImpl: Send + Sync + Debug {
         self.attributes().name
     }
     fn children(&self) -> Result<Vec<UIElement>, AutomationError>;
+    /// The children one at a time, so walking a long list doesn't read every row up front.
+    /// Platforms without a sibling-by-sibling API read them all with `children`.
+    fn children_iter(&self) -> Result<ChildIter, AutomationError> {
+        Ok(Box::new(self.children()?.into_iter().map(Ok)))
+    }
     fn parent(&self) -> Result<Option<UIElement>, AutomationError>;
     fn bounds(&self) -> Result<(f64, f64, f64, f64), AutomationError>; // x, y, width, height
     fn click(&self) -> Result<ClickResult, AutomationError>;
     fn double_click(&self) -> Result<ClickResult, AutomationError>;
-    fn right_click(&self) -> Result<(), AutomationError>;
+    fn right_click(&self) -> Result<ClickResult, AutomationError>;
     fn hover(&self) -> Result<(), AutomationError>;
     fn focus(&self) -> Result<(), AutomationError>;
     fn type_text(&self, text: &str, use_clipboard: bool) -> Result<(), AutomationError>;
     fn press_key(&self, key: &str) -> Result<(), AutomationError>;
+    /// Press a chord's key while holding its modifiers, as real key presses
+    /// into whatever has keyboard focus
+    fn press_chord(&self, _chord: &KeyChord) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "press_chord not supported on this platform".to_string(),
+        ))
+    }
     fn get_text(&self, max_depth: usize) -> Result<String, AutomationError>;
     fn set_value(&self, value: &str) -> Result<(), AutomationError>;
+    /// Read the element's value through the platform's value interface (UI Automation
+    /// ValuePattern, AXValue)
+    fn value(&self) -> Result<String, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "value not supported on this platform".to_string(),
+        ))
+    }
+    /// Set the numeric value of a range control (spinner, slider) through its native pattern
+    fn set_range_value(&self, _value: f64) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "set_range_value not supported on this platform".to_string(),
+        ))
+    }
+    /// Whether the application owning the element is processing input right now: its
+    /// window isn't hung and it has no input waiting to be handled
+    fn is_responsive(&self) -> Result<bool, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "is_responsive not supported on this platform".to_string(),
+        ))
+    }
+    /// Read the range and current value of a range control through its native pattern
+    fn range_value(&self) -> Result<RangeValue, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "range_value not supported on this platform".to_string(),
+        ))
+    }
+    /// The id of the process that owns the element
+    fn process_id(&self) -> Result<u32, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "process_id not supported on this platform".to_string(),
+        ))
+    }
+    /// Scroll the containers holding the element until it is in view, with the
+    /// platform's own scrolling (UI Automation ScrollItem, AXScrollToVisible)
+    fn scroll_into_view(&self) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "scroll_into_view not supported on this platform".to_string(),
+        ))
+    }
+    /// Open or close a combo box, menu or tree item through its native pattern
+    fn set_expanded(&self, _expanded: bool) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "set_expanded not supported on this platform".to_string(),
+        ))
+    }
+    /// Flip a checkbox or toggle button through its native pattern
+    fn toggle(&self) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "toggle not supported on this platform".to_string(),
+        ))
+    }
+    /// Make the element the selected item of its list or menu through its native pattern
+    fn select_item(&self) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "select_item not supported on this platform".to_string(),
+        ))
+    }
     fn is_enabled(&self) -> Result<bool, AutomationError>;
     fn is_visible(&self) -> Result<bool, AutomationError>;
     fn is_focused(&self) -> Result<bool, AutomationError>;
@@ -70,12 +206,54 @@ pub(crate) trait UIElementImpl: Send + Sync + Debug {
     fn mouse_click_and_hold(&self, x: f64, y: f64) -> Result<(), AutomationError>;
     fn mouse_move(&self, x: f64, y: f64) -> Result<(), AutomationError>;
     fn mouse_release(&self) -> Result<(), AutomationError>;
+    /// Turn the mouse wheel at (x, y) by whole notches; positive `delta_x` scrolls right
+    /// and positive `delta_y` down
+    fn mouse_wheel(&self, _x: f64, _y: f64, _delta_x: i32, _delta_y: i32) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "mouse_wheel not supported on this platform".to_string(),
+        ))
+    }
 }
 
 impl UIElement {
     /// Create a new UI element from a platform-specific implementation
     pub(crate) fn new(impl_: Box<dyn UIElementImpl>) -> Self {
-        Self { inner: impl_ }
+        Self {
+            inner: impl_,
+            engine: None,
+        }
+    }
+
+    /// Remember the engine the element was found through, so that locators created
+    /// from it search with that engine instead of starting a new one
+    pub(crate) fn with_engine(mut self, engine: Arc<dyn AccessibilityEngine>) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    /// Pass this element's engine on to a related element, e.g. a child
+    fn related(&self, element: UIElement) -> UIElement {
+        match &self.engine {
+            Some(engine) => element.with_engine(engine.clone()),
+            None => element,
+        }
+    }
+
+    /// Short human-readable description used in error context, e.g. `button 'OK' (#123)`
+    pub(crate) fn describe(&self) -> String {
+        let mut description = self.inner.role();
+        if let Some(name) = self.inner.name().filter(|n| !n.is_empty()) {
+            description.push_str(&format!(" '{}'", name));
+        }
+        if let Some(id) = self.inner.id() {
+            description.push_str(&format!(" (#{})", id));
+        }
+        description
+    }
+
+    /// Annotate an error with this element and the operation that failed
+    fn annotate(&self, operation: &str, error: AutomationError) -> AutomationError {
+        error.with_operation(operation).with_element(self.describe())
     }
 
     /// Get the element's ID
@@ -101,6 +279,12 @@ impl UIElement {
         self.inner.role()
     }
 
+    /// Get the element's role mapped to its canonical, platform-independent [`Role`]
+    pub fn normalized_role(&self) -> Role {
+        let role = self.inner.role();
+        role.parse().unwrap_or_else(|_| Role::from_platform_role(&role))
+    }
+
     /// Get all attributes of the element
     pub fn attributes(&self) -> UIElementAttributes {
         self.inner.attributes()
@@ -108,12 +292,44 @@ impl UIElement {
 
     /// Get child elements
     pub fn children(&self) -> Result<Vec<UIElement>, AutomationError> {
-        self.inner.children()
+        let children = self.inner.children()?;
+        Ok(children.into_iter().map(|child| self.related(child)).collect())
+    }
+
+    /// Stream the children one at a time instead of collecting them, for containers with
+    /// thousands of rows. Each child is read as the stream is polled, which blocks until
+    /// the platform answers. A child that can't be read is yielded as an error, after
+    /// which the stream ends: the remaining siblings can't be reached.
+    pub fn children_stream(&self) -> impl Stream<Item = Result<UIElement, AutomationError>> + Send + use<> {
+        let parent = self.clone();
+        let children: ChildIter = match self.inner.children_iter() {
+            Ok(children) => children,
+            Err(e) => Box::new(std::iter::once(Err(e))),
+        };
+        let mut failed = false;
+        futures::stream::iter(children.map_while(move |child| {
+            if failed {
+                return None;
+            }
+            failed = child.is_err();
+            Some(child.map(|child| parent.related(child)))
+        }))
+    }
+
+    /// Every element below this one down to `max_depth` levels, in tree order, read
+    /// lazily as the iterator advances
+    pub(crate) fn descendants(&self, max_depth: usize) -> Descendants {
+        Descendants {
+            origin: self.clone(),
+            stack: Vec::new(),
+            pending: Some((self.clone(), 0)),
+            max_depth,
+        }
     }
 
     /// Get parent element
     pub fn parent(&self) -> Result<Option<UIElement>, AutomationError> {
-        self.inner.parent()
+        Ok(self.inner.parent()?.map(|parent| self.related(parent)))
     }
 
     /// Get element bounds (x, y, width, height)
@@ -121,13 +337,299 @@ impl UIElement {
         self.inner.bounds()
     }
 
+    /// Wait until the element's bounds have not changed for `quiet_period`, e.g. for a
+    /// slide-in panel or resizing dialog to finish animating before clicking it.
+    /// Returns the settled bounds. Gives up after `timeout` (10 seconds by default).
+    #[instrument(skip(self))]
+    pub async fn wait_for_stable_bounds(
+        &self,
+        quiet_period: Duration,
+        timeout: Option<Duration>,
+    ) -> Result<(f64, f64, f64, f64), AutomationError> {
+        let start = Instant::now();
+        let timeout = timeout.unwrap_or(DEFAULT_STABLE_BOUNDS_TIMEOUT);
+        info!("Waiting for element bounds to settle");
+
+        let mut bounds = self.bounds().map_err(|e| self.annotate("wait_for_stable_bounds", e))?;
+        let mut stable_since = Instant::now();
+        loop {
+            if stable_since.elapsed() >= quiet_period {
+                info!(
+                    duration_ms = start.elapsed().as_millis(),
+                    ?bounds,
+                    "Element bounds settled"
+                );
+                return Ok(bounds);
+            }
+            if start.elapsed() >= timeout {
+                return Err(self.annotate(
+                    "wait_for_stable_bounds",
+                    AutomationError::Timeout(format!(
+                        "Bounds still changing after {:?} (last {:?})",
+                        timeout, bounds
+                    )),
+                ));
+            }
+
+            crate::abort::check()?;
+            tokio::time::sleep(BOUNDS_POLL_INTERVAL.min(quiet_period)).await;
+            let current = self.bounds().map_err(|e| self.annotate("wait_for_stable_bounds", e))?;
+            if current != bounds {
+                bounds = current;
+                stable_since = Instant::now();
+            }
+        }
+    }
+
+    /// Wait until a freshly launched application or window is ready for input: it isn't
+    /// hung, its input queue is idle and its content has appeared (it has children).
+    /// Use this instead of a fixed sleep after `open_application`. Checks the platform
+    /// can't make are skipped.
+    #[instrument(skip(self))]
+    pub async fn wait_until_responsive(&self, timeout: Duration) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!("Waiting for application to become responsive");
+
+        loop {
+            let responsive = match self.inner.is_responsive() {
+                Ok(responsive) => responsive,
+                Err(e) if matches!(e.inner(), AutomationError::UnsupportedOperation(_)) => true,
+                Err(e) => return Err(self.annotate("wait_until_responsive", e)),
+            };
+            let has_content = self.inner.children().is_ok_and(|children| !children.is_empty());
+            let pending = if !responsive {
+                "not responding to input"
+            } else if !has_content {
+                "showing no content"
+            } else {
+                info!(duration_ms = start.elapsed().as_millis(), "Application is responsive");
+                return Ok(());
+            };
+
+            if start.elapsed() >= timeout {
+                return Err(self.annotate(
+                    "wait_until_responsive",
+                    AutomationError::Timeout(format!("Still {} after {:?}", pending, timeout)),
+                ));
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(RESPONSIVE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Wait until a progress bar reaches 100%, or closes, calling `on_progress` with each
+    /// new reading between 0.0 and 1.0. The reading comes from the native range value,
+    /// ARIA properties, a displayed percentage, or as a last resort OCR of the bar's area.
+    /// Fails with `Timeout` if it isn't done within `timeout`.
+    #[instrument(skip(self, on_progress))]
+    pub async fn wait_for_progress_complete(
+        &self,
+        timeout: Duration,
+        mut on_progress: impl FnMut(f64) + Send,
+    ) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!("Waiting for progress to complete");
+
+        let mut last = None;
+        loop {
+            // A progress dialog usually closes when its work is done
+            let closed = match self.is_visible() {
+                Ok(visible) => !visible,
+                Err(e) if matches!(e.inner(), AutomationError::StaleElement(_) | AutomationError::ElementNotFound(_)) => true,
+                Err(e) => return Err(self.annotate("wait_for_progress_complete", e)),
+            };
+            if closed {
+                info!(duration_ms = start.elapsed().as_millis(), "Progress indicator closed");
+                return Ok(());
+            }
+
+            let progress = crate::progress::read_progress(self, self.engine.as_deref())
+                .map_err(|e| self.annotate("wait_for_progress_complete", e))?;
+            if let Some(progress) = progress.filter(|&progress| Some(progress) != last) {
+                debug!(progress, "Progress changed");
+                on_progress(progress);
+                last = Some(progress);
+                if progress >= 1.0 - PROGRESS_COMPLETE_TOLERANCE {
+                    info!(duration_ms = start.elapsed().as_millis(), "Progress complete");
+                    return Ok(());
+                }
+            }
+
+            if start.elapsed() >= timeout {
+                let status = match last {
+                    Some(progress) => format!("at {:.0}%", progress * 100.0),
+                    None => "showing no progress".to_string(),
+                };
+                return Err(self.annotate(
+                    "wait_for_progress_complete",
+                    AutomationError::Timeout(format!("Progress still {} after {:?}", status, timeout)),
+                ));
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(PROGRESS_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Wait until the element can take input as described by `options`: visible, enabled,
+    /// on screen and not covered by another element. An element that is hidden or off
+    /// screen is scrolled into view once first. Locator actions run this before acting;
+    /// call it directly before acting on an element held on to.
+    #[instrument(skip(self))]
+    pub async fn wait_until_actionable(
+        &self,
+        options: &ActionabilityOptions,
+        timeout: Duration,
+    ) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        let mut scrolled = false;
+        loop {
+            let problem = self
+                .actionability_problem(options)
+                .map_err(|e| self.annotate("wait_until_actionable", e))?;
+            let Some(problem) = problem else {
+                return Ok(());
+            };
+            // Items scrolled out of their list report themselves offscreen
+            if !scrolled && matches!(problem, "not visible" | "off screen") {
+                scrolled = true;
+                if let Err(e) = self.scroll_into_view() {
+                    debug!(error = %e, "Could not scroll element into view");
+                }
+                continue;
+            }
+            if start.elapsed() >= timeout {
+                return Err(self.annotate(
+                    "wait_until_actionable",
+                    AutomationError::Timeout(format!("Element still {} after {:?}", problem, timeout)),
+                ));
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(ACTIONABILITY_POLL_INTERVAL).await;
+        }
+    }
+
+    /// The first check in `options` the element fails, if any
+    fn actionability_problem(&self, options: &ActionabilityOptions) -> Result<Option<&'static str>, AutomationError> {
+        if options.visible && !unless_unsupported(self.inner.is_visible(), true)? {
+            return Ok(Some("not visible"));
+        }
+        if options.enabled && !unless_unsupported(self.inner.is_enabled(), true)? {
+            return Ok(Some("disabled"));
+        }
+        // The screen checks need the engine the element was found through
+        let Some(engine) = self.engine.as_ref().filter(|_| options.on_screen || options.unobscured) else {
+            return Ok(None);
+        };
+
+        let (x, y, width, height) = self.inner.bounds()?;
+        let (center_x, center_y) = (x + width / 2.0, y + height / 2.0);
+        if options.on_screen {
+            if width <= 0.0 || height <= 0.0 {
+                return Ok(Some("zero-sized"));
+            }
+            let monitors = unless_unsupported(engine.list_monitors(), Vec::new())?;
+            let on_a_monitor = monitors.iter().any(|m| {
+                center_x >= m.x as f64
+                    && center_x < m.x as f64 + m.width as f64
+                    && center_y >= m.y as f64
+                    && center_y < m.y as f64 + m.height as f64
+            });
+            if !monitors.is_empty() && !on_a_monitor {
+                return Ok(Some("off screen"));
+            }
+        }
+        if options.unobscured && self.is_covered_at(engine.as_ref(), center_x, center_y) {
+            return Ok(Some("covered by another element"));
+        }
+        Ok(None)
+    }
+
+    /// Whether the element hit at `(x, y)` is something other than this element, its
+    /// descendants or its ancestors. Ancestors don't count: some platforms hit-test to
+    /// the container of an element that isn't hit-testable itself.
+    fn is_covered_at(&self, engine: &dyn AccessibilityEngine, x: f64, y: f64) -> bool {
+        let Ok(hit) = engine.get_element_at_point(x, y) else {
+            return false;
+        };
+        let lineage = |from: Option<UIElement>| {
+            std::iter::successors(from, |element| element.inner.parent().ok().flatten()).take(MAX_HIT_TEST_DEPTH)
+        };
+        let within_self = lineage(Some(hit.clone())).any(|element| element == *self);
+        !within_self && !lineage(self.inner.parent().ok().flatten()).any(|element| element == hit)
+    }
+
+    /// Capture the screen within the element's bounds, for `operation`
+    fn capture_image(&self, operation: &str) -> Result<RgbaImage, AutomationError> {
+        let (x, y, width, height) = self.bounds()?;
+        crate::screen::capture_region(
+            x.round() as i32,
+            y.round() as i32,
+            width.round() as u32,
+            height.round() as u32,
+        )
+        .map_err(|e| self.annotate(operation, e))
+    }
+
+    /// The most common color within the element's bounds, e.g. the state of a status LED
+    #[instrument(skip(self))]
+    pub fn dominant_color(&self) -> Result<Color, AutomationError> {
+        let image = self.capture_image("dominant_color")?;
+        crate::screen::dominant_color(&image).ok_or_else(|| {
+            self.annotate(
+                "dominant_color",
+                AutomationError::InvalidArgument("Element has an empty capture".to_string()),
+            )
+        })
+    }
+
+    /// WCAG contrast ratio between the element's foreground (text, icon) and background,
+    /// both estimated from its pixels: 1.0 for a plain area, 21.0 for black on white.
+    /// Useful to check that a control stays readable in every theme.
+    #[instrument(skip(self))]
+    pub fn contrast_ratio(&self) -> Result<f64, AutomationError> {
+        let image = self.capture_image("contrast_ratio")?;
+        let (foreground, background) = crate::screen::foreground_background(&image).ok_or_else(|| {
+            self.annotate(
+                "contrast_ratio",
+                AutomationError::InvalidArgument("Element has an empty capture".to_string()),
+            )
+        })?;
+        Ok(foreground.contrast_ratio(&background))
+    }
+
+    /// A perceptual fingerprint of the element's pixels. Store it and compare it with
+    /// [`VisualFingerprint::looks_like`] on a later run to notice a control that was
+    /// restyled without any change to its accessibility data.
+    #[instrument(skip(self))]
+    pub fn visual_fingerprint(&self) -> Result<VisualFingerprint, AutomationError> {
+        let image = self.capture_image("visual_fingerprint")?;
+        VisualFingerprint::of_image(&image).ok_or_else(|| {
+            self.annotate(
+                "visual_fingerprint",
+                AutomationError::InvalidArgument("Element has an empty capture".to_string()),
+            )
+        })
+    }
+
+    /// Watch the pixels inside the element's bounds, sampling `fps` times per second, and
+    /// report when more than `threshold` (0.0 to 1.0) of them changed since the last report.
+    ///
+    /// For content that repaints without accessibility events, like charts, terminals
+    /// or video. Must be called from within a tokio runtime.
+    #[instrument(skip(self))]
+    pub fn watch_visual_changes(&self, fps: f64, threshold: f32) -> Result<VisualChangeWatcher, AutomationError> {
+        VisualChangeWatcher::spawn(self.clone(), fps, threshold)
+            .map_err(|e| self.annotate("watch_visual_changes", e))
+    }
+
     /// Click on this element
     #[instrument(skip(self))]
     pub fn click(&self) -> Result<ClickResult, AutomationError> {
+        check_input()?;
         let start = Instant::now();
         info!("Clicking element");
         
-        let result = self.inner.click();
+        let result = self.inner.click().map_err(|e| self.annotate("click", e));
         
         let duration = start.elapsed();
         info!(
@@ -135,16 +637,18 @@ impl UIElement {
             "Element clicked"
         );
         
-        result
+        result.map(|click| self.with_action_context(click))
     }
 
-    /// Double-click on this element
+    /// Double-click on this element at its clickable point, or its center if it has
+    /// none. `method` in the result says which.
     #[instrument(skip(self))]
     pub fn double_click(&self) -> Result<ClickResult, AutomationError> {
+        check_input()?;
         let start = Instant::now();
         info!("Double clicking element");
         
-        let result = self.inner.double_click();
+        let result = self.inner.double_click().map_err(|e| self.annotate("double_click", e));
         
         let duration = start.elapsed();
         info!(
@@ -152,16 +656,31 @@ impl UIElement {
             "Element double clicked"
         );
         
-        result
+        result.map(|click| self.with_action_context(click))
     }
 
-    /// Right-click on this element
+    /// Which window and element hold keyboard focus right now, for recording where an
+    /// action's input went. `None` if the element wasn't found through an engine.
+    pub fn action_context(&self) -> Option<ActionContext> {
+        self.engine.as_deref().map(ActionContext::capture)
+    }
+
+    fn with_action_context(&self, mut click: ClickResult) -> ClickResult {
+        if click.context.is_none() {
+            click.context = self.action_context();
+        }
+        click
+    }
+
+    /// Right-click on this element, opening its context menu through accessibility
+    /// if the platform can and with the mouse otherwise. `method` in the result says which.
     #[instrument(skip(self))]
-    pub fn right_click(&self) -> Result<(), AutomationError> {
+    pub fn right_click(&self) -> Result<ClickResult, AutomationError> {
+        check_input()?;
         let start = Instant::now();
         info!("Right clicking element");
         
-        let result = self.inner.right_click();
+        let result = self.inner.right_click().map_err(|e| self.annotate("right_click", e));
         
         let duration = start.elapsed();
         info!(
@@ -169,27 +688,83 @@ impl UIElement {
             "Element right clicked"
         );
         
-        result
+        result.map(|click| self.with_action_context(click))
+    }
+
+    /// This element's context menu, e.g. `element.context_menu().select("Rename").await`
+    pub fn context_menu(&self) -> ContextMenu {
+        ContextMenu::new(self.clone())
     }
 
-    /// Hover over this element
+    /// Move the mouse cursor to the center of this element. Use [`UIElement::hover_with`]
+    /// to also rest there until tooltips and hover menus open, or to hover elsewhere.
     pub fn hover(&self) -> Result<(), AutomationError> {
+        check_input()?;
         self.inner.hover()
+            .map_err(|e| self.annotate("hover", e))
+    }
+
+    /// Move the mouse cursor to this element, offset from its center as `options` says,
+    /// and wait there for the app's hover delay
+    pub async fn hover_with(&self, options: &HoverOptions) -> Result<(), AutomationError> {
+        check_input()?;
+        let (dx, dy) = options.offset;
+        let moved = if dx == 0.0 && dy == 0.0 {
+            self.inner.hover()
+        } else {
+            self.bounds()
+                .and_then(|(x, y, width, height)| self.inner.mouse_move(x + width / 2.0 + dx, y + height / 2.0 + dy))
+        };
+        moved.map_err(|e| self.annotate("hover", e))?;
+        debug!(dwell_ms = options.dwell.as_millis(), "Hovering");
+        tokio::time::sleep(options.dwell).await;
+        Ok(())
     }
 
     /// Focus this element
     pub fn focus(&self) -> Result<(), AutomationError> {
+        check_input()?;
         self.inner.focus()
+            .map_err(|e| self.annotate("focus", e))
     }
 
     /// Type text into this element
     pub fn type_text(&self, text: &str, use_clipboard: bool) -> Result<(), AutomationError> {
+        check_input()?;
         self.inner.type_text(text, use_clipboard)
+            .map_err(|e| self.annotate("type_text", e))
     }
 
-    /// Press a key while this element is focused
+    /// Type a secret (password, token) into this element.
+    ///
+    /// Unlike `type_text`, the text is never written to logs, error messages or
+    /// failure artifacts, and it never passes through the clipboard: on Windows it is
+    /// typed as keystrokes, on macOS it is set as the field's `AXValue`, replacing
+    /// what the field held.
+    pub fn type_secret(&self, secret: &SecretString) -> Result<(), AutomationError> {
+        check_input()?;
+        info!("Typing secret");
+        self.inner
+            .type_text(secret.expose_secret(), false)
+            .map_err(|e| self.annotate("type_secret", scrub_secret(e, secret)))
+    }
+
+    /// Focus this element and press a key or shortcut, e.g. `Enter`, `F5`,
+    /// `Ctrl+Shift+S` or `Cmd+Q`. Modifiers are held while the key is pressed, so
+    /// this covers shortcuts that `type_text` can't express.
+    ///
+    /// Text that isn't a chord is passed to the platform as is, e.g. `{ctrl}v` on Windows.
     pub fn press_key(&self, key: &str) -> Result<(), AutomationError> {
-        self.inner.press_key(key)
+        check_input()?;
+        let Ok(chord) = KeyChord::parse(key) else {
+            return self.inner.press_key(key).map_err(|e| self.annotate("press_key", e));
+        };
+        self.inner.focus().map_err(|e| self.annotate("press_key", e))?;
+        match self.inner.press_chord(&chord) {
+            Err(e) if matches!(e.inner(), AutomationError::UnsupportedOperation(_)) => self.inner.press_key(key),
+            result => result,
+        }
+        .map_err(|e| self.annotate("press_key", e))
     }
 
     /// Get text content of this element
@@ -197,9 +772,154 @@ impl UIElement {
         self.inner.get_text(max_depth)
     }
 
-    /// Set value of this element
+    /// Read the element's value, e.g. the contents of an input field, straight from the
+    /// platform's value interface (UI Automation ValuePattern, AXValue).
+    /// `UnsupportedOperation` if the element has no value.
+    pub fn value(&self) -> Result<String, AutomationError> {
+        self.inner.value()
+            .map_err(|e| self.annotate("value", e))
+    }
+
+    /// Set the element's value through the platform's value interface, without typing.
+    /// `UnsupportedOperation` if the element has no settable value; type into it instead.
     pub fn set_value(&self, value: &str) -> Result<(), AutomationError> {
+        check_input()?;
         self.inner.set_value(value)
+            .map_err(|e| self.annotate("set_value", e))
+    }
+
+    /// Set the numeric value of a range control through its native pattern
+    pub(crate) fn set_range_value(&self, value: f64) -> Result<(), AutomationError> {
+        check_input()?;
+        self.inner.set_range_value(value)
+            .map_err(|e| self.annotate("set_range_value", e))
+    }
+
+    /// Read the minimum, maximum and current value of a slider, progress bar or spinner
+    /// through its native range value; `UnsupportedOperation` if it has none
+    pub fn range_value(&self) -> Result<RangeValue, AutomationError> {
+        self.inner.range_value()
+            .map_err(|e| self.annotate("range_value", e))
+    }
+
+    /// Read the element's value as a number: its native range value if it has one,
+    /// otherwise the displayed value parsed, so `1,234.5 MB` reads as `1234.5`.
+    /// Fails with `UnexpectedValue` if it shows no number.
+    pub fn value_as_f64(&self) -> Result<f64, AutomationError> {
+        crate::value::value_as_f64(self).map_err(|e| self.annotate("value_as_f64", e))
+    }
+
+    /// Parse the displayed value into a number and its unit, e.g. `75%` or `$12.50`
+    pub fn value_with_unit(&self) -> Result<NumericValue, AutomationError> {
+        crate::value::value_with_unit(self).map_err(|e| self.annotate("value_with_unit", e))
+    }
+
+    /// Read the displayed value as a date, numeric (`03/15/2024`, `15.03.2024`,
+    /// `2024-03-15`) or with the month written out in English. Fails with
+    /// `UnexpectedValue` if it isn't one.
+    pub fn value_as_date(&self) -> Result<NaiveDate, AutomationError> {
+        crate::value::value_as_date(self).map_err(|e| self.annotate("value_as_date", e))
+    }
+
+    /// Set a date picker to `date`.
+    ///
+    /// Tries the control's value first and falls back to typing the date in the
+    /// numeric layout the picker currently shows, e.g. `03/15/2024` or `15.03.2024`.
+    /// Fails with `VerificationFailed` if the picker doesn't end up showing `date`.
+    #[instrument(skip(self))]
+    pub fn set_date(&self, date: NaiveDate) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!("Setting date");
+
+        crate::controls::set_date(self, date).map_err(|e| self.annotate("set_date", e))?;
+
+        info!(duration_ms = start.elapsed().as_millis(), "Date set");
+        Ok(())
+    }
+
+    /// Set a spinner (numeric up/down control) to `value`.
+    ///
+    /// Uses the range value pattern where available, then the value of the spinner
+    /// or its edit box, then the keyboard. Values outside the control's range are
+    /// rejected with `InvalidArgument`.
+    #[instrument(skip(self))]
+    pub fn set_spinner_value(&self, value: f64) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!("Setting spinner value");
+
+        crate::controls::set_spinner_value(self, value)
+            .map_err(|e| self.annotate("set_spinner_value", e))?;
+
+        info!(duration_ms = start.elapsed().as_millis(), "Spinner value set");
+        Ok(())
+    }
+
+    /// Choose `option` in a combo box, drop-down or list box: open the control, find the
+    /// item with that name (case-insensitive) and select it, then check the control shows it.
+    ///
+    /// Uses the platform's selection pattern where the item has one and clicks the item
+    /// otherwise. Items are looked for inside the control, then next to it (where a web
+    /// combo box keeps its list). Fails with `ElementNotFound` if there's no such option
+    /// and `VerificationFailed` if the control doesn't end up showing it.
+    #[instrument(skip(self))]
+    pub fn select_option(&self, option: &str) -> Result<(), AutomationError> {
+        check_input()?;
+        let start = Instant::now();
+        info!("Selecting option");
+
+        crate::controls::select_option(self, option).map_err(|e| self.annotate("select_option", e))?;
+
+        info!(duration_ms = start.elapsed().as_millis(), "Option selected");
+        Ok(())
+    }
+
+    /// Whether this checkbox, toggle button or radio button is checked. Fails with
+    /// `UnexpectedValue` for a checkbox in the mixed state and for elements that can't
+    /// be checked at all.
+    #[instrument(skip(self))]
+    pub fn is_checked(&self) -> Result<bool, AutomationError> {
+        crate::controls::checked_state(self).ok_or_else(|| {
+            self.annotate(
+                "is_checked",
+                AutomationError::UnexpectedValue(
+                    "Element is neither checked nor unchecked: it is mixed, or not a checkbox or radio button"
+                        .to_string(),
+                ),
+            )
+        })
+    }
+
+    /// Check or uncheck this checkbox, toggle button or radio button.
+    ///
+    /// Does nothing if it is already in that state. Otherwise flips it through the
+    /// platform's toggle or selection pattern, or clicks it, and reads the state back,
+    /// failing with `VerificationFailed` if it didn't change. A radio button can only
+    /// be checked; uncheck it by checking another one in its group.
+    #[instrument(skip(self))]
+    pub fn set_checked(&self, checked: bool) -> Result<(), AutomationError> {
+        check_input()?;
+        let start = Instant::now();
+        info!("Setting checked state");
+
+        crate::controls::set_checked(self, checked).map_err(|e| self.annotate("set_checked", e))?;
+
+        info!(duration_ms = start.elapsed().as_millis(), "Checked state set");
+        Ok(())
+    }
+
+    /// Flip this checkbox or toggle button through its native pattern
+    pub(crate) fn toggle(&self) -> Result<(), AutomationError> {
+        self.inner.toggle()
+    }
+
+    /// Open or close this combo box, menu or tree item through its native pattern
+    pub(crate) fn set_expanded(&self, expanded: bool) -> Result<(), AutomationError> {
+        self.inner.set_expanded(expanded)
+    }
+
+    /// Select this list or menu item through its native pattern
+    pub(crate) fn select_item(&self) -> Result<(), AutomationError> {
+        self.inner.select_item()
     }
 
     /// Check if element is enabled
@@ -232,7 +952,9 @@ impl UIElement {
 
     /// Perform a named action on this element
     pub fn perform_action(&self, action: &str) -> Result<(), AutomationError> {
+        check_input()?;
         self.inner.perform_action(action)
+            .map_err(|e| self.annotate("perform_action", e))
     }
 
     /// Get the underlying implementation as a specific type
@@ -240,20 +962,46 @@ impl UIElement {
         self.inner.as_any()
     }
 
-    /// Find elements matching the selector within this element
+    /// Find elements matching the selector within this element's subtree only, e.g.
+    /// `dialog.locator("role:button")`. Searching a window or panel instead of the whole
+    /// desktop is much faster in large applications.
     pub fn locator(&self, selector: impl Into<Selector>) -> Result<Locator, AutomationError> {
         let selector = selector.into();
-        self.inner.create_locator(selector)
+        match &self.engine {
+            Some(engine) => Ok(Locator::new(engine.clone(), selector).within(self.clone())),
+            None => self.inner.create_locator(selector),
+        }
     }
 
     /// Scroll the element in a given direction
     pub fn scroll(&self, direction: &str, amount: f64) -> Result<(), AutomationError> {
+        check_input()?;
         self.inner.scroll(direction, amount)
+            .map_err(|e| self.annotate("scroll", e))
+    }
+
+    /// The id of the process that owns the element
+    pub(crate) fn process_id(&self) -> Result<u32, AutomationError> {
+        self.inner.process_id()
+    }
+
+    /// Scroll the element into view so it can be clicked: through the platform's
+    /// scroll-into-view support if the element has it, otherwise by turning the mouse
+    /// wheel over the nearest ancestor that cuts it off
+    #[instrument(skip(self))]
+    pub fn scroll_into_view(&self) -> Result<(), AutomationError> {
+        check_input()?;
+        match self.inner.scroll_into_view() {
+            Ok(()) => return Ok(()),
+            Err(e) => debug!(error = %e, "Platform could not scroll element into view, using the mouse wheel"),
+        }
+        crate::scroll::scroll_into_view_with_wheel(self).map_err(|e| self.annotate("scroll_into_view", e))
     }
 
     /// Activate the window containing this element (bring to foreground)
     pub fn activate_window(&self) -> Result<(), AutomationError> {
         self.inner.activate_window()
+            .map_err(|e| self.annotate("activate_window", e))
     }
 
     /// Get the element's name
@@ -279,18 +1027,40 @@ impl UIElement {
         self.inner.is_keyboard_focusable()
     }
 
+    /// Drag this element onto `target`, from center to center, e.g. to reorder list
+    /// items or move a file into a folder. `options` sets how many intermediate moves
+    /// are made and how fast.
+    #[instrument(skip(self, target))]
+    pub fn drag_to(&self, target: &UIElement, options: &DragOptions) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!(target = %target.describe(), "Dragging element");
+
+        let center = |element: &UIElement| {
+            element.bounds().map(|(x, y, width, height)| (x + width / 2.0, y + height / 2.0))
+        };
+        let from = center(self).map_err(|e| self.annotate("drag_to", e))?;
+        let to = center(target).map_err(|e| target.annotate("drag_to", e))?;
+        crate::drag::drag(self, from, to, options).map_err(|e| self.annotate("drag_to", e))?;
+
+        info!(duration_ms = start.elapsed().as_millis(), "Element dragged");
+        Ok(())
+    }
+
     /// Drag mouse from start to end coordinates
     pub fn mouse_drag(&self, start_x: f64, start_y: f64, end_x: f64, end_y: f64) -> Result<(), AutomationError> {
+        check_input()?;
         self.inner.mouse_drag(start_x, start_y, end_x, end_y)
     }
 
     /// Press and hold mouse at (x, y)
     pub fn mouse_click_and_hold(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        check_input()?;
         self.inner.mouse_click_and_hold(x, y)
     }
 
     /// Move mouse to (x, y)
     pub fn mouse_move(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        check_input()?;
         self.inner.mouse_move(x, y)
     }
 
@@ -298,6 +1068,80 @@ impl UIElement {
     pub fn mouse_release(&self) -> Result<(), AutomationError> {
         self.inner.mouse_release()
     }
+
+    /// Turn the mouse wheel at (x, y) by whole notches; positive `delta_x` scrolls right
+    /// and positive `delta_y` down
+    pub fn mouse_wheel(&self, x: f64, y: f64, delta_x: i32, delta_y: i32) -> Result<(), AutomationError> {
+        check_input()?;
+        self.inner.mouse_wheel(x, y, delta_x, delta_y)
+    }
+}
+
+/// What every input action checks first: the kill switch, and that a bound session
+/// still receives input
+fn check_input() -> Result<(), AutomationError> {
+    crate::abort::check()?;
+    crate::session::check_bound_input()
+}
+
+/// The result of a check, or `fallback` if the platform can't make it
+fn unless_unsupported<T>(result: Result<T, AutomationError>, fallback: T) -> Result<T, AutomationError> {
+    match result {
+        Err(e) if matches!(e.inner(), AutomationError::UnsupportedOperation(_)) => Ok(fallback),
+        result => result,
+    }
+}
+
+/// Replace an error that mentions the secret with a generic one
+fn scrub_secret(error: AutomationError, secret: &SecretString) -> AutomationError {
+    if !secret.is_empty() && error.to_string().contains(secret.expose_secret()) {
+        AutomationError::platform("Failed to type secret (details redacted)")
+    } else {
+        error
+    }
+}
+
+/// Depth-first walk below an element, see [`UIElement::descendants`]
+pub(crate) struct Descendants {
+    origin: UIElement,
+    /// Children still to visit at each level, with that level's depth
+    stack: Vec<(ChildIter, usize)>,
+    /// The element last returned, whose children are read only if the walk goes on
+    pending: Option<(UIElement, usize)>,
+    max_depth: usize,
+}
+
+impl Iterator for Descendants {
+    type Item = UIElement;
+
+    fn next(&mut self) -> Option<UIElement> {
+        if let Some((element, depth)) = self.pending.take()
+            && depth < self.max_depth
+        {
+            match element.inner.children_iter() {
+                Ok(children) => self.stack.push((children, depth + 1)),
+                Err(e) => debug!(error = %e, element = %element.describe(), "Skipping unreadable subtree"),
+            }
+        }
+        loop {
+            let (children, depth) = self.stack.last_mut()?;
+            let depth = *depth;
+            match children.next() {
+                Some(Ok(child)) => {
+                    let child = self.origin.related(child);
+                    self.pending = Some((child.clone(), depth));
+                    return Some(child);
+                }
+                Some(Err(e)) => {
+                    debug!(error = %e, "Skipping the rest of an unreadable subtree");
+                    self.stack.pop();
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
 }
 
 impl PartialEq for UIElement {
@@ -321,6 +1165,13 @@ impl Clone for UIElement {
         // that will behave the same way
         Self {
             inner: self.inner.clone_box(),
+            engine: self.engine.clone(),
         }
     }
 }
+
+impl Debug for UIElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UIElement").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
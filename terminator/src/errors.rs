@@ -1,28 +1,378 @@
+use std::fmt;
 use thiserror::Error;
 
+/// A platform error kept as the `source()` of an [`AutomationError`]
+pub type PlatformSource = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// The platform API call an error came from and the code it failed with, for
+/// triaging failures from logs alone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeError {
+    /// The failing call, e.g. `IUIAutomationElement::GetCurrentPattern` or
+    /// `AXUIElementSetAttributeValue`
+    pub call: String,
+    /// The code it returned: an HRESULT on Windows, an AXError on macOS
+    pub code: i32,
+}
+
+impl fmt::Display for NativeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed with {} ({:#010x})", self.call, self.code, self.code)
+    }
+}
+
+/// Describes what an operation was targeting when it failed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The operation that failed (e.g. "click", "type_text")
+    pub operation: Option<String>,
+    /// The selector that was being resolved, if any
+    pub selector: Option<String>,
+    /// A short description of the element being acted on, if any
+    pub element: Option<String>,
+    /// Diagnostics captured when the failure happened, if enabled
+    pub artifacts: Option<Box<crate::FailureArtifacts>>,
+    /// The platform call that failed, if the error came from one
+    pub native: Option<NativeError>,
+}
+
+impl ErrorContext {
+    fn merge(&mut self, other: ErrorContext) {
+        // Inner (earlier) context wins, outer layers only fill the gaps
+        if self.operation.is_none() {
+            self.operation = other.operation;
+        }
+        if self.selector.is_none() {
+            self.selector = other.selector;
+        }
+        if self.element.is_none() {
+            self.element = other.element;
+        }
+        if self.artifacts.is_none() {
+            self.artifacts = other.artifacts;
+        }
+        if self.native.is_none() {
+            self.native = other.native;
+        }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(operation) = &self.operation {
+            parts.push(format!("operation: {}", operation));
+        }
+        if let Some(selector) = &self.selector {
+            parts.push(format!("selector: {}", selector));
+        }
+        if let Some(element) = &self.element {
+            parts.push(format!("element: {}", element));
+        }
+        if let Some(native) = &self.native {
+            parts.push(format!("native: {}", native));
+        }
+        if let Some(artifacts) = &self.artifacts {
+            match &artifacts.saved_to {
+                Some(path) => parts.push(format!("artifacts: {}", path.display())),
+                None => parts.push("artifacts attached".to_string()),
+            }
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum AutomationError {
     #[error("Element not found: {0}")]
     ElementNotFound(String),
 
+    /// The element was found earlier but no longer exists in the UI tree
+    #[error("Element is stale: {0}")]
+    StaleElement(String),
+
     #[error("Operation timed out: {0}")]
     Timeout(String),
 
+    /// The target application is alive but not accepting input right now
+    #[error("Target is busy: {0}")]
+    TargetBusy(String),
+
+    /// The action ran but the UI did not reflect it afterwards
+    #[error("Verification failed: {0}")]
+    VerificationFailed(String),
+
+    /// An [`crate::expect`] assertion still didn't hold when it timed out
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
+
+    /// The element's value couldn't be read as the requested type
+    #[error("Unexpected value: {0}")]
+    UnexpectedValue(String),
+
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
 
-    #[error("Platform-specific error: {0}")]
-    PlatformError(String),
+    #[error("Platform-specific error: {message}")]
+    PlatformError {
+        message: String,
+        #[source]
+        source: Option<PlatformSource>,
+    },
 
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
 
-    #[error("Unsupported platform: {0}")]
-    UnsupportedPlatform(String),
+    #[error("Platform not supported: {0}")]
+    PlatformNotSupported(String),
 
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// The kill switch was triggered, see [`crate::Desktop::abort_all`]
+    #[error("Aborted: {0}")]
+    Aborted(String),
+
+    /// Another error annotated with the selector/element it happened on
+    #[error("{source} ({context})")]
+    WithContext {
+        context: ErrorContext,
+        #[source]
+        source: Box<AutomationError>,
+    },
+}
+
+impl AutomationError {
+    /// Create a platform error from a message
+    pub fn platform(message: impl Into<String>) -> Self {
+        AutomationError::PlatformError {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Create a platform error that keeps the underlying error as its source
+    pub fn platform_with_source(
+        message: impl Into<String>,
+        source: impl Into<PlatformSource>,
+    ) -> Self {
+        AutomationError::PlatformError {
+            message: message.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Whether retrying the same operation may succeed.
+    ///
+    /// Not-found, stale, timeout and busy errors describe a UI that is still
+    /// settling; everything else will fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AutomationError::ElementNotFound(_)
+            | AutomationError::StaleElement(_)
+            | AutomationError::Timeout(_)
+            | AutomationError::TargetBusy(_) => true,
+            AutomationError::WithContext { source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// The error with any context layers stripped, useful for matching on the category
+    pub fn inner(&self) -> &AutomationError {
+        match self {
+            AutomationError::WithContext { source, .. } => source.inner(),
+            other => other,
+        }
+    }
+
+    /// The context attached to this error, if any
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            AutomationError::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Attach context to this error, merging with any context already present
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            AutomationError::WithContext {
+                context: mut existing,
+                source,
+            } => {
+                existing.merge(context);
+                AutomationError::WithContext {
+                    context: existing,
+                    source,
+                }
+            }
+            other => AutomationError::WithContext {
+                context,
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Attach the operation name to this error
+    pub fn with_operation(self, operation: impl Into<String>) -> Self {
+        self.with_context(ErrorContext {
+            operation: Some(operation.into()),
+            ..Default::default()
+        })
+    }
+
+    /// Attach the selector being resolved to this error
+    pub fn with_selector(self, selector: &crate::Selector) -> Self {
+        self.with_context(ErrorContext {
+            selector: Some(format!("{:?}", selector)),
+            ..Default::default()
+        })
+    }
+
+    /// Attach failure diagnostics to this error
+    pub fn with_artifacts(self, artifacts: crate::FailureArtifacts) -> Self {
+        self.with_context(ErrorContext {
+            artifacts: Some(Box::new(artifacts)),
+            ..Default::default()
+        })
+    }
+
+    /// Failure diagnostics attached to this error, if any
+    pub fn artifacts(&self) -> Option<&crate::FailureArtifacts> {
+        self.context().and_then(|c| c.artifacts.as_deref())
+    }
+
+    /// Attach the platform call this error came from and the code it returned
+    pub fn with_native(self, call: impl Into<String>, code: i32) -> Self {
+        self.with_context(ErrorContext {
+            native: Some(NativeError {
+                call: call.into(),
+                code,
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// The platform call this error came from, if known
+    pub fn native_error(&self) -> Option<&NativeError> {
+        self.context().and_then(|c| c.native.as_ref())
+    }
+
+    /// Attach a description of the element being acted on to this error
+    pub fn with_element(self, element: impl Into<String>) -> Self {
+        self.with_context(ErrorContext {
+            element: Some(element.into()),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifacts(tree_dump: &str) -> crate::FailureArtifacts {
+        crate::FailureArtifacts {
+            tree_dump: Some(tree_dump.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// A context layer wrapped directly around another, as a platform layer would
+    /// build it without going through `with_context`
+    fn nested(error: AutomationError, element: &str) -> AutomationError {
+        AutomationError::WithContext {
+            context: ErrorContext {
+                element: Some(element.to_string()),
+                ..Default::default()
+            },
+            source: Box::new(error),
+        }
+    }
+
+    #[test]
+    fn retryable_errors_describe_a_settling_ui() {
+        for error in [
+            AutomationError::ElementNotFound("button".to_string()),
+            AutomationError::StaleElement("row".to_string()),
+            AutomationError::Timeout("5s".to_string()),
+            AutomationError::TargetBusy("hung".to_string()),
+        ] {
+            assert!(error.is_retryable(), "{}", error);
+        }
+        for error in [
+            AutomationError::VerificationFailed("text".to_string()),
+            AutomationError::PermissionDenied("elevated".to_string()),
+            AutomationError::platform("COM failure"),
+            AutomationError::UnsupportedOperation("scroll".to_string()),
+            AutomationError::InvalidArgument("key".to_string()),
+            AutomationError::Aborted("kill switch".to_string()),
+        ] {
+            assert!(!error.is_retryable(), "{}", error);
+        }
+    }
+
+    #[test]
+    fn context_layers_keep_the_category() {
+        let error = AutomationError::Timeout("5s".to_string())
+            .with_operation("click")
+            .with_element("Button 'Save'");
+        assert!(error.is_retryable());
+        assert!(matches!(error.inner(), AutomationError::Timeout(_)));
+
+        let error = nested(nested(AutomationError::platform("COM failure"), "Row 3"), "Grid");
+        assert!(!error.is_retryable());
+        assert!(matches!(error.inner(), AutomationError::PlatformError { .. }));
+        assert!(nested(nested(AutomationError::TargetBusy("hung".to_string()), "a"), "b").is_retryable());
+
+        let plain = AutomationError::InvalidArgument("key".to_string());
+        assert!(std::ptr::eq(plain.inner(), &plain));
+        assert!(plain.context().is_none());
+    }
+
+    #[test]
+    fn with_context_merges_into_one_layer() {
+        let error = AutomationError::ElementNotFound("Save".to_string())
+            .with_operation("click")
+            .with_selector(&crate::Selector::Name("Save".to_string()))
+            .with_element("Window 'Invoice'");
+        assert!(matches!(&error, AutomationError::WithContext { source, .. }
+            if matches!(**source, AutomationError::ElementNotFound(_))));
+
+        let context = error.context().unwrap();
+        assert_eq!(context.operation.as_deref(), Some("click"));
+        assert!(context.selector.as_deref().unwrap().contains("Save"));
+        assert_eq!(context.element.as_deref(), Some("Window 'Invoice'"));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Element not found: Save (operation: click, selector: {}, element: Window 'Invoice')",
+                context.selector.as_deref().unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn first_writer_wins_when_merging() {
+        let error = AutomationError::platform("COM failure")
+            .with_native("IUIAutomationElement::GetCurrentPattern", -2147220991)
+            .with_artifacts(artifacts("inner tree"))
+            .with_operation("click")
+            .with_native("IUIAutomation::ElementFromPoint", 5)
+            .with_artifacts(artifacts("outer tree"))
+            .with_operation("press_key");
+
+        let native = error.native_error().unwrap();
+        assert_eq!(native.call, "IUIAutomationElement::GetCurrentPattern");
+        assert_eq!(native.code, -2147220991);
+        assert_eq!(error.artifacts().unwrap().tree_dump.as_deref(), Some("inner tree"));
+        assert_eq!(error.context().unwrap().operation.as_deref(), Some("click"));
+        // Fields the first layer left empty are still filled in
+        let error = error.with_element("Button 'OK'");
+        assert_eq!(error.context().unwrap().element.as_deref(), Some("Button 'OK'"));
+        assert!(error.to_string().contains("native: IUIAutomationElement::GetCurrentPattern failed with -2147220991 (0x80040201)"));
+    }
 }
@@ -0,0 +1,204 @@
+//! Assertions that retry until the UI catches up
+//!
+//! `expect(&locator).to_have_text("Done").await?` keeps re-checking the
+//! element until the expectation holds or the timeout runs out, like
+//! Playwright's web-first assertions. A failure reports what was last seen
+//! and the tree around the element, so a red test explains itself. A
+//! [soft](Expectation::soft) expectation records its failure and lets the run
+//! continue instead.
+
+use crate::{AutomationError, Locator, SoftAssertions, UIElement};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+// How long each check searches for the element before counting it as missing
+const EXPECT_SEARCH_TIMEOUT: Duration = Duration::from_millis(100);
+// How deep to read text for text expectations
+const EXPECT_TEXT_DEPTH: usize = 5;
+// How many levels of the tree around the element a failure message shows
+const EXPECT_TREE_DEPTH: usize = 2;
+
+/// Start an assertion on the element `locator` matches, e.g.
+/// `expect(&desktop.locator("name:Status")).to_have_text("Done").await?`
+pub fn expect(locator: &Locator) -> Expectation {
+    Expectation {
+        locator: locator.clone(),
+        timeout: None,
+        soft: None,
+    }
+}
+
+/// Assertions on a locator's element, see [`expect`]. Each one re-checks until it holds,
+/// up to the timeout (the locator's default timeout unless set), and fails with
+/// [`AutomationError::AssertionFailed`].
+#[derive(Clone)]
+pub struct Expectation {
+    locator: Locator,
+    timeout: Option<Duration>,
+    soft: Option<SoftAssertions>,
+}
+
+/// What the last check saw when an expectation didn't hold
+enum Observation {
+    Missing,
+    Mismatch { element: UIElement, actual: String },
+}
+
+impl Expectation {
+    /// Give up after `timeout` instead of the locator's default timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Record a failure in `soft` and return `Ok` instead of failing, so the run
+    /// continues and reports everything at [`SoftAssertions::finish`]
+    pub fn soft(mut self, soft: &SoftAssertions) -> Self {
+        self.soft = Some(soft.clone());
+        self
+    }
+
+    /// The element's text equals `expected`, ignoring surrounding whitespace
+    pub async fn to_have_text(&self, expected: &str) -> Result<(), AutomationError> {
+        let expected = expected.trim();
+        self.poll(&format!("to have text '{}'", expected), false, |element| {
+            let actual = element.text(EXPECT_TEXT_DEPTH)?;
+            Ok(mismatch(actual.trim() == expected, || format!("has text '{}'", actual.trim())))
+        })
+        .await
+    }
+
+    /// The element's text contains `expected`
+    pub async fn to_contain_text(&self, expected: &str) -> Result<(), AutomationError> {
+        self.poll(&format!("to contain text '{}'", expected), false, |element| {
+            let actual = element.text(EXPECT_TEXT_DEPTH)?;
+            Ok(mismatch(actual.contains(expected), || format!("has text '{}'", actual.trim())))
+        })
+        .await
+    }
+
+    pub async fn to_be_enabled(&self) -> Result<(), AutomationError> {
+        self.poll("to be enabled", false, |element| {
+            Ok(mismatch(element.is_enabled()?, || "is disabled".to_string()))
+        })
+        .await
+    }
+
+    pub async fn to_be_disabled(&self) -> Result<(), AutomationError> {
+        self.poll("to be disabled", false, |element| {
+            Ok(mismatch(!element.is_enabled()?, || "is enabled".to_string()))
+        })
+        .await
+    }
+
+    pub async fn to_be_visible(&self) -> Result<(), AutomationError> {
+        self.poll("to be visible", false, |element| {
+            Ok(mismatch(element.is_visible()?, || "is not visible".to_string()))
+        })
+        .await
+    }
+
+    /// No element matches, or the one that does is not visible
+    pub async fn to_be_hidden(&self) -> Result<(), AutomationError> {
+        self.poll("to be hidden", true, |element| {
+            Ok(mismatch(!element.is_visible()?, || "is visible".to_string()))
+        })
+        .await
+    }
+
+    /// Re-check `check` on the matching element until it passes, recording the failure
+    /// instead of returning it for soft expectations
+    async fn poll(
+        &self,
+        expectation: &str,
+        absent_passes: bool,
+        check: impl Fn(&UIElement) -> Result<Option<String>, AutomationError>,
+    ) -> Result<(), AutomationError> {
+        let result = self.poll_until(expectation, absent_passes, check).await;
+        match &self.soft {
+            Some(soft) => soft
+                .check(&format!("{:?} {}", self.locator.selector(), expectation), result)
+                .map(drop),
+            None => result,
+        }
+    }
+
+    /// Re-check `check` on the matching element until it passes. `check` returns what it
+    /// saw instead when it doesn't; with `absent_passes`, no match counts as passing.
+    async fn poll_until(
+        &self,
+        expectation: &str,
+        absent_passes: bool,
+        check: impl Fn(&UIElement) -> Result<Option<String>, AutomationError>,
+    ) -> Result<(), AutomationError> {
+        let timeout = self.timeout.unwrap_or(self.locator.default_timeout());
+        let start = Instant::now();
+        let polling = self.locator.polling();
+        let mut delay = polling.interval;
+        let mut checks = 0;
+
+        loop {
+            checks += 1;
+            let observation = match self.locator.find_once(EXPECT_SEARCH_TIMEOUT) {
+                Ok(element) => match check(&element) {
+                    Ok(None) => {
+                        info!(expectation, checks, duration_ms = start.elapsed().as_millis(), "Expectation met");
+                        return Ok(());
+                    }
+                    Ok(Some(actual)) => Observation::Mismatch { element, actual },
+                    Err(e) if e.is_retryable() => Observation::Missing,
+                    Err(e) => return Err(e.with_selector(self.locator.selector())),
+                },
+                Err(e) if e.is_retryable() => {
+                    if absent_passes {
+                        return Ok(());
+                    }
+                    Observation::Missing
+                }
+                Err(e) => return Err(e.with_selector(self.locator.selector())),
+            };
+
+            if start.elapsed() >= timeout {
+                let target = match &observation {
+                    Observation::Missing => self.locator.scope().cloned(),
+                    Observation::Mismatch { element, .. } => Some(element.clone()),
+                };
+                let error = self.failure(expectation, observation, timeout, checks);
+                return Err(self.locator.on_failure(error, target.as_ref()).await);
+            }
+            polling.pause(&mut delay).await?;
+        }
+    }
+
+    fn failure(&self, expectation: &str, observation: Observation, timeout: Duration, checks: usize) -> AutomationError {
+        let (seen, context) = match &observation {
+            Observation::Missing => (
+                "no element matched".to_string(),
+                self.locator.scope().map(|scope| ("the search scope", scope.clone())),
+            ),
+            Observation::Mismatch { element, actual } => (
+                format!("{} {}", element.describe(), actual),
+                Some(("the element's parent", element.parent().ok().flatten().unwrap_or_else(|| element.clone()))),
+            ),
+        };
+
+        let mut message = format!(
+            "expected {:?} {}, but {}\n  checked {} times over {:?}",
+            self.locator.selector(),
+            expectation,
+            seen,
+            checks,
+            timeout
+        );
+        if let Some((label, element)) = context {
+            message.push_str(&format!("\n  tree under {}:\n", label));
+            crate::artifacts::dump_tree(&element, 2, 2 + EXPECT_TREE_DEPTH, &mut message);
+        }
+        AutomationError::AssertionFailed(message.trim_end().to_string()).with_selector(self.locator.selector())
+    }
+}
+
+/// `None` if a check passed, otherwise what was seen instead
+fn mismatch(passed: bool, actual: impl FnOnce() -> String) -> Option<String> {
+    if passed { None } else { Some(actual()) }
+}
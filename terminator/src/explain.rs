@@ -0,0 +1,378 @@
+//! Why a selector does or doesn't match
+//!
+//! A locator that finds nothing only says so once its timeout runs out.
+//! [`crate::Locator::explain`] instead walks the tree once and reports, for
+//! each stage of the selector, how many elements it looked at, which
+//! condition ruled out how many of them, and the elements that came closest
+//! along with their attributes.
+
+use crate::platforms::AccessibilityEngine;
+use crate::selector::attribute_value;
+use crate::utils::normalize;
+use crate::{AutomationError, ElementPredicate, Selector, StateFilter, UIElement, UIElementAttributes};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+// How far below the scope elements are tested, as deep as the engines search
+const MAX_EXPLAIN_DEPTH: usize = 50;
+// Most elements tested per stage, so explaining a search of a huge tree still ends
+const MAX_VISITED: usize = 20_000;
+const MAX_NEAR_MISSES: usize = 5;
+// How long stages that can't be tested element by element get to search
+const OPAQUE_SEARCH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How a locator's selector fares against the current UI, see [`crate::Locator::explain`]
+///
+/// Displays as a readable multi-line report.
+#[derive(Debug)]
+pub struct MatchExplanation {
+    /// One entry per stage of a chain (a single one otherwise), up to the first stage
+    /// that matched nothing
+    pub stages: Vec<StageExplanation>,
+    /// Later stages of the chain that weren't searched because an earlier one matched nothing
+    pub unreached_stages: usize,
+}
+
+impl MatchExplanation {
+    /// Whether every stage matched, i.e. the locator would find an element right now
+    pub fn matched(&self) -> bool {
+        self.unreached_stages == 0 && self.stages.last().is_some_and(|stage| !stage.matches.is_empty())
+    }
+}
+
+/// How one stage of a selector fares, searched below the previous stage's first match
+#[derive(Debug)]
+pub struct StageExplanation {
+    /// The stage's selector, written as in error messages
+    pub selector: String,
+    /// Elements the stage was tested against; 0 for stages searched as a whole
+    pub visited: usize,
+    /// Whether testing stopped at the element limit before covering the whole scope
+    pub truncated: bool,
+    /// Elements matching the stage, in tree order
+    pub matches: Vec<UIElement>,
+    /// How many elements each condition ruled out, in the order they are tested; an
+    /// element counts against the first condition it fails. Empty for stages searched
+    /// as a whole: text, paths, labels, relations, images and OCR.
+    pub eliminations: Vec<Elimination>,
+    /// The non-matching elements that came closest, fewest failed conditions first
+    pub near_misses: Vec<NearMiss>,
+}
+
+/// Elements a condition of a stage ruled out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elimination {
+    /// The condition, e.g. `name contains 'Save'`
+    pub condition: String,
+    pub eliminated: usize,
+}
+
+/// An element that almost matched a stage
+#[derive(Debug)]
+pub struct NearMiss {
+    pub element: UIElement,
+    pub attributes: UIElementAttributes,
+    /// The conditions it failed with the value it has, e.g. `name contains 'Save' (is 'Sav')`
+    pub failed: Vec<String>,
+}
+
+/// One test an element has to pass to match a stage
+enum Condition {
+    /// A selector decided by the element's own attributes
+    Matches(Selector),
+    State(StateFilter),
+    Predicate(ElementPredicate),
+}
+
+impl Condition {
+    fn holds(&self, element: &UIElement) -> bool {
+        match self {
+            Condition::Matches(selector) => selector.matches_element(element).unwrap_or(false),
+            Condition::State(filter) => filter.matches(element),
+            Condition::Predicate(predicate) => predicate.matches(element),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Condition::Matches(Selector::Role { role, .. }) => format!("role is {}", role),
+            Condition::Matches(Selector::Name(name)) => format!("name contains '{}'", name),
+            Condition::Matches(Selector::Id(id)) => format!("id is '{}'", id),
+            Condition::Matches(Selector::ClassName(class_name)) => format!("class name is '{}'", class_name),
+            Condition::Matches(Selector::Attribute { key, value }) => format!("{} is '{}'", key, value),
+            Condition::Matches(Selector::Regex { key, pattern }) => format!("{} matches /{}/", key, pattern.as_str()),
+            Condition::Matches(selector) => format!("matches {:?}", selector),
+            Condition::State(filter) => {
+                let (state, wanted) = match *filter {
+                    StateFilter::Visible(wanted) => ("visible", wanted),
+                    StateFilter::Enabled(wanted) => ("enabled", wanted),
+                    StateFilter::Focusable(wanted) => ("focusable", wanted),
+                    StateFilter::Offscreen(wanted) => ("offscreen", wanted),
+                };
+                format!("is {}{}", if wanted { "" } else { "not " }, state)
+            }
+            Condition::Predicate(_) => "passes the filter".to_string(),
+        }
+    }
+
+    /// The attribute the condition reads and, for exact or substring tests, the text
+    /// it looks for
+    fn compared(&self) -> Option<(&str, Option<&str>)> {
+        match self {
+            Condition::Matches(Selector::Role { role, .. }) => Some(("role", Some(role))),
+            Condition::Matches(Selector::Name(name)) => Some(("name", Some(name))),
+            Condition::Matches(Selector::Id(id)) => Some(("id", Some(id))),
+            Condition::Matches(Selector::ClassName(class_name)) => Some(("ClassName", Some(class_name))),
+            Condition::Matches(Selector::Attribute { key, value }) => Some((key, Some(value))),
+            Condition::Matches(Selector::Regex { key, .. }) => Some((key, None)),
+            _ => None,
+        }
+    }
+}
+
+/// The conditions of a stage that can be tested element by element, and the match it
+/// picks by index; `None` for stages the engine has to search as a whole
+fn conditions(selector: &Selector) -> Option<(Vec<Condition>, Option<isize>)> {
+    match selector {
+        Selector::Role { role, name } => {
+            let mut conditions = vec![Condition::Matches(Selector::Role {
+                role: role.clone(),
+                name: None,
+            })];
+            if let Some(name) = name {
+                conditions.push(Condition::Matches(Selector::Name(name.clone())));
+            }
+            Some((conditions, None))
+        }
+        Selector::Name(_)
+        | Selector::Id(_)
+        | Selector::ClassName(_)
+        | Selector::Attribute { .. }
+        | Selector::Regex { .. } => Some((vec![Condition::Matches(selector.clone())], None)),
+        Selector::Attributes(attributes) => Some((
+            attributes
+                .iter()
+                .map(|(key, value)| {
+                    Condition::Matches(Selector::Attribute {
+                        key: key.clone(),
+                        value: value.clone(),
+                    })
+                })
+                .collect(),
+            None,
+        )),
+        Selector::State { base, filters } => {
+            let (mut conditions, None) = conditions(base)? else {
+                return None;
+            };
+            conditions.extend(filters.iter().copied().map(Condition::State));
+            Some((conditions, None))
+        }
+        Selector::Filter { base, predicate } => {
+            let (mut conditions, None) = conditions(base)? else {
+                return None;
+            };
+            conditions.push(Condition::Predicate(predicate.clone()));
+            Some((conditions, None))
+        }
+        Selector::Nth { base, index } => {
+            let (conditions, None) = conditions(base)? else {
+                return None;
+            };
+            Some((conditions, Some(*index)))
+        }
+        _ => None,
+    }
+}
+
+/// Explain each of `stages` in turn, each below the previous stage's first match
+pub(crate) fn explain(
+    engine: &Arc<dyn AccessibilityEngine>,
+    stages: &[Selector],
+    root: Option<&UIElement>,
+) -> Result<MatchExplanation, AutomationError> {
+    let mut explanation = MatchExplanation {
+        stages: Vec::new(),
+        unreached_stages: 0,
+    };
+    let mut scope = root.cloned();
+    for (i, stage) in stages.iter().enumerate() {
+        let explained = match conditions(stage) {
+            Some((conditions, index)) => {
+                let scope = scope
+                    .clone()
+                    .unwrap_or_else(|| engine.get_root_element().with_engine(engine.clone()));
+                explain_stage(stage, &conditions, index, &scope)?
+            }
+            None => search_stage(engine, stage, scope.as_ref())?,
+        };
+        scope = explained.matches.first().cloned();
+        explanation.stages.push(explained);
+        if scope.is_none() {
+            explanation.unreached_stages = stages.len() - i - 1;
+            break;
+        }
+    }
+    Ok(explanation)
+}
+
+/// Test every element below `scope` against the stage's conditions
+fn explain_stage(
+    stage: &Selector,
+    conditions: &[Condition],
+    index: Option<isize>,
+    scope: &UIElement,
+) -> Result<StageExplanation, AutomationError> {
+    let mut visited = 0;
+    let mut truncated = false;
+    let mut matches = Vec::new();
+    let mut eliminated = vec![0; conditions.len()];
+    // (failed conditions not even close, failed conditions, element, failures)
+    let mut near_misses: Vec<(usize, usize, UIElement, Vec<String>)> = Vec::new();
+
+    for element in scope.descendants(MAX_EXPLAIN_DEPTH) {
+        if visited == MAX_VISITED {
+            truncated = true;
+            break;
+        }
+        visited += 1;
+        crate::abort::check()?;
+
+        let failed: Vec<(usize, &Condition)> = conditions
+            .iter()
+            .enumerate()
+            .filter(|(_, condition)| !condition.holds(&element))
+            .collect();
+        let Some(&(first, _)) = failed.first() else {
+            matches.push(element);
+            continue;
+        };
+        eliminated[first] += 1;
+
+        let mut attributes = None;
+        let mut far = 0;
+        let mut failures = Vec::with_capacity(failed.len());
+        for (_, condition) in &failed {
+            let Some((key, expected)) = condition.compared() else {
+                far += 1;
+                failures.push(condition.describe());
+                continue;
+            };
+            let actual = attribute_value(&element, &mut attributes, key);
+            if !expected.zip(actual.as_deref()).is_some_and(|(expected, actual)| is_close(expected, actual)) {
+                far += 1;
+            }
+            failures.push(match actual {
+                Some(actual) => format!("{} (is '{}')", condition.describe(), actual),
+                None => format!("{} (unset)", condition.describe()),
+            });
+        }
+        // Elements failing every condition outright aren't near anything
+        if far < conditions.len() {
+            near_misses.push((far, failed.len(), element, failures));
+            if near_misses.len() > MAX_NEAR_MISSES {
+                near_misses.sort_by_key(|&(far, failed, ..)| (far, failed));
+                near_misses.truncate(MAX_NEAR_MISSES);
+            }
+        }
+    }
+    near_misses.sort_by_key(|&(far, failed, ..)| (far, failed));
+
+    let mut eliminations: Vec<Elimination> = conditions
+        .iter()
+        .zip(eliminated)
+        .map(|(condition, eliminated)| Elimination {
+            condition: condition.describe(),
+            eliminated,
+        })
+        .collect();
+    if let Some(index) = index {
+        let found = matches.len();
+        let picked = if index >= 0 {
+            Some(index as usize)
+        } else {
+            found.checked_sub(index.unsigned_abs())
+        };
+        matches = picked.and_then(|picked| matches.get(picked).cloned()).into_iter().collect();
+        eliminations.push(Elimination {
+            condition: format!("is match {} of the {} found", index, found),
+            eliminated: found - matches.len(),
+        });
+    }
+
+    Ok(StageExplanation {
+        selector: format!("{:?}", stage),
+        visited,
+        truncated,
+        matches,
+        eliminations,
+        near_misses: near_misses
+            .into_iter()
+            .map(|(_, _, element, failed)| NearMiss {
+                attributes: element.attributes(),
+                element,
+                failed,
+            })
+            .collect(),
+    })
+}
+
+/// Run a stage that needs the engine's own search, e.g. text or a path
+fn search_stage(
+    engine: &Arc<dyn AccessibilityEngine>,
+    stage: &Selector,
+    scope: Option<&UIElement>,
+) -> Result<StageExplanation, AutomationError> {
+    let matches = match engine.find_elements(stage, scope, Some(OPAQUE_SEARCH_TIMEOUT), None) {
+        Ok(matches) => matches.into_iter().map(|element| element.with_engine(engine.clone())).collect(),
+        Err(e) if matches!(e.inner(), AutomationError::ElementNotFound(_) | AutomationError::Timeout(_)) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    Ok(StageExplanation {
+        selector: format!("{:?}", stage),
+        visited: 0,
+        truncated: false,
+        matches,
+        eliminations: Vec::new(),
+        near_misses: Vec::new(),
+    })
+}
+
+/// Whether an attribute only narrowly misses the text a condition looks for: one
+/// contains the other once case and invisible characters are ignored
+fn is_close(expected: &str, actual: &str) -> bool {
+    let (expected, actual) = (normalize(expected.trim()), normalize(actual.trim()));
+    !expected.is_empty() && !actual.is_empty() && (actual.contains(&expected) || expected.contains(&actual))
+}
+
+impl fmt::Display for MatchExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, stage) in self.stages.iter().enumerate() {
+            writeln!(f, "Stage {}: {}", i + 1, stage.selector)?;
+            if stage.visited > 0 {
+                let limit = if stage.truncated { " (stopped at the limit)" } else { "" };
+                writeln!(f, "  tested {} elements{}", stage.visited, limit)?;
+            }
+            writeln!(f, "  {} matched", stage.matches.len())?;
+            for elimination in &stage.eliminations {
+                writeln!(f, "  {} ruled out by: {}", elimination.eliminated, elimination.condition)?;
+            }
+            if !stage.near_misses.is_empty() {
+                writeln!(f, "  closest:")?;
+                for miss in &stage.near_misses {
+                    writeln!(f, "    {}: {}", miss.element.describe(), miss.failed.join(", "))?;
+                }
+            }
+        }
+        if self.unreached_stages > 0 {
+            writeln!(
+                f,
+                "{} later stage(s) not searched because stage {} matched nothing",
+                self.unreached_stages,
+                self.stages.len()
+            )?;
+        }
+        Ok(())
+    }
+}
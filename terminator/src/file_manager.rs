@@ -0,0 +1,180 @@
+//! Helpers for driving the system file manager (File Explorer / Finder)
+//!
+//! Navigating to a folder, selecting files and invoking context-menu verbs is
+//! a flow almost every workflow needs and is easy to get subtly wrong, so it
+//! lives here once with the platform-specific element searches.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, Locator, Selector, UIElement};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, instrument};
+
+// How long to wait for the file manager to react to input before looking again
+const SETTLE_TIME: Duration = Duration::from_millis(400);
+// How long to wait for items and menus to show up
+const FIND_TIMEOUT: Duration = Duration::from_secs(5);
+// How deep to search from the desktop root for context menu items
+const MENU_SEARCH_DEPTH: usize = 6;
+
+#[cfg(target_os = "windows")]
+mod keys {
+    /// Moves focus to the address bar
+    pub const GO_TO_FOLDER: &str = "{alt}d";
+    pub const CONFIRM: &str = "{enter}";
+    /// Role of a file or folder entry in the items view
+    pub const ITEM_ROLE: &str = "listitem";
+    pub const MENU_ITEM_ROLE: &str = "menuitem";
+    /// Attribute property holding whether an entry is selected
+    pub const SELECTED_PROPERTY: &str = "IsSelected";
+    /// Action that adds an entry to the current selection
+    pub const ADD_TO_SELECTION: Option<&str> = Some("add_to_selection");
+}
+
+#[cfg(not(target_os = "windows"))]
+mod keys {
+    /// Opens Finder's "Go to Folder" sheet
+    pub const GO_TO_FOLDER: &str = "cmd+shift+g";
+    pub const CONFIRM: &str = "return";
+    /// Role of a file or folder entry in list view
+    pub const ITEM_ROLE: &str = "AXRow";
+    pub const MENU_ITEM_ROLE: &str = "AXMenuItem";
+    /// Attribute property holding whether an entry is selected
+    pub const SELECTED_PROPERTY: &str = "AXSelected";
+    /// Finder has no accessibility action for extending the selection
+    pub const ADD_TO_SELECTION: Option<&str> = None;
+}
+
+/// A File Explorer (Windows) or Finder (macOS) window, see [`crate::Desktop::open_file_manager`]
+pub struct FileManager {
+    engine: Arc<dyn AccessibilityEngine>,
+    window: UIElement,
+}
+
+impl FileManager {
+    pub(crate) fn new(engine: Arc<dyn AccessibilityEngine>, window: UIElement) -> Self {
+        Self { engine, window }
+    }
+
+    /// The file manager window
+    pub fn window(&self) -> &UIElement {
+        &self.window
+    }
+
+    /// Show the folder at `path`
+    #[instrument(skip(self))]
+    pub async fn navigate(&self, path: &str) -> Result<(), AutomationError> {
+        info!(path, "Navigating file manager");
+        self.window.activate_window()?;
+        self.window.press_key(keys::GO_TO_FOLDER)?;
+        tokio::time::sleep(SETTLE_TIME).await;
+
+        let input = self.engine.get_focused_element()?;
+        input.type_text(path, false)?;
+        input.press_key(keys::CONFIRM)?;
+        tokio::time::sleep(SETTLE_TIME).await;
+        Ok(())
+    }
+
+    /// The entries (files and folders) shown in the current folder
+    pub async fn items(&self) -> Result<Vec<UIElement>, AutomationError> {
+        self.item_locator().all(Some(FIND_TIMEOUT), None).await
+    }
+
+    /// Find the entry with the given file name in the current folder
+    pub async fn item(&self, name: &str) -> Result<UIElement, AutomationError> {
+        self.items()
+            .await?
+            .into_iter()
+            .find(|item| item.name().as_deref() == Some(name))
+            .ok_or_else(|| {
+                AutomationError::ElementNotFound(format!("No item named '{}' in the file manager", name))
+            })
+    }
+
+    /// Select the given entries, replacing the current selection
+    #[instrument(skip(self))]
+    pub async fn select(&self, names: &[&str]) -> Result<(), AutomationError> {
+        info!(count = names.len(), "Selecting items");
+        for (i, name) in names.iter().enumerate() {
+            let item = self.item(name).await?;
+            if i == 0 {
+                item.click()?;
+                continue;
+            }
+            match keys::ADD_TO_SELECTION {
+                Some(action) => item.perform_action(action)?,
+                None => {
+                    return Err(AutomationError::UnsupportedOperation(
+                        "Selecting multiple items is not supported on this platform".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Names of the currently selected entries
+    pub async fn selected_items(&self) -> Result<Vec<String>, AutomationError> {
+        let selected = self
+            .items()
+            .await?
+            .into_iter()
+            .filter(|item| {
+                item.attributes()
+                    .properties
+                    .get(keys::SELECTED_PROPERTY)
+                    .cloned()
+                    .flatten()
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+            })
+            .filter_map(|item| item.name())
+            .collect();
+        Ok(selected)
+    }
+
+    /// Right-click the entry `name` and choose `verb` (e.g. "Open", "Rename") from its context menu
+    #[instrument(skip(self))]
+    pub async fn invoke_verb(&self, name: &str, verb: &str) -> Result<(), AutomationError> {
+        info!(name, verb, "Invoking context menu verb");
+        self.item(name).await?.right_click()?;
+        tokio::time::sleep(SETTLE_TIME).await;
+
+        let menu_items = Locator::new(
+            self.engine.clone(),
+            Selector::Role {
+                role: keys::MENU_ITEM_ROLE.to_string(),
+                name: None,
+            },
+        )
+        .all(Some(FIND_TIMEOUT), Some(MENU_SEARCH_DEPTH))
+        .await?;
+        debug!(count = menu_items.len(), "Context menu items found");
+
+        // Menu entries may carry an accelerator marker, e.g. "&Open"
+        let entry = menu_items
+            .into_iter()
+            .find(|item| {
+                item.name()
+                    .map(|n| n.replace('&', "").trim().eq_ignore_ascii_case(verb))
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| {
+                AutomationError::ElementNotFound(format!("No context menu entry '{}' for '{}'", verb, name))
+            })?;
+        entry.click()?;
+        Ok(())
+    }
+
+    fn item_locator(&self) -> Locator {
+        Locator::new(
+            self.engine.clone(),
+            Selector::Role {
+                role: keys::ITEM_ROLE.to_string(),
+                name: None,
+            },
+        )
+        .within(self.window.clone())
+    }
+}
@@ -0,0 +1,205 @@
+//! Perceptual fingerprints of what an element looks like
+//!
+//! An app update can restyle a button, swap its icon or change its color
+//! while its name, role and position in the accessibility tree stay the same.
+//! A [`VisualFingerprint`] is a small summary of an element's pixels that can
+//! be stored between runs and compared, so a workflow notices when a control
+//! looks different from last time. Small rendering noise (antialiasing, a
+//! blinking caret, a slightly different size) barely moves it.
+
+use crate::AutomationError;
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Hash bits that may differ, out of 64, for two fingerprints to look alike
+const MAX_LOOK_ALIKE_DISTANCE: u32 = 8;
+// Largest difference of any mean color channel for two fingerprints to look alike
+const MAX_LOOK_ALIKE_COLOR_DISTANCE: u32 = 24;
+
+/// A perceptual hash of an element's pixels and their mean color,
+/// see [`crate::UIElement::visual_fingerprint`]
+///
+/// Displays as `3c7e7e3c1818183c-336699`, which [`str::parse`] reads back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VisualFingerprint {
+    /// Difference hash: each bit says whether a cell of a 9x8 grayscale thumbnail is
+    /// brighter than its right neighbour. Captures shapes and layout, not color.
+    pub hash: u64,
+    /// Mean red, green and blue of all pixels
+    pub mean_color: [u8; 3],
+}
+
+impl VisualFingerprint {
+    /// Fingerprint an image; `None` if it is empty
+    pub fn of_image(image: &RgbaImage) -> Option<Self> {
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let gray = imageops::grayscale(image);
+        let thumbnail = imageops::resize(&gray, 9, 8, FilterType::Triangle);
+        let mut hash = 0u64;
+        for y in 0..8 {
+            for x in 0..8 {
+                hash <<= 1;
+                if thumbnail.get_pixel(x, y).0[0] > thumbnail.get_pixel(x + 1, y).0[0] {
+                    hash |= 1;
+                }
+            }
+        }
+
+        let mut sums = [0u64; 3];
+        for pixel in image.pixels() {
+            for (sum, channel) in sums.iter_mut().zip(pixel.0) {
+                *sum += channel as u64;
+            }
+        }
+        let pixels = width as u64 * height as u64;
+        let mean_color = sums.map(|sum| (sum / pixels) as u8);
+
+        Some(Self { hash, mean_color })
+    }
+
+    /// How many of the 64 hash bits differ: 0 for the same shapes, above 10 or so for
+    /// a visibly different picture
+    pub fn distance(&self, other: &Self) -> u32 {
+        (self.hash ^ other.hash).count_ones()
+    }
+
+    /// The largest difference of the mean red, green or blue, from 0 to 255
+    pub fn color_distance(&self, other: &Self) -> u32 {
+        self.mean_color
+            .iter()
+            .zip(other.mean_color)
+            .map(|(a, b)| a.abs_diff(b) as u32)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether the two fingerprints are close enough in shape and color to be the
+    /// same look, allowing for rendering noise
+    pub fn looks_like(&self, other: &Self) -> bool {
+        self.distance(other) <= MAX_LOOK_ALIKE_DISTANCE
+            && self.color_distance(other) <= MAX_LOOK_ALIKE_COLOR_DISTANCE
+    }
+}
+
+impl fmt::Display for VisualFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b] = self.mean_color;
+        write!(f, "{:016x}-{:02x}{:02x}{:02x}", self.hash, r, g, b)
+    }
+}
+
+impl FromStr for VisualFingerprint {
+    type Err = AutomationError;
+
+    /// Read a fingerprint written by its `Display` implementation
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AutomationError::InvalidArgument(format!("'{}' is not a visual fingerprint", s));
+        let (hash, color) = s.trim().split_once('-').ok_or_else(invalid)?;
+        if hash.len() != 16 || color.len() != 6 {
+            return Err(invalid());
+        }
+        let hash = u64::from_str_radix(hash, 16).map_err(|_| invalid())?;
+        let color = u32::from_str_radix(color, 16).map_err(|_| invalid())?;
+        Ok(Self {
+            hash,
+            mean_color: [(color >> 16) as u8, (color >> 8) as u8, color as u8],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    /// A `width` x `height` image of `background` with a black box covering the
+    /// fractions `left..right` of the width and `top..bottom` of the height
+    fn boxed(width: u32, height: u32, background: Rgba<u8>, (left, right): (f64, f64), (top, bottom): (f64, f64)) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| {
+            let (fx, fy) = (x as f64 / width as f64, y as f64 / height as f64);
+            if (left..right).contains(&fx) && (top..bottom).contains(&fy) {
+                BLACK
+            } else {
+                background
+            }
+        })
+    }
+
+    fn button(width: u32, height: u32) -> RgbaImage {
+        boxed(width, height, WHITE, (0.2, 0.45), (0.25, 0.75))
+    }
+
+    fn fingerprint(image: &RgbaImage) -> VisualFingerprint {
+        VisualFingerprint::of_image(image).unwrap()
+    }
+
+    #[test]
+    fn same_image_is_distance_zero() {
+        let original = fingerprint(&button(180, 80));
+        assert_eq!(original.distance(&fingerprint(&button(180, 80))), 0);
+        assert_eq!(original.color_distance(&original), 0);
+        assert!(original.looks_like(&original));
+    }
+
+    #[test]
+    fn resizing_and_noise_barely_move_the_hash() {
+        let original = fingerprint(&button(180, 80));
+
+        let resized = fingerprint(&button(171, 77));
+        assert!(resized.distance(&original) <= MAX_LOOK_ALIKE_DISTANCE, "{}", resized.distance(&original));
+        assert!(resized.looks_like(&original));
+
+        let mut noisy = button(180, 80);
+        for (i, pixel) in noisy.pixels_mut().enumerate() {
+            if i % 97 == 0 {
+                pixel.0[..3].iter_mut().for_each(|channel| *channel = 255 - *channel);
+            }
+        }
+        let noisy = fingerprint(&noisy);
+        assert!(noisy.distance(&original) <= MAX_LOOK_ALIKE_DISTANCE, "{}", noisy.distance(&original));
+        assert!(noisy.looks_like(&original));
+    }
+
+    #[test]
+    fn different_shapes_are_far_apart() {
+        let original = fingerprint(&button(180, 80));
+        let moved = fingerprint(&boxed(180, 80, WHITE, (0.6, 0.85), (0.25, 0.75)));
+        assert!(moved.distance(&original) > MAX_LOOK_ALIKE_DISTANCE, "{}", moved.distance(&original));
+        assert!(!moved.looks_like(&original));
+    }
+
+    #[test]
+    fn recoloring_keeps_the_hash_but_not_the_look() {
+        let original = fingerprint(&button(180, 80));
+        let recolored = fingerprint(&boxed(180, 80, Rgba([235, 235, 255, 255]), (0.2, 0.45), (0.25, 0.75)));
+        assert!(recolored.distance(&original) <= MAX_LOOK_ALIKE_DISTANCE);
+        assert!(recolored.color_distance(&original) <= MAX_LOOK_ALIKE_COLOR_DISTANCE);
+
+        let dark = fingerprint(&boxed(180, 80, Rgba([60, 60, 60, 255]), (0.2, 0.45), (0.25, 0.75)));
+        assert!(dark.color_distance(&original) > MAX_LOOK_ALIKE_COLOR_DISTANCE);
+        assert!(!dark.looks_like(&original));
+    }
+
+    #[test]
+    fn empty_images_have_no_fingerprint() {
+        assert_eq!(VisualFingerprint::of_image(&RgbaImage::new(0, 10)), None);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let original = fingerprint(&button(180, 80));
+        assert_eq!(original.to_string().parse::<VisualFingerprint>().unwrap(), original);
+        assert!("3c7e7e3c1818183c".parse::<VisualFingerprint>().is_err());
+        assert!("3c7e7e3c1818183c-33669".parse::<VisualFingerprint>().is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! Filling in forms by field caption
+//!
+//! [`crate::Desktop::fill_form`] finds each field the way `label:` selectors
+//! do, sets its value, reads it back and reports per field, so one bad field
+//! doesn't hide whether the rest of the form went in.
+
+use crate::{AutomationError, UIElement};
+use std::time::Duration;
+use tracing::warn;
+
+// How long to let a field settle before reading it back
+const FIELD_SETTLE_DELAY: Duration = Duration::from_millis(150);
+
+/// What happened to one field of [`crate::Desktop::fill_form`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldOutcome {
+    /// The field was set and reads back the requested value
+    Filled,
+    /// The field was set but doesn't expose its contents (e.g. a password box)
+    Unverified,
+    /// The field was set but reads back something else
+    Mismatch { actual: String },
+    /// No field with this label was found
+    NotFound,
+    /// Finding or setting the field failed
+    Failed(String),
+}
+
+/// The outcome of filling one field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldReport {
+    pub label: String,
+    pub outcome: FieldOutcome,
+}
+
+impl FieldReport {
+    /// Whether the field holds its value, or was set and can't be checked
+    pub fn succeeded(&self) -> bool {
+        matches!(self.outcome, FieldOutcome::Filled | FieldOutcome::Unverified)
+    }
+}
+
+/// Per-field results of [`crate::Desktop::fill_form`], in the order the fields were given
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormReport {
+    pub fields: Vec<FieldReport>,
+}
+
+impl FormReport {
+    /// Whether every field succeeded
+    pub fn is_complete(&self) -> bool {
+        self.fields.iter().all(FieldReport::succeeded)
+    }
+
+    /// The fields that did not succeed
+    pub fn failures(&self) -> impl Iterator<Item = &FieldReport> {
+        self.fields.iter().filter(|field| !field.succeeded())
+    }
+}
+
+/// Set `field` to `value` and read it back. The value is set directly if the field
+/// allows it and typed otherwise; a field that reads back wrong is cleared and typed
+/// once more. Only an abort is returned as an error, everything else is an outcome.
+pub(crate) async fn fill_field(field: &UIElement, value: &str) -> Result<FieldOutcome, AutomationError> {
+    let set = match field.set_value(value) {
+        Ok(()) => Ok(()),
+        Err(_) => type_into(field, value),
+    };
+    if let Err(e) = set {
+        return failed(e);
+    }
+    tokio::time::sleep(FIELD_SETTLE_DELAY).await;
+
+    let actual = match crate::action::read_field(field) {
+        Some(actual) if actual.trim() == value.trim() => return Ok(FieldOutcome::Filled),
+        Some(actual) => actual,
+        None => return Ok(FieldOutcome::Unverified),
+    };
+
+    warn!(expected = value, actual = %actual, "Field did not take its value, re-typing");
+    if let Err(e) = type_into(field, value) {
+        return failed(e);
+    }
+    tokio::time::sleep(FIELD_SETTLE_DELAY).await;
+    match crate::action::read_field(field) {
+        Some(actual) if actual.trim() == value.trim() => Ok(FieldOutcome::Filled),
+        Some(actual) => Ok(FieldOutcome::Mismatch { actual }),
+        None => Ok(FieldOutcome::Unverified),
+    }
+}
+
+fn type_into(field: &UIElement, value: &str) -> Result<(), AutomationError> {
+    field.focus()?;
+    crate::action::clear_field(field)?;
+    field.type_text(value, false)
+}
+
+fn failed(e: AutomationError) -> Result<FieldOutcome, AutomationError> {
+    match e.inner() {
+        AutomationError::Aborted(_) => Err(e),
+        _ => Ok(FieldOutcome::Failed(e.to_string())),
+    }
+}
@@ -0,0 +1,192 @@
+//! Engine-independent heuristics for UIs with poor accessibility metadata
+//!
+//! Forms built without accessible names still show a visible caption next to
+//! each input. These helpers recover that association from geometry and
+//! reading order so selectors like `label:Username` keep working, and find
+//! elements by where they sit relative to another one. They only
+//! use [`AccessibilityEngine`] searches and element bounds, so every engine
+//! can delegate to them.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, Relation, Role, Selector, UIElement};
+use std::time::Duration;
+
+/// Element bounds as `(x, y, width, height)`
+pub(crate) type Bounds = (f64, f64, f64, f64);
+
+// Slack (in pixels) for fields that start slightly before the caption's edge
+const EDGE_TOLERANCE: f64 = 4.0;
+// Captions further than this from a field are not considered its label
+const MAX_LABEL_GAP: f64 = 200.0;
+// Cap on each role search; waiting for the field to appear is the locator's job
+const ROLE_SEARCH_TIMEOUT: Duration = Duration::from_millis(1000);
+// Extra distance charged to fields below a caption, so a field on the same row wins
+const BELOW_PENALTY: f64 = 10.0;
+
+const CAPTION_ROLES: &[Role] = &[Role::Text];
+const FIELD_ROLES: &[Role] = &[Role::Edit, Role::ComboBox];
+
+/// Find input fields labelled `label`, best match first.
+///
+/// Fields whose own accessible name is the label come first, followed by
+/// unnamed fields closest to a caption with that text: to its right on the
+/// same row, or directly below it.
+pub(crate) fn find_by_label(
+    engine: &dyn AccessibilityEngine,
+    label: &str,
+    root: Option<&UIElement>,
+    timeout: Option<Duration>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let wanted = normalize_caption(label);
+    let fields = find_all_roles(engine, FIELD_ROLES, root, timeout)?;
+
+    let (mut matches, unnamed): (Vec<UIElement>, Vec<UIElement>) = fields
+        .into_iter()
+        .partition(|field| field.name().is_some_and(|name| normalize_caption(&name) == wanted));
+
+    let captions = find_all_roles(engine, CAPTION_ROLES, root, timeout)?
+        .into_iter()
+        .filter(|caption| caption.name().is_some_and(|name| normalize_caption(&name) == wanted));
+
+    let field_bounds: Vec<Bounds> = unnamed
+        .iter()
+        .map(|field| field.bounds().unwrap_or_default())
+        .collect();
+    for caption in captions {
+        let Ok(caption_bounds) = caption.bounds() else {
+            continue;
+        };
+        if let Some(index) = nearest_field(caption_bounds, &field_bounds)
+            && !matches.contains(&unnamed[index])
+        {
+            matches.push(unnamed[index].clone());
+        }
+    }
+
+    if matches.is_empty() {
+        return Err(AutomationError::ElementNotFound(format!(
+            "No input field labelled '{}'",
+            label
+        )));
+    }
+    Ok(matches)
+}
+
+/// Find elements matching `base` that lie in `relation` to an element matching
+/// `anchor`, nearest first. An element never counts as its own anchor.
+pub(crate) fn find_relative(
+    engine: &dyn AccessibilityEngine,
+    base: &Selector,
+    relation: Relation,
+    anchor: &Selector,
+    root: Option<&UIElement>,
+    timeout: Option<Duration>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let timeout = Some(timeout.map_or(ROLE_SEARCH_TIMEOUT, |t| t.min(ROLE_SEARCH_TIMEOUT)));
+    let anchors: Vec<(UIElement, Bounds)> = engine
+        .find_elements(anchor, root, timeout, None)?
+        .into_iter()
+        .filter_map(|element| element.bounds().ok().map(|bounds| (element, bounds)))
+        .collect();
+
+    let mut matches: Vec<(UIElement, f64)> = engine
+        .find_elements(base, root, timeout, None)?
+        .into_iter()
+        .filter_map(|candidate| {
+            let bounds = candidate.bounds().ok()?;
+            let distance = anchors
+                .iter()
+                .filter(|(anchor, _)| *anchor != candidate)
+                .filter_map(|&(_, anchor_bounds)| relative_distance(relation, anchor_bounds, bounds))
+                .min_by(f64::total_cmp)?;
+            Some((candidate, distance))
+        })
+        .collect();
+    matches.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    if matches.is_empty() {
+        return Err(AutomationError::ElementNotFound(format!(
+            "No element matching {:?} is {:?} an element matching {:?}",
+            base, relation, anchor
+        )));
+    }
+    Ok(matches.into_iter().map(|(element, _)| element).collect())
+}
+
+/// Gap between `anchor` and `element` if `element` lies in `relation` to it
+fn relative_distance(relation: Relation, anchor: Bounds, element: Bounds) -> Option<f64> {
+    let (ax, ay, aw, ah) = anchor;
+    let (ex, ey, ew, eh) = element;
+    if ew <= 0.0 || eh <= 0.0 {
+        return None; // offscreen or collapsed
+    }
+    let overlaps_rows = ey < ay + ah && ay < ey + eh;
+    let overlaps_columns = ex < ax + aw && ax < ex + ew;
+    let gap_x = (ex - (ax + aw)).max(ax - (ex + ew)).max(0.0);
+    let gap_y = (ey - (ay + ah)).max(ay - (ey + eh)).max(0.0);
+
+    match relation {
+        Relation::RightOf if overlaps_rows && ex >= ax + aw - EDGE_TOLERANCE => Some(gap_x),
+        Relation::LeftOf if overlaps_rows && ex + ew <= ax + EDGE_TOLERANCE => Some(gap_x),
+        Relation::Above if overlaps_columns && ey + eh <= ay + EDGE_TOLERANCE => Some(gap_y),
+        Relation::Below if overlaps_columns && ey >= ay + ah - EDGE_TOLERANCE => Some(gap_y),
+        Relation::Near(max_distance) => {
+            let distance = gap_x.hypot(gap_y);
+            (distance <= max_distance as f64).then_some(distance)
+        }
+        _ => None,
+    }
+}
+
+/// Index of the field a caption most plausibly labels, if any
+pub(crate) fn nearest_field(caption: Bounds, fields: &[Bounds]) -> Option<usize> {
+    let (cx, cy, cw, ch) = caption;
+    let caption_right = cx + cw;
+    let caption_bottom = cy + ch;
+    let caption_middle = cy + ch / 2.0;
+
+    fields
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &(fx, fy, fw, fh))| {
+            if fw <= 0.0 || fh <= 0.0 {
+                return None; // offscreen or collapsed
+            }
+            let same_row = fy <= caption_middle && caption_middle <= fy + fh;
+            let distance = if same_row && fx >= caption_right - EDGE_TOLERANCE {
+                fx - caption_right
+            } else if fy >= caption_bottom - EDGE_TOLERANCE && fx < caption_right && fx + fw > cx {
+                fy - caption_bottom + BELOW_PENALTY
+            } else {
+                return None;
+            };
+            (distance <= MAX_LABEL_GAP).then_some((index, distance.max(0.0)))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+/// Caption text as it is compared: trimmed, case-folded, without trailing `:` or `*` markers
+fn normalize_caption(text: &str) -> String {
+    text.trim()
+        .trim_end_matches(|c: char| c == ':' || c == '*' || c.is_whitespace())
+        .to_lowercase()
+}
+
+fn find_all_roles(
+    engine: &dyn AccessibilityEngine,
+    roles: &[Role],
+    root: Option<&UIElement>,
+    timeout: Option<Duration>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let timeout = Some(timeout.map_or(ROLE_SEARCH_TIMEOUT, |t| t.min(ROLE_SEARCH_TIMEOUT)));
+    let mut elements = Vec::new();
+    for role in roles {
+        match engine.find_elements(&Selector::from(*role), root, timeout, None) {
+            Ok(found) => elements.extend(found),
+            Err(e) if e.is_retryable() => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(elements)
+}
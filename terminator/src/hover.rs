@@ -0,0 +1,29 @@
+//! Hovering with the mouse
+//!
+//! Tooltips, hover cards and menus that open on mouse-over only react to the
+//! real cursor resting over an element, not to accessibility actions.
+//! [`crate::UIElement::hover_with`] moves the cursor there and waits long enough
+//! for the app's hover delay to pass, so whatever it shows is on screen when the
+//! call returns.
+
+use std::time::Duration;
+
+/// Where a hover puts the cursor and how long it waits, see [`crate::UIElement::hover_with`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoverOptions {
+    /// Distance from the element's center to move to, in screen pixels, e.g. to hover
+    /// one part of a wide element
+    pub offset: (f64, f64),
+    /// How long to rest over the element before returning. Windows shows tooltips
+    /// after 500ms by default, so the default waits a little longer than that.
+    pub dwell: Duration,
+}
+
+impl Default for HoverOptions {
+    fn default() -> Self {
+        Self {
+            offset: (0.0, 0.0),
+            dwell: Duration::from_millis(700),
+        }
+    }
+}
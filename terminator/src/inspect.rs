@@ -0,0 +1,213 @@
+//! Interactive element picking and selector suggestions
+//!
+//! Used by [`crate::Desktop::pick_element`] so workflow authors can point at an
+//! element on screen and get back a selector string they can paste into code.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, Role, Selector, UIElement};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// How often the mouse state is polled while waiting for a pick
+const PICK_POLL_INTERVAL: Duration = Duration::from_millis(30);
+
+// Property names platforms use for the developer-assigned automation identifier
+const AUTOMATION_ID_PROPERTIES: &[&str] = &["AutomationId", "AXIdentifier"];
+
+/// An element chosen interactively, together with a selector that finds it again
+#[derive(Debug, Clone)]
+pub struct PickedElement {
+    /// The element that was under the cursor
+    pub element: UIElement,
+    /// A selector string suggested for the element, e.g. `name:Save`
+    pub selector: String,
+    /// Screen position of the click that picked it
+    pub position: (f64, f64),
+}
+
+/// Suggest a selector string that can be passed to `Desktop::locator` to find `element` again.
+///
+/// Prefers the accessible name (the most stable across runs), then the element ID,
+/// and falls back to the role. A name or ID the selector syntax would read differently,
+/// e.g. one containing `>>` or `/` or ending in `:last`, is matched exactly with a
+/// regex instead: `name~=/^Open >> Recent$/`.
+pub fn suggest_selector(element: &UIElement) -> String {
+    if let Some(name) = element.name().filter(|n| !n.trim().is_empty()) {
+        return exact_selector(format!("name:{}", name), Selector::Name(name.clone()), "name", &name);
+    }
+    if let Some(id) = element.id().filter(|id| !id.is_empty()) {
+        return exact_selector(format!("#{}", id), Selector::Id(id.clone()), "id", &id);
+    }
+    match element.normalized_role() {
+        Role::Unknown => format!("role:{}", element.role()),
+        role => format!("role:{}", role),
+    }
+}
+
+/// `plain` if it parses back to `expected`, otherwise a regex matching the `key`
+/// attribute against exactly `value`
+fn exact_selector(plain: String, expected: Selector, key: &str, value: &str) -> String {
+    if Selector::from(plain.as_str()) == expected {
+        return plain;
+    }
+    // `/` ends the regex, so it is escaped along with the regex syntax
+    format!("{}~=/^{}$/", key, regex::escape(value).replace('/', "\\/"))
+}
+
+/// Wait for a left click anywhere on screen and return the element under the cursor
+pub(crate) async fn wait_for_pick(
+    engine: &Arc<dyn AccessibilityEngine>,
+    timeout: Option<Duration>,
+) -> Result<PickedElement, AutomationError> {
+    let start = Instant::now();
+    // A pick is a full press-and-release, so a press already in progress on entry doesn't count
+    let mut ready = !engine.get_mouse_state()?.left_pressed;
+    let mut pressed = false;
+
+    loop {
+        let state = engine.get_mouse_state()?;
+        if !ready {
+            ready = !state.left_pressed;
+        } else if state.left_pressed {
+            pressed = true;
+        } else if pressed {
+            let element = engine.get_element_at_point(state.x, state.y)?;
+            return Ok(PickedElement {
+                selector: suggest_selector(&element),
+                element,
+                position: (state.x, state.y),
+            });
+        }
+
+        if let Some(timeout) = timeout
+            && start.elapsed() >= timeout
+        {
+            return Err(AutomationError::Timeout(format!(
+                "No element picked within {:?}",
+                timeout
+            )));
+        }
+        tokio::time::sleep(PICK_POLL_INTERVAL).await;
+    }
+}
+
+/// Options for [`crate::Desktop::run_inspector`]
+#[derive(Debug, Clone)]
+pub struct InspectorOptions {
+    /// Key that toggles live inspection on and off
+    pub toggle_key: String,
+    /// Key that ends the inspector session
+    pub exit_key: String,
+    /// How often the element under the cursor is re-read
+    pub poll_interval: Duration,
+}
+
+impl Default for InspectorOptions {
+    fn default() -> Self {
+        Self {
+            toggle_key: "f8".to_string(),
+            exit_key: "escape".to_string(),
+            poll_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// What the inspector reports for the element under the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectedElement {
+    pub role: String,
+    pub name: Option<String>,
+    pub automation_id: Option<String>,
+    /// Selector suggested by [`suggest_selector`]
+    pub selector: String,
+}
+
+impl InspectedElement {
+    /// Read the inspector fields from an element
+    pub fn from_element(element: &UIElement) -> Self {
+        let attributes = element.attributes();
+        let automation_id = AUTOMATION_ID_PROPERTIES.iter().find_map(|key| {
+            attributes
+                .properties
+                .get(*key)
+                .cloned()
+                .flatten()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .filter(|id| !id.is_empty())
+        });
+        Self {
+            role: attributes.role,
+            name: attributes.name.filter(|n| !n.is_empty()),
+            automation_id,
+            selector: suggest_selector(element),
+        }
+    }
+
+    /// One-line label suitable for an overlay badge
+    pub fn label(&self) -> String {
+        let mut label = self.role.clone();
+        if let Some(name) = &self.name {
+            label.push_str(&format!(" '{}'", name));
+        }
+        if let Some(id) = &self.automation_id {
+            label.push_str(&format!(" [{}]", id));
+        }
+        label.push_str(&format!(" -> {}", self.selector));
+        label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FakeElement;
+
+    fn assert_round_trips(element: &UIElement) {
+        let suggested = suggest_selector(element);
+        let selector = Selector::from(suggested.as_str());
+        assert_eq!(
+            selector.matches_element(element),
+            Some(true),
+            "{} parsed as {:?} doesn't find {}",
+            suggested,
+            selector,
+            element.describe()
+        );
+    }
+
+    #[test]
+    fn plain_names_and_ids_stay_readable() {
+        assert_eq!(suggest_selector(&FakeElement::named("Save").into_element()), "name:Save");
+        assert_eq!(suggest_selector(&FakeElement::with_id("saveButton").into_element()), "#saveButton");
+    }
+
+    #[test]
+    fn suggested_selectors_find_the_element_again() {
+        let values = [
+            "Save",
+            "Open >> Recent",
+            "Item:last",
+            "Item:first",
+            "Page:nth(2)",
+            "Status:visible",
+            "Ready:enabled(false)",
+            "name~=/x/",
+            "a/b",
+            "Save as... >> a/b:last",
+            "/usr/local",
+            "window[name=\"x\"]/pane",
+            "Total: $5 (incl. tax) [draft]",
+            "#hash",
+            "role:button",
+            "attr:key=value",
+            "C:\\Users\\me",
+            "Say \"hi\"",
+            "button",
+            "AXButton",
+        ];
+        for value in values {
+            assert_round_trips(&FakeElement::named(value).into_element());
+            assert_round_trips(&FakeElement::with_id(value).into_element());
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Picking one instance of an application that may be running several times
+//!
+//! Two copies of the same app means two identical-looking windows, and input
+//! meant for one easily lands in the other. See [`crate::Desktop::ensure_single_instance`].
+
+use crate::window_criteria::strip_exe;
+use crate::{ProcessInfo, UIElement, WindowPlacement};
+
+/// What to do with the other instances when an app is running more than once
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExtraInstances {
+    /// Leave them running; only the most recently active instance is picked
+    #[default]
+    Keep,
+    /// Ask them to quit, or terminate them where the platform can't ask
+    Close,
+}
+
+/// The instance picked by [`crate::Desktop::ensure_single_instance`]
+#[derive(Debug, Clone)]
+pub struct AppInstance {
+    pub pid: u32,
+    /// The instance's application element
+    pub application: UIElement,
+    /// The other instances that were running, most recently active first
+    pub extras: Vec<u32>,
+}
+
+/// Processes of `app` that own windows, most recently active first.
+///
+/// `placements` (topmost first) decides the order; processes without a placed
+/// window keep their process-list order after the ones with one. If no process
+/// of `app` owns a window, all of them are returned.
+pub(crate) fn rank_instances(
+    app: &str,
+    processes: &[ProcessInfo],
+    placements: &[WindowPlacement],
+) -> Vec<u32> {
+    let wanted = strip_exe(app);
+    let matching: Vec<&ProcessInfo> = processes
+        .iter()
+        .filter(|process| executable_name(&process.name).eq_ignore_ascii_case(wanted))
+        .collect();
+    let with_windows: Vec<&ProcessInfo> = matching
+        .iter()
+        .copied()
+        .filter(|process| process.window_count > 0)
+        .collect();
+    let candidates = if with_windows.is_empty() { matching } else { with_windows };
+
+    let topmost_window = |pid: u32| {
+        placements
+            .iter()
+            .filter(|placement| placement.process_id == pid)
+            .map(|placement| placement.z_order)
+            .min()
+            .unwrap_or(usize::MAX)
+    };
+    let mut pids: Vec<u32> = candidates.iter().map(|process| process.pid).collect();
+    pids.sort_by_key(|&pid| topmost_window(pid));
+    pids
+}
+
+/// Executable name without its directory (macOS lists full paths) or `.exe` suffix
+fn executable_name(name: &str) -> &str {
+    strip_exe(name.rsplit(['/', '\\']).next().unwrap_or(name))
+}
@@ -0,0 +1,212 @@
+//! Key chords like `Ctrl+Shift+S`
+//!
+//! Shortcuts are a set of held modifiers plus one key, which `type_text`
+//! can't express. [`KeyChord::parse`] reads the way shortcuts are written in
+//! menus and documentation, case-insensitively and with the usual aliases, and
+//! each platform injects the result as real key presses.
+
+use crate::AutomationError;
+
+/// A key held down while the chord's key is pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Modifier {
+    Ctrl,
+    Shift,
+    /// Alt, or Option on macOS
+    Alt,
+    /// The Windows key, or Command on macOS
+    Meta,
+}
+
+/// The key a chord presses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Key {
+    /// A key that types this character
+    Char(char),
+    Enter,
+    Tab,
+    Escape,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+    /// F1 to F24
+    Function(u8),
+}
+
+/// Modifiers to hold and the key to press while they are held
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyChord {
+    /// In the order written, without duplicates
+    pub(crate) modifiers: Vec<Modifier>,
+    pub(crate) key: Key,
+}
+
+impl KeyChord {
+    /// Parse a chord such as `Ctrl+Shift+S`, `alt+f4`, `Cmd+Q`, `Shift+Tab` or a single
+    /// key like `Enter`. `Ctrl++` presses the plus key.
+    pub(crate) fn parse(text: &str) -> Result<Self, AutomationError> {
+        let invalid = |reason: &str| AutomationError::InvalidArgument(format!("'{}' is not a key chord: {}", text, reason));
+        let text = text.trim();
+        let (modifiers, key) = match text.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+        if key.trim().is_empty() {
+            return Err(invalid("no key after the modifiers"));
+        }
+
+        let mut chord = KeyChord {
+            modifiers: Vec::new(),
+            key: parse_key(key.trim()).ok_or_else(|| invalid("unknown key"))?,
+        };
+        for name in modifiers.split('+').map(str::trim).filter(|name| !name.is_empty()) {
+            let modifier = parse_modifier(name).ok_or_else(|| invalid("unknown modifier"))?;
+            if !chord.modifiers.contains(&modifier) {
+                chord.modifiers.push(modifier);
+            }
+        }
+        // `Ctrl+S` means the S key, not a capital S that would add Shift
+        if let Key::Char(c) = chord.key
+            && !chord.modifiers.is_empty()
+        {
+            chord.key = Key::Char(c.to_ascii_lowercase());
+        }
+        Ok(chord)
+    }
+}
+
+fn parse_modifier(name: &str) -> Option<Modifier> {
+    let modifier = match name.to_lowercase().as_str() {
+        "ctrl" | "control" | "ctl" => Modifier::Ctrl,
+        "shift" => Modifier::Shift,
+        "alt" | "option" | "opt" => Modifier::Alt,
+        "win" | "windows" | "meta" | "super" | "cmd" | "command" => Modifier::Meta,
+        _ => return None,
+    };
+    Some(modifier)
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Key::Char(c));
+    }
+    let lower = name.to_lowercase();
+    let key = match lower.as_str() {
+        "enter" | "return" => Key::Enter,
+        "tab" => Key::Tab,
+        "esc" | "escape" => Key::Escape,
+        "backspace" | "back" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "insert" | "ins" => Key::Insert,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" | "page_up" | "pgup" => Key::PageUp,
+        "pagedown" | "page_down" | "pgdn" => Key::PageDown,
+        "up" | "arrowup" => Key::Up,
+        "down" | "arrowdown" => Key::Down,
+        "left" | "arrowleft" => Key::Left,
+        "right" | "arrowright" => Key::Right,
+        "space" | "spacebar" => Key::Space,
+        "plus" => Key::Char('+'),
+        "minus" => Key::Char('-'),
+        _ => {
+            let number: u8 = lower.strip_prefix('f')?.parse().ok()?;
+            if !(1..=24).contains(&number) {
+                return None;
+            }
+            Key::Function(number)
+        }
+    };
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(modifiers: &[Modifier], key: Key) -> KeyChord {
+        KeyChord {
+            modifiers: modifiers.to_vec(),
+            key,
+        }
+    }
+
+    #[test]
+    fn parses_modifiers_in_written_order() {
+        assert_eq!(
+            KeyChord::parse("Ctrl+Shift+S").unwrap(),
+            chord(&[Modifier::Ctrl, Modifier::Shift], Key::Char('s'))
+        );
+        assert_eq!(
+            KeyChord::parse("Shift+Alt+Meta+Ctrl+F4").unwrap(),
+            chord(&[Modifier::Shift, Modifier::Alt, Modifier::Meta, Modifier::Ctrl], Key::Function(4))
+        );
+        assert_eq!(KeyChord::parse("Ctrl+ctrl+S").unwrap(), chord(&[Modifier::Ctrl], Key::Char('s')));
+    }
+
+    #[test]
+    fn accepts_aliases() {
+        for (text, modifier) in [
+            ("control+a", Modifier::Ctrl),
+            ("ctl+a", Modifier::Ctrl),
+            ("option+a", Modifier::Alt),
+            ("opt+a", Modifier::Alt),
+            ("cmd+a", Modifier::Meta),
+            ("command+a", Modifier::Meta),
+            ("win+a", Modifier::Meta),
+            ("super+a", Modifier::Meta),
+        ] {
+            assert_eq!(KeyChord::parse(text).unwrap(), chord(&[modifier], Key::Char('a')), "{}", text);
+        }
+        for (text, key) in [
+            ("Return", Key::Enter),
+            ("Esc", Key::Escape),
+            ("Del", Key::Delete),
+            ("PgUp", Key::PageUp),
+            ("page_down", Key::PageDown),
+            ("ArrowLeft", Key::Left),
+            ("Spacebar", Key::Space),
+            ("Minus", Key::Char('-')),
+            ("F24", Key::Function(24)),
+        ] {
+            assert_eq!(KeyChord::parse(text).unwrap(), chord(&[], key), "{}", text);
+        }
+    }
+
+    #[test]
+    fn ignores_case_and_spacing() {
+        assert_eq!(
+            KeyChord::parse("CTRL+SHIFT+ENTER").unwrap(),
+            chord(&[Modifier::Ctrl, Modifier::Shift], Key::Enter)
+        );
+        assert_eq!(KeyChord::parse(" alt + f4 ").unwrap(), chord(&[Modifier::Alt], Key::Function(4)));
+        // A letter with modifiers is the key, not a capital; on its own it is typed as written
+        assert_eq!(KeyChord::parse("Cmd+Q").unwrap(), chord(&[Modifier::Meta], Key::Char('q')));
+        assert_eq!(KeyChord::parse("Q").unwrap(), chord(&[], Key::Char('Q')));
+    }
+
+    #[test]
+    fn reads_the_plus_key() {
+        assert_eq!(KeyChord::parse("Ctrl++").unwrap(), chord(&[Modifier::Ctrl], Key::Char('+')));
+        assert_eq!(KeyChord::parse("Ctrl+Shift++").unwrap(), chord(&[Modifier::Ctrl, Modifier::Shift], Key::Char('+')));
+        assert_eq!(KeyChord::parse("Ctrl+Plus").unwrap(), chord(&[Modifier::Ctrl], Key::Char('+')));
+    }
+
+    #[test]
+    fn rejects_invalid_chords() {
+        for text in ["", "Ctrl+", "Ctrl+Shift+ ", "Hyper+S", "Ctrl+Banana", "F0", "F25", "Ctrl+S+Alt"] {
+            let error = KeyChord::parse(text).expect_err(text);
+            assert!(matches!(error, AutomationError::InvalidArgument(_)), "{}: {:?}", text, error);
+        }
+    }
+}
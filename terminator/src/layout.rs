@@ -0,0 +1,39 @@
+//! Snapshot and restore of top-level window positions
+//!
+//! Lets an automation run arrange windows the way it needs them and then put
+//! everything back where the user had it.
+
+use serde::{Deserialize, Serialize};
+
+/// Show state of a top-level window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowState {
+    Normal,
+    Minimized,
+    Maximized,
+}
+
+/// Position, size and stacking of a single top-level window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowPlacement {
+    /// Platform window handle, valid for the lifetime of the window
+    pub handle: isize,
+    pub title: String,
+    pub process_id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub state: WindowState,
+    /// Name of the monitor the window is (mostly) on
+    pub monitor: Option<String>,
+    /// Stacking position, 0 being the topmost window
+    pub z_order: usize,
+}
+
+/// Snapshot of all visible top-level windows, see [`crate::Desktop::save_window_layout`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowLayout {
+    /// Windows ordered from topmost to bottommost
+    pub windows: Vec<WindowPlacement>,
+}
@@ -3,30 +3,145 @@
 //! This module provides a cross-platform API for automating desktop applications
 //! through accessibility APIs, inspired by Playwright's web automation model.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{info, instrument, warn};
 
+mod abort;
+mod action;
+mod aliases;
+mod artifacts;
+mod browser;
+mod cleanup;
+mod command;
+mod context_menu;
+mod controls;
+mod display;
+mod drag;
 mod element;
 mod errors;
+mod expect;
+mod explain;
+mod file_manager;
+mod fingerprint;
+mod form;
+mod heuristics;
+mod hover;
+mod inspect;
+mod instances;
+mod keys;
+mod layout;
+mod locale;
 mod locator;
+mod login;
+mod ocr;
 pub mod platforms;
+mod popups;
+mod power;
+mod process_monitor;
+mod progress;
+mod region;
+mod role;
+mod scrape;
+mod scroll;
+mod search;
+mod screen;
+mod screen_reader;
+mod secret;
 mod selector;
+mod session;
+mod soft;
+mod theme;
 #[cfg(test)]
 mod tests;
+mod timeline;
+mod tracked;
 pub mod utils;
+pub mod vision;
+mod value;
+mod visual_changes;
+mod webview;
+mod window_criteria;
 pub mod drawing;
 
-pub use element::{UIElement, UIElementAttributes};
-pub use errors::AutomationError;
-pub use locator::Locator;
-pub use selector::Selector;
+pub use abort::KillSwitch;
+pub use action::{ActionContext, ActionOptions, ActionabilityOptions, Verify};
+pub use aliases::AliasTable;
+pub use artifacts::{FailureArtifacts, FailureArtifactsConfig};
+pub use browser::{BrowserTab, TabTarget};
+pub use cleanup::CleanupReport;
+pub use command::{CommandBuilder, OutputLine, RunningCommand};
+pub use context_menu::ContextMenu;
+pub use display::{DisplayChange, DisplayWatcher, MonitorInfo};
+pub use drag::DragOptions;
+pub use element::{ElementState, UIElement, UIElementAttributes};
+pub use errors::{AutomationError, ErrorContext, NativeError};
+pub use expect::{expect, Expectation};
+pub use explain::{Elimination, MatchExplanation, NearMiss, StageExplanation};
+pub use file_manager::FileManager;
+pub use fingerprint::VisualFingerprint;
+pub use form::{FieldOutcome, FieldReport, FormReport};
+pub use hover::HoverOptions;
+pub use inspect::{suggest_selector, InspectedElement, InspectorOptions, PickedElement};
+pub use instances::{AppInstance, ExtraInstances};
+pub use layout::{WindowLayout, WindowPlacement, WindowState};
+pub use locale::{DateOrder, Locale};
+pub use locator::{Locator, WaitState};
+pub use login::{CommandCredentials, CredentialProvider, Credentials, EnvCredentials};
+pub use popups::PopupAction;
+pub use ocr::OcrWord;
+pub use process_monitor::{ProcessEvent, ProcessEvents, ProcessInfo, ProcessSample, ProcessStats};
+pub use role::Role;
+pub use scrape::{ScrapeSpec, ScrapedRecord};
+pub use screen_reader::{ScreenReader, ScreenReaderStatus};
+pub use search::{TextHit, TextSearchOptions, TextSource};
+pub use secret::SecretString;
+pub use webview::{WebView, WebViewKind};
+pub use selector::{ElementPredicate, Relation, Selector, SelectorRegex, StateFilter};
+pub use session::{SessionInfo, SessionState};
+pub use soft::{SoftAssertions, SoftFailure};
+pub use theme::{Theme, ThemeMode};
+pub use timeline::{Filmstrip, TimelineOptions, TimelineRecorder};
+pub use tracked::TrackedElement;
+pub use value::{NumericValue, RangeValue};
+pub use vision::{ImageMatch, ImageTemplate, TemplateMatch};
+pub use visual_changes::{VisualChange, VisualChangeWatcher};
+pub use window_criteria::WindowCriteria;
+
+// How often the screen is re-captured while waiting for an image
+const IMAGE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// Minimum confidence for an anchor image to be clicked relative to
+const ANCHOR_IMAGE_THRESHOLD: f32 = 0.9;
+// How often the process list is checked while waiting for a process to exit
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// How often to check whether the session has unlocked
+const SESSION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// How long an extra app instance gets to quit after being asked to close
+const INSTANCE_EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+// How often focus is checked after pressing Tab, and how long to wait for it to move
+const FOCUS_POLL_INTERVAL: Duration = Duration::from_millis(20);
+const FOCUS_CHANGE_TIMEOUT: Duration = Duration::from_millis(500);
+// How long each locator searches per round of wait_for_any, and the pause between rounds
+const RACE_SEARCH_TIMEOUT: Duration = Duration::from_millis(200);
+const RACE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// How long each form field gets to appear in fill_form
+const FORM_FIELD_TIMEOUT: Duration = Duration::from_secs(5);
+// Levels of ancestors checked when deciding whether focus is inside a target element
+const FOCUS_ANCESTOR_DEPTH: usize = 5;
+
+#[cfg(target_os = "windows")]
+const TAB_KEYS: (&str, &str) = ("{tab}", "{shift}{tab}");
+#[cfg(not(target_os = "windows"))]
+const TAB_KEYS: (&str, &str) = ("tab", "shift+tab");
 
 // Define a new struct to hold click result information - move to module level
 pub struct ClickResult {
     pub method: String,
     pub coordinates: Option<(f64, f64)>,
     pub details: String,
+    /// Where input was going right after the click, if it could be read
+    pub context: Option<ActionContext>,
 }
 
 /// Holds the output of a terminal command execution
@@ -36,8 +151,37 @@ pub struct CommandOutput {
     pub stderr: String,
 }
 
+/// Current cursor position and mouse button state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MouseState {
+    pub x: f64,
+    pub y: f64,
+    pub left_pressed: bool,
+    pub right_pressed: bool,
+}
+
+/// Modifier keys and mouse buttons currently held down, see [`Desktop::input_state`].
+/// The OS doesn't say who pressed them, so this includes keys the user is holding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputState {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// The Windows key, or Command on macOS
+    pub meta: bool,
+    pub left_pressed: bool,
+    pub right_pressed: bool,
+}
+
+impl InputState {
+    /// Whether nothing is held down
+    pub fn is_clear(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// Holds the screenshot data
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ScreenshotResult {
     /// Raw image data (e.g., RGBA)
     pub image_data: Vec<u8>,
@@ -47,10 +191,54 @@ pub struct ScreenshotResult {
     pub height: u32,
 }
 
+impl ScreenshotResult {
+    /// Load an image file (e.g. a template for [`Desktop::wait_for_image`])
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, AutomationError> {
+        let path = path.as_ref();
+        let image = image::open(path)
+            .map_err(|e| {
+                AutomationError::InvalidArgument(format!(
+                    "Failed to load image '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?
+            .to_rgba8();
+        Ok(Self {
+            width: image.width(),
+            height: image.height(),
+            image_data: image.into_raw(),
+        })
+    }
+}
+
+/// Whether `focused` is `target` or one of its descendants
+fn focus_is_within(focused: &UIElement, target: &UIElement) -> bool {
+    let mut current = Some(focused.clone());
+    for _ in 0..=FOCUS_ANCESTOR_DEPTH {
+        match current {
+            Some(element) if &element == target => return true,
+            Some(element) => current = element.parent().ok().flatten(),
+            None => return false,
+        }
+    }
+    false
+}
+
 /// The main entry point for UI automation
 pub struct Desktop {
     engine: Arc<dyn platforms::AccessibilityEngine>,
     visualizer: Option<drawing::OverlayEngine>,
+    failure_artifacts: Option<FailureArtifactsConfig>,
+    slow_mo: Duration,
+    sleep_inhibitor: Option<power::SleepInhibitor>,
+    process_monitor: Option<process_monitor::ProcessMonitor>,
+    kill_switch: Option<abort::KillSwitch>,
+    locale_aliases: Option<AliasTable>,
+    popup_handler: Option<popups::PopupHandler>,
+    locator_timeout: Option<Duration>,
+    polling: locator::Polling,
+    session_tracker: cleanup::SessionTracker,
 }
 
 impl Desktop {
@@ -87,6 +275,16 @@ impl Desktop {
         Ok(Self {
             engine: Arc::from(engine),
             visualizer,
+            failure_artifacts: None,
+            slow_mo: Duration::ZERO,
+            sleep_inhibitor: None,
+            process_monitor: None,
+            kill_switch: None,
+            locale_aliases: None,
+            popup_handler: None,
+            locator_timeout: None,
+            polling: locator::Polling::default(),
+            session_tracker: cleanup::SessionTracker::default(),
         })
     }
 
@@ -95,7 +293,7 @@ impl Desktop {
         let start = Instant::now();
         info!("Getting root element");
         
-        let element = self.engine.get_root_element();
+        let element = self.engine.get_root_element().with_engine(self.engine.clone());
         
         let duration = start.elapsed();
         info!(
@@ -113,7 +311,18 @@ impl Desktop {
         let selector = selector.into();
         info!(?selector, "Creating locator");
         
-        let locator = Locator::new(self.engine.clone(), selector);
+        let mut locator = Locator::new(self.engine.clone(), selector)
+            .with_slow_mo(self.slow_mo)
+            .with_polling_settings(self.polling);
+        if let Some(timeout) = self.locator_timeout {
+            locator = locator.set_default_timeout(timeout);
+        }
+        if let Some(config) = &self.failure_artifacts {
+            locator = locator.with_failure_artifacts(config.clone());
+        }
+        if let Some(aliases) = &self.locale_aliases {
+            locator = locator.with_locale_aliases(aliases.clone());
+        }
         
         let duration = start.elapsed();
         info!(
@@ -124,12 +333,437 @@ impl Desktop {
         locator
     }
 
+    /// Enable (or with `None`, disable) failure artifact capture for locators created from now on
+    pub fn set_failure_artifacts(&mut self, config: Option<FailureArtifactsConfig>) {
+        info!(enabled = config.is_some(), "Configuring failure artifacts");
+        self.failure_artifacts = config;
+    }
+
+    /// Let name selectors of locators created from now on also match their localized
+    /// equivalents from the built-in [`AliasTable`], e.g. "Aceptar" or "确定" for "OK",
+    /// so one script works across localized installs
+    pub fn set_locale_tolerant(&mut self, enabled: bool) {
+        self.set_locale_aliases(enabled.then(AliasTable::default));
+    }
+
+    /// Like [`Desktop::set_locale_tolerant`] with a custom or extended alias table;
+    /// `None` turns alias matching off
+    pub fn set_locale_aliases(&mut self, aliases: Option<AliasTable>) {
+        info!(enabled = aliases.is_some(), "Configuring locale aliases");
+        self.locale_aliases = aliases;
+    }
+
+    /// Insert `delay` before every action performed through locators created from now on.
+    /// Useful to make demos watchable or to give sluggish apps time to keep up.
+    /// Actions called directly on a `UIElement` are not throttled.
+    pub fn set_slow_mo(&mut self, delay: Duration) {
+        info!(delay_ms = delay.as_millis(), "Setting slow-mo delay");
+        self.slow_mo = delay;
+    }
+
+    /// Default timeout for waits on locators created from now on, when no timeout is passed
+    pub fn set_default_timeout(&mut self, timeout: Duration) {
+        info!(timeout_ms = timeout.as_millis(), "Setting default locator timeout");
+        self.locator_timeout = Some(timeout);
+    }
+
+    /// How often locators created from now on re-check the UI while waiting, and with
+    /// `max_interval`, let the interval double after each check up to it. See
+    /// [`Locator::with_polling`] and [`Locator::with_backoff`].
+    pub fn set_polling(&mut self, interval: Duration, max_interval: Option<Duration>) {
+        info!(
+            interval_ms = interval.as_millis(),
+            max_interval_ms = max_interval.map(|max| max.as_millis()),
+            "Setting locator polling"
+        );
+        self.polling = locator::Polling {
+            interval,
+            max_interval,
+        };
+    }
+
+    /// Keep the system and display awake (no sleep, screensaver or idle lock) while enabled.
+    /// The inhibition is also released when the `Desktop` is dropped.
+    pub fn inhibit_sleep(&mut self, inhibit: bool) -> Result<(), AutomationError> {
+        info!(inhibit, "Setting sleep inhibition");
+        match (inhibit, self.sleep_inhibitor.is_some()) {
+            (true, false) => self.sleep_inhibitor = Some(power::SleepInhibitor::acquire()?),
+            (false, true) => self.sleep_inhibitor = None,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Start sampling CPU, memory and responsiveness of the process `pid` every `interval`,
+    /// replacing any previous monitor. The latest sample is available from [`Desktop::stats`];
+    /// hangs, recoveries and exit are reported through the returned events.
+    /// Must be called from within a tokio runtime.
+    #[instrument(skip(self))]
+    pub fn monitor_process(&mut self, pid: u32, interval: Duration) -> Result<ProcessEvents, AutomationError> {
+        info!(pid, interval_ms = interval.as_millis(), "Monitoring process");
+        let (monitor, events) = process_monitor::ProcessMonitor::spawn(self.engine.clone(), pid, interval)?;
+        self.process_monitor = Some(monitor);
+        Ok(events)
+    }
+
+    /// Stop the monitor started with [`Desktop::monitor_process`]
+    pub fn stop_monitoring_process(&mut self) {
+        info!("Stopping process monitor");
+        self.process_monitor = None;
+    }
+
+    /// Resource usage of the monitored process at its last sample
+    pub fn stats(&self) -> Option<ProcessStats> {
+        self.process_monitor.as_ref().and_then(|monitor| monitor.stats())
+    }
+
+    /// Trigger [`Desktop::abort_all`] whenever `hotkey` (e.g. `ctrl+alt+f12`) is pressed,
+    /// replacing any previous kill switch hotkey. Must be called from within a tokio runtime.
+    #[instrument(skip(self))]
+    pub fn register_kill_switch(&mut self, hotkey: &str) -> Result<(), AutomationError> {
+        info!(hotkey, "Registering kill switch");
+        let clear_overlay = self
+            .visualizer
+            .as_ref()
+            .map(|visualizer| Arc::new(visualizer.clearer()) as Arc<dyn Fn() + Send + Sync>);
+        self.kill_switch = Some(abort::KillSwitch::spawn(self.engine.clone(), hotkey, clear_overlay)?);
+        Ok(())
+    }
+
+    /// Stop watching the kill switch hotkey
+    pub fn unregister_kill_switch(&mut self) {
+        info!("Unregistering kill switch");
+        self.kill_switch = None;
+    }
+
+    /// Handle a popup automatically whenever it appears for the rest of the session, e.g.
+    /// click "Later" when an "Update available" dialog shows up. `trigger` matches the
+    /// popup; a `Click` action's selector is searched inside it. Adding a rule with the
+    /// name of an existing one replaces it. Must be called from within a tokio runtime.
+    ///
+    /// Rules are checked in the background twice a second, between and during the
+    /// workflow's own actions, so keep triggers specific to the popup.
+    #[instrument(skip(self, trigger))]
+    pub fn add_popup_rule(&mut self, name: &str, trigger: Locator, action: PopupAction) {
+        info!(name, ?action, "Adding popup rule");
+        self.popup_handler
+            .get_or_insert_with(popups::PopupHandler::spawn)
+            .add(name, trigger, action);
+    }
+
+    /// Stop handling the popup rule called `name`; returns whether there was one
+    #[instrument(skip(self))]
+    pub fn remove_popup_rule(&mut self, name: &str) -> bool {
+        info!(name, "Removing popup rule");
+        let removed = self
+            .popup_handler
+            .as_ref()
+            .is_some_and(|handler| handler.remove(name));
+        if self.popup_handler.as_ref().is_some_and(|handler| handler.is_empty()) {
+            self.popup_handler = None;
+        }
+        removed
+    }
+
+    /// Stop handling every popup rule
+    pub fn clear_popup_rules(&mut self) {
+        info!("Clearing popup rules");
+        self.popup_handler = None;
+    }
+
+    /// Move the mouse cursor to (`x`, `y`) in screen coordinates without clicking
+    #[instrument(skip(self))]
+    pub fn move_mouse(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        self.root().mouse_move(x, y)
+    }
+
+    /// Drag with the left mouse button from `from` to `to` in screen coordinates, moving
+    /// through intermediate points as `options` describes. Use it to drop onto a spot
+    /// with no element of its own, e.g. between two list items.
+    #[instrument(skip(self))]
+    pub fn drag(&self, from: (f64, f64), to: (f64, f64), options: &DragOptions) -> Result<(), AutomationError> {
+        crate::drag::drag(&self.root(), from, to, options)
+    }
+
+    /// Which modifier keys and mouse buttons are held down right now
+    #[instrument(skip(self))]
+    pub fn input_state(&self) -> Result<InputState, AutomationError> {
+        self.engine.get_input_state()
+    }
+
+    /// Release every modifier key and mouse button left held down, e.g. by a key chord
+    /// interrupted by a panic, and lift any input block. Returns what is still held
+    /// afterwards, which is normally nothing.
+    #[instrument(skip(self))]
+    pub fn reset_input_state(&self) -> Result<InputState, AutomationError> {
+        let before = self.engine.get_input_state()?;
+        info!(?before, "Resetting input state");
+        self.engine.release_input()?;
+        let after = self.engine.get_input_state()?;
+        if !after.is_clear() {
+            warn!(?after, "Input still held after reset");
+        }
+        Ok(after)
+    }
+
+    /// Stop all automation: in-flight waits and every later action fail with
+    /// [`AutomationError::Aborted`] until [`Desktop::clear_abort`] is called. Held modifier
+    /// keys and mouse buttons are released, blocked input is unblocked and the overlay cleared.
+    #[instrument(skip(self))]
+    pub fn abort_all(&self) {
+        let clear_overlay = self.visualizer.as_ref().map(|visualizer| visualizer.clearer());
+        abort::trigger(
+            self.engine.as_ref(),
+            clear_overlay.as_ref().map(|clear| clear as &(dyn Fn() + Send + Sync)),
+        );
+    }
+
+    /// Whether the kill switch has been triggered and not yet cleared
+    pub fn is_aborted(&self) -> bool {
+        abort::is_aborted()
+    }
+
+    /// Allow automation to run again after [`Desktop::abort_all`] or the kill switch hotkey
+    pub fn clear_abort(&self) {
+        info!("Clearing kill switch");
+        abort::reset();
+    }
+
+    /// List the running processes, including ones this workflow didn't launch
+    #[instrument(skip(self))]
+    pub fn processes(&self) -> Result<Vec<ProcessInfo>, AutomationError> {
+        self.engine.list_processes()
+    }
+
+    /// Wait until the process `pid` is no longer running
+    #[instrument(skip(self))]
+    pub async fn wait_for_process_exit(&self, pid: u32, timeout: Duration) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!(pid, "Waiting for process to exit");
+
+        while self.engine.list_processes()?.iter().any(|p| p.pid == pid) {
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "Process {} still running after {:?}",
+                    pid, timeout
+                )));
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(PROCESS_POLL_INTERVAL).await;
+        }
+
+        info!(pid, duration_ms = start.elapsed().as_millis(), "Process exited");
+        Ok(())
+    }
+
+    /// Have [`Desktop::cleanup_session`] terminate the process `pid` too, e.g. one started
+    /// with [`Desktop::command`]. Apps and URLs opened through the desktop are
+    /// tracked already, files opened with [`Desktop::open_file`] are not.
+    #[instrument(skip(self))]
+    pub fn track_process(&self, pid: u32) -> Result<(), AutomationError> {
+        let process = self
+            .engine
+            .list_processes()?
+            .into_iter()
+            .find(|process| process.pid == pid)
+            .ok_or_else(|| AutomationError::ElementNotFound(format!("Process {} is not running", pid)))?;
+        info!(pid, name = %process.name, "Tracking process for cleanup");
+        self.session_tracker.track_process(process);
+        Ok(())
+    }
+
+    /// Close every window and terminate every process the session opened, so the desktop
+    /// is left as it was found. Only the apps launched by [`Desktop::open_application`],
+    /// [`Desktop::open_url`] and [`Desktop::open_file_manager`], their windows and the
+    /// processes passed to [`Desktop::track_process`] are cleaned up; whatever else started
+    /// meanwhile is left alone. Windows opened in apps that were already running (e.g. a
+    /// new browser window) are asked to close; processes the session started are
+    /// terminated along with any children they started.
+    ///
+    /// Fails only if processes can't be listed; what couldn't be cleaned up is in
+    /// [`CleanupReport::failures`].
+    #[instrument(skip(self))]
+    pub async fn cleanup_session(&self) -> Result<CleanupReport, AutomationError> {
+        let start = Instant::now();
+        let report = self.session_tracker.cleanup(self.engine.as_ref()).await?;
+        if !report.is_clean() {
+            warn!(failures = ?report.failures, "Session not fully cleaned up");
+        }
+        info!(duration_ms = start.elapsed().as_millis(), "Session cleanup finished");
+        Ok(report)
+    }
+
+    /// Make sure automation targets a single instance of `app` (an executable name such
+    /// as `notepad.exe` or `Safari`). When it is running more than once, the instance
+    /// whose window was most recently active is picked, and with [`ExtraInstances::Close`]
+    /// the others are closed. Fails with `ElementNotFound` if `app` isn't running.
+    #[instrument(skip(self))]
+    pub async fn ensure_single_instance(
+        &self,
+        app: &str,
+        extras: ExtraInstances,
+    ) -> Result<AppInstance, AutomationError> {
+        let start = Instant::now();
+        info!("Ensuring single application instance");
+
+        let processes = self.engine.list_processes()?;
+        // Stacking order tells which instance was used last; without it, list order decides
+        let placements = self.engine.get_window_placements().unwrap_or_default();
+        let mut ranked = instances::rank_instances(app, &processes, &placements);
+        if ranked.is_empty() {
+            return Err(AutomationError::ElementNotFound(format!(
+                "Application '{}' is not running",
+                app
+            )));
+        }
+        let pid = ranked.remove(0);
+
+        if !ranked.is_empty() {
+            warn!(pid, extra_pids = ?ranked, "Application is running more than once");
+        }
+        if extras == ExtraInstances::Close {
+            for &extra in &ranked {
+                let closed = match self.engine.terminate_process(extra) {
+                    Ok(()) => self.wait_for_process_exit(extra, INSTANCE_EXIT_TIMEOUT).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = closed {
+                    warn!(pid = extra, error = %e, "Failed to close extra instance");
+                }
+            }
+        }
+
+        let application = self.engine.get_application_by_pid(pid as i32)?.with_engine(self.engine.clone());
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            pid,
+            extra_count = ranked.len(),
+            "Application instance selected"
+        );
+
+        Ok(AppInstance {
+            pid,
+            application,
+            extras: ranked,
+        })
+    }
+
+    /// List the interactive sessions on this machine
+    #[instrument(skip(self))]
+    pub fn sessions(&self) -> Result<Vec<SessionInfo>, AutomationError> {
+        self.engine.list_sessions()
+    }
+
+    /// Bind this desktop to the session `session_id`. Fails if input from this process
+    /// cannot reach that session (it is another user's session, or it is disconnected).
+    /// From then on every input action checks that the session still receives input
+    /// and fails with a clear error otherwise, e.g. once an RDP client disconnects. The
+    /// binding is process-wide, like the kill switch.
+    #[instrument(skip(self))]
+    pub fn bind_to_session(&mut self, session_id: u32) -> Result<(), AutomationError> {
+        info!(session_id, "Binding to session");
+        session::check_input_session(&self.sessions()?, session_id)?;
+        session::bind(self.engine.clone(), session_id);
+        Ok(())
+    }
+
+    /// Verify that the bound session (or, if unbound, the current one) still receives input,
+    /// e.g. that an RDP client hasn't disconnected mid-run
+    #[instrument(skip(self))]
+    pub fn check_input_session(&self) -> Result<(), AutomationError> {
+        let session_id = match session::bound_session() {
+            Some(id) => id,
+            None => self
+                .sessions()?
+                .iter()
+                .find(|s| s.is_current)
+                .map(|s| s.id)
+                .ok_or_else(|| AutomationError::platform("Could not determine the current session"))?,
+        };
+        session::check_input(self.engine.as_ref(), session_id)
+    }
+
+    /// Whether the session this process runs in is locked. Clicks and keystrokes
+    /// sent while it is locked never reach the applications.
+    #[instrument(skip(self))]
+    pub fn is_session_locked(&self) -> Result<bool, AutomationError> {
+        self.engine.is_session_locked()
+    }
+
+    /// Time since the last keyboard or mouse input in this session, from the user
+    /// or from automation
+    #[instrument(skip(self))]
+    pub fn idle_duration(&self) -> Result<Duration, AutomationError> {
+        self.engine.get_idle_duration()
+    }
+
+    /// Unlock this session by entering `password` on the lock screen, then wait up to
+    /// `timeout` for it to unlock. Does nothing if the session isn't locked.
+    ///
+    /// Only works where the platform lets this process type into the lock screen, e.g.
+    /// on Windows a service attached to the console session; elsewhere it fails with
+    /// `PermissionDenied` or `UnsupportedOperation`.
+    #[instrument(skip(self, password))]
+    pub async fn unlock_with_credentials(
+        &self,
+        password: &SecretString,
+        timeout: Duration,
+    ) -> Result<(), AutomationError> {
+        if !self.engine.is_session_locked()? {
+            return Ok(());
+        }
+        let start = Instant::now();
+        info!("Unlocking session");
+        self.engine.unlock_session(password.expose_secret())?;
+
+        while self.engine.is_session_locked()? {
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::VerificationFailed(format!(
+                    "Session still locked after {:?}; the password may be wrong",
+                    timeout
+                )));
+            }
+            abort::check()?;
+            tokio::time::sleep(SESSION_POLL_INTERVAL).await;
+        }
+
+        info!(duration_ms = start.elapsed().as_millis(), "Session unlocked");
+        Ok(())
+    }
+
+    /// Log in to `target` through a login dialog, such as a Windows Security prompt, a
+    /// browser's basic-auth dialog or an app's sign-in window, with credentials from
+    /// `provider`.
+    ///
+    /// Fills the dialog in `window`, or else the first visible window showing a password
+    /// field, waiting for one to appear. The user name is filled into the text field
+    /// before the password field, the password is typed as a secret, and the dialog is
+    /// submitted with its OK or sign-in button, or Enter. Fails with `VerificationFailed`
+    /// if the password field is still showing afterwards, e.g. because the credentials
+    /// were rejected.
+    #[instrument(skip(self, provider, window))]
+    pub async fn login(
+        &self,
+        provider: &dyn CredentialProvider,
+        target: &str,
+        window: Option<&UIElement>,
+    ) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!("Logging in");
+        let credentials = provider.credentials(target)?;
+        login::login(&self.engine, &credentials, window).await?;
+        info!(duration_ms = start.elapsed().as_millis(), "Logged in");
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     pub fn focused_element(&self) -> Result<UIElement, AutomationError> {
         let start = Instant::now();
         info!("Getting focused element");
         
-        let element = self.engine.get_focused_element()?;
+        let element = self.engine.get_focused_element()?.with_engine(self.engine.clone());
         
         let duration = start.elapsed();
         info!(
@@ -141,12 +775,126 @@ impl Desktop {
         Ok(element)
     }
 
+    /// Wait until any of `locators` matches, e.g. either the success toast or the error
+    /// dialog. Returns the index of the locator that matched and its element; if several
+    /// match in the same round, the one listed first wins.
+    #[instrument(skip(self, locators))]
+    pub async fn wait_for_any(
+        &self,
+        locators: &[Locator],
+        timeout: Duration,
+    ) -> Result<(usize, UIElement), AutomationError> {
+        let start = Instant::now();
+        info!(locator_count = locators.len(), "Waiting for any locator to match");
+        if locators.is_empty() {
+            return Err(AutomationError::InvalidArgument(
+                "wait_for_any needs at least one locator".to_string(),
+            ));
+        }
+
+        loop {
+            for (index, locator) in locators.iter().enumerate() {
+                match locator.find_once(RACE_SEARCH_TIMEOUT) {
+                    Ok(element) => {
+                        info!(
+                            duration_ms = start.elapsed().as_millis(),
+                            index,
+                            element = %element.describe(),
+                            "Locator matched"
+                        );
+                        return Ok((index, element));
+                    }
+                    Err(e) if e.is_retryable() => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "None of {} locators matched within {:?}",
+                    locators.len(),
+                    timeout
+                )));
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(RACE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Press Tab until the element matched by `locator` has keyboard focus, at most
+    /// `max_tabs` times. Returns the focused element.
+    ///
+    /// For apps where clicks are unreliable but the tab order is deterministic.
+    /// Focus counts as reached if it lands on the target or inside it (e.g. the edit
+    /// box of a combo box).
+    #[instrument(skip(self, locator))]
+    pub async fn tab_to(&self, locator: &Locator, max_tabs: usize) -> Result<UIElement, AutomationError> {
+        self.traverse_focus(locator, max_tabs, TAB_KEYS.0).await
+    }
+
+    /// Like [`Desktop::tab_to`], but moving backwards with Shift+Tab
+    #[instrument(skip(self, locator))]
+    pub async fn shift_tab_to(&self, locator: &Locator, max_tabs: usize) -> Result<UIElement, AutomationError> {
+        self.traverse_focus(locator, max_tabs, TAB_KEYS.1).await
+    }
+
+    async fn traverse_focus(
+        &self,
+        locator: &Locator,
+        max_tabs: usize,
+        key: &str,
+    ) -> Result<UIElement, AutomationError> {
+        let start = Instant::now();
+        let target = locator.first(None).await?;
+        info!(target = %target.describe(), max_tabs, key, "Moving focus with the keyboard");
+
+        let mut focused = self.engine.get_focused_element()?;
+        for presses in 0..=max_tabs {
+            if focus_is_within(&focused, &target) {
+                info!(
+                    duration_ms = start.elapsed().as_millis(),
+                    presses,
+                    "Target element focused"
+                );
+                return Ok(focused);
+            }
+            if presses == max_tabs {
+                break;
+            }
+            focused.press_key(key)?;
+            focused = self.wait_for_focus_change(&focused).await?;
+        }
+
+        Err(AutomationError::ElementNotFound(format!(
+            "{} not focused after {} key presses",
+            target.describe(),
+            max_tabs
+        )))
+    }
+
+    /// Wait briefly for focus to leave `previous`; returns whatever is focused then
+    async fn wait_for_focus_change(&self, previous: &UIElement) -> Result<UIElement, AutomationError> {
+        let start = Instant::now();
+        loop {
+            let focused = self.engine.get_focused_element()?;
+            if &focused != previous || start.elapsed() >= FOCUS_CHANGE_TIMEOUT {
+                return Ok(focused);
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(FOCUS_POLL_INTERVAL).await;
+        }
+    }
+
     #[instrument(skip(self))]
     pub fn applications(&self) -> Result<Vec<UIElement>, AutomationError> {
         let start = Instant::now();
         info!("Getting all applications");
         
-        let apps = self.engine.get_applications()?;
+        let apps: Vec<UIElement> = self
+            .engine
+            .get_applications()?
+            .into_iter()
+            .map(|app| app.with_engine(self.engine.clone()))
+            .collect();
         
         let duration = start.elapsed();
         info!(
@@ -163,7 +911,7 @@ impl Desktop {
         let start = Instant::now();
         info!(app_name = name, "Getting application by name");
         
-        let app = self.engine.get_application_by_name(name)?;
+        let app = self.engine.get_application_by_name(name)?.with_engine(self.engine.clone());
         
         let duration = start.elapsed();
         info!(
@@ -180,7 +928,9 @@ impl Desktop {
         let start = Instant::now();
         info!(app_name, "Opening application");
         
-        self.engine.open_application(app_name)?;
+        let before = cleanup::SessionTracker::snapshot(self.engine.as_ref());
+        let app = self.engine.open_application(app_name)?;
+        self.session_tracker.record_launch(self.engine.as_ref(), before, &app);
         
         let duration = start.elapsed();
         info!(
@@ -212,7 +962,10 @@ impl Desktop {
         let start = Instant::now();
         info!(url, ?browser, "Opening URL");
         
-        self.engine.open_url(url, browser)?;
+        let before = cleanup::SessionTracker::snapshot(self.engine.as_ref());
+        let browser_window = self.engine.open_url(url, browser)?;
+        self.session_tracker
+            .record_launch(self.engine.as_ref(), before, &browser_window);
         
         let duration = start.elapsed();
         info!(
@@ -223,6 +976,8 @@ impl Desktop {
         Ok(())
     }
 
+    /// Open `file_path` with its default app. The app isn't known, so it isn't closed by
+    /// [`Desktop::cleanup_session`]; pass its process to [`Desktop::track_process`] for that.
     #[instrument(skip(self, file_path))]
     pub fn open_file(&self, file_path: &str) -> Result<(), AutomationError> {
         let start = Instant::now();
@@ -262,6 +1017,17 @@ impl Desktop {
         Ok(output)
     }
 
+    /// Configure a command with a working directory, environment, stdin input, a timeout
+    /// or streamed output. Runs `windows_command` in PowerShell on Windows and
+    /// `unix_command` in `/bin/sh` elsewhere.
+    pub fn command(
+        &self,
+        windows_command: Option<&str>,
+        unix_command: Option<&str>,
+    ) -> Result<CommandBuilder, AutomationError> {
+        CommandBuilder::new(windows_command, unix_command)
+    }
+
     #[instrument(skip(self))]
     pub async fn capture_screen(&self) -> Result<ScreenshotResult, AutomationError> {
         let start = Instant::now();
@@ -367,7 +1133,11 @@ impl Desktop {
         let start = Instant::now();
         info!(?title_contains, ?timeout, "Finding window by criteria");
         
-        let window = self.engine.find_window_by_criteria(title_contains, timeout).await?;
+        let window = self
+            .engine
+            .find_window_by_criteria(title_contains, timeout)
+            .await?
+            .with_engine(self.engine.clone());
         
         let duration = start.elapsed();
         info!(
@@ -379,12 +1149,81 @@ impl Desktop {
         Ok(window)
     }
 
+    /// The regions of `window` rendered by an embedded web engine (WebView2, WKWebView,
+    /// CEF, Electron). Their page content only appears in the tree once the engine
+    /// builds it, see [`WebView::wait_until_ready`].
+    #[instrument(skip(self, window))]
+    pub fn webviews(&self, window: &UIElement) -> Result<Vec<WebView>, AutomationError> {
+        let start = Instant::now();
+        let webviews = webview::find_webviews(&self.engine, window)?;
+        info!(
+            duration_ms = start.elapsed().as_millis(),
+            kinds = ?webviews.iter().map(WebView::kind).collect::<Vec<_>>(),
+            "Web views found"
+        );
+        Ok(webviews)
+    }
+
+    /// Every element in a visible top-level window whose name or value contains `text`,
+    /// ignoring case, e.g. to find which app shows an order number. See
+    /// [`Desktop::find_text_everywhere_with`] to also search by OCR.
+    pub async fn find_text_everywhere(&self, text: &str) -> Result<Vec<TextHit>, AutomationError> {
+        self.find_text_everywhere_with(text, &TextSearchOptions::default()).await
+    }
+
+    /// Like [`Desktop::find_text_everywhere`], searching as `options` says
+    #[instrument(skip(self))]
+    pub async fn find_text_everywhere_with(
+        &self,
+        text: &str,
+        options: &TextSearchOptions,
+    ) -> Result<Vec<TextHit>, AutomationError> {
+        let start = Instant::now();
+        info!("Searching all windows for text");
+        let hits = search::find_text_everywhere(&self.engine, text, options)?;
+        info!(
+            duration_ms = start.elapsed().as_millis(),
+            count = hits.len(),
+            "Text search finished"
+        );
+        Ok(hits)
+    }
+
+    /// Find every top-level window matching `criteria`, waiting up to `timeout` for
+    /// at least one to appear. Unlike [`Desktop::find_window_by_criteria`] this can
+    /// match the title against a regex and filter by class, process and visibility.
+    #[instrument(skip(self, timeout))]
+    pub async fn find_windows_by_criteria(
+        &self,
+        criteria: &WindowCriteria,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<UIElement>, AutomationError> {
+        let start = Instant::now();
+        info!(?timeout, "Finding windows by criteria");
+
+        let windows: Vec<UIElement> = self
+            .engine
+            .find_windows(criteria, timeout)?
+            .into_iter()
+            .map(|window| window.with_engine(self.engine.clone()))
+            .collect();
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            count = windows.len(),
+            "Windows found"
+        );
+
+        Ok(windows)
+    }
+
     #[instrument(skip(self))]
     pub async fn get_current_browser_window(&self) -> Result<UIElement, AutomationError> {
         let start = Instant::now();
         info!("Getting current browser window");
         
-        let window = self.engine.get_current_browser_window().await?;
+        let window = self.engine.get_current_browser_window().await?.with_engine(self.engine.clone());
         
         let duration = start.elapsed();
         info!(
@@ -395,7 +1234,529 @@ impl Desktop {
         
         Ok(window)
     }
+
+    /// Read the URL of the active tab in `window`, or in the focused browser window if `None`
+    #[instrument(skip(self, window))]
+    pub async fn browser_url(&self, window: Option<&UIElement>) -> Result<String, AutomationError> {
+        let window = self.browser_window(window).await?;
+        let url = browser::current_url(&self.engine, &window).await?;
+        info!(url, "Browser URL read");
+        Ok(url)
+    }
+
+    /// List the tabs of `window`, or of the focused browser window if `None`
+    #[instrument(skip(self, window))]
+    pub async fn browser_tabs(&self, window: Option<&UIElement>) -> Result<Vec<BrowserTab>, AutomationError> {
+        let window = self.browser_window(window).await?;
+        let tabs = browser::tabs(&self.engine, &window).await?;
+        info!(tab_count = tabs.len(), "Browser tabs listed");
+        Ok(tabs)
+    }
+
+    /// Switch to a tab by title (substring, case-insensitive) or index, e.g.
+    /// `select_tab("Inbox", None)` or `select_tab(2, None)`
+    #[instrument(skip(self, target, window))]
+    pub async fn select_tab(
+        &self,
+        target: impl Into<TabTarget>,
+        window: Option<&UIElement>,
+    ) -> Result<BrowserTab, AutomationError> {
+        let target = target.into();
+        info!(?target, "Selecting browser tab");
+        let window = self.browser_window(window).await?;
+        browser::select_tab(&self.engine, &window, &target).await
+    }
+
+    /// Load `url` in `window` (or the focused browser window) through its address bar and
+    /// wait for the page title to change, or for the address bar to show `url` once it
+    /// loses focus (reloads, pages with a fixed title). Returns the title.
+    #[instrument(skip(self, window))]
+    pub async fn browser_navigate(
+        &self,
+        url: &str,
+        window: Option<&UIElement>,
+        timeout: Option<Duration>,
+    ) -> Result<String, AutomationError> {
+        let start = Instant::now();
+        info!(url, "Navigating browser");
+
+        let window = self.browser_window(window).await?;
+        let title = browser::navigate(
+            &self.engine,
+            &window,
+            url,
+            timeout.unwrap_or(Duration::from_secs(30)),
+        )
+        .await?;
+
+        let duration = start.elapsed();
+        info!(duration_ms = duration.as_millis(), title, "Browser navigated");
+
+        Ok(title)
+    }
+
+    async fn browser_window(&self, window: Option<&UIElement>) -> Result<UIElement, AutomationError> {
+        match window {
+            Some(window) => Ok(window.clone()),
+            // The focused browser element may be deep inside the page
+            None => Ok(browser::top_level_window(
+                &self.engine.get_current_browser_window().await?,
+            )),
+        }
+    }
     
+    /// List the connected monitors
+    #[instrument(skip(self))]
+    pub fn monitors(&self) -> Result<Vec<MonitorInfo>, AutomationError> {
+        self.engine.list_monitors()
+    }
+
+    /// Watch for monitor hot-plug, resolution and DPI changes.
+    ///
+    /// The display configuration is polled every `poll_interval`; coordinates
+    /// saved before a reported change (window layouts, click points, capture
+    /// regions) should be re-read. Must be called from within a tokio runtime.
+    #[instrument(skip(self))]
+    pub fn watch_displays(&self, poll_interval: Duration) -> Result<DisplayWatcher, AutomationError> {
+        info!(poll_interval_ms = poll_interval.as_millis(), "Watching display configuration");
+        DisplayWatcher::spawn(self.engine.clone(), poll_interval)
+    }
+
+    /// Capture small frames of the primary monitor every `options.interval` until the
+    /// recorder is finished, then lay them out in one filmstrip image as a visual summary
+    /// of an unattended run. Must be called from within a tokio runtime.
+    #[instrument(skip(self))]
+    pub fn record_timeline(&self, options: TimelineOptions) -> Result<TimelineRecorder, AutomationError> {
+        info!(interval_ms = options.interval.as_millis(), "Recording screen timeline");
+        TimelineRecorder::spawn(self.engine.clone(), options)
+    }
+
+    /// The system color theme: dark or light, and the accent color
+    #[instrument(skip(self))]
+    pub fn current_theme(&self) -> Result<Theme, AutomationError> {
+        self.engine.get_theme()
+    }
+
+    /// How the user's regional settings write numbers, money and dates, for reading
+    /// values off the screen with [`Locale::parse_number`] and friends
+    #[instrument(skip(self))]
+    pub fn current_locale(&self) -> Result<Locale, AutomationError> {
+        self.engine.get_locale()
+    }
+
+    /// Whether a screen reader is running, and which. Accessibility trees, events and
+    /// keyboard input behave differently while one is active, see
+    /// [`ScreenReaderStatus::effects`].
+    #[instrument(skip(self))]
+    pub fn screen_reader(&self) -> Result<ScreenReaderStatus, AutomationError> {
+        let status = screen_reader::detect(self.engine.as_ref())?;
+        info!(
+            active = status.is_active(),
+            flag_set = status.flag_set,
+            running = ?status.running,
+            "Screen reader status read"
+        );
+        Ok(status)
+    }
+
+    /// Sample the color of a single screen pixel
+    #[instrument(skip(self))]
+    pub fn get_pixel(&self, x: i32, y: i32) -> Result<drawing::Color, AutomationError> {
+        let image = screen::capture_region(x, y, 1, 1)?;
+        let [r, g, b, _] = image.get_pixel(0, 0).0;
+        Ok(drawing::Color { r, g, b, a: 255 })
+    }
+
+    /// Screenshot many elements at once, keyed by element id.
+    ///
+    /// Each monitor is captured once and every element is cropped from that frame,
+    /// which is much faster than capturing elements one by one (e.g. to document a
+    /// whole form) and shows them all at the same instant. Elements without an id
+    /// are skipped.
+    #[instrument(skip(self, elements))]
+    pub fn screenshot_element_batch(
+        &self,
+        elements: &[UIElement],
+    ) -> Result<HashMap<String, ScreenshotResult>, AutomationError> {
+        let start = Instant::now();
+        info!(element_count = elements.len(), "Capturing element screenshots");
+
+        let mut ids = Vec::with_capacity(elements.len());
+        let mut regions = Vec::with_capacity(elements.len());
+        for element in elements {
+            let Some(id) = element.id() else {
+                warn!("Skipping element without an id");
+                continue;
+            };
+            let (x, y, width, height) = element.bounds()?;
+            ids.push(id);
+            regions.push((
+                x.round() as i32,
+                y.round() as i32,
+                width.round() as u32,
+                height.round() as u32,
+            ));
+        }
+
+        let mut screenshots = HashMap::with_capacity(ids.len());
+        for (id, image) in ids.into_iter().zip(screen::capture_regions(&regions)) {
+            let image = image?;
+            screenshots.insert(
+                id,
+                ScreenshotResult {
+                    width: image.width(),
+                    height: image.height(),
+                    image_data: image.into_raw(),
+                },
+            );
+        }
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            screenshot_count = screenshots.len(),
+            "Element screenshots captured"
+        );
+
+        Ok(screenshots)
+    }
+
+    /// Open the system file manager (File Explorer / Finder) at the folder `path`
+    #[instrument(skip(self))]
+    pub async fn open_file_manager(&self, path: &str) -> Result<FileManager, AutomationError> {
+        let start = Instant::now();
+        info!(path, "Opening file manager");
+
+        let before = cleanup::SessionTracker::snapshot(self.engine.as_ref());
+        self.engine.open_file(path)?;
+        // File manager windows are titled after the folder they show
+        let folder = std::path::Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        let window = self
+            .engine
+            .find_window_by_criteria(Some(&folder), Some(Duration::from_secs(10)))
+            .await;
+        let window = window?;
+        self.session_tracker
+            .record_launch(self.engine.as_ref(), before, &window);
+
+        let duration = start.elapsed();
+        info!(duration_ms = duration.as_millis(), "File manager opened");
+
+        Ok(FileManager::new(self.engine.clone(), window))
+    }
+
+    /// Poll the screen until `template` appears and return where it was found.
+    ///
+    /// `region` is `(x, y, width, height)` in screen coordinates and must lie on a
+    /// single monitor; with `None` the primary monitor is searched. `threshold` is
+    /// the minimum match confidence (0.0-1.0), 0.9 is a good starting point.
+    #[instrument(skip(self, template))]
+    pub async fn wait_for_image(
+        &self,
+        template: &ScreenshotResult,
+        region: Option<(i32, i32, u32, u32)>,
+        threshold: f32,
+        timeout: Duration,
+    ) -> Result<ImageMatch, AutomationError> {
+        let start = Instant::now();
+        info!(
+            template_width = template.width,
+            template_height = template.height,
+            "Waiting for image"
+        );
+
+        let template_image = vision::to_image(template)?;
+        let (x, y, width, height) = match region {
+            Some(region) => region,
+            None => {
+                let primary = self
+                    .monitors()?
+                    .into_iter()
+                    .find(|m| m.is_primary)
+                    .ok_or_else(|| AutomationError::platform("Could not find primary monitor"))?;
+                (primary.x, primary.y, primary.width, primary.height)
+            }
+        };
+
+        let mut best_confidence = 0.0f32;
+        loop {
+            let frame = screen::capture_region(x, y, width, height)?;
+            // Frames can be captured at a higher density than screen coordinates
+            let scale = frame.width() as f64 / width as f64;
+            if let Some((mx, my, confidence)) = vision::find_template(&frame, &template_image) {
+                if confidence >= threshold {
+                    let found = ImageMatch {
+                        x: x as f64 + mx as f64 / scale,
+                        y: y as f64 + my as f64 / scale,
+                        width: template.width as f64 / scale,
+                        height: template.height as f64 / scale,
+                        confidence,
+                    };
+                    info!(
+                        duration_ms = start.elapsed().as_millis(),
+                        confidence,
+                        "Image found"
+                    );
+                    return Ok(found);
+                }
+                best_confidence = best_confidence.max(confidence);
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "Image not found within {:?} (best confidence {:.2}, threshold {:.2})",
+                    timeout, best_confidence, threshold
+                )));
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(IMAGE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Find an anchor image on the primary monitor and click at `offset` (x, y) from
+    /// its center, e.g. to hit a canvas control next to a stable icon.
+    ///
+    /// Waits up to `timeout` for the anchor to appear with at least 0.9 confidence.
+    #[instrument(skip(self, template))]
+    pub async fn click_relative_to_image(
+        &self,
+        template: &ScreenshotResult,
+        offset: (f64, f64),
+        timeout: Duration,
+    ) -> Result<ClickResult, AutomationError> {
+        let anchor = self
+            .wait_for_image(template, None, ANCHOR_IMAGE_THRESHOLD, timeout)
+            .await?;
+        let (center_x, center_y) = anchor.center();
+        let (x, y) = (center_x + offset.0, center_y + offset.1);
+        info!(x, y, confidence = anchor.confidence, "Clicking relative to anchor image");
+
+        let root = self.root();
+        root.mouse_click_and_hold(x, y)?;
+        root.mouse_release()?;
+
+        Ok(ClickResult {
+            method: "Image Anchor".to_string(),
+            coordinates: Some((x, y)),
+            details: format!(
+                "Clicked at offset ({}, {}) from anchor found at ({:.0}, {:.0})",
+                offset.0, offset.1, anchor.x, anchor.y
+            ),
+            context: Some(ActionContext::capture(self.engine.as_ref())),
+        })
+    }
+
+    /// Fill in the fields of a form in `window`, given as `(label, value)` pairs.
+    ///
+    /// Each field is found by its caption like a `label:` selector, set to its value and
+    /// read back; a field that doesn't take its value is re-typed once. A field that
+    /// can't be found or set doesn't stop the others: check the returned report rather
+    /// than relying on `Ok`. Only a kill switch abort ends the fill early.
+    #[instrument(skip(self, window, fields))]
+    pub async fn fill_form<L: AsRef<str>, V: AsRef<str>>(
+        &self,
+        window: &UIElement,
+        fields: &[(L, V)],
+    ) -> Result<FormReport, AutomationError> {
+        let start = Instant::now();
+        info!(field_count = fields.len(), "Filling form");
+
+        let mut report = FormReport::default();
+        for (label, value) in fields {
+            let label = label.as_ref();
+            let locator = self
+                .locator(Selector::Label(label.to_string()))
+                .within(window.clone());
+            let outcome = match locator.wait(Some(FORM_FIELD_TIMEOUT)).await {
+                Ok(field) => form::fill_field(&field, value.as_ref()).await?,
+                Err(e) => match e.inner() {
+                    AutomationError::Aborted(_) => return Err(e),
+                    AutomationError::ElementNotFound(_) | AutomationError::Timeout(_) => {
+                        FieldOutcome::NotFound
+                    }
+                    _ => FieldOutcome::Failed(e.to_string()),
+                },
+            };
+            let field = FieldReport {
+                label: label.to_string(),
+                outcome,
+            };
+            if !field.succeeded() {
+                warn!(label, outcome = ?field.outcome, "Form field not filled");
+            }
+            report.fields.push(field);
+        }
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            complete = report.is_complete(),
+            "Form filled"
+        );
+
+        Ok(report)
+    }
+
+    /// Capture the position, size, monitor and stacking order of all visible top-level windows
+    #[instrument(skip(self))]
+    pub fn save_window_layout(&self) -> Result<WindowLayout, AutomationError> {
+        let start = Instant::now();
+        info!("Saving window layout");
+
+        let windows = self.engine.get_window_placements()?;
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            window_count = windows.len(),
+            "Window layout saved"
+        );
+
+        Ok(WindowLayout { windows })
+    }
+
+    /// Put windows back the way they were in `layout`, including their stacking order.
+    /// Windows that have closed since the snapshot are skipped.
+    /// Returns the number of windows restored.
+    #[instrument(skip(self, layout))]
+    pub fn restore_window_layout(&self, layout: &WindowLayout) -> Result<usize, AutomationError> {
+        let start = Instant::now();
+        info!(window_count = layout.windows.len(), "Restoring window layout");
+
+        let mut placements: Vec<&WindowPlacement> = layout.windows.iter().collect();
+        // Restore bottom-up so each window ends up above the ones placed before it
+        placements.sort_by_key(|p| std::cmp::Reverse(p.z_order));
+
+        let mut restored = 0;
+        for placement in placements {
+            match self.engine.set_window_placement(placement) {
+                Ok(()) => restored += 1,
+                Err(e) => warn!(title = %placement.title, error = %e, "Skipping window"),
+            }
+        }
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            restored,
+            "Window layout restored"
+        );
+
+        Ok(restored)
+    }
+
+    /// Get the element at the given screen coordinates
+    #[instrument(skip(self))]
+    pub fn element_at_point(&self, x: f64, y: f64) -> Result<UIElement, AutomationError> {
+        Ok(self.engine.get_element_at_point(x, y)?.with_engine(self.engine.clone()))
+    }
+
+    /// Wait for the user to click an element on screen and return it with a suggested selector.
+    ///
+    /// The click is not swallowed, so it also reaches the application underneath.
+    /// With no timeout this waits until something is picked.
+    #[instrument(skip(self))]
+    pub async fn pick_element(&self, timeout: Option<Duration>) -> Result<PickedElement, AutomationError> {
+        let start = Instant::now();
+        info!("Waiting for an element to be picked");
+
+        if let Err(e) = self.show_popup(
+            "Click an element to pick it",
+            Duration::from_secs(3),
+            Some(drawing::PopupStyle::Info),
+        ) {
+            warn!(error = %e, "Failed to show pick prompt");
+        }
+
+        let picked = inspect::wait_for_pick(&self.engine, timeout).await?;
+
+        if let Err(e) = self.highlight_elements(std::slice::from_ref(&picked.element), None, None) {
+            warn!(error = %e, "Failed to highlight picked element");
+        }
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            selector = %picked.selector,
+            "Element picked"
+        );
+
+        Ok(picked)
+    }
+
+    /// Run a hover-inspection session: while toggled on (see [`InspectorOptions::toggle_key`]),
+    /// the element under the cursor is highlighted with a label showing its role, name,
+    /// automation id and suggested selector, and passed to `on_inspect`.
+    /// Returns when the exit key is pressed, or with an error when the kill switch trips.
+    #[instrument(skip(self, options, on_inspect))]
+    pub async fn run_inspector(
+        &self,
+        options: InspectorOptions,
+        mut on_inspect: impl FnMut(&InspectedElement),
+    ) -> Result<(), AutomationError> {
+        info!(
+            toggle_key = %options.toggle_key,
+            exit_key = %options.exit_key,
+            "Inspector running"
+        );
+
+        let mut active = false;
+        let mut toggle_was_down = false;
+        let mut last_element: Option<UIElement> = None;
+
+        loop {
+            // The kill switch ends the session too; don't leave the badge on screen
+            if let Err(e) = crate::abort::check() {
+                let _ = self.clear_visualizations();
+                return Err(e);
+            }
+            if self.engine.is_key_pressed(&options.exit_key)? {
+                break;
+            }
+
+            let toggle_down = self.engine.is_key_pressed(&options.toggle_key)?;
+            if toggle_down && !toggle_was_down {
+                active = !active;
+                info!(active, "Inspector toggled");
+                if !active {
+                    last_element = None;
+                    self.clear_visualizations()?;
+                }
+            }
+            toggle_was_down = toggle_down;
+
+            if active {
+                let mouse = self.engine.get_mouse_state()?;
+                // The cursor can be over something without an accessible element (e.g. the desktop)
+                if let Ok(element) = self.engine.get_element_at_point(mouse.x, mouse.y)
+                    && last_element.as_ref() != Some(&element)
+                {
+                    let inspected = InspectedElement::from_element(&element);
+                    info!(label = %inspected.label(), "Inspecting element");
+                    self.highlight_elements(
+                        std::slice::from_ref(&element),
+                        Some(drawing::HighlightStyle::Badge {
+                            text: inspected.label(),
+                            position: drawing::Corner::TopLeft,
+                        }),
+                        None,
+                    )?;
+                    on_inspect(&inspected);
+                    last_element = Some(element);
+                }
+            }
+
+            tokio::time::sleep(options.poll_interval).await;
+        }
+
+        self.clear_visualizations()?;
+        info!("Inspector stopped");
+        Ok(())
+    }
+
     // Visualization methods
     
     /// Highlight UI elements on screen
@@ -429,6 +1790,33 @@ impl Desktop {
         Ok(())
     }
     
+    /// Number elements as the steps of a walkthrough, with a legend popup of their labels.
+    /// The annotations stay up until [`Desktop::clear_visualizations`].
+    #[instrument(skip(self, steps))]
+    pub fn annotate_steps<S: AsRef<str>>(&self, steps: &[(UIElement, S)]) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!(step_count = steps.len(), "Annotating steps");
+
+        if let Some(visualizer) = &self.visualizer {
+            if !visualizer.is_enabled() {
+                warn!("Visualization engine is not enabled");
+                return Ok(());
+            }
+
+            visualizer.annotate_steps(steps)?;
+
+            let duration = start.elapsed();
+            info!(
+                duration_ms = duration.as_millis(),
+                "Steps annotated"
+            );
+        } else {
+            warn!("Visualization engine not available");
+        }
+
+        Ok(())
+    }
+
     /// Show a popup message on screen
     #[instrument(skip(self, message, duration, style))]
     pub fn show_popup(
@@ -460,6 +1848,76 @@ impl Desktop {
         Ok(())
     }
     
+    /// Show a popup message on a specific monitor, or on the one the user is looking at
+    #[instrument(skip(self, message, duration, style))]
+    pub fn show_popup_on(
+        &self,
+        message: &str,
+        duration: Duration,
+        style: Option<drawing::PopupStyle>,
+        target: drawing::DisplayTarget,
+    ) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!(message, ?duration, ?target, "Showing popup");
+
+        if let Some(visualizer) = &self.visualizer {
+            if !visualizer.is_enabled() {
+                warn!("Visualization engine is not enabled");
+                return Ok(());
+            }
+
+            let area = self.display_area(&target)?;
+            visualizer.show_popup_in(message, duration, style, area)?;
+
+            let duration = start.elapsed();
+            info!(
+                duration_ms = duration.as_millis(),
+                "Popup shown"
+            );
+        } else {
+            warn!("Visualization engine not available");
+        }
+
+        Ok(())
+    }
+
+    /// Bounds of the monitor a `DisplayTarget` refers to
+    fn display_area(&self, target: &drawing::DisplayTarget) -> Result<drawing::Rect, AutomationError> {
+        let monitors = self.engine.list_monitors()?;
+        let point = match target {
+            drawing::DisplayTarget::Primary => None,
+            drawing::DisplayTarget::Monitor(name) => {
+                let monitor = monitors.iter().find(|m| &m.name == name).ok_or_else(|| {
+                    AutomationError::InvalidArgument(format!("No monitor named '{}'", name))
+                })?;
+                return Ok(monitor_rect(monitor));
+            }
+            drawing::DisplayTarget::ActiveWindow => {
+                let (x, y, width, height) = self.engine.get_focused_element()?.bounds()?;
+                Some((x + width / 2.0, y + height / 2.0))
+            }
+            drawing::DisplayTarget::Cursor => {
+                let mouse = self.engine.get_mouse_state()?;
+                Some((mouse.x, mouse.y))
+            }
+        };
+
+        let monitor = match point {
+            Some((x, y)) => monitors.iter().find(|m| {
+                x >= m.x as f64
+                    && x < m.x as f64 + m.width as f64
+                    && y >= m.y as f64
+                    && y < m.y as f64 + m.height as f64
+            }),
+            None => None,
+        };
+        monitor
+            .or_else(|| monitors.iter().find(|m| m.is_primary))
+            .or_else(|| monitors.first())
+            .map(monitor_rect)
+            .ok_or_else(|| AutomationError::platform("No monitors found"))
+    }
+
     /// Start the visualization engine
     #[instrument(skip(self))]
     pub fn start_visualization(&mut self) -> Result<(), AutomationError> {
@@ -553,3 +2011,12 @@ impl Desktop {
         Ok(())
     }
 }
+
+fn monitor_rect(monitor: &MonitorInfo) -> drawing::Rect {
+    drawing::Rect {
+        x: monitor.x as f32,
+        y: monitor.y as f32,
+        width: monitor.width as f32,
+        height: monitor.height as f32,
+    }
+}
@@ -0,0 +1,369 @@
+//! Reading and writing numbers and dates the way a locale does
+//!
+//! The same total reads `1,234.56 $` on one machine and `1.234,56 €` on
+//! another, and `03/04/2024` is a different day in the US than in Germany.
+//! Guessing from the text alone (as [`crate::UIElement::value_as_f64`] does)
+//! can't tell `1.234` apart from `1,234`. A [`Locale`] knows the separators and
+//! date order, taken from the machine (see [`crate::Desktop::current_locale`])
+//! or from a tag, so verification reads UI text the way the app wrote it.
+
+use crate::value::{find_number, parse_named_month_date, unit_around, NumericValue};
+use chrono::{Datelike, NaiveDate};
+
+// Languages that write the decimal point as a comma, by the grouping separator they use
+const COMMA_DECIMAL_DOT_GROUPING: &[&str] = &["de", "es", "it", "pt", "nl", "da", "tr", "el", "id", "vi", "ro", "sl", "hr", "sr"];
+const COMMA_DECIMAL_SPACE_GROUPING: &[&str] = &["fr", "ru", "pl", "cs", "sk", "sv", "nb", "no", "nn", "fi", "uk", "hu", "bg", "lt", "lv", "et"];
+// Languages that write dates year first
+const YEAR_FIRST_LANGUAGES: &[&str] = &["ja", "zh", "ko", "hu", "lt", "sv"];
+// Languages that separate numeric dates with dots or dashes rather than slashes
+const DOT_DATE_LANGUAGES: &[&str] = &[
+    "de", "ru", "pl", "cs", "sk", "fi", "nb", "no", "nn", "tr", "uk", "bg", "ro", "hr", "sr", "sl", "et", "lv", "da", "hu", "ko",
+];
+const DASH_DATE_LANGUAGES: &[&str] = &["nl", "lt", "sv"];
+// Regions that write dates month first
+const MONTH_FIRST_REGIONS: &[&str] = &["US", "PH", "FM", "MH", "PW"];
+const EURO_REGIONS: &[&str] = &[
+    "AT", "BE", "CY", "DE", "EE", "ES", "FI", "FR", "GR", "HR", "IE", "IT", "LT", "LU", "LV", "MT", "NL", "PT", "SI", "SK",
+];
+const CURRENCY_BY_REGION: &[(&str, &str)] = &[
+    ("US", "$"), ("CA", "$"), ("AU", "$"), ("NZ", "$"), ("MX", "$"), ("GB", "£"), ("JP", "¥"), ("CN", "¥"),
+    ("IN", "₹"), ("RU", "₽"), ("KR", "₩"), ("BR", "R$"), ("CH", "CHF"), ("SE", "kr"), ("NO", "kr"), ("DK", "kr."),
+    ("PL", "zł"), ("CZ", "Kč"), ("HU", "Ft"), ("TR", "₺"), ("UA", "₴"), ("IL", "₪"),
+];
+// Two-digit years below this are read as 20xx, the rest as 19xx
+const TWO_DIGIT_YEAR_PIVOT: i32 = 70;
+
+/// The order a locale writes the day, month and year of a numeric date in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// `15.03.2024`
+    DayMonthYear,
+    /// `03/15/2024`
+    MonthDayYear,
+    /// `2024-03-15`
+    YearMonthDay,
+}
+
+/// How a locale writes numbers and dates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    /// BCP 47 tag, e.g. `de-DE`
+    pub tag: String,
+    pub decimal_separator: char,
+    /// Between groups of thousands; any kind of space matches a space separator when parsing
+    pub grouping_separator: char,
+    pub date_order: DateOrder,
+    /// Between the fields of a numeric date, e.g. `.` in `15.03.2024`
+    pub date_separator: char,
+    /// The local currency's symbol, e.g. `€`, if known
+    pub currency_symbol: Option<String>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::from_tag("en-US")
+    }
+}
+
+impl Locale {
+    /// The conventions of a locale tag such as `de-DE`, `fr_CH.UTF-8` or `ja`.
+    /// Languages and regions it doesn't know get a `.` decimal point and day-first dates.
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag.split(['.', '@']).next().unwrap_or_default().replace('_', "-");
+        let mut parts = tag.split('-');
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts
+            .find(|part| part.len() == 2 && part.chars().all(|c| c.is_ascii_alphabetic()))
+            .map(|part| part.to_ascii_uppercase());
+        let region = region.as_deref().unwrap_or_default();
+
+        let (decimal_separator, grouping_separator) = if region == "CH" && matches!(language.as_str(), "de" | "it" | "fr") {
+            ('.', '\u{2019}')
+        } else if COMMA_DECIMAL_DOT_GROUPING.contains(&language.as_str()) {
+            (',', '.')
+        } else if COMMA_DECIMAL_SPACE_GROUPING.contains(&language.as_str()) {
+            (',', '\u{a0}')
+        } else {
+            ('.', ',')
+        };
+        let date_order = if YEAR_FIRST_LANGUAGES.contains(&language.as_str()) {
+            DateOrder::YearMonthDay
+        } else if MONTH_FIRST_REGIONS.contains(&region) {
+            DateOrder::MonthDayYear
+        } else {
+            DateOrder::DayMonthYear
+        };
+        let date_separator = if DOT_DATE_LANGUAGES.contains(&language.as_str()) {
+            '.'
+        } else if DASH_DATE_LANGUAGES.contains(&language.as_str()) {
+            '-'
+        } else {
+            '/'
+        };
+        let currency_symbol = if EURO_REGIONS.contains(&region) {
+            Some("€".to_string())
+        } else {
+            CURRENCY_BY_REGION
+                .iter()
+                .find(|(code, _)| *code == region)
+                .map(|(_, symbol)| symbol.to_string())
+        };
+
+        Self {
+            tag,
+            decimal_separator,
+            grouping_separator,
+            date_order,
+            date_separator,
+            currency_symbol,
+        }
+    }
+
+    /// Parse the first number in `text` as this locale writes it, with the unit or
+    /// currency around it: `1.234,56 €` is 1234.56 in `€` for `de-DE`. `None` if there
+    /// is no number or it can't be one in this locale, e.g. a grouping separator after
+    /// the decimal point.
+    pub fn parse_number(&self, text: &str) -> Option<NumericValue> {
+        let span = find_number(text, self.decimal_separator, |c| {
+            c.is_ascii_digit() || c == self.decimal_separator || self.is_grouping(c)
+        })?;
+
+        let mut number = String::with_capacity(span.number.len());
+        let mut seen_decimal = false;
+        for c in span.number.chars() {
+            if c == self.decimal_separator {
+                if seen_decimal {
+                    return None;
+                }
+                seen_decimal = true;
+                number.push('.');
+            } else if self.is_grouping(c) {
+                if seen_decimal {
+                    return None;
+                }
+            } else {
+                number.push(c);
+            }
+        }
+        let magnitude: f64 = number.parse().ok()?;
+        // An ISO currency code in front, e.g. `CHF 99.90`
+        let code = span
+            .prefix
+            .split_whitespace()
+            .last()
+            .filter(|word| word.len() == 3 && word.chars().all(|c| c.is_ascii_uppercase()));
+        Some(NumericValue {
+            value: span.signed(magnitude),
+            unit: unit_around(span.prefix, span.suffix, &[self.currency_symbol.as_deref(), code]),
+        })
+    }
+
+    /// Parse a money amount: a number with a currency symbol or code next to it, e.g.
+    /// `1.234,56 €`, `$12.50` or `CHF 99.90`. `None` if no currency is written.
+    pub fn parse_currency(&self, text: &str) -> Option<NumericValue> {
+        self.parse_number(text).filter(|amount| {
+            amount.unit.as_deref().is_some_and(|unit| {
+                Some(unit) == self.currency_symbol.as_deref()
+                    || unit.chars().count() == 1 && !unit.chars().all(char::is_alphanumeric)
+                    || unit.len() == 3 && unit.chars().all(|c| c.is_ascii_uppercase())
+            })
+        })
+    }
+
+    /// Parse a numeric date in this locale's order with any separators, e.g. `15.03.24`
+    /// for `de-DE`, or a date with the month written out in English
+    pub fn parse_date(&self, text: &str) -> Option<NaiveDate> {
+        let fields: Vec<&str> = text.split(|c: char| !c.is_ascii_digit()).filter(|field| !field.is_empty()).collect();
+        let numbers = <[&str; 3]>::try_from(fields).ok().and_then(|fields| {
+            let [a, b, c] = fields.map(|field| field.parse::<i32>().ok());
+            Some([a?, b?, c?])
+        });
+        let Some([a, b, c]) = numbers else {
+            return parse_named_month_date(text);
+        };
+        let (year, month, day) = match self.date_order {
+            DateOrder::DayMonthYear => (c, b, a),
+            DateOrder::MonthDayYear => (c, a, b),
+            DateOrder::YearMonthDay => (a, b, c),
+        };
+        let year = match year {
+            0..TWO_DIGIT_YEAR_PIVOT => 2000 + year,
+            TWO_DIGIT_YEAR_PIVOT..100 => 1900 + year,
+            _ => year,
+        };
+        NaiveDate::from_ymd_opt(year, month.try_into().ok()?, day.try_into().ok()?)
+    }
+
+    /// Write `value` with `decimals` digits after the decimal point and grouped
+    /// thousands, e.g. `1.234,56` for `de-DE`
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let fixed = format!("{:.*}", decimals, value.abs());
+        let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+        let mut formatted = String::with_capacity(fixed.len() + whole.len() / 3 + 1);
+        if value < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            formatted.push('-');
+        }
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i).is_multiple_of(3) {
+                formatted.push(self.grouping_separator);
+            }
+            formatted.push(digit);
+        }
+        if !fraction.is_empty() {
+            formatted.push(self.decimal_separator);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
+    /// Write `date` as a numeric date in this locale's order, e.g. `15.03.2024` for `de-DE`
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        let (day, month, year) = (
+            format!("{:02}", date.day()),
+            format!("{:02}", date.month()),
+            format!("{:04}", date.year()),
+        );
+        let fields = match self.date_order {
+            DateOrder::DayMonthYear => [day, month, year],
+            DateOrder::MonthDayYear => [month, day, year],
+            DateOrder::YearMonthDay => [year, month, day],
+        };
+        fields.join(&self.date_separator.to_string())
+    }
+
+    fn is_grouping(&self, c: char) -> bool {
+        const SPACES: &[char] = &[' ', '\u{a0}', '\u{202f}'];
+        const APOSTROPHES: &[char] = &['\'', '\u{2019}'];
+        c == self.grouping_separator
+            || SPACES.contains(&self.grouping_separator) && SPACES.contains(&c)
+            || APOSTROPHES.contains(&self.grouping_separator) && APOSTROPHES.contains(&c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(tag: &str, text: &str) -> Option<(f64, Option<String>)> {
+        Locale::from_tag(tag).parse_number(text).map(|number| (number.value, number.unit))
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    #[test]
+    fn from_tag_reads_language_and_region() {
+        let german = Locale::from_tag("de-DE");
+        assert_eq!((german.decimal_separator, german.grouping_separator), (',', '.'));
+        assert_eq!((german.date_order, german.date_separator), (DateOrder::DayMonthYear, '.'));
+        assert_eq!(german.currency_symbol.as_deref(), Some("€"));
+
+        let swiss = Locale::from_tag("fr_CH.UTF-8");
+        assert_eq!(swiss.tag, "fr-CH");
+        assert_eq!((swiss.decimal_separator, swiss.grouping_separator), ('.', '\u{2019}'));
+        assert_eq!(swiss.currency_symbol.as_deref(), Some("CHF"));
+
+        let american = Locale::default();
+        assert_eq!((american.date_order, american.date_separator), (DateOrder::MonthDayYear, '/'));
+        assert_eq!(american.currency_symbol.as_deref(), Some("$"));
+
+        assert_eq!(Locale::from_tag("ja").date_order, DateOrder::YearMonthDay);
+        assert_eq!(Locale::from_tag("ja").currency_symbol, None);
+        assert_eq!(Locale::from_tag("sv-SE").date_separator, '-');
+        assert_eq!(Locale::from_tag("fr-FR").grouping_separator, '\u{a0}');
+        // Script subtags are skipped when looking for the region
+        assert_eq!(Locale::from_tag("sr-Latn-RS").date_separator, '.');
+
+        let unknown = Locale::from_tag("xx");
+        assert_eq!((unknown.decimal_separator, unknown.date_order), ('.', DateOrder::DayMonthYear));
+    }
+
+    #[test]
+    fn parse_number_uses_the_locale_separators() {
+        assert_eq!(number("de-DE", "Summe: 1.234,56 €"), Some((1234.56, Some("€".to_string()))));
+        assert_eq!(number("en-US", "$1,234.56"), Some((1234.56, Some("$".to_string()))));
+        assert_eq!(number("en-US", "1.234"), Some((1.234, None)));
+        assert_eq!(number("de-DE", "1.234"), Some((1234.0, None)));
+        assert_eq!(number("de-DE", "-12,5 %"), Some((-12.5, Some("%".to_string()))));
+        assert_eq!(number("fr-FR", "1\u{202f}234,5"), Some((1234.5, None)));
+        assert_eq!(number("fr-FR", "1 234,5"), Some((1234.5, None)));
+        assert_eq!(number("en-GB", "CHF 99.90"), Some((99.9, Some("CHF".to_string()))));
+        assert_eq!(number("en-US", "no digits"), None);
+    }
+
+    #[test]
+    fn parse_number_rejects_grouping_after_the_decimal() {
+        assert_eq!(number("en-US", "1.234,5"), None);
+        assert_eq!(number("de-DE", "1,234.5"), None);
+        assert_eq!(number("de-DE", "1,2,3"), None);
+    }
+
+    #[test]
+    fn parse_number_reads_swiss_apostrophes() {
+        assert_eq!(number("de-CH", "1'234.50"), Some((1234.5, None)));
+        assert_eq!(number("de-CH", "1\u{2019}234.50"), Some((1234.5, None)));
+        assert_eq!(number("de-CH", "CHF 12'000"), Some((12000.0, Some("CHF".to_string()))));
+        // Apostrophes only group where the locale uses them
+        assert_eq!(number("de-DE", "1'234"), Some((1.0, Some("'234".to_string()))));
+    }
+
+    #[test]
+    fn parse_number_reads_negative_zero_as_zero() {
+        let (value, _) = number("de-DE", "-0,00").unwrap();
+        assert_eq!(value, 0.0);
+    }
+
+    #[test]
+    fn parse_date_follows_the_locale_order() {
+        assert_eq!(Locale::from_tag("de-DE").parse_date("15.03.2024"), date(2024, 3, 15));
+        assert_eq!(Locale::from_tag("en-US").parse_date("03/15/2024"), date(2024, 3, 15));
+        assert_eq!(Locale::from_tag("en-GB").parse_date("03/04/2024"), date(2024, 4, 3));
+        assert_eq!(Locale::from_tag("ja-JP").parse_date("2024/03/15"), date(2024, 3, 15));
+        assert_eq!(Locale::from_tag("de-DE").parse_date("31.02.2024"), None);
+        assert_eq!(Locale::from_tag("de-DE").parse_date("15 March 2024"), date(2024, 3, 15));
+        assert_eq!(Locale::from_tag("de-DE").parse_date("15.03"), None);
+    }
+
+    #[test]
+    fn parse_date_pivots_two_digit_years() {
+        let german = Locale::from_tag("de-DE");
+        assert_eq!(german.parse_date("15.03.24"), date(2024, 3, 15));
+        assert_eq!(german.parse_date("01.01.00"), date(2000, 1, 1));
+        assert_eq!(german.parse_date("31.12.69"), date(2069, 12, 31));
+        assert_eq!(german.parse_date("01.01.70"), date(1970, 1, 1));
+        assert_eq!(german.parse_date("31.12.99"), date(1999, 12, 31));
+        assert_eq!(german.parse_date("01.01.100"), date(100, 1, 1));
+    }
+
+    #[test]
+    fn format_number_groups_thousands() {
+        assert_eq!(Locale::from_tag("de-DE").format_number(1234.56, 2), "1.234,56");
+        assert_eq!(Locale::from_tag("en-US").format_number(-1234567.891, 2), "-1,234,567.89");
+        assert_eq!(Locale::from_tag("de-CH").format_number(1234.5, 2), "1\u{2019}234.50");
+        assert_eq!(Locale::from_tag("fr-FR").format_number(1234.5, 1), "1\u{a0}234,5");
+        assert_eq!(Locale::from_tag("en-US").format_number(999.999, 2), "1,000.00");
+        assert_eq!(Locale::from_tag("en-US").format_number(100.0, 0), "100");
+    }
+
+    #[test]
+    fn format_number_drops_the_sign_of_zero() {
+        let american = Locale::default();
+        assert_eq!(american.format_number(-0.0, 0), "0");
+        assert_eq!(american.format_number(-0.001, 2), "0.00");
+        assert_eq!(american.format_number(-0.005, 1), "0.0");
+        assert_eq!(american.format_number(-0.05, 1), "-0.1");
+    }
+
+    #[test]
+    fn formatted_numbers_and_dates_parse_back() {
+        for tag in ["de-DE", "de-CH", "fr-FR", "en-US", "ja-JP", "sv-SE"] {
+            let locale = Locale::from_tag(tag);
+            let formatted = locale.format_number(-1234567.25, 2);
+            assert_eq!(locale.parse_number(&formatted).map(|n| n.value), Some(-1234567.25), "{}", tag);
+            let day = date(2024, 3, 15).unwrap();
+            assert_eq!(locale.parse_date(&locale.format_date(day)), Some(day), "{}", tag);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::platforms::AccessibilityEngine;
-use crate::{AutomationError, Selector, UIElement, UIElementAttributes};
+use crate::{ActionContext, ActionOptions, ActionabilityOptions, AliasTable, AutomationError, ElementPredicate, FailureArtifactsConfig, MatchExplanation, Relation, ScrapeSpec, ScrapedRecord, SecretString, Selector, StateFilter, TrackedElement, UIElement, UIElementAttributes};
+use futures::{Stream, StreamExt};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -7,6 +8,59 @@ use super::ClickResult;
 
 // Default timeout if none is specified on the locator itself
 const DEFAULT_LOCATOR_TIMEOUT: Duration = Duration::from_secs(30);
+// How often waits re-check the UI if no polling interval is set
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// How long each scroll step searches the realized items, and waits for new ones to render
+const SCROLL_SEARCH_TIMEOUT: Duration = Duration::from_millis(300);
+const SCROLL_SETTLE_DELAY: Duration = Duration::from_millis(200);
+// How long count() and exists() search before concluding there is no match
+const PRESENCE_SEARCH_TIMEOUT: Duration = Duration::from_millis(100);
+// How far below the scope stream() walks if no depth is given, as deep as the engines search
+const DEFAULT_STREAM_DEPTH: usize = 50;
+// How many matches stream() reads ahead of the consumer
+const STREAM_BUFFER: usize = 64;
+
+/// How often a waiting locator re-checks the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Polling {
+    pub interval: Duration,
+    /// With backoff, the interval doubles after each check up to this
+    pub max_interval: Option<Duration>,
+}
+
+impl Default for Polling {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_POLL_INTERVAL,
+            max_interval: None,
+        }
+    }
+}
+
+impl Polling {
+    /// Sleep `delay` before the next check, then grow it if backing off
+    pub(crate) async fn pause(&self, delay: &mut Duration) -> Result<(), AutomationError> {
+        crate::abort::check()?;
+        tokio::time::sleep(*delay).await;
+        if let Some(max_interval) = self.max_interval {
+            *delay = (*delay * 2).min(max_interval.max(self.interval));
+        }
+        Ok(())
+    }
+}
+
+/// A state [`Locator::wait_for`] waits for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitState {
+    /// An element matches and is visible
+    Visible,
+    /// An element matches and is enabled
+    Enabled,
+    /// No element matches, or the one that does is not visible
+    Hidden,
+    /// No element matches
+    Gone,
+}
 
 /// A high-level API for finding and interacting with UI elements
 #[derive(Clone)]
@@ -15,6 +69,11 @@ pub struct Locator {
     selector: Selector,
     timeout: Duration, // Default timeout for this locator instance
     root: Option<UIElement>,
+    failure_artifacts: Option<FailureArtifactsConfig>,
+    slow_mo: Duration, // Delay inserted before each action
+    locale_aliases: Option<AliasTable>,
+    polling: Polling,
+    actionability: ActionabilityOptions,
 }
 
 impl Locator {
@@ -25,6 +84,11 @@ impl Locator {
             selector,
             timeout: DEFAULT_LOCATOR_TIMEOUT, // Use default
             root: None,
+            failure_artifacts: None,
+            slow_mo: Duration::ZERO,
+            locale_aliases: None,
+            polling: Polling::default(),
+            actionability: ActionabilityOptions::default(),
         }
     }
 
@@ -41,13 +105,261 @@ impl Locator {
         self
     }
 
-    /// Get all elements matching this locator, waiting up to the specified timeout.
-    /// If no timeout is provided, uses the locator's default timeout.
+    /// Capture failure artifacts (screenshot, focused element, tree dump) when an action or
+    /// expectation fails
+    pub fn with_failure_artifacts(mut self, config: FailureArtifactsConfig) -> Self {
+        self.failure_artifacts = Some(config);
+        self
+    }
+
+    /// Insert a delay before each action performed through this locator (like Playwright's slowMo)
+    pub fn with_slow_mo(mut self, delay: Duration) -> Self {
+        self.slow_mo = delay;
+        self
+    }
+
+    /// Re-check the UI every `interval` while waiting, instead of every 100ms. A longer
+    /// interval puts less load on the accessibility tree and the app behind it.
+    pub fn with_polling(mut self, interval: Duration) -> Self {
+        self.polling.interval = interval;
+        self
+    }
+
+    /// Back off exponentially while waiting: the polling interval doubles after each
+    /// check, up to `max_interval`. Suits long waits where the first checks matter most.
+    pub fn with_backoff(mut self, max_interval: Duration) -> Self {
+        self.polling.max_interval = Some(max_interval);
+        self
+    }
+
+    /// Choose which checks actions wait on before acting; all of them by default. Use
+    /// [`ActionabilityOptions::none`] to act as soon as the element is found.
+    pub fn with_actionability(mut self, options: ActionabilityOptions) -> Self {
+        self.actionability = options;
+        self
+    }
+
+    pub(crate) fn with_polling_settings(mut self, polling: Polling) -> Self {
+        self.polling = polling;
+        self
+    }
+
+    pub(crate) fn selector(&self) -> &Selector {
+        &self.selector
+    }
+
+    /// The element searches are limited to, if any
+    pub(crate) fn scope(&self) -> Option<&UIElement> {
+        self.root.as_ref()
+    }
+
+    pub(crate) fn default_timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub(crate) fn polling(&self) -> Polling {
+        self.polling
+    }
+
+    /// Also match localized equivalents of the names in this locator's selector, and in
+    /// selectors chained onto it later, e.g. "Aceptar" for "OK"
+    pub fn with_locale_aliases(mut self, aliases: AliasTable) -> Self {
+        self.selector = aliases.expand(self.selector);
+        self.locale_aliases = Some(aliases);
+        self
+    }
+
+    /// Wait for the element an action targets, then for it to pass the actionability
+    /// checks, both within the action's timeout
+    async fn actionable(&self, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
+        let effective_timeout = timeout.unwrap_or(self.timeout);
+        let start = Instant::now();
+        let element = match self.wait(Some(effective_timeout)).await {
+            Ok(element) => element,
+            Err(e) => return Err(self.on_failure(e, self.root.as_ref()).await),
+        };
+        let remaining = effective_timeout.saturating_sub(start.elapsed());
+        let ready = element.wait_until_actionable(&self.actionability, remaining).await;
+        self.checked(&element, ready).await?;
+        Ok(element)
+    }
+
+    /// Sleep for the configured slow-mo delay, if any
+    async fn throttle(&self) {
+        if !self.slow_mo.is_zero() {
+            tokio::time::sleep(self.slow_mo).await;
+        }
+    }
+
+    /// Annotate a failed action or expectation with the selector and, if enabled, failure
+    /// artifacts. Lookups like [`Locator::wait`] and [`Locator::is_visible`] only add the
+    /// selector, since not finding an element is often what the caller is checking for.
+    pub(crate) async fn on_failure(&self, error: AutomationError, target: Option<&UIElement>) -> AutomationError {
+        let error = error.with_selector(&self.selector);
+        match &self.failure_artifacts {
+            Some(config) if error.artifacts().is_none() => {
+                let artifacts =
+                    crate::artifacts::capture(&self.engine, target, config, &error.to_string()).await;
+                error.with_artifacts(artifacts)
+            }
+            _ => error,
+        }
+    }
+
+    /// The stages of this locator's selector: the parts of a chain, or the selector itself
+    fn stages(&self) -> &[Selector] {
+        match &self.selector {
+            Selector::Chain(stages) => stages,
+            selector => std::slice::from_ref(selector),
+        }
+    }
+
+    /// Find the first element matching every stage in turn, each searched only below
+    /// the previous stage's match, sharing `timeout` between them
+    fn find_first_in(&self, root: Option<&UIElement>, timeout: Duration) -> Result<UIElement, AutomationError> {
+        if self.stages().is_empty() {
+            return Err(empty_chain());
+        }
+        self.resolve_stages(self.stages(), root, timeout, Instant::now())?
+            .ok_or_else(empty_chain)
+    }
+
+    /// Look for the first match once, giving the search at most `timeout`; for callers
+    /// that poll several locators in turn
+    pub(crate) fn find_once(&self, timeout: Duration) -> Result<UIElement, AutomationError> {
+        self.find_first_in(self.root.as_ref(), timeout)
+    }
+
+    /// Resolve `stages` one after the other starting from `root`, returning the last match
+    /// (or `root` itself if there are no stages)
+    fn resolve_stages(
+        &self,
+        stages: &[Selector],
+        root: Option<&UIElement>,
+        timeout: Duration,
+        start: Instant,
+    ) -> Result<Option<UIElement>, AutomationError> {
+        let mut current = root.cloned();
+        for stage in stages {
+            let remaining = timeout.saturating_sub(start.elapsed());
+            let found = self.engine.find_element(stage, current.as_ref(), Some(remaining))?;
+            current = Some(found.with_engine(self.engine.clone()));
+        }
+        Ok(current)
+    }
+
+    /// Pass through a successful action result, or annotate its error via `on_failure`
+    async fn checked<T>(&self, element: &UIElement, result: Result<T, AutomationError>) -> Result<T, AutomationError> {
+        match result {
+            Ok(value) => Ok(value),
+            Err(e) => Err(self.on_failure(e, Some(element)).await),
+        }
+    }
+
+    /// Get every element matching this locator, in tree order, e.g. to iterate rows or
+    /// checkboxes. Waits like [`Locator::wait`] until at least one matches, up to the
+    /// specified timeout; if no timeout is provided, uses the locator's default timeout.
+    /// `depth` limits how far below the scope the last stage searches.
     pub async fn all(&self, timeout: Option<Duration>, depth: Option<usize>) -> Result<Vec<UIElement>, AutomationError> {
         let effective_timeout = timeout.unwrap_or(self.timeout);
-        // find_elements itself handles the timeout now
-        self.engine
-            .find_elements(&self.selector, self.root.as_ref(), Some(effective_timeout), depth)
+        let start = Instant::now();
+        let mut delay = self.polling.interval;
+
+        loop {
+            match self.find_all_in(effective_timeout, start, depth) {
+                Ok(elements) if !elements.is_empty() => return Ok(elements),
+                Ok(_) => {}
+                Err(e) if e.is_retryable() => {}
+                Err(e) => return Err(e.with_selector(&self.selector)),
+            }
+            if start.elapsed() >= effective_timeout {
+                let timeout_error = AutomationError::Timeout(format!(
+                    "Timed out after {:?} waiting for elements {:?}",
+                    effective_timeout, self.selector
+                ));
+                return Err(timeout_error.with_selector(&self.selector));
+            }
+            self.polling.pause(&mut delay).await?;
+        }
+    }
+
+    /// Stream the elements matching this locator in tree order, reading the tree on a
+    /// blocking thread at most a few dozen matches ahead of the consumer, so thousands of
+    /// rows can be processed without collecting them first or stalling the executor. Waits like [`Locator::wait`] for the earlier chain stages to match, up to the
+    /// specified timeout; no match of the last stage is an empty stream, not an error.
+    /// `depth` limits how far below the scope the walk goes.
+    ///
+    /// Selectors decided by an element's own attributes (roles, names, ids, attributes,
+    /// regexes, state filters) are matched during the walk. Others (text, paths, labels,
+    /// relations, images, OCR) are found with [`Locator::all`] up front and then streamed.
+    pub async fn stream(
+        &self,
+        timeout: Option<Duration>,
+        depth: Option<usize>,
+    ) -> Result<impl Stream<Item = UIElement> + Send + use<>, AutomationError> {
+        let (last, scopes) = self.stages().split_last().ok_or_else(empty_chain)?;
+        let scope = if scopes.is_empty() {
+            match &self.root {
+                Some(root) => root.clone(),
+                None => self.engine.get_root_element().with_engine(self.engine.clone()),
+            }
+        } else {
+            let scope_locator = Locator {
+                selector: Selector::Chain(scopes.to_vec()),
+                ..self.clone()
+            };
+            scope_locator.wait(timeout).await?
+        };
+
+        // Whether a selector can be matched element by element doesn't depend on the element
+        if last.matches_element(&scope).is_none() {
+            return match self.all(timeout, depth).await {
+                Ok(elements) => Ok(futures::stream::iter(elements).boxed()),
+                Err(e) if matches!(e.inner(), AutomationError::Timeout(_)) => Ok(futures::stream::empty().boxed()),
+                Err(e) => Err(e),
+            };
+        }
+
+        let last = last.clone();
+        let depth = depth.unwrap_or(DEFAULT_STREAM_DEPTH);
+        let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || {
+            for element in scope.descendants(depth) {
+                // Stop walking once the stream is dropped
+                if sender.is_closed() {
+                    break;
+                }
+                if last.matches_element(&element) == Some(true) && sender.blocking_send(element).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|element| (element, receiver))
+        })
+        .boxed())
+    }
+
+    /// Find every element matching the last stage below the first match of the earlier
+    /// ones, within what is left of `timeout` since `start`
+    fn find_all_in(&self, timeout: Duration, start: Instant, depth: Option<usize>) -> Result<Vec<UIElement>, AutomationError> {
+        // find_elements itself handles the timeout now; earlier chain stages only narrow the scope
+        let (last, scopes) = self.stages().split_last().ok_or_else(empty_chain)?;
+        let scope = self.resolve_stages(scopes, self.root.as_ref(), timeout, start)?;
+        let elements = self.engine.find_elements(
+            last,
+            scope.as_ref(),
+            Some(timeout.saturating_sub(start.elapsed())),
+            depth,
+        )?;
+        Ok(elements.into_iter().map(|element| element.with_engine(self.engine.clone())).collect())
+    }
+
+    /// Wait for the first matching element like [`Locator::wait`] and track it: actions on
+    /// the result find the element again through this locator if the app re-renders it
+    pub async fn track(&self, timeout: Option<Duration>) -> Result<TrackedElement, AutomationError> {
+        let element = self.wait(timeout).await?;
+        Ok(TrackedElement::new(self.clone(), element))
     }
 
     pub async fn first(&self, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
@@ -60,37 +372,113 @@ impl Locator {
     pub async fn wait(&self, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
         let effective_timeout = timeout.unwrap_or(self.timeout);
         let start = std::time::Instant::now();
+        let mut delay = self.polling.interval;
 
         loop {
             // Directly use find_element with the timeout
-            match self.engine.find_element(
-                &self.selector,
+            match self.find_first_in(
                 self.root.as_ref(),
-                Some(effective_timeout - start.elapsed()), // Pass remaining time
+                effective_timeout.saturating_sub(start.elapsed()), // Pass remaining time
             ) {
                 Ok(element) => return Ok(element),
-                Err(AutomationError::ElementNotFound(_)) => {
-                    // Continue looping if not found yet
+                Err(e) if e.is_retryable() => {
+                    // Continue looping if not found yet (or the UI is still settling)
                     if start.elapsed() >= effective_timeout {
-                         // Use the original error message format if possible, or create a new one
-                         return Err(AutomationError::Timeout(format!(
-                            "Timed out after {:?} waiting for element {:?}",
-                            effective_timeout, self.selector
-                        )));
+                        let timeout_error = AutomationError::Timeout(format!(
+                            "Timed out after {:?} waiting for element {:?}: {}",
+                            effective_timeout, self.selector, e
+                        ));
+                        return Err(timeout_error.with_selector(&self.selector));
                     }
-                    tokio::time::sleep(Duration::from_millis(100)).await; // Small delay before retry
+                    self.polling.pause(&mut delay).await?;
                 }
-                 // Propagate other errors immediately
-                 Err(e) => return Err(e),
+                // Propagate other errors immediately
+                Err(e) => return Err(e.with_selector(&self.selector)),
+            }
+        }
+    }
+
+    /// Find an item of a virtualized list or table by scrolling `container` in `direction`
+    /// ("up" or "down") one page at a time, at most `max_scrolls` times.
+    ///
+    /// Virtualized controls only create elements for the rows on screen, so a plain
+    /// locator can't see the rest. Stops early once scrolling no longer changes the
+    /// visible items, i.e. the end of the list was reached.
+    pub async fn find_scrolling(&self, container: &UIElement, direction: &str, max_scrolls: usize) -> Result<UIElement, AutomationError> {
+        let visible_items = || -> Vec<Option<String>> {
+            container
+                .children()
+                .map(|children| children.iter().map(|child| child.name()).collect())
+                .unwrap_or_default()
+        };
+
+        let mut scrolls = 0;
+        loop {
+            match self.find_first_in(Some(container), SCROLL_SEARCH_TIMEOUT) {
+                Ok(element) => return Ok(element),
+                Err(e) if e.is_retryable() => {}
+                Err(e) => return Err(self.on_failure(e, Some(container)).await),
+            }
+            if scrolls == max_scrolls {
+                break;
+            }
+
+            let before = visible_items();
+            if let Err(e) = container.scroll(direction, 1.0) {
+                return Err(self.on_failure(e, Some(container)).await);
+            }
+            scrolls += 1;
+            crate::abort::check()?;
+            tokio::time::sleep(SCROLL_SETTLE_DELAY).await;
+            if visible_items() == before {
+                break; // reached the end of the list
             }
-            // Redundant check, loop condition handles timeout
-            // if start.elapsed() >= effective_timeout { ... }
         }
+
+        let error = AutomationError::ElementNotFound(format!(
+            "Element {:?} not found after scrolling {} {} time(s)",
+            self.selector, direction, scrolls
+        ));
+        Err(self.on_failure(error, Some(container)).await)
+    }
+
+    /// Extract a record from every item in the first matching element, as described by
+    /// `spec`, waiting up to the locator's default timeout for the container.
+    ///
+    /// With [`ScrapeSpec::scroll`] set, the container is scrolled down page by page and
+    /// the rows each page brings into view are appended, so virtualized lists yield every
+    /// item rather than only the rendered ones.
+    pub async fn scrape(&self, spec: &ScrapeSpec) -> Result<Vec<ScrapedRecord>, AutomationError> {
+        let container = self.wait(None).await?;
+        let page = spec.read_page(self.engine.as_ref(), &container);
+        let mut page = self.checked(&container, page).await?;
+        let mut records = page.clone();
+
+        for _ in 0..spec.max_scrolls() {
+            if let Err(e) = container.scroll("down", 1.0) {
+                return Err(self.on_failure(e, Some(&container)).await);
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(SCROLL_SETTLE_DELAY).await;
+
+            let next = spec.read_page(self.engine.as_ref(), &container);
+            let next = self.checked(&container, next).await?;
+            if next == page {
+                break; // reached the end of the list
+            }
+            crate::scrape::append_page(&mut records, &page, next.clone());
+            page = next;
+        }
+
+        Ok(records)
     }
 
     /// Get a nested locator
     pub fn locator(&self, selector: impl Into<Selector>) -> Locator {
-        let next_selector = selector.into();
+        let mut next_selector = selector.into();
+        if let Some(aliases) = &self.locale_aliases {
+            next_selector = aliases.expand(next_selector);
+        }
         let new_chain = match self.selector.clone() {
              // If the current selector is already a chain, append to it
              Selector::Chain(mut existing_chain) => {
@@ -108,6 +496,109 @@ impl Locator {
             selector: Selector::Chain(new_chain), // Create the chain variant
             timeout: self.timeout, // Inherit timeout
             root: self.root.clone(), // Inherit root
+            failure_artifacts: self.failure_artifacts.clone(),
+            slow_mo: self.slow_mo,
+            locale_aliases: self.locale_aliases.clone(),
+            polling: self.polling,
+            actionability: self.actionability.clone(),
+        }
+    }
+
+    /// Narrow this locator to elements right of an element matched by `anchor`, on the
+    /// same row, nearest first. Useful for unnamed fields next to a visible caption.
+    pub fn right_of(&self, anchor: &Locator) -> Locator {
+        self.relative(Relation::RightOf, anchor)
+    }
+
+    /// Narrow this locator to elements left of an element matched by `anchor`, nearest first
+    pub fn left_of(&self, anchor: &Locator) -> Locator {
+        self.relative(Relation::LeftOf, anchor)
+    }
+
+    /// Narrow this locator to elements above an element matched by `anchor`, nearest first
+    pub fn above(&self, anchor: &Locator) -> Locator {
+        self.relative(Relation::Above, anchor)
+    }
+
+    /// Narrow this locator to elements below an element matched by `anchor`, nearest first
+    pub fn below(&self, anchor: &Locator) -> Locator {
+        self.relative(Relation::Below, anchor)
+    }
+
+    /// Narrow this locator to elements within `max_distance` pixels of an element matched
+    /// by `anchor`, nearest first
+    pub fn near(&self, anchor: &Locator, max_distance: u32) -> Locator {
+        self.relative(Relation::Near(max_distance), anchor)
+    }
+
+    /// Narrow this locator to its `index`-th match (counting from 0), like `:nth(index)`.
+    /// `nth(0)` stops searching at the first match.
+    pub fn nth(&self, index: usize) -> Locator {
+        self.map_last_stage(|base| Selector::Nth {
+            base: Box::new(base),
+            index: index as isize,
+        })
+    }
+
+    /// Narrow this locator to its last match, like `:last`
+    pub fn last(&self) -> Locator {
+        self.map_last_stage(|base| Selector::Nth {
+            base: Box::new(base),
+            index: -1,
+        })
+    }
+
+    /// Narrow this locator to matches in the given state, like `:visible` or `:enabled`.
+    /// Call it again to require several states.
+    pub fn with_state(&self, filter: StateFilter) -> Locator {
+        self.map_last_stage(|base| match base {
+            Selector::State { base, mut filters } => {
+                filters.push(filter);
+                Selector::State { base, filters }
+            }
+            base => Selector::State {
+                base: Box::new(base),
+                filters: vec![filter],
+            },
+        })
+    }
+
+    /// Narrow this locator to matches `predicate` accepts, e.g. ones inside a screen region
+    /// or with a combination of attributes no selector expresses
+    pub fn filter(&self, predicate: impl Fn(&UIElement) -> bool + Send + Sync + 'static) -> Locator {
+        let predicate = ElementPredicate::new(predicate);
+        self.map_last_stage(|base| Selector::Filter {
+            base: Box::new(base),
+            predicate,
+        })
+    }
+
+    /// Wrap the last stage of this locator's selector in a [`Selector::Relative`], so the
+    /// anchor is searched in the same scope as the elements it constrains. The anchor
+    /// locator contributes only its selector, not its root.
+    fn relative(&self, relation: Relation, anchor: &Locator) -> Locator {
+        self.map_last_stage(|base| Selector::Relative {
+            base: Box::new(base),
+            relation,
+            anchor: Box::new(anchor.selector.clone()),
+        })
+    }
+
+    /// Replace the last stage of this locator's selector (the selector itself if it
+    /// isn't a chain) with `wrap` applied to it
+    fn map_last_stage(&self, wrap: impl FnOnce(Selector) -> Selector) -> Locator {
+        let selector = match self.selector.clone() {
+            Selector::Chain(mut stages) if !stages.is_empty() => {
+                let last = stages.pop().unwrap();
+                stages.push(wrap(last));
+                Selector::Chain(stages)
+            }
+            base => wrap(base),
+        };
+
+        Locator {
+            selector,
+            ..self.clone()
         }
     }
 
@@ -117,22 +608,156 @@ impl Locator {
     /// Click on the first matching element, waiting up to the specified timeout.
     /// If no timeout is provided, uses the locator's default timeout.
     pub async fn click(&self, timeout: Option<Duration>) -> Result<ClickResult, AutomationError> {
-        let element = self.wait(timeout).await?;
-        element.click()
+        let element = self.actionable(timeout).await?;
+        self.throttle().await;
+        self.checked(&element, element.click()).await
     }
 
     /// Type text into the first matching element, waiting up to the specified timeout.
     /// If no timeout is provided, uses the locator's default timeout.
     pub async fn type_text(&self, text: &str, use_clipboard: bool, timeout: Option<Duration>) -> Result<(), AutomationError> {
-        let element = self.wait(timeout).await?;
-        element.type_text(text, use_clipboard)
+        let element = self.actionable(timeout).await?;
+        self.throttle().await;
+        self.checked(&element, element.type_text(text, use_clipboard)).await
+    }
+
+    /// Like [`Locator::type_text`], and return where keyboard focus was once the text
+    /// was typed, to record where the input went
+    pub async fn type_text_with_context(&self, text: &str, use_clipboard: bool, timeout: Option<Duration>) -> Result<ActionContext, AutomationError> {
+        self.type_text(text, use_clipboard, timeout).await?;
+        Ok(ActionContext::capture(self.engine.as_ref()))
+    }
+
+    /// Type a secret into the first matching element, see [`UIElement::type_secret`].
+    /// If no timeout is provided, uses the locator's default timeout.
+    pub async fn type_secret(&self, secret: &SecretString, timeout: Option<Duration>) -> Result<(), AutomationError> {
+        let element = self.actionable(timeout).await?;
+        self.throttle().await;
+        self.checked(&element, element.type_secret(secret)).await
+    }
+
+    /// Like [`Locator::type_secret`], and return where keyboard focus was once the secret
+    /// was typed
+    pub async fn type_secret_with_context(&self, secret: &SecretString, timeout: Option<Duration>) -> Result<ActionContext, AutomationError> {
+        self.type_secret(secret, timeout).await?;
+        Ok(ActionContext::capture(self.engine.as_ref()))
     }
 
     /// Press a key on the first matching element, waiting up to the specified timeout.
     /// If no timeout is provided, uses the locator's default timeout.
     pub async fn press_key(&self, key: &str, timeout: Option<Duration>) -> Result<(), AutomationError> {
-        let element = self.wait(timeout).await?;
-        element.press_key(key)
+        let element = self.actionable(timeout).await?;
+        self.throttle().await;
+        self.checked(&element, element.press_key(key)).await
+    }
+
+    /// Click on the first matching element, verifying the click took effect as described by `options`.
+    pub async fn click_with_options(&self, options: &ActionOptions) -> Result<ClickResult, AutomationError> {
+        let element = self.actionable(options.timeout).await?;
+        self.perform_verified(&element, options, |element, _| element.click()).await
+    }
+
+    /// Type text into the first matching element, verifying the input took effect as described by `options`.
+    ///
+    /// A retry clears the field and re-types its previous contents followed by `text`, so
+    /// the text isn't appended twice. Fields that don't expose their contents can't be
+    /// restored that way and are typed into only once.
+    pub async fn type_text_with_options(&self, text: &str, use_clipboard: bool, options: &ActionOptions) -> Result<(), AutomationError> {
+        let element = self.actionable(options.timeout).await?;
+        let Some(before) = crate::action::read_field(&element) else {
+            let options = ActionOptions { retries: 0, ..options.clone() };
+            return self
+                .perform_verified(&element, &options, |element, _| element.type_text(text, use_clipboard))
+                .await;
+        };
+        let expected = format!("{}{}", before, text);
+        self.perform_verified(&element, options, |element, attempt| {
+            if attempt == 1 {
+                element.type_text(text, use_clipboard)
+            } else {
+                crate::action::clear_field(element)?;
+                element.type_text(&expected, use_clipboard)
+            }
+        })
+        .await
+    }
+
+    /// Type text into the first matching element, then read the field back and correct it
+    /// until it holds what was intended (keystrokes get dropped over RDP and in slow apps).
+    ///
+    /// The field is expected to end up as its previous contents followed by `text`. On a
+    /// mismatch it is cleared and the full expected contents re-typed, up to `options.retries`
+    /// times. `options.verify` is ignored. Returns the value the field finally accepted.
+    pub async fn type_text_read_back(&self, text: &str, use_clipboard: bool, options: &ActionOptions) -> Result<String, AutomationError> {
+        let element = self.actionable(options.timeout).await?;
+        let before = crate::action::read_field(&element).ok_or_else(|| {
+            AutomationError::UnsupportedOperation("Element does not expose its contents for read-back".to_string())
+        });
+        let before = self.checked(&element, before).await?;
+        let expected = format!("{}{}", before, text);
+
+        self.throttle().await;
+        self.checked(&element, element.type_text(text, use_clipboard)).await?;
+
+        let mut accepted = String::new();
+        for attempt in 0..=options.retries {
+            if attempt > 0 {
+                tracing::warn!(attempt, accepted = %accepted, "Typed text was not accepted as-is, re-typing");
+                self.throttle().await;
+                self.checked(&element, crate::action::clear_field(&element)).await?;
+                self.checked(&element, element.type_text(&expected, use_clipboard)).await?;
+            }
+            tokio::time::sleep(options.settle_time).await;
+
+            accepted = crate::action::read_field(&element).unwrap_or_default();
+            if accepted == expected {
+                return Ok(accepted);
+            }
+        }
+
+        let error = AutomationError::VerificationFailed(format!(
+            "field holds '{}' instead of '{}' after {} attempt(s)",
+            accepted, expected, options.retries + 1
+        ));
+        Err(self.on_failure(error, Some(&element)).await)
+    }
+
+    /// Run `action` on `element`, re-running it until `options.verify` holds. `action`
+    /// gets the attempt number, starting at 1, so a re-run can first undo what an earlier
+    /// attempt left behind.
+    async fn perform_verified<T>(
+        &self,
+        element: &UIElement,
+        options: &ActionOptions,
+        action: impl Fn(&UIElement, usize) -> Result<T, AutomationError>,
+    ) -> Result<T, AutomationError> {
+        let verify = match &options.verify {
+            Some(verify) => verify,
+            None => {
+                self.throttle().await;
+                return self.checked(element, action(element, 1)).await;
+            }
+        };
+
+        let attempts = options.retries + 1;
+        let mut last_mismatch = String::new();
+        for attempt in 1..=attempts {
+            let before = verify.snapshot(&self.engine, element);
+            self.throttle().await;
+            let result = self.checked(element, action(element, attempt)).await?;
+            tokio::time::sleep(options.settle_time).await;
+
+            match verify.check(&self.engine, element, &before) {
+                Ok(()) => return Ok(result),
+                Err(mismatch) => {
+                    tracing::warn!(attempt, attempts, %mismatch, "Action verification failed");
+                    last_mismatch = mismatch;
+                }
+            }
+        }
+
+        let error = crate::action::verification_failed(verify, attempts, &last_mismatch);
+        Err(self.on_failure(error, Some(element)).await)
     }
 
     /// Get text from the first matching element, waiting up to the specified timeout.
@@ -162,7 +787,7 @@ impl Locator {
         // Wait might return ElementNotFound or Timeout, handle appropriately
         match self.wait(timeout).await {
             Ok(element) => element.is_visible(),
-            Err(AutomationError::Timeout(_)) | Err(AutomationError::ElementNotFound(_)) => {
+            Err(e) if matches!(e.inner(), AutomationError::Timeout(_) | AutomationError::ElementNotFound(_)) => {
                  // If the element wasn't found within the timeout, it's not visible
                  Ok(false)
             }
@@ -170,6 +795,74 @@ impl Locator {
         }
     }
 
+    /// Count the elements matching right now, without waiting for any to appear.
+    /// Zero matches is `Ok(0)`, not an error.
+    pub fn count(&self) -> Result<usize, AutomationError> {
+        match self.find_all_in(PRESENCE_SEARCH_TIMEOUT, Instant::now(), None) {
+            Ok(elements) => Ok(elements.len()),
+            Err(e) if matches!(e.inner(), AutomationError::Timeout(_) | AutomationError::ElementNotFound(_)) => Ok(0),
+            Err(e) => Err(e.with_selector(&self.selector)),
+        }
+    }
+
+    /// Whether any element matches right now, without waiting for one to appear
+    pub fn exists(&self) -> Result<bool, AutomationError> {
+        match self.find_first_in(self.root.as_ref(), PRESENCE_SEARCH_TIMEOUT) {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.inner(), AutomationError::Timeout(_) | AutomationError::ElementNotFound(_)) => Ok(false),
+            Err(e) => Err(e.with_selector(&self.selector)),
+        }
+    }
+
+    /// Explain how the selector fares against the UI right now: for each stage, how
+    /// many elements were tested, which condition ruled out how many of them, and the
+    /// elements that came closest to matching. Print the result for a readable report.
+    pub fn explain(&self) -> Result<MatchExplanation, AutomationError> {
+        if self.stages().is_empty() {
+            return Err(empty_chain());
+        }
+        crate::explain::explain(&self.engine, self.stages(), self.root.as_ref())
+            .map_err(|e| e.with_selector(&self.selector))
+    }
+
+    /// Wait until the locator reaches `state`, e.g. a button becoming enabled or a dialog
+    /// going away. If no timeout is provided, uses the locator's default timeout.
+    ///
+    /// Returns the matched element for `Visible` and `Enabled`, and `None` for `Hidden`
+    /// and `Gone`, which hold while nothing matches.
+    pub async fn wait_for(&self, state: WaitState, timeout: Option<Duration>) -> Result<Option<UIElement>, AutomationError> {
+        let effective_timeout = timeout.unwrap_or(self.timeout);
+        let start = Instant::now();
+        let mut delay = self.polling.interval;
+
+        loop {
+            let found = match self.find_first_in(self.root.as_ref(), PRESENCE_SEARCH_TIMEOUT) {
+                Ok(element) => Some(element),
+                Err(e) if e.is_retryable() => None,
+                Err(e) => return Err(e.with_selector(&self.selector)),
+            };
+            let reached = match (&found, state) {
+                (Some(element), WaitState::Visible) => element.is_visible().unwrap_or(false),
+                (Some(element), WaitState::Enabled) => element.is_enabled().unwrap_or(false),
+                (Some(element), WaitState::Hidden) => !element.is_visible().unwrap_or(true),
+                (None, WaitState::Hidden | WaitState::Gone) => true,
+                _ => false,
+            };
+            if reached {
+                return Ok(found.filter(|_| matches!(state, WaitState::Visible | WaitState::Enabled)));
+            }
+
+            if start.elapsed() >= effective_timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "Timed out after {:?} waiting for element {:?} to be {:?}",
+                    effective_timeout, self.selector, state
+                ))
+                .with_selector(&self.selector));
+            }
+            self.polling.pause(&mut delay).await?;
+        }
+    }
+
     // --- Expectation Methods ---
     // These already handle timeouts internally via loops
 
@@ -177,33 +870,35 @@ impl Locator {
     pub async fn expect_enabled(&self, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
         let effective_timeout = timeout.unwrap_or(self.timeout);
         let start = Instant::now();
+        let mut delay = self.polling.interval;
 
         loop {
             // Use self.wait with a short internal timeout for each check? Or direct find_element?
             // Using find_element directly within the loop is more efficient here.
-            match self.engine.find_element(&self.selector, self.root.as_ref(), Some(Duration::from_millis(100))) { // Short timeout for check
+            match self.find_first_in(self.root.as_ref(), Duration::from_millis(100)) { // Short timeout for check
                 Ok(element) => {
                     match element.is_enabled() {
                         Ok(true) => return Ok(element),
                         Ok(false) => { /* Condition not met, continue loop */ }
                         Err(e) => { /* Error checking enabled state, maybe retry or fail */
-                             if !matches!(e, AutomationError::ElementNotFound(_)) { // Ignore not found during check
-                                return Err(e); // Return other errors from is_enabled
+                             if !e.is_retryable() { // Ignore not found / stale during check
+                                return Err(e.with_selector(&self.selector)); // Return other errors from is_enabled
                              }
                         }
                     }
                 }
-                Err(AutomationError::ElementNotFound(_)) => { /* Element not found yet, continue loop */ }
-                Err(e) => return Err(e), // Error finding the element
+                Err(e) if e.is_retryable() => { /* Element not found yet, continue loop */ }
+                Err(e) => return Err(e.with_selector(&self.selector)), // Error finding the element
             }
 
             if start.elapsed() >= effective_timeout {
                 return Err(AutomationError::Timeout(format!(
                     "Timed out after {:?} waiting for element {:?} to be enabled",
                     effective_timeout, self.selector
-                )));
+                ))
+                .with_selector(&self.selector));
             }
-            tokio::time::sleep(Duration::from_millis(100)).await;
+            self.polling.pause(&mut delay).await?;
         }
     }
 
@@ -211,32 +906,34 @@ impl Locator {
     pub async fn expect_visible(&self, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
         let effective_timeout = timeout.unwrap_or(self.timeout);
         let start = Instant::now();
+        let mut delay = self.polling.interval;
 
         loop {
              // Use find_element directly
-             match self.engine.find_element(&self.selector, self.root.as_ref(), Some(Duration::from_millis(100))) {
+             match self.find_first_in(self.root.as_ref(), Duration::from_millis(100)) {
                 Ok(element) => {
                     match element.is_visible() {
                         Ok(true) => return Ok(element),
                         Ok(false) => { /* Condition not met, continue loop */ }
                         Err(e) => {
-                             if !matches!(e, AutomationError::ElementNotFound(_)) {
-                                return Err(e);
+                             if !e.is_retryable() {
+                                return Err(e.with_selector(&self.selector));
                              }
                         }
                     }
                 }
-                Err(AutomationError::ElementNotFound(_)) => { /* Element not found yet, continue loop */ }
-                Err(e) => return Err(e),
+                Err(e) if e.is_retryable() => { /* Element not found yet, continue loop */ }
+                Err(e) => return Err(e.with_selector(&self.selector)),
             }
 
             if start.elapsed() >= effective_timeout {
                 return Err(AutomationError::Timeout(format!(
                     "Timed out after {:?} waiting for element {:?} to be visible",
                     effective_timeout, self.selector
-                )));
+                ))
+                .with_selector(&self.selector));
             }
-            tokio::time::sleep(Duration::from_millis(100)).await;
+            self.polling.pause(&mut delay).await?;
         }
     }
 
@@ -244,24 +941,25 @@ impl Locator {
     pub async fn expect_text_equals(&self, expected_text: &str, max_depth: usize, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
         let effective_timeout = timeout.unwrap_or(self.timeout);
         let start = Instant::now();
+        let mut delay = self.polling.interval;
 
         loop {
              // Use find_element directly
-             match self.engine.find_element(&self.selector, self.root.as_ref(), Some(Duration::from_millis(100))) {
+             match self.find_first_in(self.root.as_ref(), Duration::from_millis(100)) {
                 Ok(element) => {
                     match element.text(max_depth) {
                         // Trim both actual and expected for comparison robustness
                         Ok(actual_text) if actual_text.trim() == expected_text.trim() => return Ok(element),
                         Ok(_) => { /* Text doesn't match, continue loop */ }
                         Err(e) => {
-                             if !matches!(e, AutomationError::ElementNotFound(_)) {
-                                return Err(e);
+                             if !e.is_retryable() {
+                                return Err(e.with_selector(&self.selector));
                              }
                         }
                     }
                 }
-                Err(AutomationError::ElementNotFound(_)) => { /* Element not found yet, continue loop */ }
-                Err(e) => return Err(e),
+                Err(e) if e.is_retryable() => { /* Element not found yet, continue loop */ }
+                Err(e) => return Err(e.with_selector(&self.selector)),
             }
 
             if start.elapsed() >= effective_timeout {
@@ -269,9 +967,14 @@ impl Locator {
                 return Err(AutomationError::Timeout(format!(
                     "Timed out after {:?} waiting for element {:?} text to equal '{}'",
                     effective_timeout, self.selector, expected_text
-                )));
+                ))
+                .with_selector(&self.selector));
             }
-            tokio::time::sleep(Duration::from_millis(100)).await;
+            self.polling.pause(&mut delay).await?;
         }
     }
 }
+
+fn empty_chain() -> AutomationError {
+    AutomationError::InvalidArgument("Selector chain cannot be empty".to_string())
+}
@@ -0,0 +1,301 @@
+//! Filling in login dialogs from a credential store
+//!
+//! Windows Security prompts, browser basic-auth dialogs and app sign-in
+//! windows all come down to an optional user name field, a password field and
+//! a button. [`crate::Desktop::login`] finds the dialog by its password field,
+//! fills it with credentials from a [`CredentialProvider`], typing the password
+//! as a secret, and checks that the dialog went away. Credentials come from
+//! environment variables ([`EnvCredentials`]) or from the command line tool of
+//! a keyring or vault ([`CommandCredentials`]); other stores implement the trait.
+
+use crate::action::{clear_field, read_field};
+use crate::platforms::AccessibilityEngine;
+use crate::search::top_level_windows;
+use crate::utils::normalize;
+use crate::{AutomationError, Role, SecretString, UIElement};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+// How long to wait for a login dialog to show up, and then to go away once submitted
+const LOGIN_DIALOG_TIMEOUT: Duration = Duration::from_secs(10);
+const LOGIN_SUBMIT_TIMEOUT: Duration = Duration::from_secs(10);
+const LOGIN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// How deep below a window to look for the fields and the button
+const LOGIN_FIELD_DEPTH: usize = 30;
+// Captions of the buttons that submit a login, compared normalized
+const SUBMIT_BUTTON_NAMES: &[&str] = &["ok", "sign in", "log in", "login", "submit", "continue", "connect"];
+
+/// A user name and password for [`crate::Desktop::login`]
+#[derive(Debug)]
+pub struct Credentials {
+    /// `None` for prompts that only ask for a password or already show the account
+    pub username: Option<String>,
+    pub password: SecretString,
+}
+
+/// Looks up the credentials to log in to `target`, e.g. a host, app or account name
+pub trait CredentialProvider: Send + Sync {
+    fn credentials(&self, target: &str) -> Result<Credentials, AutomationError>;
+}
+
+/// Credentials from environment variables: `<PREFIX>_<TARGET>_PASSWORD` and, if set,
+/// `<PREFIX>_<TARGET>_USERNAME`, with the target upper-cased and anything but letters
+/// and digits replaced by `_`. `EnvCredentials::new("BOT")` reads `BOT_SAP_PROD_PASSWORD`
+/// for the target `sap-prod`.
+#[derive(Debug, Clone)]
+pub struct EnvCredentials {
+    prefix: String,
+}
+
+impl EnvCredentials {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self { prefix: prefix.into() }
+    }
+
+    fn variable(&self, target: &str, field: &str) -> String {
+        let target: String = target
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+            .collect();
+        format!("{}_{}_{}", self.prefix, target, field)
+    }
+}
+
+impl CredentialProvider for EnvCredentials {
+    fn credentials(&self, target: &str) -> Result<Credentials, AutomationError> {
+        let password_variable = self.variable(target, "PASSWORD");
+        let password = std::env::var(&password_variable).map_err(|_| {
+            AutomationError::InvalidArgument(format!(
+                "No password for '{}': ${} is not set",
+                target, password_variable
+            ))
+        })?;
+        Ok(Credentials {
+            username: std::env::var(self.variable(target, "USERNAME")).ok(),
+            password: SecretString::from(password),
+        })
+    }
+}
+
+/// Credentials printed by a shell command, such as a keyring or vault client. `{target}`
+/// in a command is replaced by the target, and the command's output, without its
+/// trailing newline, is the password. For example:
+///
+/// - macOS Keychain: `security find-generic-password -s {target} -w`
+/// - Linux Secret Service: `secret-tool lookup service {target}`
+/// - HashiCorp Vault: `vault kv get -field=password secret/{target}`
+///
+/// Commands run with `sh -c`, or `cmd /C` on Windows.
+#[derive(Debug, Clone)]
+pub struct CommandCredentials {
+    password_command: String,
+    username: Option<Username>,
+}
+
+#[derive(Debug, Clone)]
+enum Username {
+    Fixed(String),
+    Command(String),
+}
+
+impl CommandCredentials {
+    pub fn new(password_command: impl Into<String>) -> Self {
+        Self {
+            password_command: password_command.into(),
+            username: None,
+        }
+    }
+
+    /// Log in as `username` for every target
+    pub fn with_username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(Username::Fixed(username.into()));
+        self
+    }
+
+    /// Read the user name from the output of `command`, e.g.
+    /// `vault kv get -field=username secret/{target}`
+    pub fn with_username_command(mut self, command: impl Into<String>) -> Self {
+        self.username = Some(Username::Command(command.into()));
+        self
+    }
+}
+
+impl CredentialProvider for CommandCredentials {
+    fn credentials(&self, target: &str) -> Result<Credentials, AutomationError> {
+        // The target goes into a shell command line, so keep it to characters that can't
+        // end an argument or start another command
+        if target.is_empty()
+            || !target
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "._-:/@".contains(c))
+        {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Credential target '{}' may only contain letters, digits and . _ - : / @",
+                target
+            )));
+        }
+        let username = match &self.username {
+            Some(Username::Fixed(username)) => Some(username.clone()),
+            Some(Username::Command(command)) => Some(run_credential_command(command, target)?),
+            None => None,
+        };
+        Ok(Credentials {
+            username,
+            password: SecretString::from(run_credential_command(&self.password_command, target)?),
+        })
+    }
+}
+
+/// Run `command` for `target` and return its output without the trailing newline.
+/// The output is never logged.
+fn run_credential_command(command: &str, target: &str) -> Result<String, AutomationError> {
+    let command = command.replace("{target}", target);
+    #[cfg(target_os = "windows")]
+    let output = Command::new("cmd").args(["/C", &command]).output();
+    #[cfg(not(target_os = "windows"))]
+    let output = Command::new("sh").args(["-c", &command]).output();
+    let output = output.map_err(|e| AutomationError::platform(format!("Cannot run credential command: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(AutomationError::platform(format!(
+            "Credential command for '{}' failed ({}): {}",
+            target,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let mut value = String::from_utf8(output.stdout).map_err(|_| {
+        AutomationError::UnexpectedValue(format!("Credential command for '{}' printed invalid UTF-8", target))
+    })?;
+    // Truncate in place so the secret isn't copied into a second buffer
+    value.truncate(value.trim_end_matches(['\r', '\n']).len());
+    if value.is_empty() {
+        return Err(AutomationError::UnexpectedValue(format!(
+            "Credential command for '{}' printed nothing",
+            target
+        )));
+    }
+    Ok(value)
+}
+
+/// Fill and submit the login dialog in `window`, or in the first visible window that
+/// shows a password field, then wait for the password field to go away
+pub(crate) async fn login(
+    engine: &Arc<dyn AccessibilityEngine>,
+    credentials: &Credentials,
+    window: Option<&UIElement>,
+) -> Result<(), AutomationError> {
+    let (dialog, password_field) = wait_for_dialog(engine, window).await?;
+    info!(dialog = %dialog.describe(), "Found login dialog");
+
+    if let Some(username) = &credentials.username {
+        match username_field(&dialog, &password_field) {
+            Some(field) => fill_username(&field, username)?,
+            None => debug!("Login dialog has no user name field, entering the password only"),
+        }
+    }
+
+    password_field.focus()?;
+    clear_field(&password_field)?;
+    password_field.type_secret(&credentials.password)?;
+
+    match submit_button(&dialog) {
+        Some(button) => {
+            debug!(button = %button.describe(), "Submitting login");
+            button.click()?;
+        }
+        None => password_field.press_key("Enter")?,
+    }
+
+    let start = Instant::now();
+    while password_field.is_visible().unwrap_or(false) {
+        if start.elapsed() >= LOGIN_SUBMIT_TIMEOUT {
+            return Err(AutomationError::VerificationFailed(format!(
+                "Login dialog still open after {:?}; the credentials may have been rejected",
+                LOGIN_SUBMIT_TIMEOUT
+            )));
+        }
+        crate::abort::check()?;
+        tokio::time::sleep(LOGIN_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+/// The window to log in with and its password field, waiting for one to appear
+async fn wait_for_dialog(
+    engine: &Arc<dyn AccessibilityEngine>,
+    window: Option<&UIElement>,
+) -> Result<(UIElement, UIElement), AutomationError> {
+    let start = Instant::now();
+    loop {
+        crate::abort::check()?;
+        let windows = match window {
+            Some(window) => vec![window.clone()],
+            None => top_level_windows(engine)?,
+        };
+        for window in windows {
+            if let Some(field) = password_field(&window) {
+                return Ok((window, field));
+            }
+        }
+        if start.elapsed() >= LOGIN_DIALOG_TIMEOUT {
+            return Err(AutomationError::ElementNotFound(format!(
+                "No login dialog with a password field appeared within {:?}",
+                LOGIN_DIALOG_TIMEOUT
+            )));
+        }
+        tokio::time::sleep(LOGIN_POLL_INTERVAL).await;
+    }
+}
+
+fn password_field(window: &UIElement) -> Option<UIElement> {
+    window.descendants(LOGIN_FIELD_DEPTH).find(|element| {
+        element.attributes().state.password == Some(true) && element.is_visible().unwrap_or(false)
+    })
+}
+
+/// The last text field before the password field, which is where dialogs put the
+/// user name
+fn username_field(dialog: &UIElement, password_field: &UIElement) -> Option<UIElement> {
+    let mut candidate = None;
+    for element in dialog.descendants(LOGIN_FIELD_DEPTH) {
+        if element == *password_field {
+            return candidate;
+        }
+        if matches!(element.normalized_role(), Role::Edit | Role::ComboBox)
+            && element.attributes().state.read_only != Some(true)
+            && element.is_enabled().unwrap_or(false)
+            && element.is_visible().unwrap_or(false)
+        {
+            candidate = Some(element);
+        }
+    }
+    None
+}
+
+/// Set the user name directly if the field allows it and type it otherwise, leaving a
+/// field that already shows it alone
+fn fill_username(field: &UIElement, username: &str) -> Result<(), AutomationError> {
+    let shows_username = |field: &UIElement| read_field(field).is_some_and(|shown| shown.trim() == username);
+    if shows_username(field) {
+        return Ok(());
+    }
+    if field.set_value(username).is_ok() && shows_username(field) {
+        return Ok(());
+    }
+    field.focus()?;
+    clear_field(field)?;
+    field.type_text(username, false)
+}
+
+fn submit_button(dialog: &UIElement) -> Option<UIElement> {
+    dialog.descendants(LOGIN_FIELD_DEPTH).find(|element| {
+        element.normalized_role() == Role::Button
+            && element
+                .name()
+                .is_some_and(|name| SUBMIT_BUTTON_NAMES.contains(&normalize(name.trim()).as_str()))
+            && element.is_enabled().unwrap_or(false)
+    })
+}
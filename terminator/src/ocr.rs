@@ -0,0 +1,96 @@
+//! Finding text on screen by OCR
+//!
+//! Custom-drawn controls often show a caption that never reaches the
+//! accessibility tree. A [`crate::Selector::OcrText`] reads the screen
+//! instead and resolves to the area where the words were recognized.
+
+use crate::platforms::AccessibilityEngine;
+use crate::region::{ScopeCapture, ScreenRegionElement};
+use crate::{AutomationError, Role, UIElement};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+// How often the screen is read again while an OCR selector waits
+const OCR_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A word recognized by OCR and where it was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrWord {
+    pub text: String,
+    /// Index of the line the word is on, in reading order
+    pub line: usize,
+    /// `(x, y, width, height)` in pixels of the recognized image
+    pub bounds: (f64, f64, f64, f64),
+}
+
+/// Resolve a [`crate::Selector::OcrText`]: every place in `root`'s bounds (the primary
+/// monitor without a root) where `text` was recognized, in reading order. A phrase
+/// matches consecutive words on one line, ignoring case and surrounding punctuation.
+/// The screen is read again until `timeout` runs out.
+pub(crate) fn find_ocr_text(
+    engine: &dyn AccessibilityEngine,
+    text: &str,
+    root: Option<&UIElement>,
+    timeout: Option<Duration>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let wanted: Vec<String> = text
+        .split_whitespace()
+        .map(normalize_word)
+        .filter(|word| !word.is_empty())
+        .collect();
+    if wanted.is_empty() {
+        return Err(AutomationError::InvalidArgument(format!(
+            "OCR selector needs text to look for, got '{}'",
+            text
+        )));
+    }
+
+    let start = Instant::now();
+    loop {
+        let capture = ScopeCapture::new(engine, root)?;
+        let words = engine.ocr_words(&capture.image)?;
+        let matches: Vec<UIElement> = find_phrase(&words, &wanted)
+            .into_iter()
+            .map(|(x, y, width, height)| {
+                ScreenRegionElement::new(engine, capture.to_screen(x, y, width, height), Role::Text)
+                    .with_name(text)
+                    .into_element()
+            })
+            .collect();
+        if !matches.is_empty() {
+            debug!(count = matches.len(), "OCR selector matched");
+            return Ok(matches);
+        }
+        if timeout.is_none_or(|timeout| start.elapsed() >= timeout) {
+            return Err(AutomationError::ElementNotFound(format!(
+                "Text '{}' not found on screen by OCR ({} words recognized)",
+                text,
+                words.len()
+            )));
+        }
+        crate::abort::check()?;
+        std::thread::sleep(OCR_POLL_INTERVAL);
+    }
+}
+
+/// The bounds of each run of consecutive `words` on one line that reads `wanted`
+fn find_phrase(words: &[OcrWord], wanted: &[String]) -> Vec<(f64, f64, f64, f64)> {
+    words
+        .windows(wanted.len())
+        .filter(|run| {
+            run.iter().all(|word| word.line == run[0].line)
+                && run.iter().zip(wanted).all(|(word, wanted)| normalize_word(&word.text) == *wanted)
+        })
+        .map(|run| {
+            let left = run.iter().map(|w| w.bounds.0).fold(f64::INFINITY, f64::min);
+            let top = run.iter().map(|w| w.bounds.1).fold(f64::INFINITY, f64::min);
+            let right = run.iter().map(|w| w.bounds.0 + w.bounds.2).fold(f64::NEG_INFINITY, f64::max);
+            let bottom = run.iter().map(|w| w.bounds.1 + w.bounds.3).fold(f64::NEG_INFINITY, f64::max);
+            (left, top, right - left, bottom - top)
+        })
+        .collect()
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
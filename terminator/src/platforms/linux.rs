@@ -9,7 +9,7 @@ pub struct LinuxEngine;
 
 impl LinuxEngine {
     pub fn new(use_background_apps: bool, activate_app: bool) -> Result<Self, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
@@ -22,25 +22,25 @@ impl AccessibilityEngine for LinuxEngine {
     }
 
     fn get_focused_element(&self) -> Result<UIElement, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn get_applications(&self) -> Result<Vec<UIElement>, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn get_application_by_name(&self, _name: &str) -> Result<UIElement, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn get_application_by_pid(&self, _pid: i32) -> Result<UIElement, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
@@ -50,7 +50,7 @@ impl AccessibilityEngine for LinuxEngine {
         selector: &Selector,
         root: Option<&UIElement>,
     ) -> Result<UIElement, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
@@ -60,25 +60,25 @@ impl AccessibilityEngine for LinuxEngine {
         _selector: &Selector,
         _root: Option<&UIElement>,
     ) -> Result<Vec<UIElement>, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn open_application(&self, _app_name: &str) -> Result<UIElement, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn open_url(&self, _url: &str, _browser: Option<&str>) -> Result<UIElement, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn open_file(&self, _file_path: &str) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
@@ -88,31 +88,31 @@ impl AccessibilityEngine for LinuxEngine {
         _windows_command: Option<&str>,
         _unix_command: Option<&str>,
     ) -> Result<crate::CommandOutput, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn capture_screen(&self) -> Result<ScreenshotResult, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn capture_monitor_by_name(&self, _name: &str) -> Result<ScreenshotResult, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     async fn ocr_image_path(&self, _image_path: &str) -> Result<String, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     async fn ocr_screenshot(&self, _screenshot: &ScreenshotResult) -> Result<String, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
@@ -122,7 +122,7 @@ impl AccessibilityEngine for LinuxEngine {
         title_contains: Option<&str>,
         timeout: Option<Duration>,
     ) -> Result<UIElement, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "find_window_by_criteria not yet implemented for Linux".to_string(),
         ))
     }
@@ -159,11 +159,13 @@ impl UIElementImpl for LinuxUIElement {
     fn attributes(&self) -> UIElementAttributes {
         UIElementAttributes {
             role: "".to_string(),
+            normalized_role: crate::Role::Unknown,
             name: None,
             label: None,
             value: None,
             description: None,
             properties: std::collections::HashMap::new(),
+            ..Default::default()
         }
     }
 
@@ -172,97 +174,97 @@ impl UIElementImpl for LinuxUIElement {
     }
 
     fn children(&self) -> Result<Vec<UIElement>, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn parent(&self) -> Result<Option<UIElement>, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn bounds(&self) -> Result<(f64, f64, f64, f64), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn click(&self) -> Result<ClickResult, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn double_click(&self) -> Result<ClickResult, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
-    fn right_click(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+    fn right_click(&self) -> Result<ClickResult, AutomationError> {
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn hover(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn focus(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn type_text(&self, _text: &str, _use_clipboard: bool) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn press_key(&self, _key: &str) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn get_text(&self, max_depth: usize) -> Result<String, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn set_value(&self, _value: &str) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn is_enabled(&self) -> Result<bool, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn is_visible(&self) -> Result<bool, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn is_focused(&self) -> Result<bool, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
 
     fn perform_action(&self, _action: &str) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
@@ -272,7 +274,7 @@ impl UIElementImpl for LinuxUIElement {
     }
 
     fn create_locator(&self, _selector: Selector) -> Result<Locator, AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
@@ -282,7 +284,7 @@ impl UIElementImpl for LinuxUIElement {
     }
 
     fn scroll(&self, _direction: &str, _amount: f64) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Linux implementation is not yet available".to_string(),
         ))
     }
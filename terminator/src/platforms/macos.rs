@@ -1,8 +1,10 @@
+use crate::keys::{Key, KeyChord, Modifier};
 use crate::platforms::AccessibilityEngine;
 use crate::{
-    AutomationError, Locator, Selector, UIElement, UIElementAttributes, element::UIElementImpl,
+    AutomationError, ElementState, Locator, Role, Selector, SelectorRegex, UIElement, UIElementAttributes,
+    element::UIElementImpl,
 };
-use crate::{ClickResult, ScreenshotResult};
+use crate::{ClickResult, RangeValue, ScreenshotResult};
 
 use accessibility::AXUIElementAttributes;
 use accessibility::{AXAttribute, AXUIElement};
@@ -38,8 +40,32 @@ unsafe extern "C" {
         attribute: *const ::std::os::raw::c_void,
         value: *const ::std::os::raw::c_void,
     ) -> i32;
+    fn AXUIElementSetMessagingTimeout(element: *mut ::std::os::raw::c_void, timeout_seconds: f32) -> i32;
 }
 
+// Current keyboard and mouse state as seen by the window server
+#[link(name = "ApplicationServices", kind = "framework")]
+unsafe extern "C" {
+    fn CGEventSourceFlagsState(state_id: i32) -> u64;
+    fn CGEventSourceButtonState(state_id: i32, button: u32) -> bool;
+    fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+    fn CGSessionCopyCurrentDictionary() -> core_foundation::dictionary::CFDictionaryRef;
+}
+
+// kCGEventSourceStateCombinedSessionState: hardware and posted events together
+const COMBINED_SESSION_STATE: i32 = 0;
+// kCGAnyInputEventType
+const ANY_INPUT_EVENT_TYPE: u32 = !0;
+// How long an application gets to answer before is_responsive treats it as busy
+const RESPONSIVE_MESSAGING_TIMEOUT_SECS: f32 = 0.25;
+// AXError codes that map onto a specific AutomationError category
+const AX_ERROR_INVALID_UI_ELEMENT: i32 = -25202;
+// kAXErrorCannotComplete: the application did not answer in time
+const AX_ERROR_CANNOT_COMPLETE: i32 = -25204;
+const AX_ERROR_ATTRIBUTE_UNSUPPORTED: i32 = -25205;
+const AX_ERROR_ACTION_UNSUPPORTED: i32 = -25206;
+const AX_ERROR_API_DISABLED: i32 = -25211;
+
 // Add these extern "C" declarations if not already present
 unsafe extern "C" {
     fn AXValueGetValue(
@@ -53,23 +79,18 @@ unsafe extern "C" {
 const K_AXVALUE_CGPOINT_TYPE: u32 = 1;
 const K_AXVALUE_CGSIZE_TYPE: u32 = 2;
 
-// Add these constant definitions for key codes
-const KEY_RETURN: u16 = 36;
-const KEY_TAB: u16 = 48;
-const KEY_SPACE: u16 = 49;
-const KEY_DELETE: u16 = 51;
-const KEY_ESCAPE: u16 = 53;
-const KEY_ARROW_LEFT: u16 = 123;
-const KEY_ARROW_RIGHT: u16 = 124;
-const KEY_ARROW_DOWN: u16 = 125;
-const KEY_ARROW_UP: u16 = 126;
+// How long find_windows waits for a match by default, and how often it re-checks
+const DEFAULT_WINDOW_SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+const WINDOW_SEARCH_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Left and right Command, Shift, Option and Control
+const MODIFIER_KEY_CODES: [CGKeyCode; 8] = [55, 54, 56, 60, 58, 61, 59, 62];
 
 // Add these constants for modifier keys
 const MODIFIER_COMMAND: CGEventFlags = CGEventFlags::CGEventFlagCommand;
 const MODIFIER_SHIFT: CGEventFlags = CGEventFlags::CGEventFlagShift;
 const MODIFIER_OPTION: CGEventFlags = CGEventFlags::CGEventFlagAlternate;
 const MODIFIER_CONTROL: CGEventFlags = CGEventFlags::CGEventFlagControl;
-const MODIFIER_FN: CGEventFlags = CGEventFlags::CGEventFlagSecondaryFn;
 
 // Thread-safe wrapper for AXUIElement
 #[derive(Clone)]
@@ -172,6 +193,37 @@ impl MacOSEngine {
         }))
     }
 
+    // Collector for elements whose `key` attribute matches a regex selector; the
+    // regex is compiled once (and cached on the selector) rather than per element
+    fn regex_collector(
+        &self,
+        root: &AXUIElement,
+        key: &str,
+        pattern: &SelectorRegex,
+    ) -> Result<ElementsCollectorWithWindows, AutomationError> {
+        let regex = pattern.regex()?.clone();
+        let key = key.to_string();
+        let use_bg = self.use_background_apps;
+        let activate = self.activate_app;
+        Ok(ElementsCollectorWithWindows::new(root, move |e| {
+            let element = UIElement::new(Box::new(MacOSUIElement {
+                element: ThreadSafeAXUIElement::new(e.clone()),
+                use_background_apps: use_bg,
+                activate_app: activate,
+            }));
+            crate::selector::regex_matches(&element, &key, &regex)
+        }))
+    }
+
+    // Collector for elements whose AX attribute `key` equals `value`
+    fn attribute_collector(&self, root: &AXUIElement, key: &str, value: &str) -> ElementsCollectorWithWindows {
+        let key = key.to_string();
+        let value = value.to_string();
+        ElementsCollectorWithWindows::new(root, move |e| {
+            ax_attribute_string(e, &key).is_some_and(|actual| actual == value)
+        })
+    }
+
     // Add this new method to refresh the accessibility tree
     #[allow(clippy::unexpected_cfg_condition)]
     pub fn refresh_accessibility_tree(
@@ -414,9 +466,10 @@ impl MacOSUIElement {
                     method: "AXPress".to_string(),
                     coordinates: None,
                     details: "Used accessibility AXPress action".to_string(),
+                    context: None,
                 })
             }
-            Err(e) => Err(AutomationError::PlatformError(format!(
+            Err(e) => Err(AutomationError::platform(format!(
                 "AXPress click failed: {:?}",
                 e
             ))),
@@ -432,9 +485,10 @@ impl MacOSUIElement {
                     method: "AXClick".to_string(),
                     coordinates: None,
                     details: "Used accessibility AXClick action".to_string(),
+                    context: None,
                 })
             }
-            Err(e) => Err(AutomationError::PlatformError(format!(
+            Err(e) => Err(AutomationError::platform(format!(
                 "AXClick click failed: {:?}",
                 e
             ))),
@@ -460,7 +514,7 @@ impl MacOSUIElement {
                     core_graphics::event_source::CGEventSourceStateID::HIDSystemState,
                 )
                 .map_err(|_| {
-                    AutomationError::PlatformError("Failed to create event source".to_string())
+                    AutomationError::platform("Failed to create event source".to_string())
                 })?;
 
                 // Move mouse to position
@@ -471,7 +525,7 @@ impl MacOSUIElement {
                     CGMouseButton::Left,
                 )
                 .map_err(|_| {
-                    AutomationError::PlatformError("Failed to create mouse move event".to_string())
+                    AutomationError::platform("Failed to create mouse move event".to_string())
                 })?;
                 mouse_move.post(core_graphics::event::CGEventTapLocation::HID);
 
@@ -488,7 +542,7 @@ impl MacOSUIElement {
                     CGMouseButton::Left,
                 )
                 .map_err(|_| {
-                    AutomationError::PlatformError("Failed to create mouse down event".to_string())
+                    AutomationError::platform("Failed to create mouse down event".to_string())
                 })?;
                 mouse_down.post(core_graphics::event::CGEventTapLocation::HID);
 
@@ -505,7 +559,7 @@ impl MacOSUIElement {
                     CGMouseButton::Left,
                 )
                 .map_err(|_| {
-                    AutomationError::PlatformError("Failed to create mouse up event".to_string())
+                    AutomationError::platform("Failed to create mouse up event".to_string())
                 })?;
                 mouse_up.post(core_graphics::event::CGEventTapLocation::HID);
 
@@ -521,82 +575,16 @@ impl MacOSUIElement {
                         "Used mouse simulation at coordinates ({:.1}, {:.1}), element bounds: ({:.1}, {:.1}, {:.1}, {:.1})",
                         center_x, center_y, x, y, width, height
                     ),
+                    context: None,
                 })
             }
-            Err(e) => Err(AutomationError::PlatformError(format!(
+            Err(e) => Err(AutomationError::platform(format!(
                 "Failed to determine element bounds for click: {}",
                 e
             ))),
         }
     }
 
-    fn get_key_code(&self, key: &str) -> Result<u16, AutomationError> {
-        let key_map: HashMap<&str, u16> = [
-            ("return", KEY_RETURN),
-            ("enter", KEY_RETURN),
-            ("tab", KEY_TAB),
-            ("space", KEY_SPACE),
-            ("delete", KEY_DELETE),
-            ("backspace", KEY_DELETE),
-            ("esc", KEY_ESCAPE),
-            ("escape", KEY_ESCAPE),
-            ("left", KEY_ARROW_LEFT),
-            ("right", KEY_ARROW_RIGHT),
-            ("down", KEY_ARROW_DOWN),
-            ("up", KEY_ARROW_UP),
-        ]
-        .iter()
-        .cloned()
-        .collect();
-
-        key_map
-            .get(key.to_lowercase().as_str())
-            .copied()
-            .ok_or_else(|| AutomationError::InvalidArgument(format!("Unknown key: {}", key)))
-    }
-
-    // Add a method to parse key combinations with modifiers
-    fn parse_key_combination(
-        &self,
-        key_combo: &str,
-    ) -> Result<(u16, CGEventFlags), AutomationError> {
-        // Change Vec<&str> to Vec<String> to match the to_lowercase() output type
-        let parts: Vec<String> = key_combo
-            .split('+')
-            .map(|s| s.trim().to_lowercase())
-            .collect();
-
-        if parts.is_empty() {
-            return Err(AutomationError::InvalidArgument(
-                "Empty key combination".to_string(),
-            ));
-        }
-
-        // The last part is the actual key
-        let key = &parts[parts.len() - 1];
-        let key_code = self.get_key_code(key)?;
-
-        // All parts except the last one are modifiers
-        let mut flags = CGEventFlags::empty();
-        for modifier in &parts[0..parts.len() - 1] {
-            match modifier.as_str() {
-                "cmd" | "command" => flags.insert(MODIFIER_COMMAND),
-                "shift" => flags.insert(MODIFIER_SHIFT),
-                "alt" | "option" => flags.insert(MODIFIER_OPTION),
-                "ctrl" | "control" => flags.insert(MODIFIER_CONTROL),
-                "fn" => flags.insert(MODIFIER_FN),
-                _ => {
-                    return Err(AutomationError::InvalidArgument(format!(
-                        "Unknown modifier: {}",
-                        modifier
-                    )));
-                }
-            }
-        }
-
-        Ok((key_code, flags))
-    }
-
     fn generate_stable_id(&self) -> String {
         let mut hasher = DefaultHasher::new();
 
@@ -711,11 +699,13 @@ impl UIElementImpl for MacOSUIElement {
 
             let mut attrs = UIElementAttributes {
                 role: "window".to_string(),
+                normalized_role: Role::Window,
                 name: None,
                 label: None,
                 value: None,
                 description: None,
                 properties,
+                ..Default::default()
             };
 
             // Special handling for window title - try multiple attributes
@@ -771,11 +761,17 @@ impl UIElementImpl for MacOSUIElement {
         let mut attrs = UIElementAttributes {
             // Use our role() method which handles the mapping of AXMenuItem to button
             role: self.role(),
+            normalized_role: self
+                .element
+                .0
+                .role()
+                .map_or(Role::Unknown, |r| Role::from_ax_role(&r.to_string())),
             name: None,
             label: None,
             value: None,
             description: None,
             properties,
+            ..Default::default()
         };
 
         // Debug attribute collection
@@ -850,6 +846,83 @@ impl UIElementImpl for MacOSUIElement {
             debug!("Failed to get attribute names");
         }
 
+        let string_property = |name: &str| {
+            attrs
+                .properties
+                .get(name)
+                .and_then(|v| v.as_ref()?.as_str().map(str::to_string))
+                .filter(|s| !s.is_empty())
+        };
+        let bool_property =
+            |name: &str| attrs.properties.get(name).and_then(|v| v.as_ref()?.as_bool());
+        let help_text = string_property("AXHelp");
+        let placeholder = string_property("AXPlaceholderValue");
+        let accelerator_key = string_property("AXMenuItemCmdChar").map(|key| {
+            // Modifier bits: 1 = shift, 2 = option, 4 = control, 8 = no command key
+            let modifiers = attrs
+                .properties
+                .get("AXMenuItemCmdModifiers")
+                .and_then(|v| v.as_ref()?.as_i64())
+                .unwrap_or(0);
+            let mut parts = Vec::new();
+            if modifiers & 4 != 0 {
+                parts.push("ctrl");
+            }
+            if modifiers & 2 != 0 {
+                parts.push("option");
+            }
+            if modifiers & 1 != 0 {
+                parts.push("shift");
+            }
+            if modifiers & 8 == 0 {
+                parts.push("cmd");
+            }
+            parts.push(&key);
+            parts.join("+")
+        });
+        let is_checkable = matches!(attrs.normalized_role, Role::CheckBox | Role::RadioButton);
+        let state = ElementState {
+            enabled: bool_property("AXEnabled"),
+            focused: bool_property("AXFocused"),
+            offscreen: None,
+            read_only: None,
+            required: bool_property("AXRequired"),
+            password: Some(string_property("AXSubrole").as_deref() == Some("AXSecureTextField")),
+            // Checkboxes report 0 (off), 1 (on) or 2 (mixed) as their value
+            checked: if is_checkable {
+                match attrs.properties.get("AXValue").and_then(|v| v.as_ref()?.as_i64()) {
+                    Some(0) => Some(false),
+                    Some(1) => Some(true),
+                    _ => None,
+                }
+            } else {
+                None
+            },
+            expanded: bool_property("AXExpanded"),
+            selected: bool_property("AXSelected"),
+        };
+        attrs.help_text = help_text;
+        attrs.placeholder = placeholder;
+        attrs.accelerator_key = accelerator_key;
+        attrs.state = state;
+
+        // The caption element linked to this one, e.g. the static text next to a field
+        let title_element_attr = AXAttribute::new(&CFString::new("AXTitleUIElement"));
+        attrs.labeled_by = self
+            .element
+            .0
+            .attribute(&title_element_attr)
+            .ok()
+            .and_then(|value| value.downcast_into::<AXUIElement>())
+            .and_then(|title_element| {
+                title_element
+                    .value()
+                    .ok()
+                    .and_then(|v| v.downcast_into::<CFString>())
+                    .or_else(|| title_element.title().ok())
+            })
+            .map(|s| s.to_string());
+
         attrs
     }
 
@@ -904,7 +977,7 @@ impl UIElementImpl for MacOSUIElement {
                     Ok(all_children)
                 } else {
                     // Otherwise return the error
-                    Err(AutomationError::PlatformError(format!(
+                    Err(AutomationError::platform(format!(
                         "Failed to get children: {}",
                         e
                     )))
@@ -1010,22 +1083,52 @@ impl UIElementImpl for MacOSUIElement {
                         "Double-click: First click: {}, Second click: {}",
                         first_click.details, second_click.details
                     ),
+                    context: second_click.context,
                 })
             }
             Err(e) => Err(e),
         }
     }
 
-    fn right_click(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedOperation(
-            "Right-click not yet implemented for macOS".to_string(),
-        ))
+    fn right_click(&self) -> Result<ClickResult, AutomationError> {
+        use core_graphics::event::CGEventType;
+
+        // 1. Try AXShowMenu, the accessibility action for opening a context menu
+        let show_menu = AXAttribute::new(&CFString::new("AXShowMenu"));
+        match self.element.0.perform_action(&show_menu.as_CFString()) {
+            Ok(_) => {
+                return Ok(ClickResult {
+                    method: "AXShowMenu".to_string(),
+                    coordinates: None,
+                    details: "Used accessibility AXShowMenu action".to_string(),
+                    context: None,
+                });
+            }
+            Err(e) => debug!("AXShowMenu failed: {:?}, trying mouse simulation", e),
+        }
+
+        // 2. Right-click the center of the element
+        let (x, y, width, height) = self.bounds()?;
+        let (center_x, center_y) = (x + width / 2.0, y + height / 2.0);
+        post_mouse_event(CGEventType::MouseMoved, center_x, center_y)?;
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        post_mouse_event(CGEventType::RightMouseDown, center_x, center_y)?;
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        post_mouse_event(CGEventType::RightMouseUp, center_x, center_y)?;
+        Ok(ClickResult {
+            method: "MouseSimulation".to_string(),
+            coordinates: Some((center_x, center_y)),
+            details: format!(
+                "Used right mouse button at coordinates ({:.1}, {:.1}), element bounds: ({:.1}, {:.1}, {:.1}, {:.1})",
+                center_x, center_y, x, y, width, height
+            ),
+            context: None,
+        })
     }
 
     fn hover(&self) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedOperation(
-            "Hover not yet implemented for macOS".to_string(),
-        ))
+        let (x, y, width, height) = self.bounds()?;
+        self.mouse_move(x + width / 2.0, y + height / 2.0)
     }
 
     fn focus(&self) -> Result<(), AutomationError> {
@@ -1136,10 +1239,7 @@ impl UIElementImpl for MacOSUIElement {
                     result
                 );
 
-                return Err(AutomationError::PlatformError(format!(
-                    "Failed to set text: error code {}",
-                    result
-                )));
+                return Err(map_ax_error("AXUIElementSetAttributeValue", result, "Failed to set text"));
             }
         }
 
@@ -1162,48 +1262,11 @@ impl UIElementImpl for MacOSUIElement {
                     element_role, element_label, key_combo, e
                 );
                 debug!("{}", error_msg);
-                return Err(AutomationError::PlatformError(error_msg));
+                return Err(AutomationError::platform(error_msg));
             }
         }
 
-        // Parse the key combination
-        let (key_code, flags) = self.parse_key_combination(key_combo)?;
-
-        // Create event source
-        let source =
-            CGEventSource::new(core_graphics::event_source::CGEventSourceStateID::HIDSystemState)
-                .map_err(|_| {
-                AutomationError::PlatformError("Failed to create event source".to_string())
-            })?;
-
-        // Key down event with modifiers
-        let key_down = CGEvent::new_keyboard_event(source.clone(), key_code as CGKeyCode, true)
-            .map_err(|_| {
-                AutomationError::PlatformError("Failed to create key down event".to_string())
-            })?;
-
-        // Set modifiers if any
-        if !flags.is_empty() {
-            key_down.set_flags(flags);
-        }
-
-        key_down.post(core_graphics::event::CGEventTapLocation::HID);
-
-        // Brief pause
-        std::thread::sleep(std::time::Duration::from_millis(50));
-
-        // Key up event with same modifiers
-        let key_up =
-            CGEvent::new_keyboard_event(source, key_code as CGKeyCode, false).map_err(|_| {
-                AutomationError::PlatformError("Failed to create key up event".to_string())
-            })?;
-
-        // Set the same modifiers for key up
-        if !flags.is_empty() {
-            key_up.set_flags(flags);
-        }
-
-        key_up.post(core_graphics::event::CGEventTapLocation::HID);
+        self.press_chord(&KeyChord::parse(key_combo)?)?;
 
         debug!("Successfully pressed key combination: {}", key_combo);
         Ok(())
@@ -1267,16 +1330,132 @@ impl UIElementImpl for MacOSUIElement {
             if result != 0 {
                 debug!("Failed to set value via AXValue: error code {}", result);
 
-                return Err(AutomationError::PlatformError(format!(
-                    "Failed to set value: error code {}",
-                    result
-                )));
+                return Err(map_ax_error("AXUIElementSetAttributeValue", result, "Failed to set value"));
             }
         }
 
         Ok(())
     }
 
+    fn value(&self) -> Result<String, AutomationError> {
+        use core_foundation::number::CFNumber;
+
+        let value = self.element.0.value().map_err(|_| {
+            AutomationError::UnsupportedOperation("Element has no AXValue".to_string())
+        })?;
+        if let Some(text) = value.downcast::<CFString>() {
+            return Ok(text.to_string());
+        }
+        // Checkboxes, sliders and steppers hold a number
+        match value.downcast::<CFNumber>().and_then(|number| number.to_f64()) {
+            Some(number) => Ok(number.to_string()),
+            None => Err(AutomationError::UnexpectedValue(
+                "AXValue is neither text nor a number".to_string(),
+            )),
+        }
+    }
+
+    fn set_range_value(&self, value: f64) -> Result<(), AutomationError> {
+        // Steppers and sliders take a numeric AXValue rather than a string
+        let number = core_foundation::number::CFNumber::from(value);
+
+        unsafe {
+            let element_ref = self.element.0.as_concrete_TypeRef() as *mut ::std::os::raw::c_void;
+            let attr_str = CFString::new("AXValue");
+            let attr_str_ref = attr_str.as_concrete_TypeRef() as *const ::std::os::raw::c_void;
+            let value_ref = number.as_concrete_TypeRef() as *const ::std::os::raw::c_void;
+
+            let result = AXUIElementSetAttributeValue(element_ref, attr_str_ref, value_ref);
+
+            if result != 0 {
+                debug!("Failed to set numeric AXValue: error code {}", result);
+
+                return Err(map_ax_error("AXUIElementSetAttributeValue", result, "Failed to set range value"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn range_value(&self) -> Result<RangeValue, AutomationError> {
+        let read = |name| ax_attribute_f64(&self.element.0, name);
+        match (read("AXMinValue"), read("AXMaxValue"), read("AXValue")) {
+            (Some(minimum), Some(maximum), Some(value)) => Ok(RangeValue {
+                minimum,
+                maximum,
+                value,
+            }),
+            _ => Err(AutomationError::UnsupportedOperation(
+                "Element has no numeric AXMinValue, AXMaxValue and AXValue".to_string(),
+            )),
+        }
+    }
+
+    fn is_responsive(&self) -> Result<bool, AutomationError> {
+        // A hung application never answers accessibility requests, so ask it something
+        // cheap with a short timeout instead of the system default of several seconds
+        let element_ref = self.element.0.as_concrete_TypeRef() as *mut ::std::os::raw::c_void;
+        unsafe { AXUIElementSetMessagingTimeout(element_ref, RESPONSIVE_MESSAGING_TIMEOUT_SECS) };
+        let answer = self.element.0.role();
+        // Zero restores the global timeout
+        unsafe { AXUIElementSetMessagingTimeout(element_ref, 0.0) };
+        match answer {
+            Ok(_) => Ok(true),
+            Err(accessibility::Error::Ax(AX_ERROR_CANNOT_COMPLETE)) => Ok(false),
+            Err(accessibility::Error::Ax(code)) => Err(map_ax_error("AXUIElementCopyAttributeValue", code, "Failed to query element")),
+            Err(e) => Err(AutomationError::platform(format!("Failed to query element: {:?}", e))),
+        }
+    }
+
+    fn process_id(&self) -> Result<u32, AutomationError> {
+        u32::try_from(get_pid_for_element(&self.element)).map_err(|_| {
+            AutomationError::platform("AXUIElementGetPid failed".to_string())
+        })
+    }
+
+    fn scroll_into_view(&self) -> Result<(), AutomationError> {
+        let scroll_to_visible = AXAttribute::new(&CFString::new("AXScrollToVisible"));
+        self.element
+            .0
+            .perform_action(&scroll_to_visible.as_CFString())
+            .map_err(|e| AutomationError::platform(format!("AXScrollToVisible failed: {:?}", e)))
+    }
+
+    fn set_expanded(&self, expanded: bool) -> Result<(), AutomationError> {
+        // Pop-up buttons and combo boxes open their menu on AXShowMenu or AXPress, and an
+        // open menu closes on AXCancel
+        if expanded {
+            self.perform_action("AXShowMenu").or_else(|_| self.perform_action("AXPress"))
+        } else {
+            self.perform_action("AXCancel")
+        }
+    }
+
+    fn toggle(&self) -> Result<(), AutomationError> {
+        // Checkboxes and toggle buttons flip when pressed
+        self.perform_action("AXPress")
+    }
+
+    fn select_item(&self) -> Result<(), AutomationError> {
+        // Menu items are picked; rows of lists and tables are selected through AXSelected
+        if self.perform_action("AXPick").is_ok() {
+            return Ok(());
+        }
+        let selected = core_foundation::boolean::CFBoolean::true_value();
+        unsafe {
+            let element_ref = self.element.0.as_concrete_TypeRef() as *mut ::std::os::raw::c_void;
+            let attr_str = CFString::new("AXSelected");
+            let attr_str_ref = attr_str.as_concrete_TypeRef() as *const ::std::os::raw::c_void;
+            let value_ref = selected.as_concrete_TypeRef() as *const ::std::os::raw::c_void;
+
+            let result = AXUIElementSetAttributeValue(element_ref, attr_str_ref, value_ref);
+            if result != 0 {
+                return Err(map_ax_error("AXUIElementSetAttributeValue", result, "Failed to select item"));
+            }
+        }
+        Ok(())
+    }
+
     fn is_enabled(&self) -> Result<bool, AutomationError> {
         // not implemented
         Err(AutomationError::UnsupportedOperation(
@@ -1312,11 +1491,16 @@ impl UIElementImpl for MacOSUIElement {
         self.element
             .0
             .perform_action(&action_attr.as_CFString())
-            .map_err(|e| {
-                AutomationError::PlatformError(format!(
+            .map_err(|e| match e {
+                accessibility::Error::Ax(code) => map_ax_error(
+                    "AXUIElementPerformAction",
+                    code,
+                    &format!("Failed to perform action {}", action),
+                ),
+                e => AutomationError::platform(format!(
                     "Failed to perform action {}: {}",
                     action, e
-                ))
+                )),
             })
     }
 
@@ -1377,7 +1561,7 @@ impl UIElementImpl for MacOSUIElement {
         let source =
             CGEventSource::new(core_graphics::event_source::CGEventSourceStateID::HIDSystemState)
                 .map_err(|_| {
-                AutomationError::PlatformError("Failed to create event source".to_string())
+                AutomationError::platform("Failed to create event source".to_string())
             })?;
 
         // Convert amount to scroll units (typically lines)
@@ -1402,7 +1586,7 @@ impl UIElementImpl for MacOSUIElement {
             source, 0, 1, // number of wheels (1 for standard mouse wheel)
             scroll_y, scroll_x, 0, // z scroll amount (unused)
         )
-        .map_err(|_| AutomationError::PlatformError("Failed to create scroll event".to_string()))?;
+        .map_err(|_| AutomationError::platform("Failed to create scroll event".to_string()))?;
 
         // Post the event at the center point location
         scroll_event.post(core_graphics::event::CGEventTapLocation::HID);
@@ -1425,13 +1609,152 @@ impl UIElementImpl for MacOSUIElement {
         self.focus()
     }
 
-    fn mouse_drag(&self, _start_x: f64, _start_y: f64, _end_x: f64, _end_y: f64) -> Result<(), AutomationError> {
-        Err(AutomationError::UnsupportedOperation(
-            "mouse_drag is not implemented for macOS yet".to_string(),
-        ))
+    fn mouse_drag(&self, start_x: f64, start_y: f64, end_x: f64, end_y: f64) -> Result<(), AutomationError> {
+        self.mouse_click_and_hold(start_x, start_y)?;
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        self.mouse_move(end_x, end_y)?;
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        self.mouse_release()
+    }
+
+    fn mouse_click_and_hold(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        use core_graphics::event::CGEventType;
+        post_mouse_event(CGEventType::MouseMoved, x, y)?;
+        post_mouse_event(CGEventType::LeftMouseDown, x, y)
+    }
+
+    fn mouse_move(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        use core_graphics::event::CGEventType;
+        // While the button is down, apps only see a drag if the moves are drag events
+        let event_type = if unsafe { CGEventSourceButtonState(COMBINED_SESSION_STATE, 0) } {
+            CGEventType::LeftMouseDragged
+        } else {
+            CGEventType::MouseMoved
+        };
+        post_mouse_event(event_type, x, y)
+    }
+
+    fn mouse_release(&self) -> Result<(), AutomationError> {
+        use core_graphics::event::CGEventType;
+        let (x, y) = current_mouse_location()?;
+        post_mouse_event(CGEventType::LeftMouseUp, x, y)
+    }
+
+    fn mouse_wheel(&self, x: f64, y: f64, delta_x: i32, delta_y: i32) -> Result<(), AutomationError> {
+        use core_graphics::event::{CGEventType, ScrollEventUnit};
+
+        post_mouse_event(CGEventType::MouseMoved, x, y)?;
+        let source = CGEventSource::new(core_graphics::event_source::CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| AutomationError::platform("Failed to create event source".to_string()))?;
+        // Positive wheel values scroll content up and left, the opposite of our deltas
+        let event = CGEvent::new_scroll_event(source, ScrollEventUnit::LINE, 2, -delta_y, -delta_x, 0)
+            .map_err(|_| AutomationError::platform("Failed to create scroll event".to_string()))?;
+        event.post(core_graphics::event::CGEventTapLocation::HID);
+        Ok(())
+    }
+    fn press_chord(&self, chord: &KeyChord) -> Result<(), AutomationError> {
+        let (key_code, needs_shift) = chord_key_code(chord.key).ok_or_else(|| {
+            AutomationError::InvalidArgument(format!("No key on a US keyboard for {:?}", chord.key))
+        })?;
+        let mut flags = CGEventFlags::empty();
+        for modifier in &chord.modifiers {
+            flags.insert(match modifier {
+                Modifier::Ctrl => MODIFIER_CONTROL,
+                Modifier::Shift => MODIFIER_SHIFT,
+                Modifier::Alt => MODIFIER_OPTION,
+                Modifier::Meta => MODIFIER_COMMAND,
+            });
+        }
+        if needs_shift {
+            flags.insert(MODIFIER_SHIFT);
+        }
+
+        let source = CGEventSource::new(core_graphics::event_source::CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| AutomationError::platform("Failed to create event source".to_string()))?;
+        for down in [true, false] {
+            let event = CGEvent::new_keyboard_event(source.clone(), key_code, down)
+                .map_err(|_| AutomationError::platform("Failed to create key event".to_string()))?;
+            // The flags hold the modifiers for this event without pressing their keys
+            event.set_flags(flags);
+            event.post(core_graphics::event::CGEventTapLocation::HID);
+            if down {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        }
+        Ok(())
     }
 }
 
+// Virtual key code of a key on the US (ANSI) layout, and whether it needs Shift to type
+// the character
+fn chord_key_code(key: Key) -> Option<(CGKeyCode, bool)> {
+    const CHARACTER_KEYS: &str = "asdfhgzxcv\0bqweryt123465=97-80]ou[ip\0lj'k;\\,/nm.";
+    const SHIFTED: &[(char, char)] = &[
+        ('!', '1'), ('@', '2'), ('#', '3'), ('$', '4'), ('%', '5'), ('^', '6'), ('&', '7'), ('*', '8'), ('(', '9'),
+        (')', '0'), ('_', '-'), ('+', '='), ('{', '['), ('}', ']'), ('|', '\\'), (':', ';'), ('"', '\''), ('<', ','),
+        ('>', '.'), ('?', '/'), ('~', '`'),
+    ];
+    const FUNCTION_KEYS: [CGKeyCode; 20] = [
+        122, 120, 99, 118, 96, 97, 98, 100, 101, 109, 103, 111, 105, 107, 113, 106, 64, 79, 80, 90,
+    ];
+
+    let code = match key {
+        Key::Char(c) => {
+            let (unshifted, shift) = match SHIFTED.iter().find(|(shifted, _)| *shifted == c) {
+                Some(&(_, unshifted)) => (unshifted, true),
+                None if c.is_ascii_uppercase() => (c.to_ascii_lowercase(), true),
+                None => (c, false),
+            };
+            if unshifted == '`' {
+                return Some((50, shift));
+            }
+            let index = CHARACTER_KEYS.chars().position(|k| k == unshifted && k != '\0')?;
+            return Some((index as CGKeyCode, shift));
+        }
+        Key::Enter => 36,
+        Key::Tab => 48,
+        Key::Space => 49,
+        Key::Backspace => 51,
+        Key::Escape => 53,
+        Key::Delete => 117,
+        Key::Home => 115,
+        Key::End => 119,
+        Key::PageUp => 116,
+        Key::PageDown => 121,
+        Key::Left => 123,
+        Key::Right => 124,
+        Key::Down => 125,
+        Key::Up => 126,
+        Key::Function(n) => *FUNCTION_KEYS.get(n as usize - 1)?,
+        // Mac keyboards have no Insert key
+        Key::Insert => return None,
+    };
+    Some((code, false))
+}
+
+// Post a left-button mouse event at (x, y) in screen coordinates
+fn post_mouse_event(event_type: core_graphics::event::CGEventType, x: f64, y: f64) -> Result<(), AutomationError> {
+    use core_graphics::event::CGMouseButton;
+    use core_graphics::geometry::CGPoint;
+
+    let source = CGEventSource::new(core_graphics::event_source::CGEventSourceStateID::HIDSystemState)
+        .map_err(|_| AutomationError::platform("Failed to create event source".to_string()))?;
+    let event = CGEvent::new_mouse_event(source, event_type, CGPoint::new(x, y), CGMouseButton::Left)
+        .map_err(|_| AutomationError::platform("Failed to create mouse event".to_string()))?;
+    event.post(core_graphics::event::CGEventTapLocation::HID);
+    Ok(())
+}
+
+// Where the pointer is now, in screen coordinates
+fn current_mouse_location() -> Result<(f64, f64), AutomationError> {
+    let source = CGEventSource::new(core_graphics::event_source::CGEventSourceStateID::HIDSystemState)
+        .map_err(|_| AutomationError::platform("Failed to create event source".to_string()))?;
+    let event = CGEvent::new(source)
+        .map_err(|_| AutomationError::platform("Failed to read the mouse location".to_string()))?;
+    let location = event.location();
+    Ok((location.x, location.y))
+}
+
 // Helper function to parse AXUIElement attribute values into appropriate types
 fn parse_ax_attribute_value(
     name: &str,
@@ -1447,21 +1770,32 @@ fn parse_ax_attribute_value(
     // Handle different types based on known attribute names and value types
     match name {
         // String values (text, identifiers, descriptions)
-        "AXRole" | "AXRoleDescription" | "AXIdentifier" | "AXValue" => {
+        "AXRole" | "AXSubrole" | "AXRoleDescription" | "AXIdentifier" | "AXHelp"
+        | "AXPlaceholderValue" | "AXMenuItemCmdChar" => {
             if let Some(cf_string) = value.downcast_into::<CFString>() {
                 return Some(Value::String(cf_string.to_string()));
             }
         }
 
+        // Text for most elements, a number for checkboxes, sliders and the like
+        "AXValue" => {
+            if let Some(cf_string) = value.downcast::<CFString>() {
+                return Some(Value::String(cf_string.to_string()));
+            }
+            if let Some(cf_num) = value.downcast_into::<CFNumber>() {
+                return cf_num.to_i64().map(|num| Value::Number(num.into()));
+            }
+        }
+
         // Boolean values
-        "AXEnabled" | "AXFocused" => {
+        "AXEnabled" | "AXFocused" | "AXRequired" | "AXExpanded" | "AXSelected" => {
             if let Some(cf_bool) = value.downcast_into::<CFBoolean>() {
                 return Some(Value::Bool(cf_bool == CFBoolean::true_value()));
             }
         }
 
         // Numeric values
-        "AXNumberOfCharacters" | "AXInsertionPointLineNumber" => {
+        "AXNumberOfCharacters" | "AXInsertionPointLineNumber" | "AXMenuItemCmdModifiers" => {
             if let Some(cf_num) = value.downcast_into::<CFNumber>() {
                 if let Some(num) = cf_num.to_i64() {
                     return Some(Value::Number(serde_json::Number::from(num)));
@@ -1560,6 +1894,65 @@ fn parse_ax_attribute_value(
     None
 }
 
+// The system color for an `AppleAccentColor` setting; unset means the default blue
+// Classify an AXError so callers can tell stale/busy elements from hard failures,
+// keeping the failing call and its code
+fn map_ax_error(call: &'static str, code: i32, message: &str) -> AutomationError {
+    let message = format!("{}: error code {}", message, code);
+    let error = match code {
+        AX_ERROR_INVALID_UI_ELEMENT => AutomationError::StaleElement(message),
+        AX_ERROR_CANNOT_COMPLETE => AutomationError::TargetBusy(message),
+        AX_ERROR_ATTRIBUTE_UNSUPPORTED | AX_ERROR_ACTION_UNSUPPORTED => {
+            AutomationError::UnsupportedOperation(message)
+        }
+        AX_ERROR_API_DISABLED => AutomationError::PermissionDenied(message),
+        _ => AutomationError::platform(message),
+    };
+    error.with_native(call, code)
+}
+
+fn accent_color(setting: Option<i32>) -> crate::drawing::Color {
+    let (r, g, b) = match setting {
+        Some(-1) => (142, 142, 147), // graphite
+        Some(0) => (255, 59, 48),    // red
+        Some(1) => (255, 149, 0),    // orange
+        Some(2) => (255, 204, 0),    // yellow
+        Some(3) => (40, 205, 65),    // green
+        Some(5) => (175, 82, 222),   // purple
+        Some(6) => (255, 45, 85),    // pink
+        _ => (0, 122, 255),          // blue
+    };
+    crate::drawing::Color { r, g, b, a: 255 }
+}
+
+// An AX attribute of `e` as text; only strings, booleans and numbers have one
+fn ax_attribute_string(e: &AXUIElement, name: &str) -> Option<String> {
+    use core_foundation::number::CFNumber;
+
+    let value = e.attribute(&AXAttribute::new(&CFString::new(name))).ok()?;
+    if let Some(string) = value.downcast::<CFString>() {
+        return Some(string.to_string());
+    }
+    if let Some(boolean) = value.downcast::<CFBoolean>() {
+        return Some((boolean == CFBoolean::true_value()).to_string());
+    }
+    let number = value.downcast_into::<CFNumber>()?;
+    number
+        .to_i64()
+        .map(|n| n.to_string())
+        .or_else(|| number.to_f64().map(|n| n.to_string()))
+}
+
+// A numeric AX attribute of `e`, e.g. a slider's AXValue
+fn ax_attribute_f64(e: &AXUIElement, name: &str) -> Option<f64> {
+    use core_foundation::number::CFNumber;
+
+    e.attribute(&AXAttribute::new(&CFString::new(name)))
+        .ok()?
+        .downcast_into::<CFNumber>()?
+        .to_f64()
+}
+
 // Add this helper function after the selector handler
 fn element_contains_text(e: &AXUIElement, text: &str) -> bool {
     // Check immediate element attributes for text
@@ -1632,48 +2025,53 @@ fn get_pid_for_element(element: &ThreadSafeAXUIElement) -> i32 {
 
 // Modified to return Vec<String> for multiple possible role matches
 fn map_generic_role_to_macos_roles(role: &str) -> Vec<String> {
-    match role.to_lowercase().as_str() {
-        "window" => vec!["AXWindow".to_string()],
-        "button" => vec![
-            "AXButton".to_string(),
-            "AXMenuItem".to_string(),
-            "AXMenuBarItem".to_string(),
-            "AXStaticText".to_string(), // Some text might be clickable buttons
-            "AXImage".to_string(),      // Some images might be clickable buttons
-        ], // Button can be any of these
-        "checkbox" => vec!["AXCheckBox".to_string()],
-        "menu" => vec!["AXMenu".to_string()],
-        "menuitem" => vec!["AXMenuItem".to_string(), "AXMenuBarItem".to_string()], // Include both types
-        "dialog" => vec!["AXSheet".to_string(), "AXDialog".to_string()], // macOS often uses Sheet or Dialog
-        "text" | "textfield" | "input" | "textbox" => vec![
-            "AXTextField".to_string(),
-            "AXTextArea".to_string(),
-            "AXText".to_string(),
-            "AXComboBox".to_string(),
-            "AXTextEdit".to_string(),
-            "AXSearchField".to_string(),
-            "AXWebArea".to_string(), // Web content might contain inputs
-            "AXGroup".to_string(),   // Twitter uses groups that contain editable content
-            "AXGenericElement".to_string(), // Generic elements that might be inputs
-            "AXURIField".to_string(), // Explicit URL field type
-            "AXAddressField".to_string(), // Another common name for URL fields
-            "AXStaticText".to_string(), // Static text fields
-        ],
-        // Add specific support for URL fields
-        "url" | "urlfield" => vec![
-            "AXTextField".to_string(),    // URL fields are often text fields
-            "AXURIField".to_string(),     // Explicit URL field type
-            "AXAddressField".to_string(), // Another common name for URL fields
+    let Ok(role_kind) = role.parse::<Role>() else {
+        return vec![role.to_string()]; // Keep as-is for native AX roles
+    };
+    let ax_roles: &[&str] = match role_kind {
+        Role::Application => &["AXApplication"],
+        Role::Window => &["AXWindow"],
+        Role::Dialog => &["AXSheet", "AXDialog", "AXSystemDialog"], // macOS often uses Sheet or Dialog
+        Role::Pane => &["AXScrollArea", "AXSplitGroup"],
+        Role::Group => &["AXGroup", "AXRadioGroup"],
+        Role::Button | Role::SplitButton => &["AXButton", "AXMenuButton", "AXDisclosureTriangle"],
+        Role::CheckBox => &["AXCheckBox"],
+        Role::RadioButton => &["AXRadioButton"],
+        Role::Edit => &[
+            "AXTextField",
+            "AXTextArea",
+            "AXSearchField",
+            "AXSecureTextField",
         ],
-        "list" => vec!["AXList".to_string()],
-        "listitem" => vec!["AXCell".to_string()], // List items are often cells in macOS
-        "combobox" => vec!["AXPopUpButton".to_string(), "AXComboBox".to_string()],
-        "tab" => vec!["AXTabGroup".to_string()],
-        "tabitem" => vec!["AXRadioButton".to_string()], // Tab items are sometimes radio buttons
-        "toolbar" => vec!["AXToolbar".to_string()],
-
-        _ => vec![role.to_string()], // Keep as-is for unknown roles
-    }
+        Role::Text => &["AXStaticText"],
+        Role::Hyperlink => &["AXLink"],
+        Role::Image => &["AXImage"],
+        Role::List => &["AXList"],
+        Role::ListItem => &["AXCell"], // List items are often cells in macOS
+        Role::Tree => &["AXOutline", "AXBrowser"],
+        Role::TreeItem => &["AXRow"],
+        Role::Table => &["AXTable"],
+        Role::DataGrid => &["AXGrid"],
+        Role::DataItem => &["AXRow", "AXCell"],
+        Role::ComboBox => &["AXPopUpButton", "AXComboBox"],
+        Role::Menu => &["AXMenu"],
+        Role::MenuBar => &["AXMenuBar"],
+        Role::MenuItem => &["AXMenuItem", "AXMenuBarItem"],
+        Role::Tab => &["AXTabGroup"],
+        Role::TabItem => &["AXRadioButton"], // Tab items are sometimes radio buttons
+        Role::ToolBar => &["AXToolbar"],
+        Role::ProgressBar => &["AXProgressIndicator", "AXBusyIndicator", "AXLevelIndicator"],
+        Role::ScrollBar => &["AXScrollBar"],
+        Role::Slider => &["AXSlider"],
+        Role::Spinner => &["AXIncrementor"],
+        Role::ToolTip => &["AXHelpTag"],
+        Role::Separator => &["AXSplitter"],
+        Role::Calendar => &["AXDateField"],
+        Role::Document => &["AXWebArea"],
+        // No AX equivalent
+        Role::Header | Role::HeaderItem | Role::StatusBar | Role::TitleBar | Role::Unknown => &[],
+    };
+    ax_roles.iter().map(|r| r.to_string()).collect()
 }
 
 fn macos_role_to_generic_role(role: &str) -> Vec<String> {
@@ -1975,15 +2373,43 @@ impl AccessibilityEngine for MacOSEngine {
                     .map(|e| self.wrap_element(ThreadSafeAXUIElement::new(e)))
                     .collect())
             }
-            Selector::Path(_) => Err(AutomationError::UnsupportedOperation(
-                "Path selector not yet supported for macOS".to_string(),
-            )),
+            Selector::Path(path) => crate::selector::find_by_path(self, path, root),
             Selector::Attributes(_) => Err(AutomationError::UnsupportedOperation(
                 "Attributes selector not yet supported for macOS".to_string(),
             )),
-            Selector::Filter(_) => Err(AutomationError::UnsupportedOperation(
-                "Filter selector not yet supported for macOS".to_string(),
-            )),
+            Selector::Filter { base, predicate } => {
+                crate::selector::find_filtered(self, base, predicate, root, _timeout)
+            }
+            Selector::Label(label) => {
+                crate::heuristics::find_by_label(self, label, root, _timeout)
+            }
+            Selector::Relative { base, relation, anchor } => {
+                crate::heuristics::find_relative(self, base, *relation, anchor, root, _timeout)
+            }
+            Selector::Nth { base, index } => {
+                crate::selector::find_nth(self, base, *index, root, _timeout)
+            }
+            Selector::State { base, filters } => {
+                crate::selector::find_with_state(self, base, filters, root, _timeout)
+            }
+            Selector::Image(template) => {
+                Ok(vec![crate::vision::find_image(self, template, root, _timeout)?])
+            }
+            Selector::OcrText(text) => crate::ocr::find_ocr_text(self, text, root, _timeout),
+            Selector::Attribute { key, value } => Ok(self
+                .attribute_collector(&start_element.0, key, value)
+                .find_all()
+                .into_iter()
+                .map(|e| self.wrap_element(ThreadSafeAXUIElement::new(e)))
+                .collect()),
+            Selector::Regex { key, pattern } => {
+                let collector = self.regex_collector(&start_element.0, key, pattern)?;
+                Ok(collector
+                    .find_all()
+                    .into_iter()
+                    .map(|e| self.wrap_element(ThreadSafeAXUIElement::new(e)))
+                    .collect())
+            }
             Selector::Chain(selectors) => {
                 if selectors.is_empty() {
                     return Err(AutomationError::InvalidArgument(
@@ -2002,7 +2428,7 @@ impl AccessibilityEngine for MacOSEngine {
                         // Find elements matching the current selector within the current root
                         let found_elements =
                             self.find_elements(selector, Some(root_element), _timeout, None)
-                                .map_err(|e| AutomationError::PlatformError(format!("Recursive find_elements failed: {}", e)))?;
+                                .map_err(|e| AutomationError::platform(format!("Recursive find_elements failed: {}", e)))?;
 
                         if is_last_selector {
                             // If it's the last selector, collect all found elements
@@ -2181,15 +2607,59 @@ impl AccessibilityEngine for MacOSEngine {
                     ))),
                 }
             }
-            Selector::Path(_) => Err(AutomationError::UnsupportedOperation(
-                "Path selector not yet supported for macOS".to_string(),
-            )),
+            Selector::Path(path) => {
+                let mut found = crate::selector::find_by_path(self, path, root)?;
+                Ok(found.remove(0))
+            }
             Selector::Attributes(_) => Err(AutomationError::UnsupportedOperation(
                 "Attributes selector not yet supported for macOS".to_string(),
             )),
-            Selector::Filter(_) => Err(AutomationError::UnsupportedOperation(
-                "Filter selector not yet supported for macOS".to_string(),
-            )),
+            Selector::Filter { base, predicate } => {
+                let mut found = crate::selector::find_filtered(self, base, predicate, root, _timeout)?;
+                Ok(found.remove(0))
+            }
+            Selector::Label(label) => {
+                let mut found = crate::heuristics::find_by_label(self, label, root, _timeout)?;
+                Ok(found.remove(0))
+            }
+            Selector::Relative { base, relation, anchor } => {
+                let mut found =
+                    crate::heuristics::find_relative(self, base, *relation, anchor, root, _timeout)?;
+                Ok(found.remove(0))
+            }
+            Selector::Nth { base, index } => {
+                let mut found = crate::selector::find_nth(self, base, *index, root, _timeout)?;
+                Ok(found.remove(0))
+            }
+            Selector::State { base, filters } => {
+                let mut found = crate::selector::find_with_state(self, base, filters, root, _timeout)?;
+                Ok(found.remove(0))
+            }
+            Selector::Image(template) => crate::vision::find_image(self, template, root, _timeout),
+            Selector::OcrText(text) => {
+                let mut found = crate::ocr::find_ocr_text(self, text, root, _timeout)?;
+                Ok(found.remove(0))
+            }
+            Selector::Attribute { key, value } => {
+                let collector = self.attribute_collector(&start_element.0, key, value);
+                match collector.find_all().into_iter().next() {
+                    Some(e) => Ok(self.wrap_element(ThreadSafeAXUIElement::new(e))),
+                    None => Err(AutomationError::ElementNotFound(format!(
+                        "Element with {}={} not found",
+                        key, value
+                    ))),
+                }
+            }
+            Selector::Regex { key, pattern } => {
+                let collector = self.regex_collector(&start_element.0, key, pattern)?;
+                match collector.find_all().into_iter().next() {
+                    Some(e) => Ok(self.wrap_element(ThreadSafeAXUIElement::new(e))),
+                    None => Err(AutomationError::ElementNotFound(format!(
+                        "Element with {} matching {:?} not found",
+                        key, pattern
+                    ))),
+                }
+            }
             Selector::Chain(selectors) => {
                 if selectors.is_empty() {
                     return Err(AutomationError::InvalidArgument(
@@ -2219,7 +2689,7 @@ impl AccessibilityEngine for MacOSEngine {
                         _ => {
                             // Found more than one, ambiguous chain
                             // Replace AmbiguousMatch with PlatformError
-                            return Err(AutomationError::PlatformError(format!(
+                            return Err(AutomationError::platform(format!(
                                 "Multiple elements found for selector {:?} in chain, cannot resolve single element",
                                 selector
                             )));
@@ -2239,11 +2709,11 @@ impl AccessibilityEngine for MacOSEngine {
             .arg(app_name)
             .status()
             .map_err(|e| {
-                AutomationError::PlatformError(format!("Failed to run open command: {}", e))
+                AutomationError::platform(format!("Failed to run open command: {}", e))
             })?;
 
         if !status.success() {
-            return Err(AutomationError::PlatformError(format!(
+            return Err(AutomationError::platform(format!(
                 "Failed to open application '{}'. 'open -a' command failed.",
                 app_name
             )));
@@ -2265,11 +2735,11 @@ impl AccessibilityEngine for MacOSEngine {
         command.arg(url); // The URL to open
 
         let status = command.status().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to run open command for URL: {}", e))
+            AutomationError::platform(format!("Failed to run open command for URL: {}", e))
         })?;
 
         if !status.success() {
-            return Err(AutomationError::PlatformError(format!(
+            return Err(AutomationError::platform(format!(
                 "Failed to open URL '{}' {}. 'open' command failed.",
                 url,
                 browser
@@ -2299,14 +2769,14 @@ impl AccessibilityEngine for MacOSEngine {
             .arg(file_path) // Just pass the file path to `open`
             .status()
             .map_err(|e| {
-                AutomationError::PlatformError(format!(
+                AutomationError::platform(format!(
                     "Failed to run open command for file: {}",
                     e
                 ))
             })?;
 
         if !status.success() {
-            return Err(AutomationError::PlatformError(format!(
+            return Err(AutomationError::platform(format!(
                 "Failed to open file '{}'. 'open' command failed.",
                 file_path
             )));
@@ -2330,7 +2800,7 @@ impl AccessibilityEngine for MacOSEngine {
             .arg(command_str)
             .output()
             .await // Await the async output
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(|e| AutomationError::platform(e.to_string()))?;
 
         Ok(crate::CommandOutput {
             exit_status: output.status.code(),
@@ -2342,7 +2812,7 @@ impl AccessibilityEngine for MacOSEngine {
     async fn capture_screen(&self) -> Result<ScreenshotResult, AutomationError> {
         // Directly call the implementation logic
         let monitors = xcap::Monitor::all().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to get monitors: {}", e))
+            AutomationError::platform(format!("Failed to get monitors: {}", e))
         })?;
         let mut primary_monitor: Option<xcap::Monitor> = None;
         for monitor in monitors {
@@ -2353,7 +2823,7 @@ impl AccessibilityEngine for MacOSEngine {
                 }
                 Ok(false) => continue,
                 Err(e) => {
-                    return Err(AutomationError::PlatformError(format!(
+                    return Err(AutomationError::platform(format!(
                         "Error checking monitor primary status: {}",
                         e
                     )));
@@ -2361,11 +2831,11 @@ impl AccessibilityEngine for MacOSEngine {
             }
         }
         let primary_monitor = primary_monitor.ok_or_else(|| {
-            AutomationError::PlatformError("Could not find primary monitor".to_string())
+            AutomationError::platform("Could not find primary monitor".to_string())
         })?;
 
         let image = primary_monitor.capture_image().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to capture screen: {}", e))
+            AutomationError::platform(format!("Failed to capture screen: {}", e))
         })?;
 
         Ok(ScreenshotResult {
@@ -2375,13 +2845,259 @@ impl AccessibilityEngine for MacOSEngine {
         })
     }
 
+    fn list_monitors(&self) -> Result<Vec<crate::MonitorInfo>, AutomationError> {
+        crate::display::xcap_monitors()
+    }
+
+    fn terminate_process(&self, pid: u32) -> Result<(), AutomationError> {
+        // Ask the app to quit like Cmd+Q would, so it can save its state
+        unsafe {
+            use objc::{class, msg_send, sel, sel_impl};
+
+            let app: *mut objc::runtime::Object = msg_send![
+                class!(NSRunningApplication),
+                runningApplicationWithProcessIdentifier: pid as i32
+            ];
+            if app.is_null() {
+                return Err(AutomationError::ElementNotFound(format!(
+                    "Process {} is not a running application",
+                    pid
+                )));
+            }
+            let accepted: objc::runtime::BOOL = msg_send![app, terminate];
+            if accepted == objc::runtime::NO {
+                return Err(AutomationError::platform(format!(
+                    "Application {} refused to terminate",
+                    pid
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn get_attribute(&self, element: &UIElement, name: &str) -> Result<Option<String>, AutomationError> {
+        let element = element
+            .as_any()
+            .downcast_ref::<MacOSUIElement>()
+            .ok_or_else(|| AutomationError::platform("Element is not an AX element"))?;
+        Ok(ax_attribute_string(&element.element.0, name))
+    }
+
+    fn enable_web_accessibility(&self, element: &UIElement) -> Result<(), AutomationError> {
+        let element = element
+            .as_any()
+            .downcast_ref::<MacOSUIElement>()
+            .ok_or_else(|| AutomationError::platform("Element is not an AX element"))?;
+        let app = element
+            .get_application()
+            .ok_or_else(|| AutomationError::ElementNotFound("Application of web view not found".to_string()))?;
+
+        // Chromium and Electron build their tree once an app sets AXManualAccessibility
+        unsafe {
+            let app_ref = app.element.0.as_concrete_TypeRef() as *mut ::std::os::raw::c_void;
+            let attr_str = CFString::new("AXManualAccessibility");
+            let attr_str_ref = attr_str.as_concrete_TypeRef() as *const ::std::os::raw::c_void;
+            let value_ref = CFBoolean::true_value().as_concrete_TypeRef() as *const ::std::os::raw::c_void;
+
+            let result = AXUIElementSetAttributeValue(app_ref, attr_str_ref, value_ref);
+            // WebKit doesn't know the attribute and always builds its tree
+            if result != 0 && result != AX_ERROR_ATTRIBUTE_UNSUPPORTED {
+                return Err(map_ax_error(
+                    "AXUIElementSetAttributeValue",
+                    result,
+                    "Failed to enable web accessibility",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn get_screen_reader_flag(&self) -> Result<bool, AutomationError> {
+        // Missing until VoiceOver has been turned on once
+        let output = std::process::Command::new("defaults")
+            .args(["read", "com.apple.universalaccess", "voiceOverOnOffKey"])
+            .output()
+            .map_err(|e| AutomationError::platform_with_source("Failed to run defaults", e))?;
+        Ok(output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "1")
+    }
+
+    fn get_theme(&self) -> Result<crate::Theme, AutomationError> {
+        let read_default = |domain: &str, key: &str| {
+            std::process::Command::new("defaults")
+                .args(["read", domain, key])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+
+        // Only set while the appearance is dark (including Auto at night)
+        let dark = read_default("-g", "AppleInterfaceStyle")
+            .is_some_and(|style| style.eq_ignore_ascii_case("dark"));
+        let accent = read_default("-g", "AppleAccentColor").and_then(|value| value.parse().ok());
+        let high_contrast =
+            read_default("com.apple.universalaccess", "increaseContrast").is_some_and(|value| value == "1");
+
+        Ok(crate::Theme {
+            mode: if dark {
+                crate::ThemeMode::Dark
+            } else {
+                crate::ThemeMode::Light
+            },
+            accent_color: Some(accent_color(accent)),
+            high_contrast,
+        })
+    }
+
+    fn get_locale(&self) -> Result<crate::Locale, AutomationError> {
+        // e.g. `de_DE` or `en_DE@currency=EUR`, as set in Language & Region
+        let output = std::process::Command::new("defaults")
+            .args(["read", "-g", "AppleLocale"])
+            .output()
+            .map_err(|e| AutomationError::platform_with_source("Failed to run defaults", e))?;
+        if !output.status.success() {
+            return Err(AutomationError::platform("AppleLocale is not set"));
+        }
+        Ok(crate::Locale::from_tag(String::from_utf8_lossy(&output.stdout).trim()))
+    }
+
+    fn is_session_locked(&self) -> Result<bool, AutomationError> {
+        use core_foundation::base::CFType;
+
+        let dictionary = unsafe { CGSessionCopyCurrentDictionary() };
+        if dictionary.is_null() {
+            return Err(AutomationError::platform(
+                "This process has no window server session".to_string(),
+            ));
+        }
+        let dictionary: CFDictionary<CFString, CFType> =
+            unsafe { CFDictionary::wrap_under_create_rule(dictionary) };
+        // The key is absent while the screen is unlocked
+        Ok(dictionary
+            .find(&CFString::new("CGSSessionScreenIsLocked"))
+            .and_then(|value| value.downcast::<CFBoolean>())
+            .is_some_and(|locked| locked == CFBoolean::true_value()))
+    }
+
+    fn get_idle_duration(&self) -> Result<Duration, AutomationError> {
+        let seconds = unsafe {
+            CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT_TYPE)
+        };
+        Ok(Duration::from_secs_f64(seconds.max(0.0)))
+    }
+
+    fn get_input_state(&self) -> Result<crate::InputState, AutomationError> {
+        let flags = CGEventFlags::from_bits_truncate(unsafe {
+            CGEventSourceFlagsState(COMBINED_SESSION_STATE)
+        });
+        let is_down = |button: u32| unsafe { CGEventSourceButtonState(COMBINED_SESSION_STATE, button) };
+        Ok(crate::InputState {
+            shift: flags.contains(MODIFIER_SHIFT),
+            ctrl: flags.contains(MODIFIER_CONTROL),
+            alt: flags.contains(MODIFIER_OPTION),
+            meta: flags.contains(MODIFIER_COMMAND),
+            left_pressed: is_down(0),
+            right_pressed: is_down(1),
+        })
+    }
+
+    fn release_input(&self) -> Result<(), AutomationError> {
+        use core_graphics::event::{CGEventType, CGMouseButton};
+
+        let source =
+            CGEventSource::new(core_graphics::event_source::CGEventSourceStateID::HIDSystemState)
+                .map_err(|_| {
+                    AutomationError::platform("Failed to create event source".to_string())
+                })?;
+        // A key-up for a key that isn't down is ignored, so release every modifier
+        for key_code in MODIFIER_KEY_CODES {
+            let key_up = CGEvent::new_keyboard_event(source.clone(), key_code, false).map_err(|_| {
+                AutomationError::platform("Failed to create key up event".to_string())
+            })?;
+            key_up.set_flags(CGEventFlags::CGEventFlagNull);
+            key_up.post(core_graphics::event::CGEventTapLocation::HID);
+        }
+
+        let state = self.get_input_state()?;
+        let location = CGEvent::new(source.clone())
+            .map_err(|_| AutomationError::platform("Failed to read cursor position".to_string()))?
+            .location();
+        for (held, event_type, button) in [
+            (state.left_pressed, CGEventType::LeftMouseUp, CGMouseButton::Left),
+            (state.right_pressed, CGEventType::RightMouseUp, CGMouseButton::Right),
+        ] {
+            if !held {
+                continue;
+            }
+            let mouse_up = CGEvent::new_mouse_event(source.clone(), event_type, location, button)
+                .map_err(|_| {
+                    AutomationError::platform("Failed to create mouse up event".to_string())
+                })?;
+            mouse_up.post(core_graphics::event::CGEventTapLocation::HID);
+        }
+        Ok(())
+    }
+
+    fn list_processes(&self) -> Result<Vec<crate::ProcessInfo>, AutomationError> {
+        let output = std::process::Command::new("ps")
+            .args(["-axo", "pid=,ppid=,comm="])
+            .output()
+            .map_err(|e| AutomationError::platform_with_source("Failed to run ps", e))?;
+
+        // Only GUI applications own windows; asking AX about every daemon would be slow
+        let mut gui_pids = std::collections::HashSet::new();
+        unsafe {
+            use objc::{class, msg_send, sel, sel_impl};
+
+            let workspace_class = class!(NSWorkspace);
+            let shared_workspace: *mut objc::runtime::Object =
+                msg_send![workspace_class, sharedWorkspace];
+            let running_apps: *mut objc::runtime::Object =
+                msg_send![shared_workspace, runningApplications];
+            let count: usize = msg_send![running_apps, count];
+            for i in 0..count {
+                let app: *mut objc::runtime::Object = msg_send![running_apps, objectAtIndex:i];
+                let pid: i32 = msg_send![app, processIdentifier];
+                gui_pids.insert(pid);
+            }
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let processes = stdout
+            .lines()
+            .filter_map(|line| {
+                let (pid, rest) = line.trim_start().split_once(char::is_whitespace)?;
+                let (ppid, command) = rest.trim_start().split_once(char::is_whitespace)?;
+                let pid: i32 = pid.parse().ok()?;
+                let parent_pid: u32 = ppid.parse().ok()?;
+                let window_count = if gui_pids.contains(&pid) {
+                    ThreadSafeAXUIElement::application(pid)
+                        .0
+                        .windows()
+                        .map_or(0, |windows| windows.len() as usize)
+                } else {
+                    0
+                };
+                Some(crate::ProcessInfo {
+                    pid: pid as u32,
+                    parent_pid: (parent_pid != 0).then_some(parent_pid),
+                    name: std::path::Path::new(command.trim())
+                        .file_name()
+                        .map_or_else(|| command.trim().to_string(), |n| n.to_string_lossy().to_string()),
+                    window_count,
+                })
+            })
+            .collect();
+        Ok(processes)
+    }
+
     async fn capture_monitor_by_name(
         &self,
         name: &str,
     ) -> Result<ScreenshotResult, AutomationError> {
         // Directly call the implementation logic
         let monitors = xcap::Monitor::all().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to get monitors: {}", e))
+            AutomationError::platform(format!("Failed to get monitors: {}", e))
         })?;
         let mut target_monitor: Option<xcap::Monitor> = None;
         for monitor in monitors {
@@ -2392,7 +3108,7 @@ impl AccessibilityEngine for MacOSEngine {
                 }
                 Ok(_) => continue,
                 Err(e) => {
-                    return Err(AutomationError::PlatformError(format!(
+                    return Err(AutomationError::platform(format!(
                         "Error getting monitor name: {}",
                         e
                     )));
@@ -2404,7 +3120,7 @@ impl AccessibilityEngine for MacOSEngine {
         })?;
 
         let image = target_monitor.capture_image().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to capture monitor '{}': {}", name, e))
+            AutomationError::platform(format!("Failed to capture monitor '{}': {}", name, e))
         })?;
 
         Ok(ScreenshotResult {
@@ -2418,14 +3134,14 @@ impl AccessibilityEngine for MacOSEngine {
         // Call the implementation from the MacOSEngine struct
         // Directly call the implementation logic
         let engine = OcrEngine::new(OcrProvider::Auto).map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to create OCR engine: {}", e))
+            AutomationError::platform(format!("Failed to create OCR engine: {}", e))
         })?;
 
         let (text, _language, _confidence) = engine // Destructure the tuple
             .recognize_file(image_path)
             .await
             .map_err(|e| {
-                AutomationError::PlatformError(format!("OCR recognition failed: {}", e))
+                AutomationError::platform(format!("OCR recognition failed: {}", e))
             })?;
 
         Ok(text) // Return only the text
@@ -2453,14 +3169,14 @@ impl AccessibilityEngine for MacOSEngine {
         let dynamic_image = DynamicImage::ImageRgba8(img_buffer);
 
         let engine = OcrEngine::new(OcrProvider::Auto).map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to create OCR engine: {}", e))
+            AutomationError::platform(format!("Failed to create OCR engine: {}", e))
         })?;
 
         let (text, _language, _confidence) = engine
             .recognize_image(&dynamic_image) // Use recognize_image
             .await
             .map_err(|e| {
-                AutomationError::PlatformError(format!("OCR recognition failed: {}", e))
+                AutomationError::platform(format!("OCR recognition failed: {}", e))
             })?;
 
         Ok(text)
@@ -2646,6 +3362,68 @@ impl AccessibilityEngine for MacOSEngine {
         ))
     }
 
+    fn find_windows(
+        &self,
+        criteria: &crate::WindowCriteria,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<UIElement>, AutomationError> {
+        criteria.validate()?;
+        let timeout = timeout.unwrap_or(DEFAULT_WINDOW_SEARCH_TIMEOUT);
+        let start_time = Instant::now();
+
+        loop {
+            let mut windows = Vec::new();
+            for app in self.get_applications()? {
+                let Some(macos_app) = app.as_any().downcast_ref::<MacOSUIElement>() else {
+                    continue;
+                };
+                // The application element's title is the app (process) name
+                let app_name = macos_app.element.0.title().ok().map(|t| t.to_string());
+                let filter_criteria = criteria.clone();
+                let collector = ElementsCollectorWithWindows::new(&macos_app.element.0, move |e| {
+                    if !e.role().is_ok_and(|role| role.to_string() == "AXWindow") {
+                        return false;
+                    }
+                    let title = e.title().map(|t| t.to_string()).unwrap_or_default();
+                    let subrole = e
+                        .attribute(&AXAttribute::new(&CFString::new("AXSubrole")))
+                        .ok()
+                        .and_then(|v| v.downcast_into::<CFString>())
+                        .map(|s| s.to_string());
+                    filter_criteria.matches(
+                        &title,
+                        subrole.as_deref(),
+                        || app_name.clone(),
+                        || {
+                            // Minimized windows are the ones out of sight on macOS
+                            !e.attribute(&AXAttribute::new(&CFString::new("AXMinimized")))
+                                .ok()
+                                .and_then(|v| v.downcast_into::<CFBoolean>())
+                                .is_some_and(|minimized| minimized == CFBoolean::true_value())
+                        },
+                    )
+                });
+                windows.extend(
+                    collector
+                        .find_all()
+                        .into_iter()
+                        .map(|e| self.wrap_element(ThreadSafeAXUIElement::new(e))),
+                );
+            }
+
+            if !windows.is_empty() {
+                return Ok(windows);
+            }
+            if start_time.elapsed() >= timeout {
+                return Err(AutomationError::ElementNotFound(format!(
+                    "No window matching {:?}",
+                    criteria
+                )));
+            }
+            std::thread::sleep(WINDOW_SEARCH_POLL_INTERVAL);
+        }
+    }
+
 
     fn activate_application(&self, app_name: &str) -> Result<(), AutomationError> {
         let app_element = self.get_application_by_name(app_name)?;
@@ -2654,7 +3432,7 @@ impl AccessibilityEngine for MacOSEngine {
             // Use the focus method which handles activation logic
             macos_el.focus()
         } else {
-            Err(AutomationError::PlatformError(
+            Err(AutomationError::platform(
                 "Failed to downcast to MacOSUIElement for activation".to_string(),
             ))
         }
@@ -82,8 +82,182 @@ pub trait AccessibilityEngine: Send + Sync {
         timeout: Option<Duration>,
     ) -> Result<UIElement, AutomationError>;
 
+    /// Find all top-level windows matching `criteria`, waiting up to `timeout`
+    /// for at least one
+    fn find_windows(
+        &self,
+        _criteria: &crate::WindowCriteria,
+        _timeout: Option<Duration>,
+    ) -> Result<Vec<UIElement>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "find_windows is not implemented for this platform".to_string(),
+        ))
+    }
+
     /// Get the currently focused browser window (async)
     async fn get_current_browser_window(&self) -> Result<UIElement, AutomationError>;
+
+    /// Read CPU time, memory and responsiveness of a process.
+    /// Returns `ElementNotFound` if the process is not running.
+    fn get_process_sample(&self, _pid: u32) -> Result<crate::ProcessSample, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_process_sample is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// List the running processes with the number of visible top-level windows each owns
+    fn list_processes(&self) -> Result<Vec<crate::ProcessInfo>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "list_processes is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// End the process `pid`: asks it to quit where the platform has a way to,
+    /// otherwise terminates it. Returns once the request is made, not once it has exited.
+    fn terminate_process(&self, _pid: u32) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "terminate_process is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// List the interactive sessions on this machine
+    fn list_sessions(&self) -> Result<Vec<crate::SessionInfo>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "list_sessions is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// List the connected monitors
+    fn list_monitors(&self) -> Result<Vec<crate::MonitorInfo>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "list_monitors is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Get the placement of every visible top-level window, topmost first
+    fn get_window_placements(&self) -> Result<Vec<crate::WindowPlacement>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_window_placements is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Move, resize and re-stack a top-level window to match `placement`.
+    /// The window is brought to the top of the z-order.
+    fn set_window_placement(&self, _placement: &crate::WindowPlacement) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "set_window_placement is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Ask the top-level window in `placement` to close, like its close button would
+    fn close_window(&self, _placement: &crate::WindowPlacement) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "close_window is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Get which modifier keys and mouse buttons are currently held down
+    fn get_input_state(&self) -> Result<crate::InputState, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_input_state is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Undo any input left half-done: release held modifier keys and mouse buttons,
+    /// and lift an input block if one is in place
+    fn release_input(&self) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "release_input is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Check whether the session this process runs in is locked
+    fn is_session_locked(&self) -> Result<bool, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "is_session_locked is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Get the time since the last keyboard or mouse input in this session
+    fn get_idle_duration(&self) -> Result<std::time::Duration, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_idle_duration is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Enter `password` on the lock screen of this session. Only works where the
+    /// platform lets this process send input to the lock screen.
+    fn unlock_session(&self, _password: &str) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "unlock_session is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Ask the web engine rendering `element` to build its accessibility tree, for
+    /// engines that only do so on request
+    fn enable_web_accessibility(&self, _element: &UIElement) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "enable_web_accessibility is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Whether the system setting that tells apps a screen reader is in use is on
+    fn get_screen_reader_flag(&self) -> Result<bool, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_screen_reader_flag is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Get the system color theme
+    fn get_theme(&self) -> Result<crate::Theme, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_theme is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// How the user's regional settings write numbers and dates
+    fn get_locale(&self) -> Result<crate::Locale, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_locale is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Recognize the words in `image` with their positions, in reading order
+    fn ocr_words(&self, _image: &image::RgbaImage) -> Result<Vec<crate::OcrWord>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "ocr_words is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Get the element at the given screen coordinates
+    fn get_element_at_point(&self, _x: f64, _y: f64) -> Result<UIElement, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_element_at_point is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Read a native attribute of `element` by the platform's name for it, e.g.
+    /// `AutomationId` on Windows or `AXSubrole` on macOS. `None` if the element
+    /// doesn't have it.
+    fn get_attribute(&self, _element: &UIElement, _name: &str) -> Result<Option<String>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_attribute is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Check whether a key (e.g. "f8", "escape", "ctrl", "a") is currently held down
+    fn is_key_pressed(&self, _key: &str) -> Result<bool, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "is_key_pressed is not implemented for this platform".to_string(),
+        ))
+    }
+
+    /// Get the current cursor position and mouse button state
+    fn get_mouse_state(&self) -> Result<crate::MouseState, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_mouse_state is not implemented for this platform".to_string(),
+        ))
+    }
 }
 
 #[cfg(target_os = "linux")]
@@ -116,11 +290,11 @@ pub fn create_engine(
     }
     #[cfg(target_os = "linux")]
     {
-        Err(AutomationError::UnsupportedPlatform("Linux platform not fully implemented in create_engine".to_string()))
+        Err(AutomationError::PlatformNotSupported("Linux platform not fully implemented in create_engine".to_string()))
     }
     #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     {
-        Err(AutomationError::UnsupportedPlatform(
+        Err(AutomationError::PlatformNotSupported(
             "Current platform is not supported".to_string(),
         ))
     }
@@ -1,8 +1,9 @@
-use crate::element::UIElementImpl;
+use crate::element::{ChildIter, UIElementImpl};
+use crate::keys::{Key, KeyChord, Modifier};
 use crate::platforms::AccessibilityEngine;
 use crate::utils::normalize;
-use crate::{AutomationError, Locator, Selector, UIElement, UIElementAttributes};
-use crate::{ClickResult, ScreenshotResult};
+use crate::{AutomationError, ElementState, Locator, Role, Selector, SelectorRegex, UIElement, UIElementAttributes};
+use crate::{ClickResult, RangeValue, ScreenshotResult};
 use image::DynamicImage;
 use image::{ImageBuffer, Rgba};
 use serde_json::Value;
@@ -14,7 +15,7 @@ use tokio::runtime::Runtime;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
-use uiautomation::UIAutomation;
+use uiautomation::{UIAutomation, UIMatcher};
 use uiautomation::controls::ControlType;
 use uiautomation::filters::{ClassNameFilter, ControlTypeFilter, NameFilter, OrFilter};
 use uiautomation::inputs::Mouse;
@@ -27,6 +28,67 @@ use arboard::Clipboard;
 // Define a default timeout duration
 const DEFAULT_FIND_TIMEOUT: Duration = Duration::from_millis(5000);
 
+// UI Automation HRESULTs that map onto a specific AutomationError category
+const UIA_E_ELEMENTNOTENABLED: i32 = 0x80040200_u32 as i32;
+const UIA_E_ELEMENTNOTAVAILABLE: i32 = 0x80040201_u32 as i32;
+const UIA_E_TIMEOUT: i32 = 0x80131505_u32 as i32;
+const RPC_E_SERVERCALL_RETRYLATER: i32 = 0x8001010A_u32 as i32;
+const E_ACCESSDENIED: i32 = 0x80070005_u32 as i32;
+
+// Time for the lock screen to raise its curtain and focus the password box
+const LOCK_SCREEN_SETTLE_DELAY: Duration = Duration::from_millis(800);
+
+// Classify a UI Automation error so callers can tell stale/busy elements from hard failures,
+// keeping the failing call and its HRESULT
+fn map_uia_error(call: &'static str) -> impl FnOnce(uiautomation::Error) -> AutomationError {
+    move |e| {
+        let code = e.code();
+        let error = classify_uia_error(e);
+        // Errors raised by the uiautomation crate itself carry no HRESULT
+        if code == 0 { error } else { error.with_native(call, code) }
+    }
+}
+
+fn classify_uia_error(e: uiautomation::Error) -> AutomationError {
+    let message = e.to_string();
+    match e.code() {
+        UIA_E_ELEMENTNOTAVAILABLE => AutomationError::StaleElement(message),
+        UIA_E_TIMEOUT => AutomationError::Timeout(message),
+        // a disabled element or a blocked message loop usually clears up on its own
+        UIA_E_ELEMENTNOTENABLED | RPC_E_SERVERCALL_RETRYLATER => {
+            AutomationError::TargetBusy(message)
+        }
+        E_ACCESSDENIED => AutomationError::PermissionDenied(message),
+        _ => AutomationError::platform_with_source(message, e),
+    }
+}
+
+// Keep the failing Win32 call and its HRESULT alongside the message
+fn map_win32_error(call: &'static str, message: &'static str) -> impl FnOnce(windows::core::Error) -> AutomationError {
+    move |e| {
+        let code = e.code().0;
+        AutomationError::platform_with_source(message, e).with_native(call, code)
+    }
+}
+
+// Take the field order and separator from a short date pattern like `dd.MM.yyyy`
+fn apply_short_date_pattern(locale: &mut crate::Locale, pattern: &str) {
+    let position = |field: char| pattern.find(field);
+    let (Some(day), Some(month), Some(year)) = (position('d'), position('M'), position('y')) else {
+        return;
+    };
+    locale.date_order = if year < month {
+        crate::DateOrder::YearMonthDay
+    } else if month < day {
+        crate::DateOrder::MonthDayYear
+    } else {
+        crate::DateOrder::DayMonthYear
+    };
+    if let Some(separator) = pattern.chars().find(|c| !matches!(c, 'd' | 'M' | 'y' | ' ' | '\'')) {
+        locale.date_separator = separator;
+    }
+}
+
 // List of common browser process names (without .exe)
 const KNOWN_BROWSER_PROCESS_NAMES: &[&str] = &[
     "chrome", "firefox", "msedge", "iexplore", "opera", "brave", "vivaldi", "browser", "arc"
@@ -41,12 +103,12 @@ fn get_process_name_by_pid(pid: i32) -> Result<String, AutomationError> {
     let output = std::process::Command::new("powershell")
         .args(["-NoProfile", "-WindowStyle", "hidden", "-Command", &command])
         .output()
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to execute PowerShell to get process name: {}", e)))?;
+        .map_err(|e| AutomationError::platform(format!("Failed to execute PowerShell to get process name: {}", e)))?;
 
     if output.status.success() {
         let process_name = String::from_utf8_lossy(&output.stdout).trim().to_string();
         if process_name.is_empty() {
-            Err(AutomationError::PlatformError(format!(
+            Err(AutomationError::platform(format!(
                 "Process name not found for PID {}",
                 pid
             )))
@@ -55,7 +117,7 @@ fn get_process_name_by_pid(pid: i32) -> Result<String, AutomationError> {
         }
     } else {
         let err_msg = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        Err(AutomationError::PlatformError(format!(
+        Err(AutomationError::platform(format!(
             "PowerShell command failed to get process name for PID {}: {}",
             pid, err_msg
         )))
@@ -83,7 +145,7 @@ pub struct WindowsEngine {
 impl WindowsEngine {
     pub fn new(use_background_apps: bool, activate_app: bool) -> Result<Self, AutomationError> {
         let automation =
-            UIAutomation::new().map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            UIAutomation::new().map_err(map_uia_error("CoCreateInstance(CUIAutomation)"))?;
         let arc_automation = ThreadSafeWinUIAutomation(Arc::new(automation));
         Ok(Self {
             automation: arc_automation,
@@ -91,6 +153,48 @@ impl WindowsEngine {
             activate_app,
         })
     }
+
+    /// A matcher below `root` for elements whose `key` attribute matches `pattern`.
+    /// The regex is compiled once (and cached on the selector) rather than per element.
+    fn regex_matcher(
+        &self,
+        root: &uiautomation::UIElement,
+        key: &str,
+        pattern: &SelectorRegex,
+    ) -> Result<UIMatcher, AutomationError> {
+        let regex = pattern.regex()?.clone();
+        let key = key.to_string();
+        Ok(self
+            .automation
+            .0
+            .create_matcher()
+            .from_ref(root)
+            .filter_fn(Box::new(move |e: &uiautomation::UIElement| {
+                let element = UIElement::new(Box::new(WindowsUIElement {
+                    element: ThreadSafeWinUIElement(Arc::new(e.clone())),
+                }));
+                Ok(crate::selector::regex_matches(&element, &key, &regex))
+            })))
+    }
+
+    /// A matcher below `root` for elements whose UI Automation property `key` equals `value`
+    fn attribute_matcher(
+        &self,
+        root: &uiautomation::UIElement,
+        key: &str,
+        value: &str,
+    ) -> Result<UIMatcher, AutomationError> {
+        let property = uia_property(key)?;
+        let value = value.to_string();
+        Ok(self
+            .automation
+            .0
+            .create_matcher()
+            .from_ref(root)
+            .filter_fn(Box::new(move |e: &uiautomation::UIElement| {
+                Ok(uia_attribute(e, property).is_some_and(|actual| actual == value))
+            })))
+    }
 }
 
 #[async_trait::async_trait]
@@ -193,7 +297,7 @@ impl AccessibilityEngine for WindowsEngine {
                     // Use stripped name
                     Some(pid) => pid,
                     None => {
-                        return Err(AutomationError::PlatformError(format!(
+                        return Err(AutomationError::platform(format!(
                             "no running application found from name: {:?} (searched as: {:?})",
                             name,
                             search_name // Include original name in error
@@ -407,20 +511,16 @@ impl AccessibilityEngine for WindowsEngine {
                     })
                     .collect());
             }
-            Selector::Path(_) => {
-                return Err(AutomationError::UnsupportedOperation(
-                    "`Path` selector not supported".to_string(),
-                ));
+            Selector::Path(path) => {
+                return crate::selector::find_by_path(self, path, root);
             }
             Selector::Attributes(_attributes) => {
                 return Err(AutomationError::UnsupportedOperation(
                     "`Attributes` selector not supported".to_string(),
                 ));
             }
-            Selector::Filter(_filter) => {
-                return Err(AutomationError::UnsupportedOperation(
-                    "`Filter` selector not supported".to_string(),
-                ));
+            Selector::Filter { base, predicate } => {
+                return crate::selector::find_filtered(self, base, predicate, root, timeout);
             }
             Selector::Chain(selectors) => {
                 if selectors.is_empty() {
@@ -477,6 +577,68 @@ impl AccessibilityEngine for WindowsEngine {
                 // Convert Vec<Option<UIElement>> to Vec<UIElement> by filtering out None values
                 return Ok(current_roots.into_iter().filter_map(|x| x).collect());
             }
+            Selector::Label(label) => {
+                return crate::heuristics::find_by_label(self, label, root, timeout);
+            }
+            Selector::Relative { base, relation, anchor } => {
+                return crate::heuristics::find_relative(self, base, *relation, anchor, root, timeout);
+            }
+            Selector::Nth { base, index } => {
+                return crate::selector::find_nth(self, base, *index, root, timeout);
+            }
+            Selector::State { base, filters } => {
+                return crate::selector::find_with_state(self, base, filters, root, timeout);
+            }
+            Selector::Image(template) => {
+                return Ok(vec![crate::vision::find_image(self, template, root, timeout)?]);
+            }
+            Selector::OcrText(text) => {
+                return crate::ocr::find_ocr_text(self, text, root, timeout);
+            }
+            Selector::Attribute { key, value } => {
+                debug!("searching elements by {}={}", key, value);
+                let matcher = self
+                    .attribute_matcher(root_ele, key, value)?
+                    .depth(depth.unwrap_or(50) as u32)
+                    .timeout(timeout_ms as u64);
+                let elements = matcher.find_all().map_err(|e| {
+                    AutomationError::ElementNotFound(format!(
+                        "attr:{}={}, Err: {}",
+                        key, value, e
+                    ))
+                })?;
+
+                return Ok(elements
+                    .into_iter()
+                    .map(|ele| {
+                        UIElement::new(Box::new(WindowsUIElement {
+                            element: ThreadSafeWinUIElement(Arc::new(ele)),
+                        }))
+                    })
+                    .collect());
+            }
+            Selector::Regex { key, pattern } => {
+                debug!("searching elements by {} matching {:?}", key, pattern);
+                let matcher = self
+                    .regex_matcher(root_ele, key, pattern)?
+                    .depth(depth.unwrap_or(50) as u32)
+                    .timeout(timeout_ms as u64);
+                let elements = matcher.find_all().map_err(|e| {
+                    AutomationError::ElementNotFound(format!(
+                        "{}~={:?}, Err: {}",
+                        key, pattern, e
+                    ))
+                })?;
+
+                return Ok(elements
+                    .into_iter()
+                    .map(|ele| {
+                        UIElement::new(Box::new(WindowsUIElement {
+                            element: ThreadSafeWinUIElement(Arc::new(ele)),
+                        }))
+                    })
+                    .collect());
+            }
             Selector::ClassName(classname) => {
                 debug!("searching elements by class name: {}", classname);
                 let matcher = self
@@ -658,20 +820,18 @@ impl AccessibilityEngine for WindowsEngine {
                     element: arc_ele,
                 })));
             }
-            Selector::Path(_) => {
-                return Err(AutomationError::UnsupportedOperation(
-                    "`Path` selector not supported".to_string(),
-                ));
+            Selector::Path(path) => {
+                let mut found = crate::selector::find_by_path(self, path, root)?;
+                return Ok(found.remove(0));
             }
             Selector::Attributes(_attributes) => {
                 return Err(AutomationError::UnsupportedOperation(
                     "`Attributes` selector not supported".to_string(),
                 ));
             }
-            Selector::Filter(_filter) => {
-                return Err(AutomationError::UnsupportedOperation(
-                    "`Filter` selector not supported".to_string(),
-                ));
+            Selector::Filter { base, predicate } => {
+                let mut found = crate::selector::find_filtered(self, base, predicate, root, timeout)?;
+                return Ok(found.remove(0));
             }
             Selector::Chain(selectors) => {
                 if selectors.is_empty() {
@@ -695,6 +855,64 @@ impl AccessibilityEngine for WindowsEngine {
                     )
                 });
             }
+            Selector::Label(label) => {
+                let mut found = crate::heuristics::find_by_label(self, label, root, timeout)?;
+                return Ok(found.remove(0));
+            }
+            Selector::Relative { base, relation, anchor } => {
+                let mut found =
+                    crate::heuristics::find_relative(self, base, *relation, anchor, root, timeout)?;
+                return Ok(found.remove(0));
+            }
+            Selector::Nth { base, index } => {
+                let mut found = crate::selector::find_nth(self, base, *index, root, timeout)?;
+                return Ok(found.remove(0));
+            }
+            Selector::State { base, filters } => {
+                let mut found = crate::selector::find_with_state(self, base, filters, root, timeout)?;
+                return Ok(found.remove(0));
+            }
+            Selector::Image(template) => {
+                return crate::vision::find_image(self, template, root, timeout);
+            }
+            Selector::OcrText(text) => {
+                let mut found = crate::ocr::find_ocr_text(self, text, root, timeout)?;
+                return Ok(found.remove(0));
+            }
+            Selector::Attribute { key, value } => {
+                debug!("searching element by {}={}", key, value);
+                let matcher = self
+                    .attribute_matcher(root_ele, key, value)?
+                    .depth(50)
+                    .timeout(timeout_ms as u64);
+                let element = matcher.find_first().map_err(|e| {
+                    AutomationError::ElementNotFound(format!(
+                        "attr:{}={}, Err: {}",
+                        key, value, e
+                    ))
+                })?;
+                let arc_ele = ThreadSafeWinUIElement(Arc::new(element));
+                return Ok(UIElement::new(Box::new(WindowsUIElement {
+                    element: arc_ele,
+                })));
+            }
+            Selector::Regex { key, pattern } => {
+                debug!("searching element by {} matching {:?}", key, pattern);
+                let matcher = self
+                    .regex_matcher(root_ele, key, pattern)?
+                    .depth(50)
+                    .timeout(timeout_ms as u64);
+                let element = matcher.find_first().map_err(|e| {
+                    AutomationError::ElementNotFound(format!(
+                        "{}~={:?}, Err: {}",
+                        key, pattern, e
+                    ))
+                })?;
+                let arc_ele = ThreadSafeWinUIElement(Arc::new(element));
+                return Ok(UIElement::new(Box::new(WindowsUIElement {
+                    element: arc_ele,
+                })));
+            }
             Selector::ClassName(classname) => {
                 debug!("searching element by class name: {}", classname);
                 let matcher = self
@@ -734,11 +952,11 @@ impl AccessibilityEngine for WindowsEngine {
             let output = std::process::Command::new("powershell")
                 .args(["-NoProfile", "-WindowStyle", "hidden", "-Command", &command])
                 .output()
-                .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                .map_err(|e| AutomationError::platform(e.to_string()))?;
 
             if !output.status.success() {
                 let error_msg = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                return Err(AutomationError::PlatformError(format!(
+                return Err(AutomationError::platform(format!(
                     "Failed to find UWP package: {}",
                     error_msg
                 )));
@@ -747,34 +965,34 @@ impl AccessibilityEngine for WindowsEngine {
             let output_str = String::from_utf8_lossy(&output.stdout);
             let json_str = output_str.trim();
             if json_str.is_empty() {
-                return Err(AutomationError::PlatformError(format!(
+                return Err(AutomationError::platform(format!(
                     "No UWP package found matching '{}'. The package may not be installed or the name is incorrect.",
                     uwp_app_name
                 )));
             }
 
             let packages: Value = serde_json::from_str(json_str).map_err(|e| {
-                AutomationError::PlatformError(format!("Failed to parse package info: {}", e))
+                AutomationError::platform(format!("Failed to parse package info: {}", e))
             })?;
 
             let packages = match packages {
                 Value::Array(arr) => arr,
                 Value::Object(obj) => vec![Value::Object(obj)],
                 Value::Null => {
-                    return Err(AutomationError::PlatformError(format!(
+                    return Err(AutomationError::platform(format!(
                         "No UWP package found matching '{}'. The package may not be installed or the name is incorrect.",
                         uwp_app_name
                     )));
                 }
                 _ => {
-                    return Err(AutomationError::PlatformError(
+                    return Err(AutomationError::platform(
                         "Invalid package info format".to_string(),
                     ));
                 }
             };
 
             if packages.is_empty() {
-                return Err(AutomationError::PlatformError(format!(
+                return Err(AutomationError::platform(format!(
                     "No UWP package found matching '{}'. The package may not be installed or the name is incorrect.",
                     uwp_app_name
                 )));
@@ -787,7 +1005,7 @@ impl AccessibilityEngine for WindowsEngine {
                     .collect::<Vec<String>>()
                     .join("\n    • ");
 
-                return Err(AutomationError::PlatformError(format!(
+                return Err(AutomationError::platform(format!(
                     "Multiple UWP packages found matching '{}'.\nPlease be more specific. Found:\n    • {}",
                     uwp_app_name, package_names
                 )));
@@ -798,21 +1016,21 @@ impl AccessibilityEngine for WindowsEngine {
                 .get("PackageFullName")
                 .and_then(|n| n.as_str())
                 .ok_or_else(|| {
-                    AutomationError::PlatformError("Failed to get package full name".to_string())
+                    AutomationError::platform("Failed to get package full name".to_string())
                 })?;
 
             let install_location = package
                 .get("InstallLocation")
                 .and_then(|n| n.as_str())
                 .ok_or_else(|| {
-                    AutomationError::PlatformError("Failed to get install location".to_string())
+                    AutomationError::platform("Failed to get install location".to_string())
                 })?;
 
             let package_family_name = package
                 .get("PackageFamilyName")
                 .and_then(|n| n.as_str())
                 .ok_or_else(|| {
-                    AutomationError::PlatformError("Failed to get package family name".to_string())
+                    AutomationError::platform("Failed to get package family name".to_string())
                 })?;
 
             // Step 2: Get the app ID and executable name
@@ -827,11 +1045,11 @@ $manifest.Package.Properties.DisplayName"#,
             let output = std::process::Command::new("powershell")
                 .args(["-NoProfile", "-WindowStyle", "hidden", "-Command", &command])
                 .output()
-                .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                .map_err(|e| AutomationError::platform(e.to_string()))?;
 
             if !output.status.success() {
                 let error_msg = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                return Err(AutomationError::PlatformError(format!(
+                return Err(AutomationError::platform(format!(
                     "Failed to get UWP app info: {}",
                     error_msg
                 )));
@@ -841,15 +1059,15 @@ $manifest.Package.Properties.DisplayName"#,
             let mut lines = output_str.lines();
 
             let app_id = lines.next().ok_or_else(|| {
-                AutomationError::PlatformError("Failed to get application ID".to_string())
+                AutomationError::platform("Failed to get application ID".to_string())
             })?;
 
             let executable_name = lines.next().ok_or_else(|| {
-                AutomationError::PlatformError("Failed to get executable name".to_string())
+                AutomationError::platform("Failed to get executable name".to_string())
             })?;
 
             let display_name = lines.next().ok_or_else(|| {
-                AutomationError::PlatformError("Failed to get display name".to_string())
+                AutomationError::platform("Failed to get display name".to_string())
             })?;
 
             // Step 3: Launch the UWP app
@@ -863,11 +1081,11 @@ explorer $appsFolderPath"#,
             let output = std::process::Command::new("powershell")
                 .args(["-NoProfile", "-WindowStyle", "hidden", "-Command", &command])
                 .output()
-                .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                .map_err(|e| AutomationError::platform(e.to_string()))?;
 
             if !output.status.success() {
                 let error_msg = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                return Err(AutomationError::PlatformError(format!(
+                return Err(AutomationError::platform(format!(
                     "Failed to launch UWP application: {}",
                     error_msg
                 )));
@@ -945,10 +1163,10 @@ $latestProcess.ProcessId"#,
                     ),
                 ])
                 .output()
-                .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                .map_err(|e| AutomationError::platform(e.to_string()))?;
 
             if !output.status.success() {
-                return Err(AutomationError::PlatformError(
+                return Err(AutomationError::platform(
                     "Failed to open application".to_string(),
                 ));
             }
@@ -1000,9 +1218,9 @@ $latestProcess.ProcessId"#,
                 url,
             ])
             .status()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(|e| AutomationError::platform(e.to_string()))?;
         if !status.success() {
-            return Err(AutomationError::PlatformError(
+            return Err(AutomationError::platform(
                 "Failed to open URL".to_string(),
             ));
         }
@@ -1031,7 +1249,7 @@ $latestProcess.ProcessId"#,
                 &command_str, // Pass the fully formed command string
             ])
             .output() // Capture output instead of just status
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(|e| AutomationError::platform(e.to_string()))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1039,7 +1257,7 @@ $latestProcess.ProcessId"#,
                 "Failed to open file '{}' using Invoke-Item. Stderr: {}",
                 file_path, stderr
             );
-            return Err(AutomationError::PlatformError(format!(
+            return Err(AutomationError::platform(format!(
                 "Failed to open file '{}' using Invoke-Item. Error: {}",
                 file_path, stderr
             )));
@@ -1067,7 +1285,7 @@ $latestProcess.ProcessId"#,
             ])
             .output()
             .await // Await the async output
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(|e| AutomationError::platform(e.to_string()))?;
 
         Ok(crate::CommandOutput {
             exit_status: output.status.code(),
@@ -1078,7 +1296,7 @@ $latestProcess.ProcessId"#,
 
     async fn capture_screen(&self) -> Result<ScreenshotResult, AutomationError> {
         let monitors = xcap::Monitor::all().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to get monitors: {}", e))
+            AutomationError::platform(format!("Failed to get monitors: {}", e))
         })?;
         let mut primary_monitor: Option<xcap::Monitor> = None;
         for monitor in monitors {
@@ -1089,7 +1307,7 @@ $latestProcess.ProcessId"#,
                 }
                 Ok(false) => continue,
                 Err(e) => {
-                    return Err(AutomationError::PlatformError(format!(
+                    return Err(AutomationError::platform(format!(
                         "Error checking monitor primary status: {}",
                         e
                     )));
@@ -1097,11 +1315,11 @@ $latestProcess.ProcessId"#,
             }
         }
         let primary_monitor = primary_monitor.ok_or_else(|| {
-            AutomationError::PlatformError("Could not find primary monitor".to_string())
+            AutomationError::platform("Could not find primary monitor".to_string())
         })?;
 
         let image = primary_monitor.capture_image().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to capture screen: {}", e))
+            AutomationError::platform(format!("Failed to capture screen: {}", e))
         })?;
 
         Ok(ScreenshotResult {
@@ -1111,12 +1329,16 @@ $latestProcess.ProcessId"#,
         })
     }
 
+    fn list_monitors(&self) -> Result<Vec<crate::MonitorInfo>, AutomationError> {
+        crate::display::xcap_monitors()
+    }
+
     async fn capture_monitor_by_name(
         &self,
         name: &str,
     ) -> Result<ScreenshotResult, AutomationError> {
         let monitors = xcap::Monitor::all().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to get monitors: {}", e))
+            AutomationError::platform(format!("Failed to get monitors: {}", e))
         })?;
         let mut target_monitor: Option<xcap::Monitor> = None;
         for monitor in monitors {
@@ -1127,7 +1349,7 @@ $latestProcess.ProcessId"#,
                 }
                 Ok(_) => continue,
                 Err(e) => {
-                    return Err(AutomationError::PlatformError(format!(
+                    return Err(AutomationError::platform(format!(
                         "Error getting monitor name: {}",
                         e
                     )));
@@ -1139,7 +1361,7 @@ $latestProcess.ProcessId"#,
         })?;
 
         let image = target_monitor.capture_image().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to capture monitor '{}': {}", name, e))
+            AutomationError::platform(format!("Failed to capture monitor '{}': {}", name, e))
         })?;
 
         Ok(ScreenshotResult {
@@ -1152,20 +1374,20 @@ $latestProcess.ProcessId"#,
     async fn ocr_image_path(&self, image_path: &str) -> Result<String, AutomationError> {
         // Create a Tokio runtime to run the async OCR operation
         let rt = Runtime::new().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to create Tokio runtime: {}", e))
+            AutomationError::platform(format!("Failed to create Tokio runtime: {}", e))
         })?;
 
         // Run the async code block on the runtime
         rt.block_on(async {
             let engine = OcrEngine::new(OcrProvider::Auto).map_err(|e| {
-                AutomationError::PlatformError(format!("Failed to create OCR engine: {}", e))
+                AutomationError::platform(format!("Failed to create OCR engine: {}", e))
             })?;
 
             let (text, _language, _confidence) = engine // Destructure the tuple
                 .recognize_file(image_path)
                 .await
                 .map_err(|e| {
-                    AutomationError::PlatformError(format!("OCR recognition failed: {}", e))
+                    AutomationError::platform(format!("OCR recognition failed: {}", e))
                 })?;
 
             Ok(text) // Return only the text
@@ -1193,19 +1415,85 @@ $latestProcess.ProcessId"#,
 
         // Directly await the OCR operation within the existing async context
         let engine = OcrEngine::new(OcrProvider::Auto).map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to create OCR engine: {}", e))
+            AutomationError::platform(format!("Failed to create OCR engine: {}", e))
         })?;
 
         let (text, _language, _confidence) = engine
             .recognize_image(&dynamic_image) // Use recognize_image
             .await // << Directly await here
             .map_err(|e| {
-                AutomationError::PlatformError(format!("OCR recognition failed: {}", e))
+                AutomationError::platform(format!("OCR recognition failed: {}", e))
             })?;
 
         Ok(text)
     }
 
+    fn ocr_words(&self, image: &image::RgbaImage) -> Result<Vec<crate::OcrWord>, AutomationError> {
+        use windows::Graphics::Imaging::{BitmapAlphaMode, BitmapPixelFormat, SoftwareBitmap};
+        use windows::Media::Ocr::OcrEngine as WinOcrEngine;
+        use windows::Storage::Streams::DataWriter;
+
+        let ocr_error = |e: windows::core::Error| AutomationError::platform(format!("OCR recognition failed: {}", e));
+        let engine = WinOcrEngine::TryCreateFromUserProfileLanguages().map_err(|e| {
+            AutomationError::platform(format!("Failed to create OCR engine: {}", e))
+        })?;
+
+        // Windows OCR rejects images larger than this on either side
+        let max_side = WinOcrEngine::MaxImageDimension().map_err(ocr_error)?;
+        let factor = (max_side as f64 / image.width().max(image.height()) as f64).min(1.0);
+        let resized;
+        let image = if factor < 1.0 {
+            resized = image::imageops::resize(
+                image,
+                (image.width() as f64 * factor) as u32,
+                (image.height() as f64 * factor) as u32,
+                image::imageops::FilterType::Triangle,
+            );
+            &resized
+        } else {
+            image
+        };
+
+        // SoftwareBitmap wants BGRA
+        let mut pixels = image.as_raw().clone();
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+        let writer = DataWriter::new().map_err(ocr_error)?;
+        writer.WriteBytes(&pixels).map_err(ocr_error)?;
+        let buffer = writer.DetachBuffer().map_err(ocr_error)?;
+        let bitmap = SoftwareBitmap::CreateCopyWithAlphaFromBuffer(
+            &buffer,
+            BitmapPixelFormat::Bgra8,
+            image.width() as i32,
+            image.height() as i32,
+            BitmapAlphaMode::Premultiplied,
+        )
+        .map_err(ocr_error)?;
+
+        let result = engine
+            .RecognizeAsync(&bitmap)
+            .and_then(|operation| operation.get())
+            .map_err(ocr_error)?;
+        let mut words = Vec::new();
+        for (line_index, line) in result.Lines().map_err(ocr_error)?.into_iter().enumerate() {
+            for word in line.Words().map_err(ocr_error)? {
+                let rect = word.BoundingRect().map_err(ocr_error)?;
+                words.push(crate::OcrWord {
+                    text: word.Text().map_err(ocr_error)?.to_string(),
+                    line: line_index,
+                    bounds: (
+                        rect.X as f64 / factor,
+                        rect.Y as f64 / factor,
+                        rect.Width as f64 / factor,
+                        rect.Height as f64 / factor,
+                    ),
+                });
+            }
+        }
+        Ok(words)
+    }
+
     fn activate_browser_window_by_title(&self, title: &str) -> Result<(), AutomationError> {
         info!(
             "Attempting to activate browser window containing title: {}",
@@ -1216,7 +1504,7 @@ $latestProcess.ProcessId"#,
             .0
             .get_root_element() // Cache root element lookup
             .map_err(|e| {
-                AutomationError::PlatformError(format!("Failed to get root element: {}", e))
+                AutomationError::platform(format!("Failed to get root element: {}", e))
             })?;
 
         // Find top-level windows
@@ -1233,13 +1521,13 @@ $latestProcess.ProcessId"#,
             .timeout(5000);
 
         let window = window_matcher.find_first().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to find top-level windows: {}", e))
+            AutomationError::platform(format!("Failed to find top-level windows: {}", e))
         })?;
 
         // TODO: focus part does not work (at least in browser firefox)
         // If find_first succeeds, 'window' is the UIElement. Now try to focus it.
         window.set_focus().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to set focus on window/tab: {}", e))
+            AutomationError::platform(format!("Failed to set focus on window/tab: {}", e))
         })?; // Map focus error
 
         Ok(()) // If focus succeeds, return Ok
@@ -1261,7 +1549,7 @@ $latestProcess.ProcessId"#,
         // first find element by matcher
         let root_ele = self.automation.0.get_root_element().unwrap();
         let automation_engine_instance = WindowsEngine::new(false, false) 
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(|e| AutomationError::platform(e.to_string()))?;
         let matcher = automation_engine_instance 
             .automation
             .0
@@ -1297,16 +1585,80 @@ $latestProcess.ProcessId"#,
         })));
     }
 
+    fn find_windows(
+        &self,
+        criteria: &crate::WindowCriteria,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<UIElement>, AutomationError> {
+        criteria.validate()?;
+        let timeout_duration = timeout.unwrap_or(DEFAULT_FIND_TIMEOUT);
+        info!("Searching for windows: {:?}, timeout={:?}", criteria, timeout_duration);
+
+        // One process snapshot up front instead of a lookup per window
+        let process_names: HashMap<u32, String> = if criteria.process_name.is_some() {
+            self.list_processes()?
+                .into_iter()
+                .map(|process| (process.pid, process.name))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        let root_ele = self.automation.0.get_root_element().map_err(map_uia_error("IUIAutomation::GetRootElement"))?;
+        let filter_criteria = criteria.clone();
+        let matcher = self
+            .automation
+            .0
+            .create_matcher()
+            .filter(Box::new(OrFilter {
+                left: Box::new(ControlTypeFilter {
+                    control_type: ControlType::Window,
+                }),
+                right: Box::new(ControlTypeFilter {
+                    control_type: ControlType::Pane,
+                }),
+            }))
+            .filter_fn(Box::new(move |e: &uiautomation::UIElement| {
+                let title = e.get_name().unwrap_or_default();
+                let class_name = e.get_classname().ok();
+                Ok(filter_criteria.matches(
+                    &title,
+                    class_name.as_deref(),
+                    || {
+                        let pid = e.get_process_id().ok()?;
+                        process_names.get(&(pid as u32)).cloned()
+                    },
+                    || !e.is_offscreen().unwrap_or(true),
+                ))
+            }))
+            .from_ref(&root_ele)
+            .depth(3)
+            .timeout(timeout_duration.as_millis() as u64);
+
+        let windows = matcher.find_all().map_err(|e| {
+            AutomationError::ElementNotFound(format!("No window matching {:?}: {}", criteria, e))
+        })?;
+
+        Ok(windows
+            .into_iter()
+            .map(|ele| {
+                UIElement::new(Box::new(WindowsUIElement {
+                    element: ThreadSafeWinUIElement(Arc::new(ele)),
+                }))
+            })
+            .collect())
+    }
+
     async fn get_current_browser_window(&self) -> Result<UIElement, AutomationError> {
         info!("Attempting to get the current focused browser window.");
         let focused_element_raw = self
             .automation
             .0
             .get_focused_element()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get focused element: {}", e)))?;
+            .map_err(|e| AutomationError::platform(format!("Failed to get focused element: {}", e)))?;
 
         let pid = focused_element_raw.get_process_id().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to get process ID for focused element: {}", e))
+            AutomationError::platform(format!("Failed to get process ID for focused element: {}", e))
         })?;
 
         let process_name_raw = get_process_name_by_pid(pid as i32)?;
@@ -1379,114 +1731,865 @@ $latestProcess.ProcessId"#,
             .as_any()
             .downcast_ref::<WindowsUIElement>()
             .ok_or_else(|| {
-                AutomationError::PlatformError(
+                AutomationError::platform(
                     "Failed to get window element implementation for activation".to_string(),
                 )
             })?;
 
         // Use set_focus, which typically brings the window forward on Windows
         win_element_impl.element.0.set_focus().map_err(|e| {
-            AutomationError::PlatformError(format!(
+            AutomationError::platform(format!(
                 "Failed to set focus on application window '{}': {}",
                 app_name, e
             ))
         })
     }
-}
 
-// thread-safety
-#[derive(Clone)]
-pub struct ThreadSafeWinUIElement(Arc<uiautomation::UIElement>);
+    fn get_process_sample(&self, pid: u32) -> Result<crate::ProcessSample, AutomationError> {
+        use windows::Win32::Foundation::{CloseHandle, FILETIME, STILL_ACTIVE};
+        use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+        use windows::Win32::System::Threading::{
+            GetExitCodeProcess, GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+            PROCESS_VM_READ,
+        };
+        use windows::Win32::UI::WindowsAndMessaging::{GetWindowThreadProcessId, IsHungAppWindow};
 
-// send and sync for wrapper
-unsafe impl Send for ThreadSafeWinUIElement {}
-unsafe impl Sync for ThreadSafeWinUIElement {}
+        let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, false, pid) }
+            .map_err(|_| AutomationError::ElementNotFound(format!("Process {} is not running", pid)))?;
 
-pub struct WindowsUIElement {
-    element: ThreadSafeWinUIElement,
-}
+        let sample = (|| {
+            let mut exit_code = 0u32;
+            unsafe { GetExitCodeProcess(handle, &mut exit_code) }
+                .map_err(map_win32_error("GetExitCodeProcess", "Failed to query process"))?;
+            if exit_code != STILL_ACTIVE.0 as u32 {
+                return Err(AutomationError::ElementNotFound(format!("Process {} has exited", pid)));
+            }
 
-impl Debug for WindowsUIElement {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("WindowsUIElement").finish()
-    }
-}
+            let (mut creation, mut exit, mut kernel, mut user) = (
+                FILETIME::default(),
+                FILETIME::default(),
+                FILETIME::default(),
+                FILETIME::default(),
+            );
+            unsafe { GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) }
+                .map_err(map_win32_error("GetProcessTimes", "Failed to read process times"))?;
+            // FILETIME counts 100ns intervals
+            let ticks = |t: FILETIME| ((t.dwHighDateTime as u64) << 32) | t.dwLowDateTime as u64;
+            let cpu_time = Duration::from_nanos((ticks(kernel) + ticks(user)) * 100);
+
+            let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+            let mut counters = PROCESS_MEMORY_COUNTERS {
+                cb: size,
+                ..Default::default()
+            };
+            unsafe { GetProcessMemoryInfo(handle, &mut counters, size) }
+                .map_err(map_win32_error("GetProcessMemoryInfo", "Failed to read process memory"))?;
 
-impl UIElementImpl for WindowsUIElement {
-    fn object_id(&self) -> usize {
-        // Use the common function to generate ID
-        generate_element_id(&self.element.0).unwrap_or(0)
-    }
+            let responding = visible_top_level_windows().into_iter().all(|hwnd| {
+                let mut window_pid = 0u32;
+                unsafe { GetWindowThreadProcessId(hwnd, Some(&mut window_pid as *mut u32)) };
+                window_pid != pid || !unsafe { IsHungAppWindow(hwnd) }.as_bool()
+            });
 
-    fn id(&self) -> Option<String> {
-        Some(self.object_id().to_string())
-    }
+            Ok(crate::ProcessSample {
+                cpu_time,
+                memory_bytes: counters.WorkingSetSize as u64,
+                responding,
+            })
+        })();
 
-    fn role(&self) -> String {
-        self.element.0.get_control_type().unwrap().to_string()
+        unsafe {
+            let _ = CloseHandle(handle);
+        }
+        sample
     }
 
-    fn attributes(&self) -> UIElementAttributes {
-        let mut properties = HashMap::new();
-        // there are alot of properties, including neccessary ones
-        // ref: https://docs.rs/uiautomation/0.16.1/uiautomation/types/enum.UIProperty.html
-        let property_list = vec![
-            UIProperty::Name,
-            UIProperty::HelpText,
-            UIProperty::LabeledBy,
-            UIProperty::ValueValue,
-            UIProperty::ControlType,
-            UIProperty::AutomationId,
-            UIProperty::FullDescription,
-            UIProperty::IsKeyboardFocusable, // Added for attributes
-        ];
-        for property in property_list {
-            if let Ok(value) = self.element.0.get_property_value(property) {
-                properties.insert(
-                    format!("{:?}", property),
-                    Some(serde_json::to_value(value.to_string()).unwrap_or_default()),
-                );
-            } else {
-                properties.insert(format!("{:?}", property), None);
-            }
+    fn list_processes(&self) -> Result<Vec<crate::ProcessInfo>, AutomationError> {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+            TH32CS_SNAPPROCESS,
+        };
+        use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+
+        let mut window_counts: HashMap<u32, usize> = HashMap::new();
+        for hwnd in visible_top_level_windows() {
+            let mut pid = 0u32;
+            unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid as *mut u32)) };
+            *window_counts.entry(pid).or_default() += 1;
         }
-        UIElementAttributes {
-            role: self.role(),
-            name: self.element.0.get_name().ok(),
-            label: self
-                .element
-                .0
-                .get_labeled_by()
-                .ok().map(|e| e.get_name().unwrap_or_default()),
-            value: self
-                .element
-                .0
-                .get_property_value(UIProperty::ValueValue)
-                .ok()
-                .and_then(|v| v.get_string().ok()),
-            description: self.element.0.get_help_text().ok(),
-            properties,
-            is_keyboard_focusable: self.is_keyboard_focusable().ok(), // Added field
+
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }
+            .map_err(map_win32_error("CreateToolhelp32Snapshot", "Failed to snapshot processes"))?;
+        let mut processes = Vec::new();
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut next = unsafe { Process32FirstW(snapshot, &mut entry) };
+        while next.is_ok() {
+            let name_len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+            processes.push(crate::ProcessInfo {
+                pid: entry.th32ProcessID,
+                parent_pid: (entry.th32ParentProcessID != 0).then_some(entry.th32ParentProcessID),
+                name: String::from_utf16_lossy(&entry.szExeFile[..name_len]),
+                window_count: window_counts.get(&entry.th32ProcessID).copied().unwrap_or(0),
+            });
+            next = unsafe { Process32NextW(snapshot, &mut entry) };
+        }
+        unsafe {
+            let _ = CloseHandle(snapshot);
         }
+        Ok(processes)
     }
 
-    fn children(&self) -> Result<Vec<UIElement>, AutomationError> {
-        // Try getting cached children first
-        let children_result = self.element.0.get_cached_children();
+    fn terminate_process(&self, pid: u32) -> Result<(), AutomationError> {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Threading::{OpenProcess, PROCESS_TERMINATE, TerminateProcess};
 
-        let children = match children_result {
-            Ok(cached_children) => {
-                info!("Found {} cached children.", cached_children.len());
-                cached_children
-            }
-            Err(cache_err) => {
-                info!(
-                    "Failed to get cached children for text extraction ({}), falling back to non-cached TreeScope::Children search.",
-                    cache_err
-                );
-                // Fallback logic (similar to explore_element_children)
-                match uiautomation::UIAutomation::new() {
-                    Ok(temp_automation) => {
+        let handle = unsafe { OpenProcess(PROCESS_TERMINATE, false, pid) }
+            .map_err(|_| AutomationError::ElementNotFound(format!("Process {} is not running", pid)))?;
+        let result = unsafe { TerminateProcess(handle, 1) }
+            .map_err(map_win32_error("TerminateProcess", "Failed to terminate process"));
+        unsafe {
+            let _ = CloseHandle(handle);
+        }
+        result
+    }
+
+    fn list_sessions(&self) -> Result<Vec<crate::SessionInfo>, AutomationError> {
+        use windows::Win32::System::RemoteDesktop::{
+            ProcessIdToSessionId, WTS_SESSION_INFOW, WTSActive, WTSConnected, WTSDisconnected,
+            WTSEnumerateSessionsW, WTSFreeMemory, WTSGetActiveConsoleSessionId, WTSIdle, WTSListen,
+            WTSQuerySessionInformationW, WTSUserName,
+        };
+        use windows::core::PWSTR;
+
+        let mut current_session = 0u32;
+        unsafe { ProcessIdToSessionId(std::process::id(), &mut current_session) }
+            .map_err(map_win32_error("ProcessIdToSessionId", "Failed to get current session"))?;
+        let console_session = unsafe { WTSGetActiveConsoleSessionId() };
+
+        let mut info: *mut WTS_SESSION_INFOW = std::ptr::null_mut();
+        let mut count = 0u32;
+        unsafe { WTSEnumerateSessionsW(None, 0, 1, &mut info, &mut count) }
+            .map_err(map_win32_error("WTSEnumerateSessionsW", "Failed to enumerate sessions"))?;
+
+        let raw = unsafe { std::slice::from_raw_parts(info, count as usize) };
+        let sessions = raw
+            .iter()
+            .map(|session| {
+                let state = match session.State {
+                    s if s == WTSActive => crate::SessionState::Active,
+                    s if s == WTSConnected => crate::SessionState::Connected,
+                    s if s == WTSDisconnected => crate::SessionState::Disconnected,
+                    s if s == WTSIdle => crate::SessionState::Idle,
+                    s if s == WTSListen => crate::SessionState::Listening,
+                    _ => crate::SessionState::Other,
+                };
+
+                let mut buffer = PWSTR::null();
+                let mut bytes = 0u32;
+                let user = unsafe {
+                    WTSQuerySessionInformationW(None, session.SessionId, WTSUserName, &mut buffer, &mut bytes)
+                }
+                .ok()
+                .and_then(|_| {
+                    let name = unsafe { buffer.to_string() }.ok();
+                    unsafe { WTSFreeMemory(buffer.0 as *mut std::ffi::c_void) };
+                    name
+                })
+                .filter(|name| !name.is_empty());
+
+                crate::SessionInfo {
+                    id: session.SessionId,
+                    station: unsafe { session.pWinStationName.to_string() }.unwrap_or_default(),
+                    user,
+                    state,
+                    is_current: session.SessionId == current_session,
+                    is_console: session.SessionId == console_session,
+                }
+            })
+            .collect();
+
+        unsafe { WTSFreeMemory(info as *mut std::ffi::c_void) };
+        Ok(sessions)
+    }
+
+    fn get_screen_reader_flag(&self) -> Result<bool, AutomationError> {
+        use windows::core::BOOL;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            SPI_GETSCREENREADER, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, SystemParametersInfoW,
+        };
+
+        let mut screen_reader = BOOL(0);
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETSCREENREADER,
+                0,
+                Some(&mut screen_reader as *mut BOOL as *mut std::ffi::c_void),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+        }
+        .map_err(map_win32_error("SystemParametersInfoW", "Failed to read screen reader setting"))?;
+        Ok(screen_reader.as_bool())
+    }
+
+    fn get_theme(&self) -> Result<crate::Theme, AutomationError> {
+        use windows::core::BOOL;
+        use windows::Win32::Graphics::Dwm::DwmGetColorizationColor;
+        use windows::Win32::System::Registry::{HKEY_CURRENT_USER, RRF_RT_REG_DWORD, RegGetValueW};
+        use windows::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+        use windows::Win32::UI::WindowsAndMessaging::{
+            SPI_GETHIGHCONTRAST, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, SystemParametersInfoW,
+        };
+        use windows::core::w;
+
+        // Windows versions without dark mode lack the value, and were light
+        let mut apps_use_light_theme = 1u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let _ = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                w!(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize"),
+                w!("AppsUseLightTheme"),
+                RRF_RT_REG_DWORD,
+                None,
+                Some(&mut apps_use_light_theme as *mut u32 as *mut std::ffi::c_void),
+                Some(&mut size),
+            )
+        };
+
+        // 0xAARRGGBB
+        let mut colorization = 0u32;
+        let mut opaque_blend = BOOL(0);
+        let accent_color = unsafe { DwmGetColorizationColor(&mut colorization, &mut opaque_blend) }
+            .ok()
+            .map(|_| crate::drawing::Color {
+                r: (colorization >> 16) as u8,
+                g: (colorization >> 8) as u8,
+                b: colorization as u8,
+                a: 255,
+            });
+
+        let mut high_contrast = HIGHCONTRASTW {
+            cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+            ..Default::default()
+        };
+        let high_contrast_on = unsafe {
+            SystemParametersInfoW(
+                SPI_GETHIGHCONTRAST,
+                high_contrast.cbSize,
+                Some(&mut high_contrast as *mut HIGHCONTRASTW as *mut std::ffi::c_void),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+        }
+        .is_ok()
+            && high_contrast.dwFlags.contains(HCF_HIGHCONTRASTON);
+
+        Ok(crate::Theme {
+            mode: if apps_use_light_theme == 0 {
+                crate::ThemeMode::Dark
+            } else {
+                crate::ThemeMode::Light
+            },
+            accent_color,
+            high_contrast: high_contrast_on,
+        })
+    }
+
+    fn get_locale(&self) -> Result<crate::Locale, AutomationError> {
+        use windows::Win32::Globalization::{
+            GetLocaleInfoEx, GetUserDefaultLocaleName, LOCALE_SCURRENCY, LOCALE_SDECIMAL, LOCALE_SSHORTDATE,
+            LOCALE_STHOUSAND,
+        };
+        use windows::Win32::Foundation::GetLastError;
+        use windows::core::PCWSTR;
+
+        // LOCALE_NAME_MAX_LENGTH
+        let mut name = [0u16; 85];
+        let length = unsafe { GetUserDefaultLocaleName(&mut name) };
+        if length == 0 {
+            let error = windows::core::Error::from_hresult(unsafe { GetLastError() }.to_hresult());
+            return Err(map_win32_error("GetUserDefaultLocaleName", "Failed to read the user's locale name")(error));
+        }
+        let mut locale = crate::Locale::from_tag(&String::from_utf16_lossy(&name[..length as usize - 1]));
+
+        // The user can override each setting of their locale, so read them rather than
+        // relying on the defaults for its name
+        let info = |kind: u32| {
+            let mut buffer = [0u16; 128];
+            let length = unsafe { GetLocaleInfoEx(PCWSTR::null(), kind, Some(&mut buffer)) };
+            (length > 1).then(|| String::from_utf16_lossy(&buffer[..length as usize - 1]))
+        };
+        if let Some(decimal) = info(LOCALE_SDECIMAL).and_then(|value| value.chars().next()) {
+            locale.decimal_separator = decimal;
+        }
+        if let Some(grouping) = info(LOCALE_STHOUSAND).and_then(|value| value.chars().next()) {
+            locale.grouping_separator = grouping;
+        }
+        if let Some(currency) = info(LOCALE_SCURRENCY) {
+            locale.currency_symbol = Some(currency);
+        }
+        if let Some(pattern) = info(LOCALE_SSHORTDATE) {
+            apply_short_date_pattern(&mut locale, &pattern);
+        }
+        Ok(locale)
+    }
+
+    fn is_session_locked(&self) -> Result<bool, AutomationError> {
+        use windows::Win32::System::RemoteDesktop::{
+            ProcessIdToSessionId, WTS_SESSIONSTATE_LOCK, WTSFreeMemory, WTSINFOEXW,
+            WTSQuerySessionInformationW, WTSSessionInfoEx,
+        };
+        use windows::core::PWSTR;
+
+        let mut session = 0u32;
+        unsafe { ProcessIdToSessionId(std::process::id(), &mut session) }
+            .map_err(map_win32_error("ProcessIdToSessionId", "Failed to get current session"))?;
+
+        let mut buffer = PWSTR::null();
+        let mut bytes = 0u32;
+        unsafe { WTSQuerySessionInformationW(None, session, WTSSessionInfoEx, &mut buffer, &mut bytes) }
+            .map_err(map_win32_error("WTSQuerySessionInformationW", "Failed to query session state"))?;
+        let flags = unsafe { (*(buffer.0 as *const WTSINFOEXW)).Data.WTSInfoExLevel1.SessionFlags };
+        unsafe { WTSFreeMemory(buffer.0 as *mut std::ffi::c_void) };
+        Ok(flags == WTS_SESSIONSTATE_LOCK as i32)
+    }
+
+    fn get_idle_duration(&self) -> Result<Duration, AutomationError> {
+        use windows::Win32::System::SystemInformation::GetTickCount;
+        use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+            return Err(AutomationError::platform("Failed to get last input time"));
+        }
+        // Both are tick counts that wrap after ~49 days
+        let now = unsafe { GetTickCount() };
+        Ok(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+    }
+
+    fn unlock_session(&self, password: &str) -> Result<(), AutomationError> {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{
+            INPUT, INPUT_0, INPUT_KEYBOARD, KEYBD_EVENT_FLAGS, KEYBDINPUT, KEYEVENTF_KEYUP,
+            KEYEVENTF_UNICODE, SendInput, VIRTUAL_KEY, VK_CONTROL, VK_RETURN,
+        };
+
+        let key = |vk: VIRTUAL_KEY, scan: u16, flags: KEYBD_EVENT_FLAGS| INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: vk,
+                    wScan: scan,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+        // The lock screen lives on the secure desktop, which drops input from
+        // ordinary processes; SendInput reports that as fewer events sent
+        let send = |inputs: &[INPUT]| {
+            let sent = unsafe { SendInput(inputs, std::mem::size_of::<INPUT>() as i32) };
+            if sent as usize == inputs.len() {
+                Ok(())
+            } else {
+                Err(AutomationError::PermissionDenied(
+                    "The lock screen rejected input from this process; it has to run where input \
+                     reaches the secure desktop, e.g. as a service attached to the console session"
+                        .to_string(),
+                ))
+            }
+        };
+
+        // Any key raises the curtain, and Ctrl types nothing into the password box
+        send(&[
+            key(VK_CONTROL, 0, KEYBD_EVENT_FLAGS(0)),
+            key(VK_CONTROL, 0, KEYEVENTF_KEYUP),
+        ])?;
+        std::thread::sleep(LOCK_SCREEN_SETTLE_DELAY);
+
+        let mut inputs = Vec::new();
+        for unit in password.encode_utf16() {
+            inputs.push(key(VIRTUAL_KEY(0), unit, KEYEVENTF_UNICODE));
+            inputs.push(key(VIRTUAL_KEY(0), unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP));
+        }
+        inputs.push(key(VK_RETURN, 0, KEYBD_EVENT_FLAGS(0)));
+        inputs.push(key(VK_RETURN, 0, KEYEVENTF_KEYUP));
+        send(&inputs)
+    }
+
+    fn get_window_placements(&self) -> Result<Vec<crate::WindowPlacement>, AutomationError> {
+        use windows::Win32::Foundation::RECT;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetWindowRect, GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsZoomed,
+        };
+
+        let mut placements = Vec::new();
+        for hwnd in visible_top_level_windows() {
+            let mut title_buffer = [0u16; 512];
+            let len = unsafe { GetWindowTextW(hwnd, &mut title_buffer) };
+            if len <= 0 {
+                continue; // untitled windows are tool windows / shell surfaces
+            }
+            let title = String::from_utf16_lossy(&title_buffer[..len as usize]);
+
+            let mut rect = RECT::default();
+            if unsafe { GetWindowRect(hwnd, &mut rect) }.is_err() {
+                continue;
+            }
+
+            let mut process_id = 0u32;
+            unsafe { GetWindowThreadProcessId(hwnd, Some(&mut process_id as *mut u32)) };
+
+            let state = if unsafe { IsIconic(hwnd) }.as_bool() {
+                crate::WindowState::Minimized
+            } else if unsafe { IsZoomed(hwnd) }.as_bool() {
+                crate::WindowState::Maximized
+            } else {
+                crate::WindowState::Normal
+            };
+
+            placements.push(crate::WindowPlacement {
+                handle: hwnd.0 as isize,
+                title,
+                process_id,
+                x: rect.left,
+                y: rect.top,
+                width: rect.right - rect.left,
+                height: rect.bottom - rect.top,
+                state,
+                monitor: monitor_name_for_window(hwnd),
+                z_order: placements.len(),
+            });
+        }
+
+        Ok(placements)
+    }
+
+    fn set_window_placement(&self, placement: &crate::WindowPlacement) -> Result<(), AutomationError> {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            HWND_TOP, IsWindow, SW_MAXIMIZE, SW_RESTORE, SW_SHOWMINNOACTIVE, SWP_NOACTIVATE,
+            SetWindowPos, ShowWindow,
+        };
+
+        let hwnd = HWND(placement.handle as *mut std::ffi::c_void);
+        if !unsafe { IsWindow(Some(hwnd)) }.as_bool() {
+            return Err(AutomationError::ElementNotFound(format!(
+                "Window '{}' no longer exists",
+                placement.title
+            )));
+        }
+
+        // Position is applied in the restored state, then the show state is re-applied on top
+        unsafe {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+            SetWindowPos(
+                hwnd,
+                Some(HWND_TOP),
+                placement.x,
+                placement.y,
+                placement.width,
+                placement.height,
+                SWP_NOACTIVATE,
+            )
+            .map_err(map_win32_error("SetWindowPos", "Failed to position window"))?;
+            match placement.state {
+                crate::WindowState::Minimized => {
+                    let _ = ShowWindow(hwnd, SW_SHOWMINNOACTIVE);
+                }
+                crate::WindowState::Maximized => {
+                    let _ = ShowWindow(hwnd, SW_MAXIMIZE);
+                }
+                crate::WindowState::Normal => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn close_window(&self, placement: &crate::WindowPlacement) -> Result<(), AutomationError> {
+        use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+        use windows::Win32::UI::WindowsAndMessaging::{IsWindow, PostMessageW, WM_CLOSE};
+
+        let hwnd = HWND(placement.handle as *mut std::ffi::c_void);
+        if !unsafe { IsWindow(Some(hwnd)) }.as_bool() {
+            return Err(AutomationError::ElementNotFound(format!(
+                "Window '{}' no longer exists",
+                placement.title
+            )));
+        }
+        unsafe { PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0)) }
+            .map_err(map_win32_error("PostMessageW", "Failed to close window"))
+    }
+
+    fn get_element_at_point(&self, x: f64, y: f64) -> Result<UIElement, AutomationError> {
+        let element = self
+            .automation
+            .0
+            .element_from_point(Point::new(x as i32, y as i32))
+            .map_err(map_uia_error("IUIAutomation::ElementFromPoint"))?;
+        Ok(UIElement::new(Box::new(WindowsUIElement {
+            element: ThreadSafeWinUIElement(Arc::new(element)),
+        })))
+    }
+
+    fn get_attribute(&self, element: &UIElement, name: &str) -> Result<Option<String>, AutomationError> {
+        let property = uia_property(name)?;
+        let element = element
+            .as_any()
+            .downcast_ref::<WindowsUIElement>()
+            .ok_or_else(|| AutomationError::platform("Element is not a UI Automation element"))?;
+        Ok(uia_attribute(&element.element.0, property))
+    }
+
+    fn is_key_pressed(&self, key: &str) -> Result<bool, AutomationError> {
+        use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
+
+        let virtual_key = virtual_key_code(key).ok_or_else(|| {
+            AutomationError::InvalidArgument(format!("Unknown key name: '{}'", key))
+        })?;
+        Ok(unsafe { GetAsyncKeyState(virtual_key) } as u16 & 0x8000 != 0)
+    }
+
+    fn get_mouse_state(&self) -> Result<crate::MouseState, AutomationError> {
+        use windows::Win32::Foundation::POINT;
+        use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_LBUTTON, VK_RBUTTON};
+        use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+
+        let mut point = POINT::default();
+        unsafe { GetCursorPos(&mut point) }
+            .map_err(map_win32_error("GetCursorPos", "Failed to get cursor position"))?;
+        // the high bit of GetAsyncKeyState is set while the button is down
+        let is_down = |key: i32| unsafe { GetAsyncKeyState(key) } as u16 & 0x8000 != 0;
+
+        Ok(crate::MouseState {
+            x: point.x as f64,
+            y: point.y as f64,
+            left_pressed: is_down(VK_LBUTTON.0 as i32),
+            right_pressed: is_down(VK_RBUTTON.0 as i32),
+        })
+    }
+
+    fn get_input_state(&self) -> Result<crate::InputState, AutomationError> {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{
+            GetAsyncKeyState, VIRTUAL_KEY, VK_CONTROL, VK_LBUTTON, VK_LWIN, VK_MENU, VK_RBUTTON,
+            VK_RWIN, VK_SHIFT,
+        };
+
+        let is_down = |key: VIRTUAL_KEY| unsafe { GetAsyncKeyState(key.0 as i32) } as u16 & 0x8000 != 0;
+        Ok(crate::InputState {
+            shift: is_down(VK_SHIFT),
+            ctrl: is_down(VK_CONTROL),
+            alt: is_down(VK_MENU),
+            meta: is_down(VK_LWIN) || is_down(VK_RWIN),
+            left_pressed: is_down(VK_LBUTTON),
+            right_pressed: is_down(VK_RBUTTON),
+        })
+    }
+
+    fn release_input(&self) -> Result<(), AutomationError> {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{
+            BlockInput, GetAsyncKeyState, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT,
+            KEYEVENTF_KEYUP, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_RIGHTUP, MOUSEINPUT, SendInput,
+            VIRTUAL_KEY, VK_CONTROL, VK_LBUTTON, VK_LWIN, VK_MENU, VK_RBUTTON, VK_RWIN, VK_SHIFT,
+        };
+
+        // Only fails when no block is in place (or the process isn't elevated)
+        let _ = unsafe { BlockInput(false) };
+
+        let is_down = |key: VIRTUAL_KEY| unsafe { GetAsyncKeyState(key.0 as i32) } as u16 & 0x8000 != 0;
+        let mut inputs = Vec::new();
+        for key in [VK_SHIFT, VK_CONTROL, VK_MENU, VK_LWIN, VK_RWIN] {
+            if is_down(key) {
+                inputs.push(INPUT {
+                    r#type: INPUT_KEYBOARD,
+                    Anonymous: INPUT_0 {
+                        ki: KEYBDINPUT {
+                            wVk: key,
+                            wScan: 0,
+                            dwFlags: KEYEVENTF_KEYUP,
+                            time: 0,
+                            dwExtraInfo: 0,
+                        },
+                    },
+                });
+            }
+        }
+        for (button, flags) in [(VK_LBUTTON, MOUSEEVENTF_LEFTUP), (VK_RBUTTON, MOUSEEVENTF_RIGHTUP)] {
+            if is_down(button) {
+                inputs.push(INPUT {
+                    r#type: INPUT_MOUSE,
+                    Anonymous: INPUT_0 {
+                        mi: MOUSEINPUT {
+                            dx: 0,
+                            dy: 0,
+                            mouseData: 0,
+                            dwFlags: flags,
+                            time: 0,
+                            dwExtraInfo: 0,
+                        },
+                    },
+                });
+            }
+        }
+
+        if !inputs.is_empty() {
+            let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+            if sent as usize != inputs.len() {
+                return Err(AutomationError::platform(format!(
+                    "Released {} of {} held keys and buttons",
+                    sent,
+                    inputs.len()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+// thread-safety
+#[derive(Clone)]
+pub struct ThreadSafeWinUIElement(Arc<uiautomation::UIElement>);
+
+// send and sync for wrapper
+unsafe impl Send for ThreadSafeWinUIElement {}
+unsafe impl Sync for ThreadSafeWinUIElement {}
+
+// Reads an element's children in the raw view one sibling at a time
+struct RawChildren {
+    walker: uiautomation::UITreeWalker,
+    // Set until the first child has been read
+    parent: Option<ThreadSafeWinUIElement>,
+    current: Option<ThreadSafeWinUIElement>,
+}
+
+// send for wrapper
+unsafe impl Send for RawChildren {}
+
+impl Iterator for RawChildren {
+    type Item = Result<UIElement, AutomationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (next, call) = match self.parent.take() {
+            Some(parent) => (
+                self.walker.get_first_child(&parent.0),
+                "IUIAutomationTreeWalker::GetFirstChildElement",
+            ),
+            None => (
+                self.walker.get_next_sibling(&self.current.take()?.0),
+                "IUIAutomationTreeWalker::GetNextSiblingElement",
+            ),
+        };
+        match next {
+            Ok(element) => {
+                let element = ThreadSafeWinUIElement(Arc::new(element));
+                self.current = Some(element.clone());
+                Some(Ok(UIElement::new(Box::new(WindowsUIElement { element }))))
+            }
+            // The walker reports running out of siblings as an error without an HRESULT
+            Err(e) if e.code() == 0 => None,
+            Err(e) => Some(Err(map_uia_error(call)(e))),
+        }
+    }
+}
+
+pub struct WindowsUIElement {
+    element: ThreadSafeWinUIElement,
+}
+
+impl Debug for WindowsUIElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowsUIElement").finish()
+    }
+}
+
+impl WindowsUIElement {
+    // Where to press the mouse: the element's clickable point, or the center of its
+    // bounding rectangle for elements that don't report one. The label says which.
+    fn mouse_point(&self) -> Result<(Point, &'static str), AutomationError> {
+        match self.element.0.get_clickable_point() {
+            Ok(Some(point)) => return Ok((point, "Clickable Point")),
+            Ok(None) => debug!("no clickable point, falling back to bounding rectangle"),
+            Err(e) => debug!("clickable point unavailable ({}), falling back to bounding rectangle", e),
+        }
+        let rect = self
+            .element
+            .0
+            .get_bounding_rectangle()
+            .map_err(map_uia_error("IUIAutomationElement::get_CurrentBoundingRectangle"))?;
+        let center = Point::new(rect.get_left() + rect.get_width() / 2, rect.get_top() + rect.get_height() / 2);
+        Ok((center, "Fallback"))
+    }
+
+    fn element_state(&self) -> ElementState {
+        use uiautomation::types::{ExpandCollapseState, ToggleState};
+
+        let element = &self.element.0;
+        ElementState {
+            enabled: element.is_enabled().ok(),
+            focused: element.has_keyboard_focus().ok(),
+            offscreen: element.is_offscreen().ok(),
+            read_only: element
+                .get_pattern::<patterns::UIValuePattern>()
+                .and_then(|value| value.is_readonly())
+                .ok(),
+            required: element.is_required_for_form().ok(),
+            password: element.is_password().ok(),
+            checked: element
+                .get_pattern::<patterns::UITogglePattern>()
+                .and_then(|toggle| toggle.get_toggle_state())
+                .ok()
+                .and_then(|state| match state {
+                    ToggleState::On => Some(true),
+                    ToggleState::Off => Some(false),
+                    _ => None,
+                }),
+            expanded: element
+                .get_pattern::<patterns::UIExpandCollapsePattern>()
+                .and_then(|expand_collapse| expand_collapse.get_state())
+                .ok()
+                .and_then(|state| match state {
+                    ExpandCollapseState::Expanded | ExpandCollapseState::PartiallyExpanded => Some(true),
+                    ExpandCollapseState::Collapsed => Some(false),
+                    _ => None, // leaf nodes can't expand
+                }),
+            selected: element
+                .get_pattern::<patterns::UISelectionItemPattern>()
+                .and_then(|selection_item| selection_item.is_selected())
+                .ok(),
+        }
+    }
+
+    // UIA has no placeholder property; browsers expose it through the ARIA properties string
+    fn placeholder(&self) -> Option<String> {
+        let aria = self.element.0.get_aria_properties().ok()?;
+        aria.split(';').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key.trim() == "placeholder").then(|| value.trim().to_string())
+        })
+    }
+
+    // Elements without a value pattern have no value to read or set, which callers
+    // handle by typing instead
+    fn value_pattern(&self) -> Result<patterns::UIValuePattern, AutomationError> {
+        self.element.0.get_pattern::<patterns::UIValuePattern>().map_err(|_| {
+            AutomationError::UnsupportedOperation("Element has no value pattern".to_string())
+        })
+    }
+}
+
+impl UIElementImpl for WindowsUIElement {
+    fn object_id(&self) -> usize {
+        // Use the common function to generate ID
+        generate_element_id(&self.element.0).unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<String> {
+        Some(self.object_id().to_string())
+    }
+
+    fn role(&self) -> String {
+        self.element.0.get_control_type().unwrap().to_string()
+    }
+
+    fn attributes(&self) -> UIElementAttributes {
+        let mut properties = HashMap::new();
+        // there are alot of properties, including neccessary ones
+        // ref: https://docs.rs/uiautomation/0.16.1/uiautomation/types/enum.UIProperty.html
+        let property_list = vec![
+            UIProperty::Name,
+            UIProperty::HelpText,
+            UIProperty::LabeledBy,
+            UIProperty::ValueValue,
+            UIProperty::ControlType,
+            UIProperty::AutomationId,
+            UIProperty::FullDescription,
+            UIProperty::IsKeyboardFocusable, // Added for attributes
+        ];
+        for property in property_list {
+            if let Ok(value) = self.element.0.get_property_value(property) {
+                properties.insert(
+                    format!("{:?}", property),
+                    Some(serde_json::to_value(value.to_string()).unwrap_or_default()),
+                );
+            } else {
+                properties.insert(format!("{:?}", property), None);
+            }
+        }
+        if let Ok(selection_item) = self.element.0.get_pattern::<patterns::UISelectionItemPattern>() {
+            properties.insert(
+                "IsSelected".to_string(),
+                selection_item.is_selected().ok().map(serde_json::Value::Bool),
+            );
+        }
+        UIElementAttributes {
+            role: self.role(),
+            normalized_role: Role::from_uia_control_type(&self.role()),
+            name: self.element.0.get_name().ok(),
+            label: self
+                .element
+                .0
+                .get_labeled_by()
+                .ok().map(|e| e.get_name().unwrap_or_default()),
+            value: self
+                .element
+                .0
+                .get_property_value(UIProperty::ValueValue)
+                .ok()
+                .and_then(|v| v.get_string().ok()),
+            description: self.element.0.get_help_text().ok(),
+            properties,
+            is_keyboard_focusable: self.is_keyboard_focusable().ok(), // Added field
+            help_text: self.element.0.get_help_text().ok().filter(|s| !s.is_empty()),
+            access_key: self.element.0.get_access_key().ok().filter(|s| !s.is_empty()),
+            accelerator_key: self
+                .element
+                .0
+                .get_accelerator_key()
+                .ok()
+                .filter(|s| !s.is_empty()),
+            placeholder: self.placeholder(),
+            labeled_by: self
+                .element
+                .0
+                .get_labeled_by()
+                .ok()
+                .and_then(|e| e.get_name().ok())
+                .filter(|s| !s.is_empty()),
+            // FullDescription carries the text of the DescribedBy targets in browsers and WinUI
+            described_by: self
+                .element
+                .0
+                .get_property_value(UIProperty::FullDescription)
+                .ok()
+                .and_then(|v| v.get_string().ok())
+                .filter(|s| !s.is_empty()),
+            state: self.element_state(),
+        }
+    }
+
+    fn children(&self) -> Result<Vec<UIElement>, AutomationError> {
+        // Try getting cached children first
+        let children_result = self.element.0.get_cached_children();
+
+        let children = match children_result {
+            Ok(cached_children) => {
+                info!("Found {} cached children.", cached_children.len());
+                cached_children
+            }
+            Err(cache_err) => {
+                info!(
+                    "Failed to get cached children for text extraction ({}), falling back to non-cached TreeScope::Children search.",
+                    cache_err
+                );
+                // Fallback logic (similar to explore_element_children)
+                match uiautomation::UIAutomation::new() {
+                    Ok(temp_automation) => {
                         match temp_automation.create_true_condition() {
                             Ok(true_condition) => {
                                 self.element
@@ -1497,7 +2600,7 @@ impl UIElementImpl for WindowsUIElement {
                                             "Failed to get children via find_all fallback: CacheErr={}, FindErr={}",
                                             cache_err, find_err
                                         );
-                                        AutomationError::PlatformError(format!(
+                                        AutomationError::platform(format!(
                                             "Failed to get children (cached and non-cached): {}",
                                             find_err
                                         ))
@@ -1508,7 +2611,7 @@ impl UIElementImpl for WindowsUIElement {
                                     "Failed to create true condition for child fallback: {}",
                                     cond_err
                                 );
-                                return Err(AutomationError::PlatformError(format!(
+                                return Err(AutomationError::platform(format!(
                                     "Failed to create true condition for fallback: {}",
                                     cond_err
                                 )));
@@ -1520,7 +2623,7 @@ impl UIElementImpl for WindowsUIElement {
                             "Failed to create temporary UIAutomation for child fallback: {}",
                             auto_err
                         );
-                        return Err(AutomationError::PlatformError(format!(
+                        return Err(AutomationError::platform(format!(
                             "Failed to create temp UIAutomation for fallback: {}",
                             auto_err
                         )));
@@ -1540,6 +2643,18 @@ impl UIElementImpl for WindowsUIElement {
             .collect())
     }
 
+    fn children_iter(&self) -> Result<ChildIter, AutomationError> {
+        let automation = UIAutomation::new().map_err(map_uia_error("CoCreateInstance(CUIAutomation)"))?;
+        let walker = automation
+            .get_raw_view_walker()
+            .map_err(map_uia_error("IUIAutomation::get_RawViewWalker"))?;
+        Ok(Box::new(RawChildren {
+            walker,
+            parent: Some(self.element.clone()),
+            current: None,
+        }))
+    }
+
     fn parent(&self) -> Result<Option<UIElement>, AutomationError> {
         let parent = self.element.0.get_cached_parent();
         match parent {
@@ -1558,7 +2673,7 @@ impl UIElementImpl for WindowsUIElement {
             .element
             .0
             .get_bounding_rectangle()
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()))?;
+            .map_err(map_uia_error("IUIAutomationElement::get_CurrentBoundingRectangle"))?;
         Ok((
             rect.get_left() as f64,
             rect.get_top() as f64,
@@ -1578,6 +2693,7 @@ impl UIElementImpl for WindowsUIElement {
                 method: "Single Click".to_string(),
                 coordinates: None,
                 details: "Clicked by Mouse".to_string(),
+                context: None,
             });
         }
         // First try using the standard clickable point
@@ -1593,10 +2709,11 @@ impl UIElementImpl for WindowsUIElement {
                         method: "Single Click (Clickable Point)".to_string(),
                         coordinates: Some((point.get_x() as f64, point.get_y() as f64)),
                         details: "Clicked by Mouse using element's clickable point".to_string(),
+                        context: None,
                     })
                 } else {
                     Err(
-                        AutomationError::PlatformError("No clickable point found".to_string())
+                        AutomationError::platform("No clickable point found".to_string())
                             .to_string()
                             .into(),
                     )
@@ -1618,69 +2735,75 @@ impl UIElementImpl for WindowsUIElement {
                 debug!("clicking at center point: ({}, {})", center_x, center_y);
                 mouse
                     .click(point)
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                    .map_err(map_uia_error("SendInput"))?;
 
                 return Ok(ClickResult {
                     method: "Single Click (Fallback)".to_string(),
                     coordinates: Some((center_x as f64, center_y as f64)),
                     details: "Clicked by Mouse using element's center coordinates".to_string(),
+                    context: None,
                 });
             }
         }
 
         // Return the result of the first attempt or propagate the error
-        click_result.map_err(|e| AutomationError::PlatformError(e.to_string()))
+        click_result.map_err(|e| AutomationError::platform(e.to_string()))
     }
 
     fn double_click(&self) -> Result<ClickResult, AutomationError> {
         self.element.0.try_focus();
-        let point = self
-            .element
-            .0
-            .get_clickable_point()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?
-            .ok_or_else(|| {
-                AutomationError::PlatformError("No clickable point found".to_string())
-            })?;
+        let (point, source) = self.mouse_point()?;
         let mouse = Mouse::default();
         mouse
             .double_click(point)
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(map_uia_error("SendInput"))?;
         Ok(ClickResult {
-            method: "Double Click".to_string(),
+            method: format!("Double Click ({})", source),
             coordinates: Some((point.get_x() as f64, point.get_y() as f64)),
-            details: "Clicked by Mouse".to_string(),
+            details: "Double-clicked by Mouse".to_string(),
+            context: None,
         })
     }
 
-    fn right_click(&self) -> Result<(), AutomationError> {
+    fn right_click(&self) -> Result<ClickResult, AutomationError> {
         self.element.0.try_focus();
-        let point = self
-            .element
-            .0
-            .get_clickable_point()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?
-            .ok_or_else(|| {
-                AutomationError::PlatformError("No clickable point found".to_string())
-            })?;
+        // Ask the element to open its context menu first; it works without the
+        // element being on screen and is what keyboard users get with Shift+F10
+        match self.element.0.show_context_menu() {
+            Ok(()) => {
+                return Ok(ClickResult {
+                    method: "ShowContextMenu".to_string(),
+                    coordinates: None,
+                    details: "Opened context menu through UI Automation".to_string(),
+                    context: None,
+                });
+            }
+            Err(e) => debug!("ShowContextMenu failed ({}), right-clicking with the mouse", e),
+        }
+
+        let (point, source) = self.mouse_point()?;
         let mouse = Mouse::default();
         mouse
             .right_click(point)
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
-        Ok(())
+            .map_err(map_uia_error("SendInput"))?;
+        Ok(ClickResult {
+            method: format!("Right Click ({})", source),
+            coordinates: Some((point.get_x() as f64, point.get_y() as f64)),
+            details: "Right-clicked by Mouse".to_string(),
+            context: None,
+        })
     }
 
     fn hover(&self) -> Result<(), AutomationError> {
-        return Err(AutomationError::UnsupportedOperation(
-            "`hover` doesn't not support".to_string(),
-        ));
+        let (x, y, width, height) = self.bounds()?;
+        self.mouse_move(x + width / 2.0, y + height / 2.0)
     }
 
     fn focus(&self) -> Result<(), AutomationError> {
         self.element
             .0
             .set_focus()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))
+            .map_err(map_uia_error("IUIAutomationElement::SetFocus"))
     }
 
     fn activate_window(&self) -> Result<(), AutomationError> {
@@ -1698,14 +2821,14 @@ impl UIElementImpl for WindowsUIElement {
             .element
             .0
             .get_control_type()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(map_uia_error("IUIAutomationElement::get_CurrentControlType"))?;
         
         debug!("typing text with control_type: {:#?}, use_clipboard: {}", control_type, use_clipboard);
 
         if use_clipboard {
             // Save current clipboard content
             let original_clipboard = match Clipboard::new()
-                .map_err(|e| AutomationError::PlatformError(format!("Failed to access clipboard: {}", e)))?
+                .map_err(|e| AutomationError::platform(format!("Failed to access clipboard: {}", e)))?
                 .get_text()
             {
                 Ok(text) => Some(text),
@@ -1714,9 +2837,9 @@ impl UIElementImpl for WindowsUIElement {
 
             // Set new text to clipboard
             Clipboard::new()
-                .map_err(|e| AutomationError::PlatformError(format!("Failed to access clipboard: {}", e)))?
+                .map_err(|e| AutomationError::platform(format!("Failed to access clipboard: {}", e)))?
                 .set_text(text)
-                .map_err(|e| AutomationError::PlatformError(format!("Failed to set clipboard content: {}", e)))?;
+                .map_err(|e| AutomationError::platform(format!("Failed to set clipboard content: {}", e)))?;
 
             // Focus the element
             self.focus()?;
@@ -1727,15 +2850,15 @@ impl UIElementImpl for WindowsUIElement {
             // Restore original clipboard content if we had any
             if let Some(original) = original_clipboard {
                 Clipboard::new()
-                    .map_err(|e| AutomationError::PlatformError(format!("Failed to access clipboard: {}", e)))?
+                    .map_err(|e| AutomationError::platform(format!("Failed to access clipboard: {}", e)))?
                     .set_text(&original)
-                    .map_err(|e| AutomationError::PlatformError(format!("Failed to restore clipboard content: {}", e)))?;
+                    .map_err(|e| AutomationError::platform(format!("Failed to restore clipboard content: {}", e)))?;
             } else {
                 // Clear clipboard if it was empty originally
                 Clipboard::new()
-                    .map_err(|e| AutomationError::PlatformError(format!("Failed to access clipboard: {}", e)))?
+                    .map_err(|e| AutomationError::platform(format!("Failed to access clipboard: {}", e)))?
                     .clear()
-                    .map_err(|e| AutomationError::PlatformError(format!("Failed to clear clipboard: {}", e)))?;
+                    .map_err(|e| AutomationError::platform(format!("Failed to clear clipboard: {}", e)))?;
             }
 
             Ok(())
@@ -1744,7 +2867,7 @@ impl UIElementImpl for WindowsUIElement {
             self.element
                 .0
                 .send_text(text, 10)
-                .map_err(|e| AutomationError::PlatformError(e.to_string()))
+                .map_err(map_uia_error("SendInput"))
         }
     }
 
@@ -1753,13 +2876,13 @@ impl UIElementImpl for WindowsUIElement {
             .element
             .0
             .get_control_type()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get control type: {:?}", e)))?;
+            .map_err(|e| AutomationError::platform(format!("Failed to get control type: {:?}", e)))?;
         // check if element accepts input, similar :D
         debug!("pressing key with control_type: {:#?}", control_type);
         self.element
             .0
             .send_keys(key, 10)
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to press key: {:?}", e)))
+            .map_err(|e| AutomationError::platform(format!("Failed to press key: {:?}", e)))
     }
 
     fn get_text(&self, max_depth: usize) -> Result<String, AutomationError> {
@@ -1879,39 +3002,169 @@ impl UIElementImpl for WindowsUIElement {
     }
 
     fn set_value(&self, value: &str) -> Result<(), AutomationError> {
-        let value_par = self
-            .element
-            .0
-            .get_pattern::<patterns::UIValuePattern>()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()));
+        let pattern = self.value_pattern()?;
         debug!(
             "setting value: {:#?} to ui element {:#?}",
             &value, &self.element.0
         );
 
-        if let Ok(v) = value_par {
-            v.set_value(value)
-                .map_err(|e| AutomationError::PlatformError(e.to_string()))
+        if pattern.is_readonly().unwrap_or(false) {
+            return Err(AutomationError::UnsupportedOperation(
+                "Element's value is read-only".to_string(),
+            ));
+        }
+        pattern
+            .set_value(value)
+            .map_err(map_uia_error("IUIAutomationValuePattern::SetValue"))
+    }
+
+    fn value(&self) -> Result<String, AutomationError> {
+        self.value_pattern()?
+            .get_value()
+            .map_err(map_uia_error("IUIAutomationValuePattern::get_CurrentValue"))
+    }
+
+    fn set_range_value(&self, value: f64) -> Result<(), AutomationError> {
+        let range = self
+            .element
+            .0
+            .get_pattern::<patterns::UIRangeValuePattern>()
+            .map_err(|_| {
+                AutomationError::UnsupportedOperation(
+                    "`UIRangeValuePattern` is not found".to_string(),
+                )
+            })?;
+        if range.is_readonly().unwrap_or(false) {
+            return Err(AutomationError::UnsupportedOperation(
+                "range value is read-only".to_string(),
+            ));
+        }
+        let min = range.get_minimum().map_err(map_uia_error("IUIAutomationRangeValuePattern::get_CurrentMinimum"))?;
+        let max = range.get_maximum().map_err(map_uia_error("IUIAutomationRangeValuePattern::get_CurrentMaximum"))?;
+        if value < min || value > max {
+            return Err(AutomationError::InvalidArgument(format!(
+                "{} is outside the range {}..={}",
+                value, min, max
+            )));
+        }
+        debug!("setting range value {} on ui element {:#?}", value, &self.element.0);
+        range.set_value(value).map_err(map_uia_error("IUIAutomationRangeValuePattern::SetValue"))
+    }
+
+    fn range_value(&self) -> Result<RangeValue, AutomationError> {
+        let range = self
+            .element
+            .0
+            .get_pattern::<patterns::UIRangeValuePattern>()
+            .map_err(|_| {
+                AutomationError::UnsupportedOperation(
+                    "`UIRangeValuePattern` is not found".to_string(),
+                )
+            })?;
+        Ok(RangeValue {
+            minimum: range.get_minimum().map_err(map_uia_error("IUIAutomationRangeValuePattern::get_CurrentMinimum"))?,
+            maximum: range.get_maximum().map_err(map_uia_error("IUIAutomationRangeValuePattern::get_CurrentMaximum"))?,
+            value: range.get_value().map_err(map_uia_error("IUIAutomationRangeValuePattern::get_CurrentValue"))?,
+        })
+    }
+
+    fn is_responsive(&self) -> Result<bool, AutomationError> {
+        use windows::Win32::Foundation::{CloseHandle, HWND, WAIT_TIMEOUT};
+        use windows::Win32::System::Threading::{
+            OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_SYNCHRONIZE, WaitForInputIdle,
+        };
+        use windows::Win32::UI::WindowsAndMessaging::IsHungAppWindow;
+
+        // Elements below the window have no handle of their own; that is not a hang
+        if let Ok(handle) = self.element.0.get_native_window_handle() {
+            let hwnd: HWND = handle.into();
+            if !hwnd.is_invalid() && unsafe { IsHungAppWindow(hwnd) }.as_bool() {
+                return Ok(false);
+            }
+        }
+
+        let pid = self.element.0.get_process_id().map_err(map_uia_error("IUIAutomationElement::get_CurrentProcessId"))?;
+        let process = unsafe { OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_SYNCHRONIZE, false, pid) }
+            .map_err(map_win32_error("OpenProcess", "Failed to open process"))?;
+        // A zero timeout only asks whether the input queue is idle. Processes without a
+        // message queue (consoles, services) fail the call and have nothing to wait for.
+        let busy = unsafe { WaitForInputIdle(process, 0) } == WAIT_TIMEOUT.0;
+        unsafe {
+            let _ = CloseHandle(process);
+        }
+        Ok(!busy)
+    }
+
+    fn process_id(&self) -> Result<u32, AutomationError> {
+        self.element
+            .0
+            .get_process_id()
+            .map_err(map_uia_error("IUIAutomationElement::get_CurrentProcessId"))
+    }
+
+    fn scroll_into_view(&self) -> Result<(), AutomationError> {
+        let scroll_item = self
+            .element
+            .0
+            .get_pattern::<patterns::UIScrollItemPattern>()
+            .map_err(map_uia_error("IUIAutomationElement::GetCurrentPattern"))?;
+        scroll_item
+            .scroll_into_view()
+            .map_err(map_uia_error("IUIAutomationScrollItemPattern::ScrollIntoView"))
+    }
+
+    fn set_expanded(&self, expanded: bool) -> Result<(), AutomationError> {
+        let expand_collapse = self
+            .element
+            .0
+            .get_pattern::<patterns::UIExpandCollapsePattern>()
+            .map_err(map_uia_error("IUIAutomationElement::GetCurrentPattern"))?;
+        if expanded {
+            expand_collapse
+                .expand()
+                .map_err(map_uia_error("IUIAutomationExpandCollapsePattern::Expand"))
         } else {
-            Err(AutomationError::PlatformError(
-                "`UIValuePattern` is not found".to_string(),
-            ))
+            expand_collapse
+                .collapse()
+                .map_err(map_uia_error("IUIAutomationExpandCollapsePattern::Collapse"))
         }
     }
 
+    fn toggle(&self) -> Result<(), AutomationError> {
+        let toggle = self
+            .element
+            .0
+            .get_pattern::<patterns::UITogglePattern>()
+            .map_err(map_uia_error("IUIAutomationElement::GetCurrentPattern"))?;
+        toggle
+            .toggle()
+            .map_err(map_uia_error("IUIAutomationTogglePattern::Toggle"))
+    }
+
+    fn select_item(&self) -> Result<(), AutomationError> {
+        let selection_item = self
+            .element
+            .0
+            .get_pattern::<patterns::UISelectionItemPattern>()
+            .map_err(map_uia_error("IUIAutomationElement::GetCurrentPattern"))?;
+        selection_item
+            .select()
+            .map_err(map_uia_error("IUIAutomationSelectionItemPattern::Select"))
+    }
+
     fn is_enabled(&self) -> Result<bool, AutomationError> {
         self.element
             .0
             .is_enabled()
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()))
+            .map_err(map_uia_error("IUIAutomationElement::get_CurrentIsEnabled"))
     }
 
     fn is_visible(&self) -> Result<bool, AutomationError> {
-        // offscreen means invisible, right?
         self.element
             .0
             .is_offscreen()
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()))
+            .map(|offscreen| !offscreen)
+            .map_err(map_uia_error("IUIAutomationElement::get_CurrentIsOffscreen"))
     }
 
     fn is_focused(&self) -> Result<bool, AutomationError> {
@@ -1919,7 +3172,7 @@ impl UIElementImpl for WindowsUIElement {
         // It created a new WindowsEngine and compared the focused element's Arc pointer,
         // which is not reliable and very slow.
         // The uiautomation::UIElement provides a direct has_keyboard_focus() method.
-        self.element.0.has_keyboard_focus().map_err(|e| AutomationError::PlatformError(format!("Failed to get keyboard focus state: {}", e)))
+        self.element.0.has_keyboard_focus().map_err(|e| AutomationError::platform(format!("Failed to get keyboard focus state: {}", e)))
     }
 
     fn perform_action(&self, action: &str) -> Result<(), AutomationError> {
@@ -1931,10 +3184,10 @@ impl UIElementImpl for WindowsUIElement {
                     .element
                     .0
                     .get_pattern::<patterns::UIInvokePattern>()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                    .map_err(map_uia_error("IUIAutomationElement::GetCurrentPattern"))?;
                 invoke_pat
                     .invoke()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))
+                    .map_err(map_uia_error("IUIAutomationInvokePattern::Invoke"))
             }
             "click" => self.click().map(|_| ()),
             "double_click" => self.double_click().map(|_| ()),
@@ -1944,20 +3197,40 @@ impl UIElementImpl for WindowsUIElement {
                     .element
                     .0
                     .get_pattern::<patterns::UITogglePattern>()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                    .map_err(map_uia_error("IUIAutomationElement::GetCurrentPattern"))?;
                 toggle_pattern
                     .toggle()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))
+                    .map_err(map_uia_error("IUIAutomationTogglePattern::Toggle"))
+            }
+            "select" => {
+                let selection_item_pattern = self
+                    .element
+                    .0
+                    .get_pattern::<patterns::UISelectionItemPattern>()
+                    .map_err(map_uia_error("IUIAutomationElement::GetCurrentPattern"))?;
+                selection_item_pattern
+                    .select()
+                    .map_err(map_uia_error("IUIAutomationSelectionItemPattern::Select"))
+            }
+            "add_to_selection" => {
+                let selection_item_pattern = self
+                    .element
+                    .0
+                    .get_pattern::<patterns::UISelectionItemPattern>()
+                    .map_err(map_uia_error("IUIAutomationElement::GetCurrentPattern"))?;
+                selection_item_pattern
+                    .add_to_selection()
+                    .map_err(map_uia_error("IUIAutomationSelectionItemPattern::AddToSelection"))
             }
             "expand_collapse" => {
                 let expand_collapse_pattern = self
                     .element
                     .0
                     .get_pattern::<patterns::UIExpandCollapsePattern>()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                    .map_err(map_uia_error("IUIAutomationElement::GetCurrentPattern"))?;
                 expand_collapse_pattern
                     .expand()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))
+                    .map_err(map_uia_error("IUIAutomationExpandCollapsePattern::Expand"))
             }
             _ => Err(AutomationError::UnsupportedOperation(format!(
                 "action '{}' not supported",
@@ -1972,7 +3245,7 @@ impl UIElementImpl for WindowsUIElement {
 
     fn create_locator(&self, selector: Selector) -> Result<Locator, AutomationError> {
         let automation = WindowsEngine::new(false, false)
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(|e| AutomationError::platform(e.to_string()))?;
 
         let attrs = self.attributes();
         debug!(
@@ -1995,7 +3268,7 @@ impl UIElementImpl for WindowsUIElement {
 
     fn scroll(&self, direction: &str, amount: f64) -> Result<(), AutomationError> {
         // First try to focus the element
-        self.focus().map_err(|e| AutomationError::PlatformError(format!("Failed to focus element: {:?}", e)))?;
+        self.focus().map_err(|e| AutomationError::platform(format!("Failed to focus element: {:?}", e)))?;
 
         // Only support up/down directions
         match direction {
@@ -2037,16 +3310,16 @@ impl UIElementImpl for WindowsUIElement {
                 match direction {
                     "up" => scroll_pattern
                         .scroll(ScrollAmount::NoAmount, scroll_amount)
-                        .map_err(|e| AutomationError::PlatformError(format!("Failed to scroll up: {:?}", e))),
+                        .map_err(|e| AutomationError::platform(format!("Failed to scroll up: {:?}", e))),
                     "down" => scroll_pattern
                         .scroll(ScrollAmount::NoAmount, scroll_amount)
-                        .map_err(|e| AutomationError::PlatformError(format!("Failed to scroll down: {:?}", e))),
+                        .map_err(|e| AutomationError::platform(format!("Failed to scroll down: {:?}", e))),
                     "left" => scroll_pattern
                         .scroll(scroll_amount, ScrollAmount::NoAmount)
-                        .map_err(|e| AutomationError::PlatformError(format!("Failed to scroll left: {:?}", e))),
+                        .map_err(|e| AutomationError::platform(format!("Failed to scroll left: {:?}", e))),
                     "right" => scroll_pattern
                         .scroll(scroll_amount, ScrollAmount::NoAmount)
-                        .map_err(|e| AutomationError::PlatformError(format!("Failed to scroll right: {:?}", e))),
+                        .map_err(|e| AutomationError::platform(format!("Failed to scroll right: {:?}", e))),
                     _ => Err(AutomationError::UnsupportedOperation(
                         "Invalid scroll direction".to_string(),
                     )),
@@ -2059,16 +3332,16 @@ impl UIElementImpl for WindowsUIElement {
                 match direction {
                     "up" => scroll_item_pattern
                         .scroll_into_view()
-                        .map_err(|e| AutomationError::PlatformError(format!("Failed to scroll item up: {:?}", e))),
+                        .map_err(|e| AutomationError::platform(format!("Failed to scroll item up: {:?}", e))),
                     "down" => scroll_item_pattern
                         .scroll_into_view()
-                        .map_err(|e| AutomationError::PlatformError(format!("Failed to scroll item down: {:?}", e))),
+                        .map_err(|e| AutomationError::platform(format!("Failed to scroll item down: {:?}", e))),
                     "left" => scroll_item_pattern
                         .scroll_into_view()
-                        .map_err(|e| AutomationError::PlatformError(format!("Failed to scroll item left: {:?}", e))),
+                        .map_err(|e| AutomationError::platform(format!("Failed to scroll item left: {:?}", e))),
                     "right" => scroll_item_pattern
                         .scroll_into_view()
-                        .map_err(|e| AutomationError::PlatformError(format!("Failed to scroll item right: {:?}", e))),
+                        .map_err(|e| AutomationError::platform(format!("Failed to scroll item right: {:?}", e))),
                     _ => Err(AutomationError::UnsupportedOperation(
                         "Invalid scroll direction".to_string(),
                     )),
@@ -2084,7 +3357,7 @@ impl UIElementImpl for WindowsUIElement {
 
             // Get the element's bounds to calculate center point
             let rect = self.element.0.get_bounding_rectangle()
-                .map_err(|e| AutomationError::PlatformError(format!("Failed to get element bounds: {:?}", e)))?;
+                .map_err(|e| AutomationError::platform(format!("Failed to get element bounds: {:?}", e)))?;
             
             let center_x = rect.get_left() + rect.get_width() / 2;
             let center_y = rect.get_top() + rect.get_height() / 2;
@@ -2119,8 +3392,8 @@ impl UIElementImpl for WindowsUIElement {
             .element
             .0
             .get_property_value(UIProperty::IsKeyboardFocusable)
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
-        variant.try_into().map_err(|e| AutomationError::PlatformError(format!("Failed to convert IsKeyboardFocusable to bool: {:?}", e)))
+            .map_err(map_uia_error("IUIAutomationElement::GetCurrentPropertyValue"))?;
+        variant.try_into().map_err(|e| AutomationError::platform(format!("Failed to convert IsKeyboardFocusable to bool: {:?}", e)))
     }
 
     // New method for mouse drag
@@ -2234,57 +3507,305 @@ impl UIElementImpl for WindowsUIElement {
         }
         Ok(())
     }
+    fn mouse_wheel(&self, x: f64, y: f64, delta_x: i32, delta_y: i32) -> Result<(), AutomationError> {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{
+            INPUT, INPUT_0, INPUT_MOUSE, MOUSE_EVENT_FLAGS, MOUSEEVENTF_HWHEEL, MOUSEEVENTF_WHEEL, MOUSEINPUT, SendInput,
+        };
+        use windows::Win32::UI::WindowsAndMessaging::WHEEL_DELTA;
+
+        self.mouse_move(x, y)?;
+        let wheel = |flags: MOUSE_EVENT_FLAGS, notches: i32| INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dx: 0,
+                    dy: 0,
+                    mouseData: (notches * WHEEL_DELTA as i32) as u32,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+        // A positive vertical wheel turn scrolls up, a positive horizontal one right
+        let mut inputs = Vec::new();
+        if delta_y != 0 {
+            inputs.push(wheel(MOUSEEVENTF_WHEEL, -delta_y));
+        }
+        if delta_x != 0 {
+            inputs.push(wheel(MOUSEEVENTF_HWHEEL, delta_x));
+        }
+        unsafe {
+            SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+        }
+        Ok(())
+    }
+    fn press_chord(&self, chord: &KeyChord) -> Result<(), AutomationError> {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{
+            INPUT, INPUT_0, INPUT_KEYBOARD, KEYBD_EVENT_FLAGS, KEYBDINPUT, KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP,
+            SendInput, VIRTUAL_KEY, VK_BACK, VK_CONTROL, VK_DELETE, VK_DOWN, VK_END, VK_ESCAPE, VK_F1, VK_HOME,
+            VK_INSERT, VK_LEFT, VK_LWIN, VK_MENU, VK_NEXT, VK_PRIOR, VK_RETURN, VK_RIGHT, VK_SHIFT, VK_SPACE, VK_TAB,
+            VK_UP, VkKeyScanW,
+        };
+
+        let mut modifiers: Vec<VIRTUAL_KEY> = chord
+            .modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                Modifier::Ctrl => VK_CONTROL,
+                Modifier::Shift => VK_SHIFT,
+                Modifier::Alt => VK_MENU,
+                Modifier::Meta => VK_LWIN,
+            })
+            .collect();
+        let vk = match chord.key {
+            Key::Char(c) => {
+                let unit = u16::try_from(c as u32)
+                    .map_err(|_| AutomationError::InvalidArgument(format!("No key types '{}'", c)))?;
+                // Low byte is the virtual key, high byte the Shift, Ctrl and Alt it needs
+                let scan = unsafe { VkKeyScanW(unit) };
+                if scan == -1 {
+                    return Err(AutomationError::InvalidArgument(format!(
+                        "No key on the current keyboard layout types '{}'",
+                        c
+                    )));
+                }
+                for (bit, modifier) in [(1, VK_SHIFT), (2, VK_CONTROL), (4, VK_MENU)] {
+                    if (scan >> 8) & bit != 0 && !modifiers.contains(&modifier) {
+                        modifiers.push(modifier);
+                    }
+                }
+                VIRTUAL_KEY((scan & 0xff) as u16)
+            }
+            Key::Enter => VK_RETURN,
+            Key::Tab => VK_TAB,
+            Key::Escape => VK_ESCAPE,
+            Key::Backspace => VK_BACK,
+            Key::Delete => VK_DELETE,
+            Key::Insert => VK_INSERT,
+            Key::Home => VK_HOME,
+            Key::End => VK_END,
+            Key::PageUp => VK_PRIOR,
+            Key::PageDown => VK_NEXT,
+            Key::Up => VK_UP,
+            Key::Down => VK_DOWN,
+            Key::Left => VK_LEFT,
+            Key::Right => VK_RIGHT,
+            Key::Space => VK_SPACE,
+            Key::Function(n) => VIRTUAL_KEY(VK_F1.0 + n as u16 - 1),
+        };
+        // The navigation block is separate from the numeric keypad, which shares its virtual keys
+        let extended = matches!(
+            chord.key,
+            Key::Delete | Key::Insert | Key::Home | Key::End | Key::PageUp | Key::PageDown | Key::Up | Key::Down | Key::Left | Key::Right
+        );
+
+        let key = |vk: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS| INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: vk,
+                    wScan: 0,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+        let key_flags = if extended { KEYEVENTF_EXTENDEDKEY } else { KEYBD_EVENT_FLAGS(0) };
+        let mut inputs: Vec<INPUT> = modifiers.iter().map(|&modifier| key(modifier, KEYBD_EVENT_FLAGS(0))).collect();
+        inputs.push(key(vk, key_flags));
+        inputs.push(key(vk, key_flags | KEYEVENTF_KEYUP));
+        inputs.extend(modifiers.iter().rev().map(|&modifier| key(modifier, KEYEVENTF_KEYUP)));
+
+        // Input into elevated windows is dropped for ordinary processes; SendInput reports that
+        // as fewer events sent
+        let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+        if sent as usize != inputs.len() {
+            return Err(AutomationError::PermissionDenied(format!(
+                "Only {} of {} key events were accepted, the focused window may be running elevated",
+                sent,
+                inputs.len()
+            )));
+        }
+        Ok(())
+    }
 }
 
 // make easier to pass roles
 fn map_generic_role_to_win_roles(role: &str) -> ControlType {
-    match role.to_lowercase().as_str() {
-        "pane" | "app" | "application" => ControlType::Pane,
-        "window" | "dialog" => ControlType::Window,
-        "button" => ControlType::Button,
-        "checkbox" => ControlType::CheckBox,
-        "menu" => ControlType::Menu,
-        "menuitem" => ControlType::MenuItem,
-        "text" => ControlType::Text,
-        "tree" => ControlType::Tree,
-        "treeitem" => ControlType::TreeItem,
-        "data" | "dataitem" => ControlType::DataItem,
-        "datagrid" => ControlType::DataGrid,
-        "url" | "urlfield" => ControlType::Edit,
-        "list" => ControlType::List,
-        "image" => ControlType::Image,
-        "title" => ControlType::TitleBar,
-        "listitem" => ControlType::ListItem,
-        "combobox" => ControlType::ComboBox,
-        "tab" => ControlType::Tab,
-        "tabitem" => ControlType::TabItem,
-        "toolbar" => ControlType::ToolBar,
-        "appbar" => ControlType::AppBar,
-        "calendar" => ControlType::Calendar,
-        "edit" => ControlType::Edit,
-        "hyperlink" => ControlType::Hyperlink,
-        "progressbar" => ControlType::ProgressBar,
-        "radiobutton" => ControlType::RadioButton,
-        "scrollbar" => ControlType::ScrollBar,
-        "slider" => ControlType::Slider,
-        "spinner" => ControlType::Spinner,
-        "statusbar" => ControlType::StatusBar,
-        "tooltip" => ControlType::ToolTip,
-        "custom" => ControlType::Custom,
-        "group" => ControlType::Group,
-        "thumb" => ControlType::Thumb,
-        "document" => ControlType::Document,
-        "splitbutton" => ControlType::SplitButton,
-        "header" => ControlType::Header,
-        "headeritem" => ControlType::HeaderItem,
-        "table" => ControlType::Table,
-        "titlebar" => ControlType::TitleBar,
-        "separator" => ControlType::Separator,
-        "semanticzoom" => ControlType::SemanticZoom,
-        _ => ControlType::Custom, // keep as it is for unknown roles
+    match role.parse::<Role>() {
+        Ok(role) => role_to_control_type(role),
+        // UIA-only control types without a canonical role
+        Err(_) => match role.to_lowercase().as_str() {
+            "appbar" => ControlType::AppBar,
+            "thumb" => ControlType::Thumb,
+            "semanticzoom" => ControlType::SemanticZoom,
+            _ => ControlType::Custom, // keep as it is for unknown roles
+        },
+    }
+}
+
+fn role_to_control_type(role: Role) -> ControlType {
+    match role {
+        Role::Application | Role::Pane => ControlType::Pane,
+        Role::Window | Role::Dialog => ControlType::Window,
+        Role::Group => ControlType::Group,
+        Role::Button => ControlType::Button,
+        Role::SplitButton => ControlType::SplitButton,
+        Role::CheckBox => ControlType::CheckBox,
+        Role::RadioButton => ControlType::RadioButton,
+        Role::Edit => ControlType::Edit,
+        Role::Text => ControlType::Text,
+        Role::Hyperlink => ControlType::Hyperlink,
+        Role::Image => ControlType::Image,
+        Role::List => ControlType::List,
+        Role::ListItem => ControlType::ListItem,
+        Role::Tree => ControlType::Tree,
+        Role::TreeItem => ControlType::TreeItem,
+        Role::Table => ControlType::Table,
+        Role::DataGrid => ControlType::DataGrid,
+        Role::DataItem => ControlType::DataItem,
+        Role::Header => ControlType::Header,
+        Role::HeaderItem => ControlType::HeaderItem,
+        Role::ComboBox => ControlType::ComboBox,
+        Role::Menu => ControlType::Menu,
+        Role::MenuBar => ControlType::MenuBar,
+        Role::MenuItem => ControlType::MenuItem,
+        Role::Tab => ControlType::Tab,
+        Role::TabItem => ControlType::TabItem,
+        Role::ToolBar => ControlType::ToolBar,
+        Role::StatusBar => ControlType::StatusBar,
+        Role::TitleBar => ControlType::TitleBar,
+        Role::ProgressBar => ControlType::ProgressBar,
+        Role::ScrollBar => ControlType::ScrollBar,
+        Role::Slider => ControlType::Slider,
+        Role::Spinner => ControlType::Spinner,
+        Role::ToolTip => ControlType::ToolTip,
+        Role::Separator => ControlType::Separator,
+        Role::Calendar => ControlType::Calendar,
+        Role::Document => ControlType::Document,
+        Role::Unknown => ControlType::Custom,
+    }
+}
+
+// Visible, unowned top-level windows in z-order, topmost first
+fn visible_top_level_windows() -> Vec<windows::Win32::Foundation::HWND> {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GW_HWNDNEXT, GW_OWNER, GetTopWindow, GetWindow, IsWindowVisible,
+    };
+
+    let mut windows = Vec::new();
+    // GetTopWindow/GetWindow walk the desktop's children in z-order
+    let mut next = unsafe { GetTopWindow(None) }.ok();
+    while let Some(hwnd) = next {
+        next = unsafe { GetWindow(hwnd, GW_HWNDNEXT) }.ok();
+
+        let visible = unsafe { IsWindowVisible(hwnd) }.as_bool();
+        let owned = unsafe { GetWindow(hwnd, GW_OWNER) }.is_ok();
+        if visible && !owned {
+            windows.push(hwnd);
+        }
+    }
+    windows
+}
+
+// Device name (e.g. `\\.\DISPLAY1`) of the monitor a window is mostly on
+fn monitor_name_for_window(hwnd: windows::Win32::Foundation::HWND) -> Option<String> {
+    use windows::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MONITOR_DEFAULTTONEAREST, MONITORINFO, MONITORINFOEXW, MonitorFromWindow,
+    };
+
+    unsafe {
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if !GetMonitorInfoW(monitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO).as_bool() {
+            return None;
+        }
+        let len = info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len());
+        Some(String::from_utf16_lossy(&info.szDevice[..len]))
     }
 }
 
+// Map a UI Automation property name, e.g. `AutomationId`, to the property
+fn uia_property(name: &str) -> Result<UIProperty, AutomationError> {
+    let property = match name.to_lowercase().as_str() {
+        "automationid" => UIProperty::AutomationId,
+        "classname" => UIProperty::ClassName,
+        "name" => UIProperty::Name,
+        "helptext" => UIProperty::HelpText,
+        "frameworkid" => UIProperty::FrameworkId,
+        "processid" => UIProperty::ProcessId,
+        "nativewindowhandle" => UIProperty::NativeWindowHandle,
+        "controltype" => UIProperty::ControlType,
+        "localizedcontroltype" => UIProperty::LocalizedControlType,
+        "acceleratorkey" => UIProperty::AcceleratorKey,
+        "accesskey" => UIProperty::AccessKey,
+        "itemtype" => UIProperty::ItemType,
+        "itemstatus" => UIProperty::ItemStatus,
+        "isenabled" => UIProperty::IsEnabled,
+        "isoffscreen" => UIProperty::IsOffscreen,
+        "iskeyboardfocusable" => UIProperty::IsKeyboardFocusable,
+        "haskeyboardfocus" => UIProperty::HasKeyboardFocus,
+        "ispassword" => UIProperty::IsPassword,
+        "valuevalue" | "value" => UIProperty::ValueValue,
+        "valueisreadonly" => UIProperty::ValueIsReadOnly,
+        "fulldescription" => UIProperty::FullDescription,
+        "ariarole" => UIProperty::AriaRole,
+        "ariaproperties" => UIProperty::AriaProperties,
+        _ => {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Unknown UI Automation property: '{}'",
+                name
+            )));
+        }
+    };
+    Ok(property)
+}
+
+// A property of `element` as text, `None` if the element doesn't support it
+fn uia_attribute(element: &uiautomation::UIElement, property: UIProperty) -> Option<String> {
+    let value = element.get_property_value(property).ok()?;
+    Some(value.get_string().unwrap_or_else(|_| value.to_string()))
+}
+
+// Map a key name to its Windows virtual-key code
+fn virtual_key_code(key: &str) -> Option<i32> {
+    let key = key.to_lowercase();
+    let code = match key.as_str() {
+        "escape" | "esc" => 0x1B,
+        "enter" | "return" => 0x0D,
+        "tab" => 0x09,
+        "space" => 0x20,
+        "backspace" => 0x08,
+        "delete" | "del" => 0x2E,
+        "shift" => 0x10,
+        "ctrl" | "control" => 0x11,
+        "alt" => 0x12,
+        "win" | "windows" => 0x5B,
+        "up" => 0x26,
+        "down" => 0x28,
+        "left" => 0x25,
+        "right" => 0x27,
+        "home" => 0x24,
+        "end" => 0x23,
+        "page_up" | "pageup" => 0x21,
+        "page_down" | "pagedown" => 0x22,
+        _ if key.len() > 1 && key.starts_with('f') => {
+            // F1..F24 are contiguous starting at 0x70
+            let n: i32 = key[1..].parse().ok()?;
+            if (1..=24).contains(&n) { 0x70 + n - 1 } else { return None; }
+        }
+        _ if key.len() == 1 => {
+            let c = key.chars().next()?.to_ascii_uppercase();
+            if c.is_ascii_alphanumeric() { c as i32 } else { return None; }
+        }
+        _ => return None,
+    };
+    Some(code)
+}
+
 fn get_pid_by_name(name: &str) -> Option<i32> {
     // window title shouldn't be empty
     let command = format!(
@@ -2312,26 +3833,26 @@ fn generate_element_id(element: &uiautomation::UIElement) -> Result<usize, Autom
     // Try cached versions first, fallback to live versions
     let control_type = element.get_cached_control_type()
         .or_else(|_| element.get_control_type())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get control type: {}", e)))?;
+        .map_err(|e| AutomationError::platform(format!("Failed to get control type: {}", e)))?;
     let name = element.get_cached_name()
         .or_else(|_| element.get_name())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get name: {}", e)))?;
+        .map_err(|e| AutomationError::platform(format!("Failed to get name: {}", e)))?;
     let automation_id = element.get_cached_automation_id()
         .or_else(|_| element.get_automation_id())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get automation ID: {}", e)))?;
+        .map_err(|e| AutomationError::platform(format!("Failed to get automation ID: {}", e)))?;
     let class_name = element.get_cached_classname()
         .or_else(|_| element.get_classname())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get classname: {}", e)))?;
+        .map_err(|e| AutomationError::platform(format!("Failed to get classname: {}", e)))?;
     let bounds = element.get_cached_bounding_rectangle()
         .or_else(|_| element.get_bounding_rectangle())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get bounding rectangle: {}", e)))?;
+        .map_err(|e| AutomationError::platform(format!("Failed to get bounding rectangle: {}", e)))?;
     // runtime_id is fundamental and less likely to have a distinct cached vs. live fetch issue here
     // It's usually retrieved when the element handle is obtained.
     let runtime_id = element.get_runtime_id()
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get runtime ID: {}", e)))?;
+        .map_err(|e| AutomationError::platform(format!("Failed to get runtime ID: {}", e)))?;
     let help_text = element.get_cached_help_text()
         .or_else(|_| element.get_help_text())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get help text: {}", e)))?;
+        .map_err(|e| AutomationError::platform(format!("Failed to get help text: {}", e)))?;
 
     // Create a stable string representation
     let id_string = format!(
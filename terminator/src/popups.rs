@@ -0,0 +1,130 @@
+//! Background handlers for popups that interrupt unattended runs
+//!
+//! Update prompts, license reminders and "tip of the day" dialogs appear at
+//! random and steal focus from whatever the workflow was doing. Rules
+//! registered with [`crate::Desktop::add_popup_rule`] are checked in the
+//! background, and a matching popup is dismissed as soon as it shows up.
+
+use crate::{AutomationError, Locator, Selector, UIElement};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+// How often the rules are checked
+const POPUP_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// How long each check searches for a popup or the control inside it
+const POPUP_SEARCH_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// A closure run on a popup by [`PopupAction::Custom`]
+type CustomHandler = Arc<dyn Fn(&UIElement) -> Result<(), AutomationError> + Send + Sync>;
+
+/// What to do with a popup once its rule's locator matches it
+#[derive(Clone)]
+pub enum PopupAction {
+    /// Click the element matching this selector inside the popup, e.g. its "Later" button
+    Click(Selector),
+    /// Press a key on the popup, e.g. `{esc}`
+    PressKey(String),
+    /// Run a closure on the popup element
+    Custom(CustomHandler),
+}
+
+impl fmt::Debug for PopupAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PopupAction::Click(selector) => f.debug_tuple("Click").field(selector).finish(),
+            PopupAction::PressKey(key) => f.debug_tuple("PressKey").field(key).finish(),
+            PopupAction::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct PopupRule {
+    name: String,
+    trigger: Locator,
+    action: PopupAction,
+}
+
+impl PopupRule {
+    /// Handle the popup if it is showing; returns whether it was
+    fn apply(&self) -> Result<bool, AutomationError> {
+        let popup = match self.trigger.find_once(POPUP_SEARCH_TIMEOUT) {
+            Ok(popup) => popup,
+            Err(e) if e.is_retryable() => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        match &self.action {
+            PopupAction::Click(selector) => {
+                self.trigger
+                    .locator(selector.clone())
+                    .find_once(POPUP_SEARCH_TIMEOUT)?
+                    .click()?;
+            }
+            PopupAction::PressKey(key) => popup.press_key(key)?,
+            PopupAction::Custom(action) => action(&popup)?,
+        }
+        Ok(true)
+    }
+}
+
+/// Checks the registered popup rules in the background. Stops when dropped.
+pub(crate) struct PopupHandler {
+    rules: Arc<Mutex<Vec<PopupRule>>>,
+    task: JoinHandle<()>,
+}
+
+impl PopupHandler {
+    pub fn spawn() -> Self {
+        let rules: Arc<Mutex<Vec<PopupRule>>> = Arc::new(Mutex::new(Vec::new()));
+        let watched = rules.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                // Leave the screen alone while the kill switch is engaged
+                if !crate::abort::is_aborted() {
+                    let rules = watched.lock().unwrap().clone();
+                    for rule in &rules {
+                        match rule.apply() {
+                            Ok(true) => info!(rule = %rule.name, action = ?rule.action, "Popup handled"),
+                            Ok(false) => {}
+                            Err(e) => warn!(rule = %rule.name, error = %e, "Failed to handle popup"),
+                        }
+                    }
+                }
+                tokio::time::sleep(POPUP_POLL_INTERVAL).await;
+            }
+        });
+        Self { rules, task }
+    }
+
+    /// Add a rule, replacing any rule with the same name
+    pub fn add(&self, name: &str, trigger: Locator, action: PopupAction) {
+        let mut rules = self.rules.lock().unwrap();
+        rules.retain(|rule| rule.name != name);
+        rules.push(PopupRule {
+            name: name.to_string(),
+            trigger,
+            action,
+        });
+    }
+
+    /// Remove the rule called `name`; returns whether there was one
+    pub fn remove(&self, name: &str) -> bool {
+        let mut rules = self.rules.lock().unwrap();
+        let count = rules.len();
+        rules.retain(|rule| rule.name != name);
+        rules.len() < count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.lock().unwrap().is_empty()
+    }
+}
+
+impl Drop for PopupHandler {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,83 @@
+//! Keeping the machine awake during long unattended runs
+//!
+//! Display sleep, screensavers and idle lock all break UI automation, so
+//! [`crate::Desktop::inhibit_sleep`] holds a platform power assertion for as
+//! long as it is enabled.
+
+use crate::AutomationError;
+
+/// Holds the platform's keep-awake assertion until dropped
+pub(crate) struct SleepInhibitor {
+    #[cfg(target_os = "windows")]
+    release: std::sync::mpsc::Sender<()>,
+    #[cfg(not(target_os = "windows"))]
+    child: std::process::Child,
+}
+
+impl SleepInhibitor {
+    /// Execution state is per-thread on Windows, so a dedicated thread holds it
+    #[cfg(target_os = "windows")]
+    pub(crate) fn acquire() -> Result<Self, AutomationError> {
+        use windows::Win32::System::Power::{
+            ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED, SetThreadExecutionState,
+        };
+
+        let (release, released) = std::sync::mpsc::channel::<()>();
+        let (acquired, result) = std::sync::mpsc::channel::<bool>();
+        std::thread::Builder::new()
+            .name("sleep-inhibitor".to_string())
+            .spawn(move || {
+                let previous = unsafe {
+                    SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED)
+                };
+                let _ = acquired.send(previous.0 != 0);
+                // Blocks until the inhibitor is dropped
+                let _ = released.recv();
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+            })
+            .map_err(|e| AutomationError::platform_with_source("Failed to start sleep inhibitor", e))?;
+
+        match result.recv() {
+            Ok(true) => Ok(Self { release }),
+            _ => Err(AutomationError::platform("SetThreadExecutionState failed")),
+        }
+    }
+
+    /// `caffeinate` holds the IOKit assertions for us and exits with our process
+    #[cfg(target_os = "macos")]
+    pub(crate) fn acquire() -> Result<Self, AutomationError> {
+        let child = std::process::Command::new("caffeinate")
+            .args(["-d", "-i", "-s", "-u", "-w", &std::process::id().to_string()])
+            .spawn()
+            .map_err(|e| AutomationError::platform_with_source("Failed to run caffeinate", e))?;
+        Ok(Self { child })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn acquire() -> Result<Self, AutomationError> {
+        let child = std::process::Command::new("systemd-inhibit")
+            .args([
+                "--what=idle:sleep",
+                "--who=terminator",
+                "--why=UI automation in progress",
+                "--mode=block",
+                "sleep",
+                "infinity",
+            ])
+            .spawn()
+            .map_err(|e| AutomationError::platform_with_source("Failed to run systemd-inhibit", e))?;
+        Ok(Self { child })
+    }
+}
+
+impl Drop for SleepInhibitor {
+    fn drop(&mut self) {
+        #[cfg(target_os = "windows")]
+        let _ = self.release.send(());
+        #[cfg(not(target_os = "windows"))]
+        {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Sampling the target application's CPU, memory and responsiveness
+//!
+//! A bot driving an app that has locked up will otherwise just time out on
+//! every locator. With a monitor attached (see
+//! [`crate::Desktop::monitor_process`]) the run can notice the hang and
+//! recover, e.g. by killing and relaunching the app.
+
+use crate::AutomationError;
+use crate::platforms::AccessibilityEngine;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Raw resource counters for a process, as read by the platform engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessSample {
+    /// Total CPU time (user + kernel) consumed since the process started
+    pub cpu_time: Duration,
+    /// Resident memory (working set)
+    pub memory_bytes: u64,
+    /// Whether all of the process's windows are processing messages
+    pub responding: bool,
+}
+
+/// A running process, as listed by [`crate::Desktop::processes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// The process that started this one, if known
+    pub parent_pid: Option<u32>,
+    /// Executable name, e.g. `notepad.exe`
+    pub name: String,
+    /// Number of visible top-level windows the process owns
+    pub window_count: usize,
+}
+
+/// Resource usage of a monitored process at the last sample
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessStats {
+    pub pid: u32,
+    /// CPU usage over the last sampling interval, as a percentage of all cores
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub responding: bool,
+    /// Peak memory seen since monitoring started
+    pub peak_memory_bytes: u64,
+    pub sampled_at: SystemTime,
+}
+
+/// A notable change in the state of a monitored process
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessEvent {
+    /// The process stopped processing window messages (it is hung)
+    Unresponsive(ProcessStats),
+    /// A previously hung process is processing messages again
+    Responsive(ProcessStats),
+    /// The process is no longer running; monitoring stops
+    Exited { pid: u32 },
+}
+
+/// Receives [`ProcessEvent`]s from a monitor started with [`crate::Desktop::monitor_process`]
+pub struct ProcessEvents {
+    receiver: mpsc::UnboundedReceiver<ProcessEvent>,
+}
+
+impl ProcessEvents {
+    /// Wait for the next event. Returns `None` once monitoring has stopped.
+    pub async fn next(&mut self) -> Option<ProcessEvent> {
+        self.receiver.recv().await
+    }
+
+    /// Return a pending event without waiting, if there is one
+    pub fn try_next(&mut self) -> Option<ProcessEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Background sampling task; stops when dropped
+pub(crate) struct ProcessMonitor {
+    stats: Arc<Mutex<Option<ProcessStats>>>,
+    task: JoinHandle<()>,
+}
+
+impl ProcessMonitor {
+    pub(crate) fn spawn(
+        engine: Arc<dyn AccessibilityEngine>,
+        pid: u32,
+        interval: Duration,
+    ) -> Result<(Self, ProcessEvents), AutomationError> {
+        // Fail early if the process can't be sampled at all
+        let first = engine.get_process_sample(pid)?;
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get()) as f64;
+
+        let stats = Arc::new(Mutex::new(None));
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let shared = stats.clone();
+
+        let task = tokio::spawn(async move {
+            let mut previous = (first, Instant::now());
+            let mut peak_memory_bytes = first.memory_bytes;
+            let mut was_responding = first.responding;
+
+            loop {
+                tokio::time::sleep(interval).await;
+                let sample = match engine.get_process_sample(pid) {
+                    Ok(sample) => sample,
+                    Err(e) if matches!(e.inner(), AutomationError::ElementNotFound(_)) => {
+                        info!(pid, "Monitored process exited");
+                        let _ = sender.send(ProcessEvent::Exited { pid });
+                        return;
+                    }
+                    Err(e) => {
+                        warn!(pid, error = %e, "Failed to sample process");
+                        continue;
+                    }
+                };
+
+                let now = Instant::now();
+                let cpu = sample.cpu_time.saturating_sub(previous.0.cpu_time).as_secs_f64();
+                let wall = now.duration_since(previous.1).as_secs_f64().max(f64::EPSILON);
+                peak_memory_bytes = peak_memory_bytes.max(sample.memory_bytes);
+                let current = ProcessStats {
+                    pid,
+                    cpu_percent: (cpu / wall / cores * 100.0) as f32,
+                    memory_bytes: sample.memory_bytes,
+                    responding: sample.responding,
+                    peak_memory_bytes,
+                    sampled_at: SystemTime::now(),
+                };
+                previous = (sample, now);
+
+                let event = match (was_responding, sample.responding) {
+                    (true, false) => Some(ProcessEvent::Unresponsive(current.clone())),
+                    (false, true) => Some(ProcessEvent::Responsive(current.clone())),
+                    _ => None,
+                };
+                was_responding = sample.responding;
+                if let Ok(mut stats) = shared.lock() {
+                    *stats = Some(current);
+                }
+                if let Some(event) = event {
+                    warn!(?event, "Monitored process responsiveness changed");
+                    // Keep sampling for `stats()` even if nobody listens for events
+                    let _ = sender.send(event);
+                }
+            }
+        });
+
+        Ok((Self { stats, task }, ProcessEvents { receiver }))
+    }
+
+    /// Stats from the most recent sample, `None` until the first interval has elapsed
+    pub(crate) fn stats(&self) -> Option<ProcessStats> {
+        self.stats.lock().ok().and_then(|stats| stats.clone())
+    }
+}
+
+impl Drop for ProcessMonitor {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,77 @@
+//! Reading how far along a progress bar is
+//!
+//! Progress is exposed in different ways depending on the toolkit: a native
+//! range value, ARIA `valuenow`/`valuemax` properties on web content, a value
+//! text like `45%`, or only pixels for custom-drawn bars with a caption.
+//! [`read_progress`] tries them in that order.
+
+use crate::platforms::AccessibilityEngine;
+use crate::region::ScopeCapture;
+use crate::value::parse_number;
+use crate::{AutomationError, OcrWord, UIElement};
+
+/// How far along `element` is, from 0.0 to 1.0, or `None` if it doesn't show it.
+/// OCR of the element's area is the last resort and needs `engine`.
+pub(crate) fn read_progress(
+    element: &UIElement,
+    engine: Option<&dyn AccessibilityEngine>,
+) -> Result<Option<f64>, AutomationError> {
+    match element.range_value() {
+        Ok(range) => {
+            if let Some(fraction) = range.fraction() {
+                return Ok(Some(fraction));
+            }
+        }
+        Err(e) if !matches!(e.inner(), AutomationError::UnsupportedOperation(_)) => return Err(e),
+        Err(_) => {}
+    }
+
+    if let Some(fraction) = engine.and_then(|engine| aria_progress(engine, element)) {
+        return Ok(Some(fraction));
+    }
+
+    if let Ok(shown) = element.value_with_unit()
+        && shown.unit.as_deref() == Some("%")
+    {
+        return Ok(Some(shown.value / 100.0));
+    }
+
+    let Some(engine) = engine else {
+        return Ok(None);
+    };
+    let capture = ScopeCapture::new(engine, Some(element))?;
+    match engine.ocr_words(&capture.image) {
+        Ok(words) => Ok(percentage_in(&words)),
+        Err(e) if matches!(e.inner(), AutomationError::UnsupportedOperation(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Progress from ARIA properties like `valuenow=40;valuemin=0;valuemax=100`, as UI
+/// Automation reports them for web content
+fn aria_progress(engine: &dyn AccessibilityEngine, element: &UIElement) -> Option<f64> {
+    let aria = engine.get_attribute(element, "AriaProperties").ok()??;
+    let property = |name: &str| {
+        aria.split(';').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key.trim() == name).then(|| value.trim().parse::<f64>().ok())?
+        })
+    };
+    let now = property("valuenow")?;
+    let min = property("valuemin").unwrap_or(0.0);
+    let max = property("valuemax").unwrap_or(100.0);
+    (max > min).then(|| (now - min) / (max - min))
+}
+
+/// The first percentage among recognized words, reading `45%` and `45 %` alike
+fn percentage_in(words: &[OcrWord]) -> Option<f64> {
+    words.iter().enumerate().find_map(|(i, word)| {
+        let text = if word.text == "%" {
+            format!("{}%", words.get(i.checked_sub(1)?)?.text)
+        } else {
+            word.text.clone()
+        };
+        let number = parse_number(&text)?;
+        (number.unit.as_deref() == Some("%")).then(|| number.value / 100.0)
+    })
+}
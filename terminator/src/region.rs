@@ -0,0 +1,258 @@
+//! Elements for screen areas found by looking at pixels
+//!
+//! Image and OCR selectors find things that have no accessibility node. They
+//! capture the search scope, look for what they want in the pixels and wrap
+//! each hit in a [`ScreenRegionElement`], which supports what can be done
+//! with bounds alone: mouse input at them and reading them back.
+
+use crate::element::UIElementImpl;
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, ClickResult, Locator, Role, Selector, UIElement, UIElementAttributes};
+use image::RgbaImage;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// A screenshot of a selector's search scope: `root`'s bounds, or the primary monitor
+/// without a root
+pub(crate) struct ScopeCapture {
+    pub image: RgbaImage,
+    x: i32,
+    y: i32,
+    /// Image pixels per screen point; frames can be captured at a higher density
+    scale: f64,
+}
+
+impl ScopeCapture {
+    pub fn new(engine: &dyn AccessibilityEngine, root: Option<&UIElement>) -> Result<Self, AutomationError> {
+        let (x, y, width, height) = match root {
+            Some(root) => {
+                let (x, y, width, height) = root.bounds()?;
+                (x.round() as i32, y.round() as i32, width.round() as u32, height.round() as u32)
+            }
+            None => {
+                let primary = engine
+                    .list_monitors()?
+                    .into_iter()
+                    .find(|m| m.is_primary)
+                    .ok_or_else(|| AutomationError::platform("Could not find primary monitor"))?;
+                (primary.x, primary.y, primary.width, primary.height)
+            }
+        };
+        let image = crate::screen::capture_region(x, y, width, height)?;
+        let scale = image.width() as f64 / width.max(1) as f64;
+        Ok(Self { image, x, y, scale })
+    }
+
+    /// Convert an area of the captured image to screen coordinates
+    pub fn to_screen(&self, x: f64, y: f64, width: f64, height: f64) -> (f64, f64, f64, f64) {
+        (
+            self.x as f64 + x / self.scale,
+            self.y as f64 + y / self.scale,
+            width / self.scale,
+            height / self.scale,
+        )
+    }
+}
+
+/// A screen area with no accessibility node behind it
+#[derive(Debug, Clone)]
+pub(crate) struct ScreenRegionElement {
+    bounds: (f64, f64, f64, f64),
+    role: Role,
+    /// Text shown in the area, if known
+    name: Option<String>,
+    properties: HashMap<String, Option<serde_json::Value>>,
+    /// Mouse input is sent through the desktop root element
+    desktop: UIElement,
+}
+
+impl ScreenRegionElement {
+    pub fn new(engine: &dyn AccessibilityEngine, bounds: (f64, f64, f64, f64), role: Role) -> Self {
+        Self {
+            bounds,
+            role,
+            name: None,
+            properties: HashMap::new(),
+            desktop: engine.get_root_element(),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_property(mut self, key: &str, value: serde_json::Value) -> Self {
+        self.properties.insert(key.to_string(), Some(value));
+        self
+    }
+
+    pub fn into_element(self) -> UIElement {
+        UIElement::new(Box::new(self))
+    }
+
+    fn center(&self) -> (f64, f64) {
+        let (x, y, width, height) = self.bounds;
+        (x + width / 2.0, y + height / 2.0)
+    }
+
+    fn unsupported<T>(&self, operation: &str) -> Result<T, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(format!(
+            "{} is not supported on a screen region found by {} matching",
+            operation,
+            if self.role == Role::Image { "image" } else { "text" }
+        )))
+    }
+}
+
+impl UIElementImpl for ScreenRegionElement {
+    fn object_id(&self) -> usize {
+        let mut hasher = DefaultHasher::new();
+        let (x, y, width, height) = self.bounds;
+        [x, y, width, height].map(f64::to_bits).hash(&mut hasher);
+        self.name.hash(&mut hasher);
+        hasher.finish() as usize
+    }
+
+    fn id(&self) -> Option<String> {
+        None
+    }
+
+    fn role(&self) -> String {
+        self.role.as_str().to_string()
+    }
+
+    fn attributes(&self) -> UIElementAttributes {
+        UIElementAttributes {
+            role: self.role(),
+            normalized_role: self.role,
+            name: self.name.clone(),
+            properties: self.properties.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    fn children(&self) -> Result<Vec<UIElement>, AutomationError> {
+        Ok(Vec::new())
+    }
+
+    fn parent(&self) -> Result<Option<UIElement>, AutomationError> {
+        Ok(None)
+    }
+
+    fn bounds(&self) -> Result<(f64, f64, f64, f64), AutomationError> {
+        Ok(self.bounds)
+    }
+
+    fn click(&self) -> Result<ClickResult, AutomationError> {
+        let (x, y) = self.center();
+        self.desktop.mouse_click_and_hold(x, y)?;
+        self.desktop.mouse_release()?;
+        Ok(ClickResult {
+            method: "Screen Region Click".to_string(),
+            coordinates: Some((x, y)),
+            details: format!("Clicked center of {:?}", self.bounds),
+            context: self.desktop.action_context(),
+        })
+    }
+
+    fn double_click(&self) -> Result<ClickResult, AutomationError> {
+        self.click()?;
+        let mut result = self.click()?;
+        result.method = "Screen Region Double Click".to_string();
+        Ok(result)
+    }
+
+    fn right_click(&self) -> Result<ClickResult, AutomationError> {
+        self.unsupported("right_click")
+    }
+
+    fn hover(&self) -> Result<(), AutomationError> {
+        let (x, y) = self.center();
+        self.desktop.mouse_move(x, y)
+    }
+
+    fn focus(&self) -> Result<(), AutomationError> {
+        self.unsupported("focus")
+    }
+
+    fn type_text(&self, _text: &str, _use_clipboard: bool) -> Result<(), AutomationError> {
+        self.unsupported("type_text")
+    }
+
+    fn press_key(&self, _key: &str) -> Result<(), AutomationError> {
+        self.unsupported("press_key")
+    }
+
+    fn get_text(&self, _max_depth: usize) -> Result<String, AutomationError> {
+        Ok(self.name.clone().unwrap_or_default())
+    }
+
+    fn set_value(&self, _value: &str) -> Result<(), AutomationError> {
+        self.unsupported("set_value")
+    }
+
+    fn is_enabled(&self) -> Result<bool, AutomationError> {
+        Ok(true)
+    }
+
+    fn is_visible(&self) -> Result<bool, AutomationError> {
+        Ok(true)
+    }
+
+    fn is_focused(&self) -> Result<bool, AutomationError> {
+        Ok(false)
+    }
+
+    fn perform_action(&self, action: &str) -> Result<(), AutomationError> {
+        self.unsupported(action)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn create_locator(&self, _selector: Selector) -> Result<Locator, AutomationError> {
+        self.unsupported("create_locator")
+    }
+
+    fn scroll(&self, _direction: &str, _amount: f64) -> Result<(), AutomationError> {
+        self.unsupported("scroll")
+    }
+
+    fn activate_window(&self) -> Result<(), AutomationError> {
+        self.unsupported("activate_window")
+    }
+
+    fn clone_box(&self) -> Box<dyn UIElementImpl> {
+        Box::new(self.clone())
+    }
+
+    fn is_keyboard_focusable(&self) -> Result<bool, AutomationError> {
+        Ok(false)
+    }
+
+    fn mouse_drag(&self, start_x: f64, start_y: f64, end_x: f64, end_y: f64) -> Result<(), AutomationError> {
+        self.desktop.mouse_drag(start_x, start_y, end_x, end_y)
+    }
+
+    fn mouse_click_and_hold(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        self.desktop.mouse_click_and_hold(x, y)
+    }
+
+    fn mouse_move(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        self.desktop.mouse_move(x, y)
+    }
+
+    fn mouse_release(&self) -> Result<(), AutomationError> {
+        self.desktop.mouse_release()
+    }
+
+    fn mouse_wheel(&self, x: f64, y: f64, delta_x: i32, delta_y: i32) -> Result<(), AutomationError> {
+        self.desktop.mouse_wheel(x, y, delta_x, delta_y)
+    }
+}
@@ -0,0 +1,310 @@
+//! Platform-independent element roles
+//!
+//! UI Automation control types, macOS AX roles and AT-SPI roles all name the
+//! same handful of widgets differently. [`Role`] is the shared vocabulary:
+//! `role:button` in a selector is parsed into it, and each engine maps it to
+//! (and from) its own role names.
+
+use crate::AutomationError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Canonical role of a UI element, the same on every platform
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Role {
+    Application,
+    Window,
+    Dialog,
+    Pane,
+    Group,
+    Button,
+    SplitButton,
+    CheckBox,
+    RadioButton,
+    /// Editable text: text fields, text areas, search and address fields
+    Edit,
+    /// Static, non-editable text
+    Text,
+    Hyperlink,
+    Image,
+    List,
+    ListItem,
+    Tree,
+    TreeItem,
+    Table,
+    DataGrid,
+    /// A row or cell of a table or grid
+    DataItem,
+    Header,
+    HeaderItem,
+    ComboBox,
+    Menu,
+    MenuBar,
+    MenuItem,
+    /// A tab strip
+    Tab,
+    TabItem,
+    ToolBar,
+    StatusBar,
+    TitleBar,
+    ProgressBar,
+    ScrollBar,
+    Slider,
+    Spinner,
+    ToolTip,
+    Separator,
+    Calendar,
+    Document,
+    /// A platform role with no canonical equivalent
+    #[default]
+    Unknown,
+}
+
+impl Role {
+    /// Canonical lowercase name, as accepted by `role:` selectors
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Application => "application",
+            Role::Window => "window",
+            Role::Dialog => "dialog",
+            Role::Pane => "pane",
+            Role::Group => "group",
+            Role::Button => "button",
+            Role::SplitButton => "splitbutton",
+            Role::CheckBox => "checkbox",
+            Role::RadioButton => "radiobutton",
+            Role::Edit => "edit",
+            Role::Text => "text",
+            Role::Hyperlink => "hyperlink",
+            Role::Image => "image",
+            Role::List => "list",
+            Role::ListItem => "listitem",
+            Role::Tree => "tree",
+            Role::TreeItem => "treeitem",
+            Role::Table => "table",
+            Role::DataGrid => "datagrid",
+            Role::DataItem => "dataitem",
+            Role::Header => "header",
+            Role::HeaderItem => "headeritem",
+            Role::ComboBox => "combobox",
+            Role::Menu => "menu",
+            Role::MenuBar => "menubar",
+            Role::MenuItem => "menuitem",
+            Role::Tab => "tab",
+            Role::TabItem => "tabitem",
+            Role::ToolBar => "toolbar",
+            Role::StatusBar => "statusbar",
+            Role::TitleBar => "titlebar",
+            Role::ProgressBar => "progressbar",
+            Role::ScrollBar => "scrollbar",
+            Role::Slider => "slider",
+            Role::Spinner => "spinner",
+            Role::ToolTip => "tooltip",
+            Role::Separator => "separator",
+            Role::Calendar => "calendar",
+            Role::Document => "document",
+            Role::Unknown => "unknown",
+        }
+    }
+
+    /// Map a native role name of the current platform to its canonical role
+    pub fn from_platform_role(name: &str) -> Role {
+        #[cfg(target_os = "windows")]
+        return Role::from_uia_control_type(name);
+        #[cfg(target_os = "macos")]
+        return Role::from_ax_role(name);
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        return Role::from_atspi_role(name);
+    }
+
+    /// Map a UI Automation control type name (e.g. `Button`, `ListItem`)
+    pub fn from_uia_control_type(name: &str) -> Role {
+        match name.trim_end_matches("ControlType") {
+            "Window" => Role::Window,
+            "Pane" => Role::Pane,
+            "Group" => Role::Group,
+            "Button" => Role::Button,
+            "SplitButton" => Role::SplitButton,
+            "CheckBox" => Role::CheckBox,
+            "RadioButton" => Role::RadioButton,
+            "Edit" => Role::Edit,
+            "Text" => Role::Text,
+            "Hyperlink" => Role::Hyperlink,
+            "Image" => Role::Image,
+            "List" => Role::List,
+            "ListItem" => Role::ListItem,
+            "Tree" => Role::Tree,
+            "TreeItem" => Role::TreeItem,
+            "Table" => Role::Table,
+            "DataGrid" => Role::DataGrid,
+            "DataItem" => Role::DataItem,
+            "Header" => Role::Header,
+            "HeaderItem" => Role::HeaderItem,
+            "ComboBox" => Role::ComboBox,
+            "Menu" => Role::Menu,
+            "MenuBar" => Role::MenuBar,
+            "MenuItem" => Role::MenuItem,
+            "Tab" => Role::Tab,
+            "TabItem" => Role::TabItem,
+            "ToolBar" | "AppBar" => Role::ToolBar,
+            "StatusBar" => Role::StatusBar,
+            "TitleBar" => Role::TitleBar,
+            "ProgressBar" => Role::ProgressBar,
+            "ScrollBar" => Role::ScrollBar,
+            "Slider" => Role::Slider,
+            "Spinner" => Role::Spinner,
+            "ToolTip" => Role::ToolTip,
+            "Separator" => Role::Separator,
+            "Calendar" => Role::Calendar,
+            "Document" => Role::Document,
+            _ => Role::Unknown,
+        }
+    }
+
+    /// Map a macOS accessibility role (e.g. `AXButton`, `AXStaticText`)
+    pub fn from_ax_role(name: &str) -> Role {
+        match name {
+            "AXApplication" => Role::Application,
+            "AXWindow" => Role::Window,
+            "AXSheet" | "AXDialog" | "AXSystemDialog" => Role::Dialog,
+            "AXScrollArea" | "AXSplitGroup" => Role::Pane,
+            "AXGroup" | "AXRadioGroup" => Role::Group,
+            "AXButton" | "AXMenuButton" | "AXDisclosureTriangle" => Role::Button,
+            "AXCheckBox" => Role::CheckBox,
+            "AXRadioButton" => Role::RadioButton,
+            "AXTextField" | "AXTextArea" | "AXSearchField" | "AXSecureTextField" => Role::Edit,
+            "AXStaticText" => Role::Text,
+            "AXLink" => Role::Hyperlink,
+            "AXImage" => Role::Image,
+            "AXList" => Role::List,
+            "AXCell" => Role::ListItem,
+            "AXOutline" | "AXBrowser" => Role::Tree,
+            "AXTable" => Role::Table,
+            "AXGrid" => Role::DataGrid,
+            "AXRow" | "AXColumn" => Role::DataItem,
+            "AXComboBox" | "AXPopUpButton" => Role::ComboBox,
+            "AXMenu" => Role::Menu,
+            "AXMenuBar" => Role::MenuBar,
+            "AXMenuItem" | "AXMenuBarItem" => Role::MenuItem,
+            "AXTabGroup" => Role::Tab,
+            "AXToolbar" => Role::ToolBar,
+            "AXProgressIndicator" | "AXBusyIndicator" | "AXLevelIndicator" => Role::ProgressBar,
+            "AXScrollBar" => Role::ScrollBar,
+            "AXSlider" => Role::Slider,
+            "AXIncrementor" => Role::Spinner,
+            "AXHelpTag" => Role::ToolTip,
+            "AXSplitter" => Role::Separator,
+            "AXWebArea" => Role::Document,
+            _ => Role::Unknown,
+        }
+    }
+
+    /// Map an AT-SPI role name (e.g. `push button`, `page tab`)
+    pub fn from_atspi_role(name: &str) -> Role {
+        match name.to_lowercase().replace('_', " ").as_str() {
+            "application" => Role::Application,
+            "frame" | "window" => Role::Window,
+            "dialog" | "alert" | "file chooser" | "color chooser" => Role::Dialog,
+            "scroll pane" | "split pane" | "viewport" | "root pane" => Role::Pane,
+            "panel" | "filler" | "grouping" | "section" | "form" => Role::Group,
+            "push button" | "toggle button" | "button" => Role::Button,
+            "check box" => Role::CheckBox,
+            "radio button" => Role::RadioButton,
+            "entry" | "password text" | "text" | "editbar" => Role::Edit,
+            "label" | "static" | "caption" => Role::Text,
+            "link" => Role::Hyperlink,
+            "image" | "icon" => Role::Image,
+            "list" | "list box" => Role::List,
+            "list item" => Role::ListItem,
+            "tree" | "tree table" => Role::Tree,
+            "tree item" => Role::TreeItem,
+            "table" => Role::Table,
+            "table cell" | "table row" => Role::DataItem,
+            "header" => Role::Header,
+            "column header" | "row header" | "table column header" | "table row header" => {
+                Role::HeaderItem
+            }
+            "combo box" => Role::ComboBox,
+            "menu" | "popup menu" => Role::Menu,
+            "menu bar" => Role::MenuBar,
+            "menu item" | "check menu item" | "radio menu item" | "tearoff menu item" => {
+                Role::MenuItem
+            }
+            "page tab list" => Role::Tab,
+            "page tab" => Role::TabItem,
+            "tool bar" => Role::ToolBar,
+            "status bar" => Role::StatusBar,
+            "progress bar" | "level bar" => Role::ProgressBar,
+            "scroll bar" => Role::ScrollBar,
+            "slider" => Role::Slider,
+            "spin button" => Role::Spinner,
+            "tool tip" => Role::ToolTip,
+            "separator" => Role::Separator,
+            "calendar" => Role::Calendar,
+            "document frame" | "document web" | "document text" => Role::Document,
+            _ => Role::Unknown,
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = AutomationError;
+
+    /// Parse a canonical role name or one of its common aliases, case-insensitively
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let role = match s.trim().to_lowercase().as_str() {
+            "application" | "app" => Role::Application,
+            "window" => Role::Window,
+            "dialog" => Role::Dialog,
+            "pane" => Role::Pane,
+            "group" => Role::Group,
+            "button" => Role::Button,
+            "splitbutton" => Role::SplitButton,
+            "checkbox" => Role::CheckBox,
+            "radiobutton" | "radio" => Role::RadioButton,
+            "edit" | "textfield" | "textbox" | "input" | "url" | "urlfield" => Role::Edit,
+            "text" | "statictext" => Role::Text,
+            "hyperlink" | "link" => Role::Hyperlink,
+            "image" => Role::Image,
+            "list" => Role::List,
+            "listitem" => Role::ListItem,
+            "tree" => Role::Tree,
+            "treeitem" => Role::TreeItem,
+            "table" => Role::Table,
+            "datagrid" | "grid" => Role::DataGrid,
+            "dataitem" | "data" | "row" | "cell" => Role::DataItem,
+            "header" => Role::Header,
+            "headeritem" => Role::HeaderItem,
+            "combobox" => Role::ComboBox,
+            "menu" => Role::Menu,
+            "menubar" => Role::MenuBar,
+            "menuitem" => Role::MenuItem,
+            "tab" | "tablist" => Role::Tab,
+            "tabitem" => Role::TabItem,
+            "toolbar" => Role::ToolBar,
+            "statusbar" => Role::StatusBar,
+            "titlebar" | "title" => Role::TitleBar,
+            "progressbar" => Role::ProgressBar,
+            "scrollbar" => Role::ScrollBar,
+            "slider" => Role::Slider,
+            "spinner" => Role::Spinner,
+            "tooltip" => Role::ToolTip,
+            "separator" => Role::Separator,
+            "calendar" => Role::Calendar,
+            "document" => Role::Document,
+            _ => {
+                return Err(AutomationError::InvalidArgument(format!(
+                    "Unknown role '{}'",
+                    s
+                )));
+            }
+        };
+        Ok(role)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,106 @@
+//! Extracting records from repeated UI regions
+//!
+//! Search results, order lists and table rows repeat one layout per item. A
+//! [`ScrapeSpec`] names the selector matching each item and, per field, the
+//! selector finding the field inside it; [`crate::Locator::scrape`] turns
+//! every item of a container into a map of field name to text.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, Selector, UIElement};
+use std::collections::HashMap;
+use std::time::Duration;
+
+// How long each item search waits; items are read as they are rendered
+const ITEM_SEARCH_TIMEOUT: Duration = Duration::from_millis(500);
+// How long a field is looked for inside an item that is already on screen
+const FIELD_SEARCH_TIMEOUT: Duration = Duration::from_millis(100);
+// How deep to look for a field's text when it has no name or value
+const FIELD_TEXT_DEPTH: usize = 3;
+
+/// One scraped item: field name to text. Fields not found in the item are left out.
+pub type ScrapedRecord = HashMap<String, String>;
+
+/// What to extract with [`crate::Locator::scrape`]: the selector for each repeated item
+/// and the fields to read from it, e.g.
+/// `ScrapeSpec::new("role:listitem").field("title", "role:text:first").field("price", "role:text:last")`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrapeSpec {
+    item: Selector,
+    fields: Vec<(String, Selector)>,
+    max_scrolls: usize,
+}
+
+impl ScrapeSpec {
+    pub fn new(item: impl Into<Selector>) -> Self {
+        Self {
+            item: item.into(),
+            fields: Vec::new(),
+            max_scrolls: 0,
+        }
+    }
+
+    /// Read `name` from the first element matching `selector` inside each item
+    pub fn field(mut self, name: impl Into<String>, selector: impl Into<Selector>) -> Self {
+        self.fields.push((name.into(), selector.into()));
+        self
+    }
+
+    /// Scroll the container down a page at a time, up to `max_scrolls` times, to reach
+    /// items a virtualized list hasn't rendered yet. Stops early at the end of the list.
+    pub fn scroll(mut self, max_scrolls: usize) -> Self {
+        self.max_scrolls = max_scrolls;
+        self
+    }
+
+    pub(crate) fn max_scrolls(&self) -> usize {
+        self.max_scrolls
+    }
+
+    /// The records of the items currently rendered in `container`, in order
+    pub(crate) fn read_page(
+        &self,
+        engine: &dyn AccessibilityEngine,
+        container: &UIElement,
+    ) -> Result<Vec<ScrapedRecord>, AutomationError> {
+        let items = match engine.find_elements(&self.item, Some(container), Some(ITEM_SEARCH_TIMEOUT), None) {
+            Ok(items) => items,
+            Err(e) if e.is_retryable() => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(items.iter().map(|item| self.read_item(engine, item)).collect())
+    }
+
+    fn read_item(&self, engine: &dyn AccessibilityEngine, item: &UIElement) -> ScrapedRecord {
+        self.fields
+            .iter()
+            .filter_map(|(name, selector)| {
+                let field = engine
+                    .find_element(selector, Some(item), Some(FIELD_SEARCH_TIMEOUT))
+                    .ok()?;
+                Some((name.clone(), field_text(&field)))
+            })
+            .collect()
+    }
+}
+
+/// The text a field shows: its name, else its value, else the text of its descendants
+fn field_text(field: &UIElement) -> String {
+    if let Some(name) = field.name().filter(|name| !name.trim().is_empty()) {
+        return name.trim().to_string();
+    }
+    if let Some(value) = field.attributes().value.filter(|value| !value.trim().is_empty()) {
+        return value.trim().to_string();
+    }
+    field.text(FIELD_TEXT_DEPTH).unwrap_or_default().trim().to_string()
+}
+
+/// Append the records of `page` that aren't already at the end of `records`. Consecutive
+/// pages of a scrolled list overlap, and the overlap is the longest run of `previous`'s
+/// tail that `page` starts with; matching whole runs keeps genuinely repeated records.
+pub(crate) fn append_page(records: &mut Vec<ScrapedRecord>, previous: &[ScrapedRecord], page: Vec<ScrapedRecord>) {
+    let overlap = (0..=previous.len().min(page.len()))
+        .rev()
+        .find(|&k| previous[previous.len() - k..] == page[..k])
+        .unwrap_or(0);
+    records.extend(page.into_iter().skip(overlap));
+}
@@ -0,0 +1,205 @@
+//! Capturing and sampling small regions of the screen
+//!
+//! Lets workflows branch on visual state (status LEDs, chart colors) without
+//! capturing a whole monitor and indexing the buffer by hand.
+
+use crate::AutomationError;
+use crate::drawing::Color;
+use image::RgbaImage;
+use std::collections::HashMap;
+
+// Bits kept per channel when bucketing colors for `dominant_color`
+const QUANTIZE_BITS: u8 = 4;
+// Share of the pixels a color needs to count as foreground, so stray anti-aliasing doesn't
+const MIN_FOREGROUND_SHARE: f64 = 0.01;
+
+/// Capture a region of the virtual desktop, in screen coordinates.
+/// The region must lie on a single monitor.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub(crate) fn capture_region(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+) -> Result<RgbaImage, AutomationError> {
+    check_region(width, height)?;
+    MonitorFrame::capture_at(x, y)?.crop(x, y, width, height)
+}
+
+/// Capture several regions of the virtual desktop, capturing each monitor they
+/// lie on only once so all crops come from the same frame
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub(crate) fn capture_regions(
+    regions: &[(i32, i32, u32, u32)],
+) -> Vec<Result<RgbaImage, AutomationError>> {
+    let mut frames: Vec<MonitorFrame> = Vec::new();
+    regions
+        .iter()
+        .map(|&(x, y, width, height)| {
+            check_region(width, height)?;
+            let index = match frames.iter().position(|frame| frame.contains(x, y)) {
+                Some(index) => index,
+                None => {
+                    frames.push(MonitorFrame::capture_at(x, y)?);
+                    frames.len() - 1
+                }
+            };
+            frames[index].crop(x, y, width, height)
+        })
+        .collect()
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn check_region(width: u32, height: u32) -> Result<(), AutomationError> {
+    if width == 0 || height == 0 {
+        return Err(AutomationError::InvalidArgument(format!(
+            "Cannot capture an empty region ({}x{})",
+            width, height
+        )));
+    }
+    Ok(())
+}
+
+/// One captured monitor and its geometry in screen coordinates
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+struct MonitorFrame {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image: RgbaImage,
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+impl MonitorFrame {
+    /// Capture the monitor containing the point
+    fn capture_at(x: i32, y: i32) -> Result<Self, AutomationError> {
+        let monitor = xcap::Monitor::from_point(x, y).map_err(|e| {
+            AutomationError::platform(format!("No monitor at ({}, {}): {}", x, y, e))
+        })?;
+        let read = |e: xcap::XCapError| AutomationError::platform(format!("Failed to read monitor: {}", e));
+        let (monitor_x, monitor_y, monitor_width, monitor_height) = (
+            monitor.x().map_err(read)?,
+            monitor.y().map_err(read)?,
+            monitor.width().map_err(read)?,
+            monitor.height().map_err(read)?,
+        );
+
+        let image = monitor
+            .capture_image()
+            .map_err(|e| AutomationError::platform(format!("Failed to capture screen: {}", e)))?;
+
+        Ok(Self {
+            x: monitor_x,
+            y: monitor_y,
+            width: monitor_width,
+            height: monitor_height,
+            image,
+        })
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && ((x - self.x) as u32) < self.width
+            && ((y - self.y) as u32) < self.height
+    }
+
+    /// Cut a region, in screen coordinates, out of the frame
+    fn crop(&self, x: i32, y: i32, width: u32, height: u32) -> Result<RgbaImage, AutomationError> {
+        let image = &self.image;
+        // Captures can be in physical pixels while monitor geometry is logical (Retina)
+        let scale = image.width() as f64 / self.width.max(1) as f64;
+        let left = ((x - self.x) as f64 * scale) as u32;
+        let top = ((y - self.y) as f64 * scale) as u32;
+        if x < self.x || y < self.y || left >= image.width() || top >= image.height() {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Region at ({}, {}) is outside the monitor",
+                x, y
+            )));
+        }
+        let crop_width = ((width as f64 * scale).ceil() as u32).clamp(1, image.width() - left);
+        let crop_height = ((height as f64 * scale).ceil() as u32).clamp(1, image.height() - top);
+
+        Ok(image::imageops::crop_imm(image, left, top, crop_width, crop_height).to_image())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub(crate) fn capture_region(
+    _x: i32,
+    _y: i32,
+    _width: u32,
+    _height: u32,
+) -> Result<RgbaImage, AutomationError> {
+    Err(AutomationError::UnsupportedOperation(
+        "Screen capture is not implemented for this platform".to_string(),
+    ))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub(crate) fn capture_regions(
+    regions: &[(i32, i32, u32, u32)],
+) -> Vec<Result<RgbaImage, AutomationError>> {
+    regions
+        .iter()
+        .map(|&(x, y, width, height)| capture_region(x, y, width, height))
+        .collect()
+}
+
+/// The most common color in `image`, with similar shades grouped together.
+///
+/// Returns the average of the largest group, so anti-aliasing and gradients
+/// don't skew the result the way a plain mean would.
+pub(crate) fn dominant_color(image: &RgbaImage) -> Option<Color> {
+    color_groups(image).first().map(|&(_, color)| color)
+}
+
+/// The background (most common) and foreground colors of `image`, e.g. a button's
+/// face and its label. The foreground is the color that stands out most from the
+/// background among those covering a noticeable share of the pixels; it equals the
+/// background for a plain area.
+pub(crate) fn foreground_background(image: &RgbaImage) -> Option<(Color, Color)> {
+    let groups = color_groups(image);
+    let &(_, background) = groups.first()?;
+    let total = (image.width() as u64 * image.height() as u64).max(1);
+    let foreground = groups
+        .iter()
+        .filter(|(count, _)| *count as f64 / total as f64 >= MIN_FOREGROUND_SHARE)
+        .map(|&(_, color)| color)
+        .max_by(|a, b| a.contrast_ratio(&background).total_cmp(&b.contrast_ratio(&background)))
+        .unwrap_or(background);
+    Some((foreground, background))
+}
+
+/// The colors of `image` with similar shades grouped together, as (pixel count, average
+/// color), largest group first
+fn color_groups(image: &RgbaImage) -> Vec<(u64, Color)> {
+    let shift = 8 - QUANTIZE_BITS;
+    let mut buckets: HashMap<(u8, u8, u8), (u64, [u64; 3])> = HashMap::new();
+    for pixel in image.pixels() {
+        let [r, g, b, _] = pixel.0;
+        let entry = buckets
+            .entry((r >> shift, g >> shift, b >> shift))
+            .or_insert((0, [0; 3]));
+        entry.0 += 1;
+        entry.1[0] += r as u64;
+        entry.1[1] += g as u64;
+        entry.1[2] += b as u64;
+    }
+
+    let mut groups: Vec<(u64, Color)> = buckets
+        .into_values()
+        .map(|(count, sums)| {
+            let color = Color {
+                r: (sums[0] / count) as u8,
+                g: (sums[1] / count) as u8,
+                b: (sums[2] / count) as u8,
+                a: 255,
+            };
+            (count, color)
+        })
+        .collect();
+    groups.sort_by_key(|&(count, _)| std::cmp::Reverse(count));
+    groups
+}
@@ -0,0 +1,123 @@
+//! Detecting a running screen reader
+//!
+//! Screen readers change what the accessibility APIs report: apps build richer
+//! trees once assistive technology is detected, more events fire, and the
+//! reader may intercept keystrokes. Tests that pass without one can fail with
+//! one running. [`crate::Desktop::screen_reader`] reports which is active so a
+//! workflow can adapt.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, ProcessInfo};
+
+/// A screen reader recognized by its process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScreenReader {
+    /// Windows Narrator
+    Narrator,
+    Nvda,
+    Jaws,
+    ZoomText,
+    /// macOS VoiceOver
+    VoiceOver,
+}
+
+// Executable names, lowercased, of each reader's main process
+const SCREEN_READER_PROCESSES: &[(&str, ScreenReader)] = &[
+    ("narrator.exe", ScreenReader::Narrator),
+    ("nvda.exe", ScreenReader::Nvda),
+    ("jfw.exe", ScreenReader::Jaws),
+    ("zt.exe", ScreenReader::ZoomText),
+    ("voiceover", ScreenReader::VoiceOver),
+];
+
+impl ScreenReader {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScreenReader::Narrator => "Narrator",
+            ScreenReader::Nvda => "NVDA",
+            ScreenReader::Jaws => "JAWS",
+            ScreenReader::ZoomText => "ZoomText",
+            ScreenReader::VoiceOver => "VoiceOver",
+        }
+    }
+
+    /// Known ways the reader changes what automation sees
+    pub fn effects(&self) -> &'static [&'static str] {
+        match self {
+            ScreenReader::Narrator => &[
+                "Narrator's scan mode turns letter keys into navigation commands in web content and some apps",
+                "Narrator moves keyboard focus along with its own cursor",
+            ],
+            ScreenReader::Nvda => &[
+                "NVDA's browse mode turns letter keys into navigation commands in web content until a field is focused",
+                "NVDA moves keyboard focus along with its browse mode cursor by default",
+            ],
+            ScreenReader::Jaws => &[
+                "The JAWS virtual cursor turns letter keys into navigation commands in web content until forms mode starts",
+            ],
+            ScreenReader::ZoomText => &[
+                "ZoomText magnifies the screen, so screenshots and pixel positions no longer match element bounds",
+            ],
+            ScreenReader::VoiceOver => &[
+                "The VoiceOver cursor is separate from keyboard focus and can be moved by VoiceOver itself",
+                "Chromium and Electron apps expose their full accessibility tree while VoiceOver runs",
+            ],
+        }
+    }
+}
+
+/// Whether a screen reader is active, see [`crate::Desktop::screen_reader`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScreenReaderStatus {
+    /// The system setting apps read to tell whether a screen reader is in use is on
+    /// (`SPI_GETSCREENREADER` on Windows, the VoiceOver setting on macOS). Readers this
+    /// crate doesn't recognize, and some other assistive tools, set it too.
+    pub flag_set: bool,
+    /// The recognized screen readers that are running
+    pub running: Vec<ScreenReader>,
+}
+
+impl ScreenReaderStatus {
+    /// Whether a screen reader appears to be in use
+    pub fn is_active(&self) -> bool {
+        self.flag_set || !self.running.is_empty()
+    }
+
+    /// Known ways the active readers change what automation sees
+    pub fn effects(&self) -> Vec<&'static str> {
+        let mut effects: Vec<&'static str> = self.running.iter().flat_map(|reader| reader.effects()).copied().collect();
+        if self.flag_set {
+            effects.push("Apps that check for a screen reader build their full accessibility tree up front and may turn off animations");
+        }
+        effects
+    }
+}
+
+/// Check the screen reader setting and the running processes for screen readers
+pub(crate) fn detect(engine: &dyn AccessibilityEngine) -> Result<ScreenReaderStatus, AutomationError> {
+    let flag_set = match engine.get_screen_reader_flag() {
+        Ok(flag_set) => flag_set,
+        Err(e) if matches!(e.inner(), AutomationError::UnsupportedOperation(_)) => false,
+        Err(e) => return Err(e),
+    };
+    Ok(ScreenReaderStatus {
+        flag_set,
+        running: running_readers(&engine.list_processes()?),
+    })
+}
+
+/// The recognized screen readers among `processes`, each listed once
+fn running_readers(processes: &[ProcessInfo]) -> Vec<ScreenReader> {
+    let mut running = Vec::new();
+    for process in processes {
+        let name = process.name.to_lowercase();
+        let reader = SCREEN_READER_PROCESSES
+            .iter()
+            .find(|(process_name, _)| *process_name == name)
+            .map(|(_, reader)| *reader);
+        if let Some(reader) = reader.filter(|reader| !running.contains(reader)) {
+            running.push(reader);
+        }
+    }
+    running
+}
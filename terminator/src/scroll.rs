@@ -0,0 +1,107 @@
+//! Bringing elements into view
+//!
+//! Lists, documents and panes only show (and only take clicks on) what is in
+//! their viewport. Platforms can scroll an item into view when it exposes the
+//! pattern for it; for the containers that don't, [`scroll_into_view_with_wheel`]
+//! turns the mouse wheel over the nearest ancestor that clips the element until
+//! the element is inside it.
+
+use crate::{AutomationError, UIElement};
+use std::thread::sleep;
+use std::time::Duration;
+use tracing::debug;
+
+// Wheel notches per step while far away, and once the element has been overshot
+const WHEEL_NOTCHES: i32 = 3;
+const FINE_WHEEL_NOTCHES: i32 = 1;
+// Most wheel steps before giving up on a very long container
+const MAX_WHEEL_STEPS: usize = 100;
+// Time for the container to scroll and lay out after each step
+const WHEEL_SETTLE_TIME: Duration = Duration::from_millis(50);
+// Most ancestors looked at for the clipping container
+const MAX_ANCESTOR_DEPTH: usize = 32;
+
+type Bounds = (f64, f64, f64, f64);
+
+/// Scroll the nearest ancestor that clips `element` with the mouse wheel until the
+/// element is inside it. Does nothing if no ancestor clips it.
+pub(crate) fn scroll_into_view_with_wheel(element: &UIElement) -> Result<(), AutomationError> {
+    let Some(viewport) = clipping_ancestor(element)? else {
+        debug!("No ancestor clips the element, nothing to scroll");
+        return Ok(());
+    };
+    let (x, y, width, height) = viewport;
+    let (center_x, center_y) = (x + width / 2.0, y + height / 2.0);
+
+    let mut bounds = element.bounds()?;
+    let mut notches = WHEEL_NOTCHES;
+    let mut last_direction = None;
+    for _ in 0..MAX_WHEEL_STEPS {
+        let Some(direction) = direction_into(bounds, viewport) else {
+            return Ok(());
+        };
+        if last_direction.is_some_and(|last| last != direction) {
+            notches = FINE_WHEEL_NOTCHES;
+        }
+        last_direction = Some(direction);
+
+        element.mouse_wheel(center_x, center_y, direction.0 * notches, direction.1 * notches)?;
+        sleep(WHEEL_SETTLE_TIME);
+        let moved = element.bounds()?;
+        if moved == bounds {
+            return Err(AutomationError::VerificationFailed(
+                "Element did not move when its container was scrolled".to_string(),
+            ));
+        }
+        bounds = moved;
+    }
+    Err(AutomationError::Timeout(format!(
+        "Element still out of view after {} wheel steps",
+        MAX_WHEEL_STEPS
+    )))
+}
+
+/// Bounds of the nearest ancestor the element doesn't fit inside, if any
+fn clipping_ancestor(element: &UIElement) -> Result<Option<Bounds>, AutomationError> {
+    let inner = element.bounds()?;
+    let mut current = element.parent()?;
+    for _ in 0..MAX_ANCESTOR_DEPTH {
+        let Some(ancestor) = current else {
+            break;
+        };
+        if let Ok(outer) = ancestor.bounds()
+            && outer.2 > 0.0
+            && outer.3 > 0.0
+            && !contains(outer, inner)
+        {
+            return Ok(Some(outer));
+        }
+        current = ancestor.parent()?;
+    }
+    Ok(None)
+}
+
+fn contains(outer: Bounds, inner: Bounds) -> bool {
+    inner.0 >= outer.0 && inner.1 >= outer.1 && inner.0 + inner.2 <= outer.0 + outer.2 && inner.1 + inner.3 <= outer.1 + outer.3
+}
+
+/// Which way to turn the wheel, in notches right and down, to bring `bounds` into
+/// `viewport`; `None` once it is in view
+fn direction_into(bounds: Bounds, viewport: Bounds) -> Option<(i32, i32)> {
+    // -1 if the element sticks out before the viewport only, 1 if after it only
+    let axis = |start: f64, length: f64, view_start: f64, view_length: f64| {
+        let (end, view_end) = (start + length, view_start + view_length);
+        if start < view_start && end < view_end {
+            -1
+        } else if end > view_end && start > view_start {
+            1
+        } else {
+            0
+        }
+    };
+    let direction = (
+        axis(bounds.0, bounds.2, viewport.0, viewport.2),
+        axis(bounds.1, bounds.3, viewport.1, viewport.3),
+    );
+    (direction != (0, 0)).then_some(direction)
+}
@@ -0,0 +1,127 @@
+//! Finding which window shows some text
+//!
+//! During triage the question is often "which app is showing order 4711?"
+//! rather than how to reach a known element. [`crate::Desktop::find_text_everywhere`] walks
+//! the accessibility tree of every visible top-level window looking for the
+//! text in element names and values and, if asked, reads the windows where
+//! that found nothing by OCR.
+
+use crate::ocr::find_ocr_text;
+use crate::platforms::AccessibilityEngine;
+use crate::utils::normalize;
+use crate::{AutomationError, UIElement, WindowCriteria};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// How [`crate::Desktop::find_text_everywhere_with`] searches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextSearchOptions {
+    /// Also read windows by OCR when their accessibility tree doesn't contain the text,
+    /// e.g. for custom-drawn apps. Slower, and needs OCR support on the platform.
+    pub ocr: bool,
+    /// How many levels below each window to look
+    pub max_depth: usize,
+    /// Compare case and special whitespace exactly instead of ignoring them
+    pub match_case: bool,
+}
+
+impl Default for TextSearchOptions {
+    fn default() -> Self {
+        Self {
+            ocr: false,
+            max_depth: 50,
+            match_case: false,
+        }
+    }
+}
+
+/// Where a [`TextHit`] found the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextSource {
+    /// The element's name, e.g. a label, button caption or window title
+    Name,
+    /// The element's value, e.g. the contents of an input field
+    Value,
+    /// Recognized in the window's pixels
+    Ocr,
+}
+
+/// An element showing the searched text, see [`crate::Desktop::find_text_everywhere`]
+#[derive(Debug, Clone)]
+pub struct TextHit {
+    /// The top-level window the element is in
+    pub window: UIElement,
+    /// The element showing the text; for OCR hits, the area it was recognized in
+    pub element: UIElement,
+    /// The element's whole name or value that contains the text
+    pub text: String,
+    pub source: TextSource,
+}
+
+pub(crate) fn find_text_everywhere(
+    engine: &Arc<dyn AccessibilityEngine>,
+    text: &str,
+    options: &TextSearchOptions,
+) -> Result<Vec<TextHit>, AutomationError> {
+    if text.trim().is_empty() {
+        return Err(AutomationError::InvalidArgument("Text to search for is empty".to_string()));
+    }
+    let prepare = |s: &str| if options.match_case { s.to_string() } else { normalize(s) };
+    let wanted = prepare(text);
+    let contains = |candidate: &str| prepare(candidate).contains(&wanted);
+
+    let mut ocr = options.ocr;
+    let mut hits = Vec::new();
+    for window in top_level_windows(engine)? {
+        crate::abort::check()?;
+        let found_before = hits.len();
+        for element in std::iter::once(window.clone()).chain(window.descendants(options.max_depth)) {
+            let attributes = element.attributes();
+            let found = [(attributes.name, TextSource::Name), (attributes.value, TextSource::Value)]
+                .into_iter()
+                .find_map(|(candidate, source)| candidate.filter(|candidate| contains(candidate)).map(|c| (c, source)));
+            if let Some((candidate, source)) = found {
+                hits.push(TextHit {
+                    window: window.clone(),
+                    element,
+                    text: candidate,
+                    source,
+                });
+            }
+        }
+
+        if ocr && hits.len() == found_before {
+            match find_ocr_text(engine.as_ref(), text, Some(&window), None) {
+                Ok(areas) => hits.extend(areas.into_iter().map(|area| TextHit {
+                    window: window.clone(),
+                    element: area,
+                    text: text.to_string(),
+                    source: TextSource::Ocr,
+                })),
+                Err(e) if matches!(e.inner(), AutomationError::ElementNotFound(_)) => {}
+                Err(e) if matches!(e.inner(), AutomationError::UnsupportedOperation(_)) => {
+                    warn!(error = %e, "OCR is not available, searching accessibility text only");
+                    ocr = false;
+                }
+                Err(e) => debug!(error = %e, window = %window.describe(), "Could not read window by OCR"),
+            }
+        }
+    }
+    Ok(hits)
+}
+
+/// The visible top-level windows, or the root's children where windows can't be listed
+pub(crate) fn top_level_windows(engine: &Arc<dyn AccessibilityEngine>) -> Result<Vec<UIElement>, AutomationError> {
+    let criteria = WindowCriteria {
+        visible_only: true,
+        ..Default::default()
+    };
+    let windows = match engine.find_windows(&criteria, Some(Duration::ZERO)) {
+        Ok(windows) => windows,
+        Err(e) if matches!(e.inner(), AutomationError::ElementNotFound(_) | AutomationError::Timeout(_)) => Vec::new(),
+        Err(e) if matches!(e.inner(), AutomationError::UnsupportedOperation(_)) => engine.get_root_element().children()?,
+        Err(e) => return Err(e),
+    };
+    Ok(windows.into_iter().map(|window| window.with_engine(engine.clone())).collect())
+}
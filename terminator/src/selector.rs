@@ -1,9 +1,21 @@
+use crate::platforms::AccessibilityEngine;
+use crate::vision::ImageTemplate;
+use crate::{AutomationError, Role, UIElement, UIElementAttributes};
+use regex::Regex;
 use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+// How deep a `//` path step searches below its context element
+const MAX_DESCENDANT_DEPTH: usize = 50;
 
 /// Represents ways to locate a UI element
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Selector {
-    /// Select by role and optional name
+    /// Select by role and optional name. Canonical [`Role`] names match the
+    /// same kind of control on every platform; native role names also work.
     Role { role: String, name: Option<String> }, // TODO: name unused 
     /// Select by accessibility ID
     Id(String),
@@ -11,20 +23,282 @@ pub enum Selector {
     Name(String),
     /// Select by text content
     Text(String),
-    /// Select using XPath-like query
+    /// Select by a path through the tree, e.g.
+    /// `window[name="Settings"]/pane[2]/button[name="OK"]`.
+    ///
+    /// Each step names a role (or `*`) and is matched among the children of the
+    /// previous step's elements; a step after `//` is matched among all
+    /// descendants instead. `[key="value"]` requires an attribute to equal a
+    /// value, `[key*="value"]` to contain it, and `[n]` keeps the n-th match
+    /// (counting from 1) under each parent. Keys are `name`, `id`, `role`,
+    /// `value`, `label`, `description`, or a platform property such as
+    /// `AutomationId`.
     Path(String),
     /// Select by multiple attributes (key-value pairs)
     Attributes(BTreeMap<String, String>),
-    /// Filter current elements by a predicate
-    Filter(usize), // Uses an ID to reference a filter predicate stored separately
-    /// Chain multiple selectors
+    /// Elements matching `base` that `predicate` accepts. Built with [`crate::Locator::filter`].
+    Filter {
+        base: Box<Selector>,
+        predicate: ElementPredicate,
+    },
+    /// Chain multiple selectors, each searched only within the previous one's match.
+    /// Written with `>>` like Playwright, e.g. `#dialog >> role:button`.
     Chain(Vec<Selector>),
     /// Select by class name
     ClassName(String),
+    /// Select an input field by its visible caption, even if it has no accessible name
+    Label(String),
+    /// Select by an attribute matching a regular expression, written
+    /// `name~=/Document\d+ - Word/` or `value~=/^\$[0-9.]+$/`. A trailing `i`
+    /// (`name~=/save/i`) ignores case. Other attribute keys work as in [`Selector::Path`].
+    Regex { key: String, pattern: SelectorRegex },
+    /// Select by a native platform attribute, written `attr:AutomationId=loginBtn` or
+    /// `attr:AXSubrole=AXCloseButton`. The key is the platform's own name (a UI Automation
+    /// property on Windows, an AX attribute on macOS) and the value must match exactly.
+    Attribute { key: String, value: String },
+    /// The `index`-th element matching `base`, counting from 0; negative indexes count
+    /// from the last match. Written `role:button:nth(2)`, `Delete:first` or `Delete:last`,
+    /// or built with [`crate::Locator::nth`].
+    Nth { base: Box<Selector>, index: isize },
+    /// Elements matching `base` that are in every one of `filters`' states, written
+    /// `Submit:visible:enabled` or `role:button:offscreen(false)`. Skips the hidden
+    /// duplicates some apps report next to the control on screen.
+    State {
+        base: Box<Selector>,
+        filters: Vec<StateFilter>,
+    },
+    /// The screen area that best matches a template image, for custom-drawn controls
+    /// (games, canvases, Java Swing) with no accessibility nodes. Written
+    /// `image:assets/play.png`; resolves to a synthetic element that has bounds and
+    /// takes clicks and hovers at its center but has no children or text.
+    Image(ImageTemplate),
+    /// Text recognized by OCR on screen, written `ocr:Continue`; a phrase matches
+    /// consecutive words on one line, ignoring case. Like [`Selector::Image`] it resolves
+    /// to synthetic elements covering the words, for captions that custom-drawn controls
+    /// don't expose to the accessibility tree.
+    OcrText(String),
+    /// Select elements matching `base` that lie in `relation` to an element matching
+    /// `anchor`, nearest first, e.g. the unnamed field to the right of a caption.
+    /// Built with [`crate::Locator::right_of`] and its siblings.
+    Relative {
+        base: Box<Selector>,
+        relation: Relation,
+        anchor: Box<Selector>,
+    },
+}
+
+impl Selector {
+    /// Whether `element` itself matches, for selectors that can be decided from its own
+    /// attributes. `None` for selectors that need a search of the tree (text, paths,
+    /// labels, positions, relations, images, OCR) or whose regex doesn't parse.
+    pub(crate) fn matches_element(&self, element: &UIElement) -> Option<bool> {
+        let equals = |key: &str, expected: &str| attribute_value(element, &mut None, key).is_some_and(|value| value == expected);
+        match self {
+            Selector::Role { role, name } => {
+                let role_matches = match role.parse::<Role>() {
+                    Ok(canonical) if canonical != Role::Unknown => element.normalized_role() == canonical,
+                    _ => element.role().eq_ignore_ascii_case(role),
+                };
+                Some(role_matches && name.as_ref().is_none_or(|name| name_contains(element, name)))
+            }
+            Selector::Name(name) => Some(name_contains(element, name)),
+            Selector::Id(id) => Some(element.id().is_some_and(|actual| actual == *id)),
+            Selector::ClassName(class_name) => Some(equals("ClassName", class_name)),
+            Selector::Attribute { key, value } => Some(equals(key, value)),
+            Selector::Attributes(attributes) => {
+                let mut cached = None;
+                Some(attributes.iter().all(|(key, value)| {
+                    attribute_value(element, &mut cached, key).is_some_and(|actual| actual == *value)
+                }))
+            }
+            Selector::Regex { key, pattern } => pattern.regex().ok().map(|regex| regex_matches(element, key, regex)),
+            Selector::State { base, filters } => base
+                .matches_element(element)
+                .map(|matched| matched && filters.iter().all(|filter| filter.matches(element))),
+            Selector::Filter { base, predicate } => base
+                .matches_element(element)
+                .map(|matched| matched && predicate.matches(element)),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `element`'s name contains `name`, ignoring case, as the engines match names
+fn name_contains(element: &UIElement, name: &str) -> bool {
+    element
+        .name()
+        .is_some_and(|actual| actual.to_lowercase().contains(&name.to_lowercase()))
+}
+
+/// A state a [`Selector::State`] element must (`true`) or must not (`false`) be in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateFilter {
+    /// `:visible`
+    Visible(bool),
+    /// `:enabled`
+    Enabled(bool),
+    /// `:focusable`, i.e. can take keyboard focus
+    Focusable(bool),
+    /// `:offscreen`, the opposite of `:visible`
+    Offscreen(bool),
+}
+
+impl StateFilter {
+    /// Whether `element` passes the filter; a state that can't be read fails it
+    pub(crate) fn matches(&self, element: &UIElement) -> bool {
+        let (state, wanted) = match *self {
+            StateFilter::Visible(wanted) => (element.is_visible(), wanted),
+            StateFilter::Enabled(wanted) => (element.is_enabled(), wanted),
+            StateFilter::Focusable(wanted) => (element.is_keyboard_focusable(), wanted),
+            StateFilter::Offscreen(wanted) => (element.is_visible(), !wanted),
+        };
+        state.is_ok_and(|state| state == wanted)
+    }
+}
+
+/// Where a [`Selector::Relative`] element lies relative to its anchor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relation {
+    /// Right of the anchor, overlapping it vertically
+    RightOf,
+    /// Left of the anchor, overlapping it vertically
+    LeftOf,
+    /// Above the anchor, overlapping it horizontally
+    Above,
+    /// Below the anchor, overlapping it horizontally
+    Below,
+    /// Within this many pixels of the anchor in any direction
+    Near(u32),
+}
+
+/// The regular expression of a [`Selector::Regex`]. It is compiled the first time
+/// it is matched and the compiled form is shared by every clone of the selector,
+/// so a locator compiles it once no matter how often it searches.
+#[derive(Clone)]
+pub struct SelectorRegex {
+    source: String,
+    compiled: Arc<OnceLock<Result<Regex, String>>>,
+}
+
+impl SelectorRegex {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            compiled: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// The pattern as written
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The compiled pattern, or `InvalidArgument` if it doesn't parse
+    pub(crate) fn regex(&self) -> Result<&Regex, AutomationError> {
+        self.compiled
+            .get_or_init(|| Regex::new(&self.source).map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|e| {
+                AutomationError::InvalidArgument(format!(
+                    "Invalid regex selector /{}/: {}",
+                    self.source, e
+                ))
+            })
+    }
+}
+
+impl fmt::Debug for SelectorRegex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/{}/", self.source)
+    }
+}
+
+impl PartialEq for SelectorRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for SelectorRegex {}
+
+impl Hash for SelectorRegex {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+    }
+}
+
+/// The closure of a [`Selector::Filter`]. Each predicate gets a unique id when created;
+/// clones share it, and selectors compare equal only if they share the predicate.
+#[derive(Clone)]
+pub struct ElementPredicate {
+    id: usize,
+    function: Arc<dyn Fn(&UIElement) -> bool + Send + Sync>,
+}
+
+impl ElementPredicate {
+    pub fn new(function: impl Fn(&UIElement) -> bool + Send + Sync + 'static) -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            function: Arc::new(function),
+        }
+    }
+
+    pub(crate) fn matches(&self, element: &UIElement) -> bool {
+        (self.function)(element)
+    }
+}
+
+impl fmt::Debug for ElementPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "predicate#{}", self.id)
+    }
+}
+
+impl PartialEq for ElementPredicate {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for ElementPredicate {}
+
+impl Hash for ElementPredicate {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl From<Role> for Selector {
+    fn from(role: Role) -> Self {
+        Selector::Role {
+            role: role.as_str().to_string(),
+            name: None,
+        }
+    }
 }
 
 impl From<&str> for Selector {
     fn from(s: &str) -> Self {
+        let stages = split_chain(s);
+        if stages.len() > 1 {
+            return Selector::Chain(stages.into_iter().map(Selector::from).collect());
+        }
+        if let Some((base, index)) = split_nth(s) {
+            return Selector::Nth {
+                base: Box::new(Selector::from(base)),
+                index,
+            };
+        }
+        if let Some((base, filters)) = split_state_filters(s) {
+            return Selector::State {
+                base: Box::new(Selector::from(base)),
+                filters,
+            };
+        }
+        if let Some(selector) = parse_regex_selector(s) {
+            return selector;
+        }
         // Make common UI roles like "window", "button", etc. default to Role selectors
         // instead of Name selectors
         match s {
@@ -51,11 +325,575 @@ impl From<&str> for Selector {
                 let parts: Vec<&str> = s.splitn(2, ':').collect();
                 Selector::ClassName(parts[1].to_string())
             }
+            _ if s.starts_with("label:") || s.starts_with("label=") => Selector::Label(s[6..].to_string()),
+            _ if s.starts_with("attr:") && s.contains('=') => {
+                let (key, value) = s[5..].split_once('=').unwrap_or_default();
+                Selector::Attribute {
+                    key: key.trim().to_string(),
+                    value: value.trim().trim_matches('"').to_string(),
+                }
+            }
+            _ if s.starts_with("ocr:") => Selector::OcrText(s[4..].trim().to_string()),
+            _ if s.starts_with("image:") => Selector::Image(ImageTemplate::from_path(s[6..].trim())),
             _ if s.starts_with('#') => Selector::Id(s[1..].to_string()),
             _ if s.starts_with("id:") => Selector::Id(s[3..].to_string()),
-            _ if s.starts_with('/') => Selector::Path(s.to_string()),
+            _ if s.starts_with('/') || looks_like_path(s) => Selector::Path(s.to_string()),
             _ if s.starts_with("text:") => Selector::Text(s[5..].to_string()),
             _ => Selector::Name(s.to_string()),
         }
     }
 }
+
+/// Split `s` at each `>>` that is not inside quotes or a `/regex/`, dropping empty stages
+fn split_chain(s: &str) -> Vec<&str> {
+    let mut stages = Vec::new();
+    let mut stage_start = 0;
+    let mut in_quotes = false;
+    let mut in_regex = false;
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if in_regex => {
+                chars.next();
+            }
+            '/' if in_regex => in_regex = false,
+            '/' if !in_quotes && s[..i].ends_with("~=") => in_regex = true,
+            '"' if !in_regex => in_quotes = !in_quotes,
+            '>' if !in_quotes && !in_regex && s[i + 1..].starts_with('>') => {
+                stages.push(s[stage_start..i].trim());
+                chars.next();
+                stage_start = i + 2;
+            }
+            _ => {}
+        }
+    }
+    stages.push(s[stage_start..].trim());
+    stages.retain(|stage| !stage.is_empty());
+    stages
+}
+
+/// Split a trailing `:nth(n)`, `:first` or `:last` off `s`
+fn split_nth(s: &str) -> Option<(&str, isize)> {
+    let (base, index) = if let Some(base) = s.strip_suffix(":first") {
+        (base, 0)
+    } else if let Some(base) = s.strip_suffix(":last") {
+        (base, -1)
+    } else {
+        let (base, rest) = s.strip_suffix(')')?.rsplit_once(":nth(")?;
+        (base, rest.trim().parse().ok()?)
+    };
+    (!base.is_empty()).then_some((base, index))
+}
+
+/// Split trailing state filters like `:visible:enabled` off `s`
+fn split_state_filters(s: &str) -> Option<(&str, Vec<StateFilter>)> {
+    let mut base = s;
+    let mut filters = Vec::new();
+    while let Some((rest, filter)) = split_state_filter(base) {
+        filters.push(filter);
+        base = rest;
+    }
+    if filters.is_empty() || base.is_empty() {
+        return None;
+    }
+    filters.reverse();
+    Some((base, filters))
+}
+
+/// Split one trailing `:state` or `:state(true|false)` off `s`
+fn split_state_filter(s: &str) -> Option<(&str, StateFilter)> {
+    let (rest, wanted) = match s.strip_suffix(')') {
+        Some(inner) => {
+            let (rest, argument) = inner.rsplit_once('(')?;
+            (rest, argument.trim().parse().ok()?)
+        }
+        None => (s, true),
+    };
+    let (base, name) = rest.rsplit_once(':')?;
+    let filter = match name {
+        "visible" => StateFilter::Visible(wanted),
+        "enabled" => StateFilter::Enabled(wanted),
+        "focusable" => StateFilter::Focusable(wanted),
+        "offscreen" => StateFilter::Offscreen(wanted),
+        _ => return None,
+    };
+    Some((base, filter))
+}
+
+/// The elements matching `base` below `root` that pass every filter
+pub(crate) fn find_with_state(
+    engine: &dyn AccessibilityEngine,
+    base: &Selector,
+    filters: &[StateFilter],
+    root: Option<&UIElement>,
+    timeout: Option<std::time::Duration>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let matches: Vec<UIElement> = engine
+        .find_elements(base, root, timeout, None)?
+        .into_iter()
+        .filter(|element| filters.iter().all(|filter| filter.matches(element)))
+        .collect();
+    if matches.is_empty() {
+        return Err(AutomationError::ElementNotFound(format!(
+            "No element matching {:?} is {:?}",
+            base, filters
+        )));
+    }
+    Ok(matches)
+}
+
+/// The elements matching `base` below `root` that `predicate` accepts
+pub(crate) fn find_filtered(
+    engine: &dyn AccessibilityEngine,
+    base: &Selector,
+    predicate: &ElementPredicate,
+    root: Option<&UIElement>,
+    timeout: Option<std::time::Duration>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let matches: Vec<UIElement> = engine
+        .find_elements(base, root, timeout, None)?
+        .into_iter()
+        .filter(|element| predicate.matches(element))
+        .collect();
+    if matches.is_empty() {
+        return Err(AutomationError::ElementNotFound(format!(
+            "No element matching {:?} passes {:?}",
+            base, predicate
+        )));
+    }
+    Ok(matches)
+}
+
+/// The `index`-th element matching `base` below `root`, as a one-element list. The first
+/// match is searched for on its own, so finding it stops early; other indexes need the
+/// matches before them (or all of them, counting from the end).
+pub(crate) fn find_nth(
+    engine: &dyn AccessibilityEngine,
+    base: &Selector,
+    index: isize,
+    root: Option<&UIElement>,
+    timeout: Option<std::time::Duration>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    if index == 0 {
+        return Ok(vec![engine.find_element(base, root, timeout)?]);
+    }
+    let mut matches = engine.find_elements(base, root, timeout, None)?;
+    let count = matches.len();
+    let position = if index < 0 {
+        count.checked_sub(index.unsigned_abs())
+    } else {
+        Some(index as usize).filter(|&i| i < count)
+    };
+    match position {
+        Some(position) => Ok(vec![matches.swap_remove(position)]),
+        None => Err(AutomationError::ElementNotFound(format!(
+            "Only {} elements match {:?}, no element at index {}",
+            count, base, index
+        ))),
+    }
+}
+
+/// Parse `key~=/pattern/` (optionally followed by `i`) into a [`Selector::Regex`]
+fn parse_regex_selector(s: &str) -> Option<Selector> {
+    let (key, rest) = s.split_once("~=/")?;
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    let (pattern, ignore_case) = match rest.strip_suffix("/i") {
+        Some(pattern) => (pattern, true),
+        None => (rest.strip_suffix('/')?, false),
+    };
+    let pattern = if ignore_case {
+        format!("(?i){}", pattern)
+    } else {
+        pattern.to_string()
+    };
+    Some(Selector::Regex {
+        key: key.to_string(),
+        pattern: SelectorRegex::new(pattern),
+    })
+}
+
+/// Whether `element`'s `key` attribute matches `regex`; used by the engines while
+/// walking the tree for a [`Selector::Regex`]
+pub(crate) fn regex_matches(element: &UIElement, key: &str, regex: &Regex) -> bool {
+    attribute_value(element, &mut None, key).is_some_and(|value| regex.is_match(&value))
+}
+
+/// Whether `s` starts with a path step like `window[` and continues with `]/`
+fn looks_like_path(s: &str) -> bool {
+    match s.find('[') {
+        Some(bracket) if bracket > 0 => {
+            s[..bracket].chars().all(|c| c.is_ascii_alphanumeric() || c == '*') && s.contains("]/")
+        }
+        _ => false,
+    }
+}
+
+/// One step of a [`Selector::Path`]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PathStep {
+    /// Search all descendants rather than only children
+    pub descendants: bool,
+    /// Role to match; `None` for `*`
+    pub role: Option<String>,
+    pub predicates: Vec<Predicate>,
+    /// 1-based position among the matches under each parent
+    pub index: Option<usize>,
+}
+
+/// An attribute condition of a path step
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Predicate {
+    pub key: String,
+    pub value: String,
+    /// `*=`: the attribute contains the value rather than equalling it
+    pub contains: bool,
+}
+
+impl Predicate {
+    pub fn matches(&self, element: &UIElement, attributes: &mut Option<UIElementAttributes>) -> bool {
+        attribute_value(element, attributes, &self.key).is_some_and(|actual| {
+            if self.contains {
+                actual.contains(&self.value)
+            } else {
+                actual == self.value
+            }
+        })
+    }
+}
+
+/// Read an attribute by its selector key. `attributes` caches the element's full
+/// attribute set, which is only fetched for keys that need it.
+pub(crate) fn attribute_value(
+    element: &UIElement,
+    attributes: &mut Option<UIElementAttributes>,
+    key: &str,
+) -> Option<String> {
+    match key.to_lowercase().as_str() {
+        "name" => return element.name(),
+        "id" => return element.id(),
+        "role" => return Some(element.role()),
+        _ => {}
+    }
+    let attributes = attributes.get_or_insert_with(|| element.attributes());
+    match key.to_lowercase().as_str() {
+        "value" => attributes.value.clone(),
+        "label" => attributes.label.clone(),
+        "description" => attributes.description.clone(),
+        _ => attributes
+            .properties
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .and_then(|(_, value)| value.as_ref())
+            .map(|value| match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            }),
+    }
+}
+
+/// Parse a [`Selector::Path`] expression into its steps
+pub(crate) fn parse_path(path: &str) -> Result<Vec<PathStep>, AutomationError> {
+    let invalid = |reason: &str| {
+        AutomationError::InvalidArgument(format!("Invalid path selector '{}': {}", path, reason))
+    };
+    let chars: Vec<char> = path.trim().chars().collect();
+    let mut steps = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let mut descendants = false;
+        if chars[i] == '/' {
+            i += 1;
+            if chars.get(i) == Some(&'/') {
+                descendants = true;
+                i += 1;
+            }
+        } else if !steps.is_empty() {
+            return Err(invalid("expected '/' between steps"));
+        }
+
+        let start = i;
+        while i < chars.len() && chars[i] != '[' && chars[i] != '/' {
+            i += 1;
+        }
+        let role: String = chars[start..i].iter().collect::<String>().trim().to_string();
+        if role.is_empty() {
+            return Err(invalid("empty step"));
+        }
+
+        let mut predicates = Vec::new();
+        let mut index = None;
+        while chars.get(i) == Some(&'[') {
+            i += 1;
+            let mut content = String::new();
+            let mut quote = None;
+            loop {
+                let c = *chars.get(i).ok_or_else(|| invalid("unclosed '['"))?;
+                i += 1;
+                match (quote, c) {
+                    (None, ']') => break,
+                    (None, '"' | '\'') => quote = Some(c),
+                    (Some(q), c) if c == q => quote = None,
+                    _ => {}
+                }
+                content.push(c);
+            }
+
+            let content = content.trim();
+            if let Ok(n) = content.parse::<usize>() {
+                if n == 0 {
+                    return Err(invalid("indices start at 1"));
+                }
+                index = Some(n);
+                continue;
+            }
+            let equals = content
+                .find('=')
+                .ok_or_else(|| invalid("expected [key=\"value\"] or [n]"))?;
+            let (key, value) = (&content[..equals], &content[equals + 1..]);
+            let (key, contains) = match key.strip_suffix('*') {
+                Some(key) => (key, true),
+                None => (key, false),
+            };
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            predicates.push(Predicate {
+                key: key.trim().to_string(),
+                value: value.to_string(),
+                contains,
+            });
+        }
+
+        steps.push(PathStep {
+            descendants,
+            role: (role != "*").then_some(role),
+            predicates,
+            index,
+        });
+    }
+
+    if steps.is_empty() {
+        return Err(invalid("no steps"));
+    }
+    Ok(steps)
+}
+
+/// Resolve a [`Selector::Path`] by walking the tree from `root` (or the desktop root)
+pub(crate) fn find_by_path(
+    engine: &dyn AccessibilityEngine,
+    path: &str,
+    root: Option<&UIElement>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let steps = parse_path(path)?;
+    let mut current = vec![root.cloned().unwrap_or_else(|| engine.get_root_element())];
+
+    for step in &steps {
+        let mut next: Vec<UIElement> = Vec::new();
+        for parent in &current {
+            let candidates = if step.descendants {
+                descendants_of(parent)
+            } else {
+                parent.children().unwrap_or_default()
+            };
+            let matching = candidates.into_iter().filter(|element| step_matches(step, element));
+            let selected: Vec<UIElement> = match step.index {
+                Some(n) => matching.skip(n - 1).take(1).collect(),
+                None => matching.collect(),
+            };
+            for element in selected {
+                if !next.contains(&element) {
+                    next.push(element);
+                }
+            }
+        }
+        if next.is_empty() {
+            return Err(AutomationError::ElementNotFound(format!(
+                "No element matches path '{}'",
+                path
+            )));
+        }
+        current = next;
+    }
+    Ok(current)
+}
+
+fn step_matches(step: &PathStep, element: &UIElement) -> bool {
+    if let Some(role) = &step.role {
+        let matches = match role.parse::<Role>() {
+            Ok(role) => element.normalized_role() == role,
+            Err(_) => element.role().eq_ignore_ascii_case(role),
+        };
+        if !matches {
+            return false;
+        }
+    }
+    let mut attributes = None;
+    step.predicates
+        .iter()
+        .all(|predicate| predicate.matches(element, &mut attributes))
+}
+
+/// All descendants of `element` in breadth-first order
+pub(crate) fn descendants_of(element: &UIElement) -> Vec<UIElement> {
+    let mut found = Vec::new();
+    let mut level = vec![element.clone()];
+    for _ in 0..MAX_DESCENDANT_DEPTH {
+        let children: Vec<UIElement> = level
+            .iter()
+            .flat_map(|e| e.children().unwrap_or_default())
+            .collect();
+        if children.is_empty() {
+            break;
+        }
+        found.extend(children.iter().cloned());
+        level = children;
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(role: &str) -> Selector {
+        Selector::Role {
+            role: role.to_string(),
+            name: None,
+        }
+    }
+
+    fn regex(key: &str, pattern: &str) -> Selector {
+        Selector::Regex {
+            key: key.to_string(),
+            pattern: SelectorRegex::new(pattern),
+        }
+    }
+
+    #[test]
+    fn splits_chains_outside_quotes_and_regexes() {
+        assert_eq!(split_chain("#dialog >> role:button"), vec!["#dialog", "role:button"]);
+        assert_eq!(split_chain(" >> Save >> "), vec!["Save"]);
+        assert_eq!(
+            split_chain("name~=/a >> b/ >> role:button"),
+            vec!["name~=/a >> b/", "role:button"]
+        );
+        assert_eq!(split_chain(r"name~=/a\/>>b/i >> OK"), vec![r"name~=/a\/>>b/i", "OK"]);
+        assert_eq!(
+            split_chain(r#"window[name="a >> b"]/button >> OK"#),
+            vec![r#"window[name="a >> b"]/button"#, "OK"]
+        );
+    }
+
+    #[test]
+    fn parses_regexes_containing_chain_separators() {
+        assert_eq!(
+            Selector::from("name~=/Open >> Recent/ >> role:button"),
+            Selector::Chain(vec![regex("name", "Open >> Recent"), role("button")])
+        );
+        assert_eq!(Selector::from("value~=/a>>b/i"), regex("value", "(?i)a>>b"));
+    }
+
+    #[test]
+    fn splits_nth_and_state_filters() {
+        assert_eq!(split_nth("role:button:nth(2)"), Some(("role:button", 2)));
+        assert_eq!(split_nth("Delete:nth(-1)"), Some(("Delete", -1)));
+        assert_eq!(split_nth("Delete:first"), Some(("Delete", 0)));
+        assert_eq!(split_nth("Delete:last"), Some(("Delete", -1)));
+        assert_eq!(split_nth(":last"), None);
+        assert_eq!(split_nth("Delete:nth(two)"), None);
+
+        assert_eq!(
+            split_state_filters("Submit:visible:enabled(false)"),
+            Some(("Submit", vec![StateFilter::Visible(true), StateFilter::Enabled(false)]))
+        );
+        assert_eq!(split_state_filters("Status:ready"), None);
+        assert_eq!(split_state_filters(":visible"), None);
+    }
+
+    #[test]
+    fn parses_nth_after_state_filters() {
+        let visible_buttons = Selector::State {
+            base: Box::new(role("button")),
+            filters: vec![StateFilter::Visible(true)],
+        };
+        assert_eq!(
+            Selector::from("role:button:visible:nth(2)"),
+            Selector::Nth {
+                base: Box::new(visible_buttons.clone()),
+                index: 2,
+            }
+        );
+        assert_eq!(
+            Selector::from("role:button:visible:last"),
+            Selector::Nth {
+                base: Box::new(visible_buttons),
+                index: -1,
+            }
+        );
+        assert_eq!(
+            Selector::from("role:button:nth(2):visible"),
+            Selector::State {
+                base: Box::new(Selector::Nth {
+                    base: Box::new(role("button")),
+                    index: 2,
+                }),
+                filters: vec![StateFilter::Visible(true)],
+            }
+        );
+    }
+
+    #[test]
+    fn parses_quoted_path_predicates_containing_slashes() {
+        let path = r#"window[name="C:/Users/me"]/button[name='a/b']"#;
+        assert_eq!(Selector::from(path), Selector::Path(path.to_string()));
+
+        let steps = parse_path(path).unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].role.as_deref(), Some("window"));
+        assert_eq!(steps[0].predicates[0].value, "C:/Users/me");
+        assert_eq!(steps[1].role.as_deref(), Some("button"));
+        assert_eq!(steps[1].predicates[0].value, "a/b");
+
+        let steps = parse_path(r#"//pane[name*="a]/b"][2]"#).unwrap();
+        assert_eq!(steps.len(), 1);
+        assert!(steps[0].descendants);
+        assert_eq!(steps[0].predicates[0].value, "a]/b");
+        assert!(steps[0].predicates[0].contains);
+        assert_eq!(steps[0].index, Some(2));
+    }
+
+    #[test]
+    fn parses_attribute_values_containing_equals() {
+        assert_eq!(
+            Selector::from("attr:Formula==SUM(A1:A3)"),
+            Selector::Attribute {
+                key: "Formula".to_string(),
+                value: "=SUM(A1:A3)".to_string(),
+            }
+        );
+        assert_eq!(
+            Selector::from(r#"attr:Url="https://example.com/?a=1&b=2""#),
+            Selector::Attribute {
+                key: "Url".to_string(),
+                value: "https://example.com/?a=1&b=2".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_simple_selectors() {
+        assert_eq!(Selector::from("#saveButton"), Selector::Id("saveButton".to_string()));
+        assert_eq!(Selector::from("id:saveButton"), Selector::Id("saveButton".to_string()));
+        assert_eq!(Selector::from("name:Save"), Selector::Name("Save".to_string()));
+        assert_eq!(Selector::from("Save"), Selector::Name("Save".to_string()));
+        assert_eq!(Selector::from("button"), role("button"));
+        assert_eq!(Selector::from("AXButton"), role("AXButton"));
+        assert_eq!(Selector::from("text:Hello"), Selector::Text("Hello".to_string()));
+    }
+}
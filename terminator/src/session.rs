@@ -0,0 +1,190 @@
+//! Interactive session awareness for shared hosts (RDS / multiple logged-in users)
+//!
+//! Input injection and UI Automation only reach the desktop of the session
+//! the process runs in, and silently do nothing while that session is
+//! disconnected. Binding a [`crate::Desktop`] to a session turns both cases
+//! into clear errors instead of mysterious timeouts: while bound, every click,
+//! keystroke and other input action first checks that the session still
+//! receives input. Like the kill switch, the binding is process-wide, since
+//! the process runs in one session whichever desktop sends the input.
+
+use crate::AutomationError;
+use crate::platforms::AccessibilityEngine;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// How long a passed check is trusted before the next input action checks again
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Binding {
+    session_id: u32,
+    engine: Arc<dyn AccessibilityEngine>,
+    last_passed: Option<Instant>,
+}
+
+static BINDING: Mutex<Option<Binding>> = Mutex::new(None);
+
+/// Connection state of a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+    /// A user is logged on and connected; input reaches the desktop
+    Active,
+    /// Connected but no user logged on yet
+    Connected,
+    /// A user is logged on but the client disconnected; input is discarded
+    Disconnected,
+    Idle,
+    /// A listener session waiting for connections
+    Listening,
+    Other,
+}
+
+/// An interactive session on this machine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    pub id: u32,
+    /// Window station name, e.g. `Console` or `RDP-Tcp#3`
+    pub station: String,
+    pub user: Option<String>,
+    pub state: SessionState,
+    /// Whether this process runs in the session
+    pub is_current: bool,
+    /// Whether this is the session attached to the physical console
+    pub is_console: bool,
+}
+
+/// Check that input from this process will land in `session_id`
+pub(crate) fn check_input_session(
+    sessions: &[SessionInfo],
+    session_id: u32,
+) -> Result<(), AutomationError> {
+    let session = sessions
+        .iter()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| AutomationError::InvalidArgument(format!("No session with id {}", session_id)))?;
+
+    if !session.is_current {
+        let current = sessions
+            .iter()
+            .find(|s| s.is_current)
+            .map_or("unknown".to_string(), |s| s.id.to_string());
+        return Err(AutomationError::PermissionDenied(format!(
+            "This process runs in session {} and cannot inject input into session {} ({}); \
+             run the automation inside that session instead",
+            current, session.id, session.station
+        )));
+    }
+    if session.state != SessionState::Active {
+        return Err(AutomationError::platform(format!(
+            "Session {} ({}) is {:?}; input sent now would be discarded",
+            session.id, session.station, session.state
+        )));
+    }
+    Ok(())
+}
+
+/// Check that input from this process will land in `session_id` and that the session
+/// isn't locked
+pub(crate) fn check_input(engine: &dyn AccessibilityEngine, session_id: u32) -> Result<(), AutomationError> {
+    check_input_session(&engine.list_sessions()?, session_id)?;
+    if engine.is_session_locked().unwrap_or(false) {
+        return Err(AutomationError::platform(format!(
+            "Session {} is locked; input sent now would go to the lock screen",
+            session_id
+        )));
+    }
+    Ok(())
+}
+
+/// Have every input action check `session_id` first, see [`check_bound_input`]
+pub(crate) fn bind(engine: Arc<dyn AccessibilityEngine>, session_id: u32) {
+    if let Ok(mut binding) = BINDING.lock() {
+        *binding = Some(Binding {
+            session_id,
+            engine,
+            last_passed: Some(Instant::now()),
+        });
+    }
+}
+
+/// The session input actions are checked against, if one is bound
+pub(crate) fn bound_session() -> Option<u32> {
+    BINDING.lock().ok()?.as_ref().map(|binding| binding.session_id)
+}
+
+/// Fail if input would miss the bound session; does nothing while no session is bound.
+/// A passed check is reused for [`SESSION_CHECK_INTERVAL`] so bursts of keystrokes don't
+/// each list the sessions.
+pub(crate) fn check_bound_input() -> Result<(), AutomationError> {
+    let Ok(mut binding) = BINDING.lock() else {
+        return Ok(());
+    };
+    let Some(binding) = binding.as_mut() else {
+        return Ok(());
+    };
+    if binding
+        .last_passed
+        .is_some_and(|passed| passed.elapsed() < SESSION_CHECK_INTERVAL)
+    {
+        return Ok(());
+    }
+    binding.last_passed = None;
+    check_input(binding.engine.as_ref(), binding.session_id)?;
+    binding.last_passed = Some(Instant::now());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: u32, user: &str, state: SessionState, is_current: bool) -> SessionInfo {
+        SessionInfo {
+            id,
+            station: format!("RDP-Tcp#{}", id),
+            user: Some(user.to_string()),
+            state,
+            is_current,
+            is_console: false,
+        }
+    }
+
+    fn sessions() -> Vec<SessionInfo> {
+        vec![
+            session(1, "bot", SessionState::Active, true),
+            session(2, "alice", SessionState::Active, false),
+            session(3, "bob", SessionState::Disconnected, false),
+        ]
+    }
+
+    #[test]
+    fn accepts_the_current_active_session() {
+        assert!(check_input_session(&sessions(), 1).is_ok());
+    }
+
+    #[test]
+    fn rejects_another_users_session() {
+        let error = check_input_session(&sessions(), 2).unwrap_err();
+        assert!(matches!(error, AutomationError::PermissionDenied(_)), "{:?}", error);
+        assert!(error.to_string().contains("runs in session 1"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_a_disconnected_session() {
+        let mut sessions = sessions();
+        sessions[0].state = SessionState::Disconnected;
+        let error = check_input_session(&sessions, 1).unwrap_err();
+        assert!(matches!(error, AutomationError::PlatformError { .. }), "{:?}", error);
+        assert!(error.to_string().contains("Disconnected"), "{}", error);
+
+        // Someone else's disconnected session is refused for not being ours first
+        let error = check_input_session(&sessions, 3).unwrap_err();
+        assert!(matches!(error, AutomationError::PermissionDenied(_)), "{:?}", error);
+    }
+
+    #[test]
+    fn rejects_an_unknown_session() {
+        let error = check_input_session(&sessions(), 42).unwrap_err();
+        assert!(matches!(error, AutomationError::InvalidArgument(_)), "{:?}", error);
+    }
+}
@@ -0,0 +1,108 @@
+//! Soft assertions: collect failures instead of stopping at the first
+//!
+//! A data-entry bot working through a batch would rather enter every row and
+//! report the rows that didn't take than die on row 3. Checks passed to a
+//! [`SoftAssertions`], including expectations made with
+//! [`crate::Expectation::soft`], record assertion and verification failures
+//! and let the run continue; [`SoftAssertions::finish`] fails at the end with
+//! all of them at once.
+
+use crate::AutomationError;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Collects assertion and verification failures so the run can carry on, see
+/// [`SoftAssertions::check`]. Clones share the same failures.
+#[derive(Debug, Clone, Default)]
+pub struct SoftAssertions {
+    results: Arc<Mutex<Results>>,
+}
+
+#[derive(Debug, Default)]
+struct Results {
+    checks: usize,
+    failures: Vec<SoftFailure>,
+}
+
+/// A check that failed, recorded by [`SoftAssertions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoftFailure {
+    /// What was being checked, as passed to [`SoftAssertions::check`]
+    pub step: String,
+    /// The failure, as the error would have displayed
+    pub message: String,
+}
+
+impl SoftAssertions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the outcome of `step`. Assertion and verification failures are recorded and
+    /// give `Ok(None)` so the run continues; any other error, e.g. an abort or a lost
+    /// window, is returned as is.
+    pub fn check<T>(&self, step: &str, result: Result<T, AutomationError>) -> Result<Option<T>, AutomationError> {
+        match result {
+            Ok(value) => {
+                self.update(|results| results.checks += 1);
+                Ok(Some(value))
+            }
+            Err(e) if matches!(e.inner(), AutomationError::AssertionFailed(_) | AutomationError::VerificationFailed(_)) => {
+                warn!(step, error = %e, "Soft assertion failed, continuing");
+                self.update(|results| {
+                    results.checks += 1;
+                    results.failures.push(SoftFailure {
+                        step: step.to_string(),
+                        message: e.to_string(),
+                    });
+                });
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// How many checks were recorded, passed or failed
+    pub fn checks(&self) -> usize {
+        self.results.lock().map(|results| results.checks).unwrap_or_default()
+    }
+
+    /// The checks that failed so far, in the order they ran
+    pub fn failures(&self) -> Vec<SoftFailure> {
+        self.results
+            .lock()
+            .map(|results| results.failures.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    /// Fail with one [`AutomationError::AssertionFailed`] listing every recorded failure,
+    /// or succeed if none were
+    pub fn finish(&self) -> Result<(), AutomationError> {
+        if self.is_clean() {
+            return Ok(());
+        }
+        Err(AutomationError::AssertionFailed(self.to_string()))
+    }
+
+    fn update(&self, change: impl FnOnce(&mut Results)) {
+        if let Ok(mut results) = self.results.lock() {
+            change(&mut results);
+        }
+    }
+}
+
+impl fmt::Display for SoftAssertions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failures = self.failures();
+        write!(f, "{} of {} checks failed", failures.len(), self.checks())?;
+        for (i, failure) in failures.iter().enumerate() {
+            write!(f, "\n  {}. {}: {}", i + 1, failure.step, failure.message.replace('\n', "\n     "))?;
+        }
+        Ok(())
+    }
+}
@@ -27,4 +27,175 @@ pub async fn cleanup_test_environment() {
 
 // Test constants
 pub const TEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
-pub const TEST_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(500); 
\ No newline at end of file
+pub const TEST_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(500); 
+/// An element with only a role, name and ID, for unit tests of code that reads elements
+/// without a desktop. Everything else is unsupported.
+#[derive(Debug, Clone)]
+pub(crate) struct FakeElement {
+    role: crate::Role,
+    name: Option<String>,
+    id: Option<String>,
+}
+
+impl FakeElement {
+    pub(crate) fn named(name: &str) -> Self {
+        Self {
+            role: crate::Role::Button,
+            name: Some(name.to_string()),
+            id: None,
+        }
+    }
+
+    pub(crate) fn with_id(id: &str) -> Self {
+        Self {
+            role: crate::Role::Button,
+            name: None,
+            id: Some(id.to_string()),
+        }
+    }
+
+    pub(crate) fn into_element(self) -> crate::UIElement {
+        crate::UIElement::new(Box::new(self))
+    }
+
+    fn unsupported<T>(&self, operation: &str) -> Result<T, crate::AutomationError> {
+        Err(crate::AutomationError::UnsupportedOperation(format!(
+            "{} not supported on a fake element",
+            operation
+        )))
+    }
+}
+
+impl crate::element::UIElementImpl for FakeElement {
+    fn object_id(&self) -> usize {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        let mut hasher = DefaultHasher::new();
+        (&self.name, &self.id).hash(&mut hasher);
+        hasher.finish() as usize
+    }
+
+    fn id(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    fn role(&self) -> String {
+        self.role.as_str().to_string()
+    }
+
+    fn attributes(&self) -> crate::UIElementAttributes {
+        crate::UIElementAttributes {
+            role: self.role(),
+            normalized_role: self.role,
+            name: self.name.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    fn children(&self) -> Result<Vec<crate::UIElement>, crate::AutomationError> {
+        Ok(Vec::new())
+    }
+
+    fn parent(&self) -> Result<Option<crate::UIElement>, crate::AutomationError> {
+        Ok(None)
+    }
+
+    fn bounds(&self) -> Result<(f64, f64, f64, f64), crate::AutomationError> {
+        self.unsupported("bounds")
+    }
+
+    fn click(&self) -> Result<crate::ClickResult, crate::AutomationError> {
+        self.unsupported("click")
+    }
+
+    fn double_click(&self) -> Result<crate::ClickResult, crate::AutomationError> {
+        self.unsupported("double_click")
+    }
+
+    fn right_click(&self) -> Result<crate::ClickResult, crate::AutomationError> {
+        self.unsupported("right_click")
+    }
+
+    fn hover(&self) -> Result<(), crate::AutomationError> {
+        self.unsupported("hover")
+    }
+
+    fn focus(&self) -> Result<(), crate::AutomationError> {
+        self.unsupported("focus")
+    }
+
+    fn type_text(&self, _text: &str, _use_clipboard: bool) -> Result<(), crate::AutomationError> {
+        self.unsupported("type_text")
+    }
+
+    fn press_key(&self, _key: &str) -> Result<(), crate::AutomationError> {
+        self.unsupported("press_key")
+    }
+
+    fn get_text(&self, _max_depth: usize) -> Result<String, crate::AutomationError> {
+        Ok(self.name.clone().unwrap_or_default())
+    }
+
+    fn set_value(&self, _value: &str) -> Result<(), crate::AutomationError> {
+        self.unsupported("set_value")
+    }
+
+    fn is_enabled(&self) -> Result<bool, crate::AutomationError> {
+        Ok(true)
+    }
+
+    fn is_visible(&self) -> Result<bool, crate::AutomationError> {
+        Ok(true)
+    }
+
+    fn is_focused(&self) -> Result<bool, crate::AutomationError> {
+        Ok(false)
+    }
+
+    fn perform_action(&self, _action: &str) -> Result<(), crate::AutomationError> {
+        self.unsupported("perform_action")
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn create_locator(&self, _selector: crate::Selector) -> Result<crate::Locator, crate::AutomationError> {
+        self.unsupported("create_locator")
+    }
+
+    fn scroll(&self, _direction: &str, _amount: f64) -> Result<(), crate::AutomationError> {
+        self.unsupported("scroll")
+    }
+
+    fn activate_window(&self) -> Result<(), crate::AutomationError> {
+        self.unsupported("activate_window")
+    }
+
+    fn clone_box(&self) -> Box<dyn crate::element::UIElementImpl> {
+        Box::new(self.clone())
+    }
+
+    fn is_keyboard_focusable(&self) -> Result<bool, crate::AutomationError> {
+        Ok(false)
+    }
+
+    fn mouse_drag(&self, _start_x: f64, _start_y: f64, _end_x: f64, _end_y: f64) -> Result<(), crate::AutomationError> {
+        self.unsupported("mouse_drag")
+    }
+
+    fn mouse_click_and_hold(&self, _x: f64, _y: f64) -> Result<(), crate::AutomationError> {
+        self.unsupported("mouse_click_and_hold")
+    }
+
+    fn mouse_move(&self, _x: f64, _y: f64) -> Result<(), crate::AutomationError> {
+        self.unsupported("mouse_move")
+    }
+
+    fn mouse_release(&self) -> Result<(), crate::AutomationError> {
+        self.unsupported("mouse_release")
+    }
+}
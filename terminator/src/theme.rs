@@ -0,0 +1,30 @@
+//! The system color theme
+//!
+//! Apps redraw in dark or light colors and tint selections with the accent
+//! color, so image templates, pixel checks and contrast audits captured
+//! under one theme can fail under another. See [`crate::Desktop::current_theme`].
+
+use crate::drawing::Color;
+
+/// Whether apps are asked to draw light or dark
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeMode {
+    Light,
+    Dark,
+}
+
+/// The system color theme
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub mode: ThemeMode,
+    /// The user's highlight color, if the platform reports one
+    pub accent_color: Option<Color>,
+    /// Whether a high-contrast theme (Windows) or Increase Contrast (macOS) is on
+    pub high_contrast: bool,
+}
+
+impl Theme {
+    pub fn is_dark(&self) -> bool {
+        self.mode == ThemeMode::Dark
+    }
+}
@@ -0,0 +1,178 @@
+//! A filmstrip of the screen over a run
+//!
+//! Full video of an unattended run is large and slow to review. A
+//! [`TimelineRecorder`] instead keeps small frames of the primary monitor
+//! taken at a fixed interval and lays them out in one image at the end, so a
+//! glance shows what the run went through and where it went wrong.
+
+use crate::platforms::AccessibilityEngine;
+use crate::region::ScopeCapture;
+use crate::AutomationError;
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+// Pixels between frames in the filmstrip, and their color
+const FRAME_GAP: u32 = 4;
+const GAP_COLOR: Rgba<u8> = Rgba([32, 32, 32, 255]);
+
+/// How a [`TimelineRecorder`] samples the screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineOptions {
+    /// Time between frames
+    pub interval: Duration,
+    /// Width each frame is scaled down to, in pixels
+    pub frame_width: u32,
+    /// Frames per row of the filmstrip
+    pub columns: u32,
+    /// Most frames kept. Once reached, every other frame is dropped and the interval
+    /// doubles, so a long run is still covered from start to end.
+    pub max_frames: usize,
+}
+
+impl Default for TimelineOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(2),
+            frame_width: 320,
+            columns: 6,
+            max_frames: 120,
+        }
+    }
+}
+
+/// The frames of a run laid out in one image, see [`TimelineRecorder::finish`]
+#[derive(Debug, Clone)]
+pub struct Filmstrip {
+    /// Frames left to right, then top to bottom, in the order they were taken
+    pub image: RgbaImage,
+    /// When each frame was taken
+    pub captured_at: Vec<SystemTime>,
+}
+
+impl Filmstrip {
+    /// Save the image, in the format the extension names (e.g. `.png`)
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AutomationError> {
+        self.image
+            .save(path.as_ref())
+            .map_err(|e| AutomationError::platform_with_source("Failed to save filmstrip", e))
+    }
+}
+
+type Frames = Arc<Mutex<Vec<(SystemTime, RgbaImage)>>>;
+
+/// Captures frames of the screen in the background, see [`crate::Desktop::record_timeline`].
+/// Stops when dropped.
+pub struct TimelineRecorder {
+    frames: Frames,
+    task: JoinHandle<()>,
+    columns: u32,
+}
+
+impl TimelineRecorder {
+    pub(crate) fn spawn(engine: Arc<dyn AccessibilityEngine>, options: TimelineOptions) -> Result<Self, AutomationError> {
+        if options.interval.is_zero() || options.frame_width == 0 || options.columns == 0 || options.max_frames < 2 {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Timeline needs a nonzero interval, frame width and column count and at least 2 frames, got {:?}",
+                options
+            )));
+        }
+        let frames: Frames = Arc::default();
+        let recorded = frames.clone();
+        let columns = options.columns;
+
+        let task = tokio::spawn(async move {
+            // Only every `stride`-th tick is captured once frames have been thinned out
+            let mut stride = 1;
+            let mut tick = 0u64;
+            loop {
+                if tick.is_multiple_of(stride) {
+                    let capture_engine = engine.clone();
+                    let frame_width = options.frame_width;
+                    match tokio::task::spawn_blocking(move || capture_frame(capture_engine.as_ref(), frame_width)).await {
+                        Ok(Ok(frame)) => {
+                            let mut frames = recorded.lock().unwrap();
+                            frames.push((SystemTime::now(), frame));
+                            if frames.len() >= options.max_frames {
+                                let mut keep = false;
+                                frames.retain(|_| {
+                                    keep = !keep;
+                                    keep
+                                });
+                                stride *= 2;
+                                debug!(kept = frames.len(), stride, "Thinned out timeline frames");
+                            }
+                        }
+                        Ok(Err(e)) => warn!(error = %e, "Failed to capture timeline frame"),
+                        Err(_) => return,
+                    }
+                }
+                tick += 1;
+                tokio::time::sleep(options.interval).await;
+            }
+        });
+
+        Ok(Self { frames, task, columns })
+    }
+
+    /// How many frames have been kept so far
+    pub fn frame_count(&self) -> usize {
+        self.frames.lock().unwrap().len()
+    }
+
+    /// Stop capturing and lay the frames out as a filmstrip.
+    /// Fails with `InvalidArgument` if no frame was captured.
+    pub fn finish(self) -> Result<Filmstrip, AutomationError> {
+        self.task.abort();
+        let frames = std::mem::take(&mut *self.frames.lock().unwrap());
+        render(frames, self.columns)
+    }
+}
+
+impl Drop for TimelineRecorder {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The primary monitor, scaled down to `frame_width`
+fn capture_frame(engine: &dyn AccessibilityEngine, frame_width: u32) -> Result<RgbaImage, AutomationError> {
+    let screen = ScopeCapture::new(engine, None)?.image;
+    let frame_height = (screen.height() as u64 * frame_width as u64 / screen.width().max(1) as u64).max(1) as u32;
+    Ok(imageops::resize(&screen, frame_width, frame_height, FilterType::Triangle))
+}
+
+fn render(frames: Vec<(SystemTime, RgbaImage)>, columns: u32) -> Result<Filmstrip, AutomationError> {
+    let Some((_, first)) = frames.first() else {
+        return Err(AutomationError::InvalidArgument("No timeline frames were captured".to_string()));
+    };
+    // Every cell has the first frame's size; frames from after a resolution change are scaled to it
+    let (cell_width, cell_height) = first.dimensions();
+    let count = frames.len() as u32;
+    let columns = columns.min(count);
+    let rows = count.div_ceil(columns);
+    let mut image = RgbaImage::from_pixel(
+        columns * cell_width + (columns + 1) * FRAME_GAP,
+        rows * cell_height + (rows + 1) * FRAME_GAP,
+        GAP_COLOR,
+    );
+
+    let mut captured_at = Vec::with_capacity(frames.len());
+    for (index, (time, frame)) in frames.into_iter().enumerate() {
+        let (column, row) = (index as u32 % columns, index as u32 / columns);
+        let frame = if frame.dimensions() == (cell_width, cell_height) {
+            frame
+        } else {
+            imageops::resize(&frame, cell_width, cell_height, FilterType::Triangle)
+        };
+        let x = FRAME_GAP + column * (cell_width + FRAME_GAP);
+        let y = FRAME_GAP + row * (cell_height + FRAME_GAP);
+        imageops::replace(&mut image, &frame, x as i64, y as i64);
+        captured_at.push(time);
+    }
+    Ok(Filmstrip { image, captured_at })
+}
@@ -0,0 +1,159 @@
+//! Element handles that survive a re-render
+//!
+//! A [`UIElement`] points at one node of the accessibility tree. When the app
+//! rebuilds that part of its UI (a list refreshes, a dialog is recreated) the
+//! node goes away and every call on the handle fails with
+//! [`AutomationError::StaleElement`], even though an identical element is on
+//! screen. A [`TrackedElement`] remembers the locator it came from and finds
+//! the element again when that happens.
+
+use crate::{ActionContext, AutomationError, ClickResult, Locator, Selector, UIElement};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+// How often an action is re-resolved and retried before its stale error is returned;
+// a window can re-render more than once while it settles
+const MAX_RE_RESOLVES: usize = 2;
+
+/// An element that is found again through its [`Locator`] when its handle goes stale,
+/// see [`Locator::track`]
+pub struct TrackedElement {
+    locator: Locator,
+    element: Mutex<UIElement>,
+    re_resolve_timeout: Option<Duration>,
+}
+
+impl fmt::Debug for TrackedElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrackedElement")
+            .field("selector", self.locator.selector())
+            .field("element", &self.element())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TrackedElement {
+    pub(crate) fn new(locator: Locator, element: UIElement) -> Self {
+        Self {
+            locator,
+            element: Mutex::new(element),
+            re_resolve_timeout: None,
+        }
+    }
+
+    /// Wait at most `timeout` for the element to reappear when re-resolving, instead of
+    /// the locator's default timeout
+    pub fn with_re_resolve_timeout(mut self, timeout: Duration) -> Self {
+        self.re_resolve_timeout = Some(timeout);
+        self
+    }
+
+    /// The selector chain the element is found again with
+    pub fn selector(&self) -> &Selector {
+        self.locator.selector()
+    }
+
+    /// The current handle; it may go stale, unlike the tracked element
+    pub fn element(&self) -> UIElement {
+        self.element.lock().unwrap().clone()
+    }
+
+    /// Find the element again through its locator and track the new handle
+    pub async fn refresh(&self) -> Result<UIElement, AutomationError> {
+        let element = self.locator.wait(self.re_resolve_timeout).await?;
+        *self.element.lock().unwrap() = element.clone();
+        Ok(element)
+    }
+
+    /// Run `action` on the element, re-resolving it and running `action` again if the
+    /// handle turns out to be stale. Use it for operations without a method here.
+    pub async fn run<T>(&self, action: impl Fn(&UIElement) -> Result<T, AutomationError>) -> Result<T, AutomationError> {
+        let mut element = self.element();
+        let mut re_resolves = 0;
+        loop {
+            match action(&element) {
+                Err(e) if is_stale(&e) && re_resolves < MAX_RE_RESOLVES => {
+                    re_resolves += 1;
+                    debug!(selector = ?self.locator.selector(), re_resolves, "Element went stale, re-resolving");
+                    element = self.refresh().await.map_err(|refresh_error| {
+                        warn!(error = %refresh_error, "Could not find stale element again");
+                        refresh_error
+                    })?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    pub async fn click(&self) -> Result<ClickResult, AutomationError> {
+        self.run(|element| element.click()).await
+    }
+
+    pub async fn double_click(&self) -> Result<ClickResult, AutomationError> {
+        self.run(|element| element.double_click()).await
+    }
+
+    pub async fn right_click(&self) -> Result<ClickResult, AutomationError> {
+        self.run(|element| element.right_click()).await
+    }
+
+    /// Type text into the element, returning where keyboard focus was afterwards
+    /// if the element knows its engine
+    pub async fn type_text(&self, text: &str, use_clipboard: bool) -> Result<Option<ActionContext>, AutomationError> {
+        self.run(|element| {
+            element.type_text(text, use_clipboard)?;
+            Ok(element.action_context())
+        })
+        .await
+    }
+
+    pub async fn press_key(&self, key: &str) -> Result<(), AutomationError> {
+        self.run(|element| element.press_key(key)).await
+    }
+
+    pub async fn select_option(&self, option: &str) -> Result<(), AutomationError> {
+        self.run(|element| element.select_option(option)).await
+    }
+
+    pub async fn set_checked(&self, checked: bool) -> Result<(), AutomationError> {
+        self.run(|element| element.set_checked(checked)).await
+    }
+
+    pub async fn is_checked(&self) -> Result<bool, AutomationError> {
+        self.run(|element| element.is_checked()).await
+    }
+
+    pub async fn value(&self) -> Result<String, AutomationError> {
+        self.run(|element| element.value()).await
+    }
+
+    pub async fn set_value(&self, value: &str) -> Result<(), AutomationError> {
+        self.run(|element| element.set_value(value)).await
+    }
+
+    pub async fn focus(&self) -> Result<(), AutomationError> {
+        self.run(|element| element.focus()).await
+    }
+
+    pub async fn text(&self, max_depth: usize) -> Result<String, AutomationError> {
+        self.run(|element| element.text(max_depth)).await
+    }
+
+    pub async fn bounds(&self) -> Result<(f64, f64, f64, f64), AutomationError> {
+        self.run(|element| element.bounds()).await
+    }
+
+    pub async fn is_visible(&self) -> Result<bool, AutomationError> {
+        self.run(|element| element.is_visible()).await
+    }
+
+    pub async fn is_enabled(&self) -> Result<bool, AutomationError> {
+        self.run(|element| element.is_enabled()).await
+    }
+}
+
+fn is_stale(error: &AutomationError) -> bool {
+    matches!(error.inner(), AutomationError::StaleElement(_))
+}
@@ -0,0 +1,236 @@
+//! Reading element values as numbers, dates and ranges
+//!
+//! Sliders, progress bars and numeric fields show their value as text like
+//! `1,234.5 MB`, `75%` or `15.03.2024`. These helpers read the native range
+//! value where the control has one and otherwise parse the displayed text,
+//! so callers don't have to pick numbers out of strings themselves.
+
+use crate::action::read_field;
+use crate::controls::DateLayout;
+use crate::{AutomationError, UIElement};
+use chrono::NaiveDate;
+
+// Written-out date formats tried when the displayed date isn't all numbers
+const NAMED_MONTH_DATE_FORMATS: &[&str] = &["%B %d, %Y", "%b %d, %Y", "%d %B %Y", "%d %b %Y", "%A, %B %d, %Y", "%A, %d %B %Y"];
+// Symbols read as the unit when written before the number, e.g. `$12.50`
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₹', '₽', '₩', '¢'];
+
+/// The range and position of a slider, progress bar or spinner, from its native range value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeValue {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub value: f64,
+}
+
+impl RangeValue {
+    /// How far along the range the value is, from 0.0 at the minimum to 1.0 at the
+    /// maximum; `None` for an empty range
+    pub fn fraction(&self) -> Option<f64> {
+        let span = self.maximum - self.minimum;
+        (span > 0.0).then(|| (self.value - self.minimum) / span)
+    }
+}
+
+/// A number read from displayed text, with the unit written next to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericValue {
+    pub value: f64,
+    /// What follows the number, e.g. `%` or `MB`, or a currency symbol before it
+    pub unit: Option<String>,
+}
+
+/// The element's value as a number: its native range value if it has one, else its
+/// displayed value parsed
+pub(crate) fn value_as_f64(element: &UIElement) -> Result<f64, AutomationError> {
+    match element.range_value() {
+        Ok(range) => return Ok(range.value),
+        Err(e) if !matches!(e.inner(), AutomationError::UnsupportedOperation(_)) => return Err(e),
+        Err(_) => {}
+    }
+    Ok(value_with_unit(element)?.value)
+}
+
+pub(crate) fn value_with_unit(element: &UIElement) -> Result<NumericValue, AutomationError> {
+    let shown = displayed_value(element)?;
+    parse_number(&shown)
+        .ok_or_else(|| AutomationError::UnexpectedValue(format!("'{}' is not a number", shown)))
+}
+
+pub(crate) fn value_as_date(element: &UIElement) -> Result<NaiveDate, AutomationError> {
+    let shown = displayed_value(element)?;
+    parse_date(&shown).ok_or_else(|| AutomationError::UnexpectedValue(format!("'{}' is not a date", shown)))
+}
+
+fn displayed_value(element: &UIElement) -> Result<String, AutomationError> {
+    read_field(element)
+        .filter(|shown| !shown.trim().is_empty())
+        .ok_or_else(|| AutomationError::UnexpectedValue("Element shows no value".to_string()))
+}
+
+/// Parse the first number in `text` and the unit around it. With both `,` and `.`
+/// present the last one is the decimal point; a lone `,` before exactly three digits
+/// groups thousands, any other lone separator is the decimal point.
+pub(crate) fn parse_number(text: &str) -> Option<NumericValue> {
+    let span = find_number(text, '.', is_number_char)?;
+    let magnitude: f64 = normalize_separators(span.number).parse().ok()?;
+    Some(NumericValue {
+        value: span.signed(magnitude),
+        unit: unit_around(span.prefix, span.suffix, &[]),
+    })
+}
+
+/// The first number written in some text, with the text around it
+pub(crate) struct NumberSpan<'a> {
+    pub(crate) prefix: &'a str,
+    /// Digits and separators, starting and ending with a digit or `decimal`
+    pub(crate) number: &'a str,
+    pub(crate) suffix: &'a str,
+    pub(crate) negative: bool,
+}
+
+impl NumberSpan<'_> {
+    pub(crate) fn signed(&self, magnitude: f64) -> f64 {
+        if self.negative { -magnitude } else { magnitude }
+    }
+}
+
+/// Find the first run of digits and the separators `is_number_char` accepts, with
+/// a `decimal` point directly in front of it and a minus sign before that
+pub(crate) fn find_number(text: &str, decimal: char, is_number_char: impl Fn(char) -> bool) -> Option<NumberSpan<'_>> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let start = chars.iter().position(|&(_, c)| c.is_ascii_digit())?;
+    let mut first = start;
+    if first > 0 && chars[first - 1].1 == decimal {
+        first -= 1;
+    }
+    let negative = first > 0 && matches!(chars[first - 1].1, '-' | '\u{2212}');
+
+    let mut end = start;
+    while end < chars.len() && is_number_char(chars[end].1) {
+        end += 1;
+    }
+    // A trailing separator belongs to the sentence, e.g. `Done: 42.`
+    while end > start && !chars[end - 1].1.is_ascii_digit() {
+        end -= 1;
+    }
+
+    let byte = |i: usize| chars.get(i).map_or(text.len(), |&(offset, _)| offset);
+    Some(NumberSpan {
+        prefix: &text[..byte(if negative { first - 1 } else { first })],
+        number: &text[byte(first)..byte(end)],
+        suffix: &text[byte(end)..],
+        negative,
+    })
+}
+
+/// The unit written after a number, or a currency symbol (or one of `currencies`)
+/// written before it
+pub(crate) fn unit_around(prefix: &str, suffix: &str, currencies: &[Option<&str>]) -> Option<String> {
+    let prefix = prefix.trim_end();
+    let suffix = suffix.trim().trim_end_matches(['.', ',', ';', ':', '!', '?']);
+    if !suffix.is_empty() {
+        return Some(suffix.to_string());
+    }
+    if let Some(currency) = currencies
+        .iter()
+        .flatten()
+        .find(|currency| !currency.is_empty() && prefix.ends_with(*currency))
+    {
+        return Some(currency.to_string());
+    }
+    prefix
+        .chars()
+        .last()
+        .filter(|c| CURRENCY_SYMBOLS.contains(c))
+        .map(String::from)
+}
+
+fn is_number_char(c: char) -> bool {
+    c.is_ascii_digit() || matches!(c, '.' | ',' | '\'' | '\u{a0}' | '\u{202f}')
+}
+
+/// Turn a number written with grouping and decimal separators into Rust's syntax
+fn normalize_separators(number: &str) -> String {
+    let digits_and_marks: String = number.chars().filter(|c| c.is_ascii_digit() || matches!(c, '.' | ',')).collect();
+    let decimal = match (digits_and_marks.rfind('.'), digits_and_marks.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (Some(dot), None) if digits_and_marks.matches('.').count() == 1 => Some(dot),
+        (None, Some(comma)) if digits_and_marks.matches(',').count() == 1 && digits_and_marks.len() - comma - 1 != 3 => {
+            Some(comma)
+        }
+        _ => None,
+    };
+    digits_and_marks
+        .char_indices()
+        .filter_map(|(i, c)| match c {
+            _ if Some(i) == decimal => Some('.'),
+            '.' | ',' => None,
+            digit => Some(digit),
+        })
+        .collect()
+}
+
+/// Parse a displayed date: all-numeric dates in the order they appear to be written
+/// in, or a date with the month written out in English
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    DateLayout::detect(text).parse(text).or_else(|| parse_named_month_date(text))
+}
+
+/// Parse a date with the month written out in English, e.g. `March 15, 2024`
+pub(crate) fn parse_named_month_date(text: &str) -> Option<NaiveDate> {
+    NAMED_MONTH_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text.trim(), format).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str) -> (&str, &str, &str, bool) {
+        let span = find_number(text, '.', is_number_char).unwrap();
+        (span.prefix, span.number, span.suffix, span.negative)
+    }
+
+    fn number(text: &str) -> Option<(f64, Option<String>)> {
+        parse_number(text).map(|number| (number.value, number.unit))
+    }
+
+    #[test]
+    fn normalize_separators_guesses_the_decimal_point() {
+        assert_eq!(normalize_separators("1,234"), "1234");
+        assert_eq!(normalize_separators("1,5"), "1.5");
+        assert_eq!(normalize_separators("1,2345"), "1.2345");
+        assert_eq!(normalize_separators("1.234.567"), "1234567");
+        assert_eq!(normalize_separators("1,234,567"), "1234567");
+        assert_eq!(normalize_separators("1.234,56"), "1234.56");
+        assert_eq!(normalize_separators("1,234.56"), "1234.56");
+        assert_eq!(normalize_separators("1'234.5"), "1234.5");
+        assert_eq!(normalize_separators(".5"), ".5");
+    }
+
+    #[test]
+    fn find_number_splits_off_sign_and_sentence() {
+        assert_eq!(span("-.5"), ("", ".5", "", true));
+        assert_eq!(span("Total \u{2212}3"), ("Total ", "3", "", true));
+        assert_eq!(span("Done: 42."), ("Done: ", "42", ".", false));
+        assert_eq!(span("75%"), ("", "75", "%", false));
+        assert_eq!(span("Items: 1,234, pages: 7"), ("Items: ", "1,234", ", pages: 7", false));
+        assert!(find_number("no digits", '.', is_number_char).is_none());
+    }
+
+    #[test]
+    fn parse_number_reads_displayed_numbers() {
+        assert_eq!(number("1,234"), Some((1234.0, None)));
+        assert_eq!(number("1,5"), Some((1.5, None)));
+        assert_eq!(number("1.234.567"), Some((1234567.0, None)));
+        assert_eq!(number("-.5"), Some((-0.5, None)));
+        assert_eq!(number("Done: 42."), Some((42.0, None)));
+        assert_eq!(number("75%"), Some((75.0, Some("%".to_string()))));
+        assert_eq!(number("$12.50"), Some((12.5, Some("$".to_string()))));
+        assert_eq!(number("3.5 MB free"), Some((3.5, Some("MB free".to_string()))));
+        assert_eq!(number("none"), None);
+    }
+}
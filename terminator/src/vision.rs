@@ -0,0 +1,500 @@
+//! Finding template images on screen
+//!
+//! Canvas-rendered and game-like UIs expose little or nothing to the
+//! accessibility tree, so the only handle on them is what they look like.
+//!
+//! Matching uses zero-mean normalized cross-correlation on grayscale pixels,
+//! which tolerates uniform brightness and contrast changes. Build with the
+//! `simd` feature to vectorize the correlation on x86-64 CPUs with AVX2.
+
+use crate::platforms::AccessibilityEngine;
+use crate::region::{ScopeCapture, ScreenRegionElement};
+use crate::{AutomationError, Role, ScreenshotResult, UIElement};
+use image::{GrayImage, RgbaImage, imageops};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+// Smallest template side (in pixels) worth searching at a downscaled level
+const COARSE_MIN_SIDE: u32 = 12;
+// Largest downscale factor used for the coarse search
+const COARSE_MAX_FACTOR: u32 = 8;
+// Variance below which a patch is considered flat (a single color)
+const FLAT_VARIANCE: f64 = 1.0;
+// Confidence an image selector needs to match
+const IMAGE_SELECTOR_THRESHOLD: f32 = 0.9;
+// How often the screen is searched again while an image selector waits
+const IMAGE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Where a template image was found, in screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageMatch {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Normalized cross-correlation score, 1.0 being a perfect match
+    pub confidence: f32,
+}
+
+impl ImageMatch {
+    /// Center of the matched area, e.g. for clicking
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+}
+
+/// Where a template was found within a screenshot, in screenshot pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemplateMatch {
+    pub x: u32,
+    pub y: u32,
+    /// Size of the matched area, i.e. of the template at `scale`
+    pub width: u32,
+    pub height: u32,
+    /// Factor the template was resized by to match
+    pub scale: f32,
+    /// Normalized cross-correlation score, 1.0 being a perfect match
+    pub confidence: f32,
+}
+
+/// Find the best match of `template` in `haystack`
+pub fn match_template(
+    haystack: &ScreenshotResult,
+    template: &ScreenshotResult,
+) -> Result<Option<TemplateMatch>, AutomationError> {
+    match_template_multiscale(haystack, template, &[1.0])
+}
+
+/// Find the best match of `template` in `haystack`, trying the template resized
+/// by each of `scales`. Useful when the template was captured at another DPI,
+/// e.g. `&[0.75, 1.0, 1.25, 1.5, 2.0]`.
+pub fn match_template_multiscale(
+    haystack: &ScreenshotResult,
+    template: &ScreenshotResult,
+    scales: &[f32],
+) -> Result<Option<TemplateMatch>, AutomationError> {
+    Ok(find_template_scaled(&to_image(haystack)?, &to_image(template)?, scales))
+}
+
+/// Find every place `template` appears in `haystack` with at least `threshold`
+/// confidence, best first. Overlapping hits are reduced to the best one.
+///
+/// Every position is scored at full resolution, so this is slower than
+/// [`match_template`] on large screenshots.
+pub fn find_all_matches(
+    haystack: &ScreenshotResult,
+    template: &ScreenshotResult,
+    threshold: f32,
+) -> Result<Vec<TemplateMatch>, AutomationError> {
+    let haystack = imageops::grayscale(&to_image(haystack)?);
+    let template = imageops::grayscale(&to_image(template)?);
+    let (tw, th) = template.dimensions();
+    let Some(scorer) = Scorer::new(&haystack, &template) else {
+        return Ok(Vec::new());
+    };
+
+    let mut hits = Vec::new();
+    for y in 0..=haystack.height() - th {
+        for x in 0..=haystack.width() - tw {
+            let confidence = scorer.score(x, y);
+            if confidence >= threshold {
+                hits.push(TemplateMatch {
+                    x,
+                    y,
+                    width: tw,
+                    height: th,
+                    scale: 1.0,
+                    confidence,
+                });
+            }
+        }
+    }
+
+    // Non-maximum suppression: keep a hit only if no better one overlaps it
+    hits.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    let mut matches: Vec<TemplateMatch> = Vec::new();
+    for hit in hits {
+        let overlaps = matches
+            .iter()
+            .any(|m| hit.x.abs_diff(m.x) < tw && hit.y.abs_diff(m.y) < th);
+        if !overlaps {
+            matches.push(hit);
+        }
+    }
+    Ok(matches)
+}
+
+/// Convert a screenshot into an image buffer
+pub(crate) fn to_image(screenshot: &ScreenshotResult) -> Result<RgbaImage, AutomationError> {
+    RgbaImage::from_raw(
+        screenshot.width,
+        screenshot.height,
+        screenshot.image_data.clone(),
+    )
+    .ok_or_else(|| {
+        AutomationError::InvalidArgument(format!(
+            "Image data does not match its {}x{} dimensions",
+            screenshot.width, screenshot.height
+        ))
+    })
+}
+
+/// The template image of a [`crate::Selector::Image`], read from a file or given as
+/// encoded bytes (PNG, JPEG, ...). It is decoded the first time it is matched and the
+/// decoded image is shared by every clone of the selector.
+#[derive(Clone)]
+pub struct ImageTemplate {
+    source: ImageSource,
+    decoded: Arc<OnceLock<Result<RgbaImage, String>>>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum ImageSource {
+    Path(PathBuf),
+    Bytes(Arc<Vec<u8>>),
+}
+
+impl ImageTemplate {
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(ImageSource::Path(path.into()))
+    }
+
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        Self::new(ImageSource::Bytes(Arc::new(bytes.into())))
+    }
+
+    fn new(source: ImageSource) -> Self {
+        Self {
+            source,
+            decoded: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// The decoded template, or `InvalidArgument` if it can't be read
+    pub(crate) fn image(&self) -> Result<&RgbaImage, AutomationError> {
+        self.decoded
+            .get_or_init(|| {
+                let image = match &self.source {
+                    ImageSource::Path(path) => image::open(path),
+                    ImageSource::Bytes(bytes) => image::load_from_memory(bytes),
+                };
+                image.map(|image| image.to_rgba8()).map_err(|e| e.to_string())
+            })
+            .as_ref()
+            .map_err(|e| AutomationError::InvalidArgument(format!("Failed to load {:?}: {}", self, e)))
+    }
+}
+
+impl fmt::Debug for ImageTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            ImageSource::Path(path) => write!(f, "image:{}", path.display()),
+            ImageSource::Bytes(bytes) => write!(f, "image:<{} bytes>", bytes.len()),
+        }
+    }
+}
+
+impl PartialEq for ImageTemplate {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for ImageTemplate {}
+
+impl Hash for ImageTemplate {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+    }
+}
+
+/// Resolve a [`crate::Selector::Image`]: the best match of `template` within `root`'s
+/// bounds (the primary monitor without a root), as an element covering the matched
+/// area. The screen is searched again until `timeout` runs out.
+pub(crate) fn find_image(
+    engine: &dyn AccessibilityEngine,
+    template: &ImageTemplate,
+    root: Option<&UIElement>,
+    timeout: Option<Duration>,
+) -> Result<UIElement, AutomationError> {
+    let start = Instant::now();
+    let template_image = template.image()?;
+
+    let mut best_confidence = 0.0f32;
+    loop {
+        let capture = ScopeCapture::new(engine, root)?;
+        if let Some((x, y, confidence)) = find_template(&capture.image, template_image) {
+            if confidence >= IMAGE_SELECTOR_THRESHOLD {
+                debug!(confidence, "Image selector matched");
+                let bounds = capture.to_screen(
+                    x as f64,
+                    y as f64,
+                    template_image.width() as f64,
+                    template_image.height() as f64,
+                );
+                return Ok(ScreenRegionElement::new(engine, bounds, Role::Image)
+                    .with_property("confidence", serde_json::json!(confidence))
+                    .into_element());
+            }
+            best_confidence = best_confidence.max(confidence);
+        }
+        if timeout.is_none_or(|timeout| start.elapsed() >= timeout) {
+            return Err(AutomationError::ElementNotFound(format!(
+                "{:?} not found on screen (best confidence {:.2})",
+                template, best_confidence
+            )));
+        }
+        crate::abort::check()?;
+        std::thread::sleep(IMAGE_POLL_INTERVAL);
+    }
+}
+
+/// Find the best match of `template` in `haystack` over several template scales
+pub(crate) fn find_template_scaled(
+    haystack: &RgbaImage,
+    template: &RgbaImage,
+    scales: &[f32],
+) -> Option<TemplateMatch> {
+    let mut best: Option<TemplateMatch> = None;
+    for &scale in scales {
+        let width = (template.width() as f32 * scale).round() as u32;
+        let height = (template.height() as f32 * scale).round() as u32;
+        if width == 0 || height == 0 {
+            continue;
+        }
+        let found = if width == template.width() && height == template.height() {
+            find_template(haystack, template)
+        } else {
+            let resized = imageops::resize(template, width, height, imageops::FilterType::Triangle);
+            find_template(haystack, &resized)
+        };
+        if let Some((x, y, confidence)) = found
+            && best.is_none_or(|b| confidence > b.confidence)
+        {
+            best = Some(TemplateMatch {
+                x,
+                y,
+                width,
+                height,
+                scale,
+                confidence,
+            });
+        }
+    }
+    best
+}
+
+/// Find the best match of `template` in `haystack`.
+/// Returns the top-left corner in haystack pixels and the match score.
+pub(crate) fn find_template(haystack: &RgbaImage, template: &RgbaImage) -> Option<(u32, u32, f32)> {
+    let haystack = imageops::grayscale(haystack);
+    let template = imageops::grayscale(template);
+    let (tw, th) = template.dimensions();
+    if tw == 0 || th == 0 || tw > haystack.width() || th > haystack.height() {
+        return None;
+    }
+
+    // Search a downscaled copy first, then refine around the best coarse hit at full size
+    let factor = (tw.min(th) / COARSE_MIN_SIDE).clamp(1, COARSE_MAX_FACTOR);
+    if factor == 1 {
+        return best_match(&haystack, &template, None);
+    }
+
+    let coarse_haystack = downscale(&haystack, factor);
+    let coarse_template = downscale(&template, factor);
+    let (cx, cy, _) = best_match(&coarse_haystack, &coarse_template, None)?;
+
+    let margin = factor * 2;
+    let window = (
+        (cx * factor).saturating_sub(margin),
+        (cy * factor).saturating_sub(margin),
+        (cx * factor + margin).min(haystack.width() - tw),
+        (cy * factor + margin).min(haystack.height() - th),
+    );
+    best_match(&haystack, &template, Some(window))
+}
+
+fn downscale(image: &GrayImage, factor: u32) -> GrayImage {
+    let width = (image.width() / factor).max(1);
+    let height = (image.height() / factor).max(1);
+    imageops::resize(image, width, height, imageops::FilterType::Triangle)
+}
+
+/// Exhaustive zero-mean normalized cross-correlation, optionally limited to
+/// top-left positions within `(min_x, min_y, max_x, max_y)`
+fn best_match(
+    haystack: &GrayImage,
+    template: &GrayImage,
+    window: Option<(u32, u32, u32, u32)>,
+) -> Option<(u32, u32, f32)> {
+    let scorer = Scorer::new(haystack, template)?;
+    let (hw, hh) = haystack.dimensions();
+    let (tw, th) = template.dimensions();
+    let (min_x, min_y, max_x, max_y) = window.unwrap_or((0, 0, hw - tw, hh - th));
+
+    let mut best: Option<(u32, u32, f32)> = None;
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let score = scorer.score(x, y);
+            if best.is_none_or(|(_, _, s)| score > s) {
+                best = Some((x, y, score));
+            }
+        }
+    }
+    best
+}
+
+/// Scores template positions within a haystack
+struct Scorer {
+    haystack: Vec<f32>,
+    haystack_width: usize,
+    template_width: u32,
+    template_height: u32,
+    template_mean: f64,
+    template_variance: f64,
+    /// Template pixels minus their mean, row by row
+    deviations: Vec<f32>,
+    integral: Integral,
+}
+
+impl Scorer {
+    /// `None` if the template is empty or larger than the haystack
+    fn new(haystack: &GrayImage, template: &GrayImage) -> Option<Self> {
+        let (hw, hh) = haystack.dimensions();
+        let (tw, th) = template.dimensions();
+        if tw == 0 || th == 0 || tw > hw || th > hh {
+            return None;
+        }
+
+        let n = (tw * th) as f64;
+        let template_mean = template.pixels().map(|p| p.0[0] as f64).sum::<f64>() / n;
+        let deviations: Vec<f32> = template
+            .pixels()
+            .map(|p| (p.0[0] as f64 - template_mean) as f32)
+            .collect();
+        let template_variance = deviations.iter().map(|&d| d as f64 * d as f64).sum::<f64>();
+
+        Some(Self {
+            haystack: haystack.pixels().map(|p| p.0[0] as f32).collect(),
+            haystack_width: hw as usize,
+            template_width: tw,
+            template_height: th,
+            template_mean,
+            template_variance,
+            deviations,
+            integral: Integral::new(haystack),
+        })
+    }
+
+    /// Correlation of the template with the patch whose top-left corner is `(x, y)`
+    fn score(&self, x: u32, y: u32) -> f32 {
+        let (tw, th) = (self.template_width, self.template_height);
+        let n = (tw * th) as f64;
+        let (sum, sum_sq) = self.integral.sums(x, y, tw, th);
+        let mean = sum / n;
+        let variance = (sum_sq - sum * mean).max(0.0);
+
+        if self.template_variance < FLAT_VARIANCE || variance < FLAT_VARIANCE {
+            // Correlation is undefined for flat patches, compare brightness instead
+            return if self.template_variance < FLAT_VARIANCE && variance < FLAT_VARIANCE {
+                (1.0 - ((mean - self.template_mean).abs() / 255.0)) as f32
+            } else {
+                0.0
+            };
+        }
+
+        let tw = tw as usize;
+        let mut cross = 0.0f64;
+        for ty in 0..th as usize {
+            let start = (y as usize + ty) * self.haystack_width + x as usize;
+            let row = &self.haystack[start..start + tw];
+            let deviations = &self.deviations[ty * tw..(ty + 1) * tw];
+            cross += dot(row, deviations) as f64;
+        }
+        (cross / (variance * self.template_variance).sqrt()) as f32
+    }
+}
+
+/// Dot product of two equally long rows
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            // SAFETY: the CPU supports the features `dot_avx2` is compiled for
+            return unsafe { dot_avx2(a, b) };
+        }
+    }
+
+    // Independent accumulators let the compiler vectorize the portable version
+    let mut sums = [0.0f32; 8];
+    let chunks = a.chunks_exact(8).zip(b.chunks_exact(8));
+    for (a, b) in chunks {
+        for i in 0..8 {
+            sums[i] += a[i] * b[i];
+        }
+    }
+    let tail = a.len() - a.len() % 8;
+    let rest: f32 = a[tail..].iter().zip(&b[tail..]).map(|(x, y)| x * y).sum();
+    sums.iter().sum::<f32>() + rest
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn dot_avx2(a: &[f32], b: &[f32]) -> f32 {
+    use std::arch::x86_64::*;
+
+    let len = a.len().min(b.len());
+    let mut acc = _mm256_setzero_ps();
+    let mut i = 0;
+    while i + 8 <= len {
+        // SAFETY: `i + 8 <= len` keeps both unaligned loads in bounds
+        let (va, vb) = unsafe {
+            (
+                _mm256_loadu_ps(a.as_ptr().add(i)),
+                _mm256_loadu_ps(b.as_ptr().add(i)),
+            )
+        };
+        acc = _mm256_fmadd_ps(va, vb, acc);
+        i += 8;
+    }
+    let mut lanes = [0.0f32; 8];
+    // SAFETY: `lanes` holds exactly one 256-bit vector
+    unsafe { _mm256_storeu_ps(lanes.as_mut_ptr(), acc) };
+    lanes.iter().sum::<f32>() + a[i..len].iter().zip(&b[i..len]).map(|(x, y)| x * y).sum::<f32>()
+}
+
+/// Summed-area tables for fast patch sums and sums of squares
+struct Integral {
+    width: usize,
+    sum: Vec<f64>,
+    sum_sq: Vec<f64>,
+}
+
+impl Integral {
+    fn new(image: &GrayImage) -> Self {
+        let width = image.width() as usize + 1;
+        let height = image.height() as usize + 1;
+        let mut sum = vec![0.0; width * height];
+        let mut sum_sq = vec![0.0; width * height];
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let v = pixel.0[0] as f64;
+            let (x, y) = (x as usize + 1, y as usize + 1);
+            let i = y * width + x;
+            sum[i] = v + sum[i - 1] + sum[i - width] - sum[i - width - 1];
+            sum_sq[i] = v * v + sum_sq[i - 1] + sum_sq[i - width] - sum_sq[i - width - 1];
+        }
+        Self { width, sum, sum_sq }
+    }
+
+    fn sums(&self, x: u32, y: u32, w: u32, h: u32) -> (f64, f64) {
+        let (x0, y0) = (x as usize, y as usize);
+        let (x1, y1) = (x0 + w as usize, y0 + h as usize);
+        let at = |table: &[f64], x: usize, y: usize| table[y * self.width + x];
+        let area = |table: &[f64]| at(table, x1, y1) - at(table, x0, y1) - at(table, x1, y0) + at(table, x0, y0);
+        (area(&self.sum), area(&self.sum_sq))
+    }
+}
@@ -0,0 +1,163 @@
+//! Detecting repaints by comparing screen captures of an element
+//!
+//! Charts, terminals, video and custom-drawn controls often update without
+//! raising any accessibility event. A [`VisualChangeWatcher`] samples the
+//! pixels inside an element's bounds and reports when enough of them change.
+
+use crate::{AutomationError, UIElement};
+use image::RgbaImage;
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+// Per-channel difference below which a pixel counts as unchanged (compression / dithering noise)
+const PIXEL_TOLERANCE: u8 = 16;
+
+/// A repaint of a watched element
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisualChange {
+    /// Fraction of the element's pixels that changed, from 0.0 to 1.0
+    pub changed_fraction: f32,
+    /// Bounding box of the changed pixels in screen coordinates `(x, y, width, height)`
+    pub region: (f64, f64, f64, f64),
+    pub detected_at: SystemTime,
+}
+
+/// Reports repaints of an element, see [`UIElement::watch_visual_changes`].
+/// Stops watching when dropped.
+pub struct VisualChangeWatcher {
+    receiver: mpsc::UnboundedReceiver<VisualChange>,
+    task: JoinHandle<()>,
+}
+
+impl VisualChangeWatcher {
+    pub(crate) fn spawn(
+        element: UIElement,
+        fps: f64,
+        threshold: f32,
+    ) -> Result<Self, AutomationError> {
+        if !(fps > 0.0 && fps.is_finite()) {
+            return Err(AutomationError::InvalidArgument(format!(
+                "fps must be positive, got {}",
+                fps
+            )));
+        }
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(AutomationError::InvalidArgument(format!(
+                "threshold must be between 0.0 and 1.0, got {}",
+                threshold
+            )));
+        }
+        let interval = Duration::from_secs_f64(1.0 / fps);
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let task = tokio::spawn(async move {
+            // The reference frame is kept until a change is reported, so slow fades add up
+            let mut baseline: Option<(Bounds, RgbaImage)> = None;
+            loop {
+                tokio::time::sleep(interval).await;
+                let capture_of = element.clone();
+                let frame = match tokio::task::spawn_blocking(move || capture(&capture_of)).await {
+                    Ok(Ok(frame)) => frame,
+                    Ok(Err(e)) => {
+                        warn!(error = %e, "Failed to capture watched element");
+                        continue;
+                    }
+                    Err(_) => return,
+                };
+
+                let Some((bounds, reference)) = &baseline else {
+                    baseline = Some(frame);
+                    continue;
+                };
+                // A moved or resized element can't be compared pixel by pixel; start over
+                if *bounds != frame.0 || reference.dimensions() != frame.1.dimensions() {
+                    debug!("Watched element moved or resized, resetting reference frame");
+                    baseline = Some(frame);
+                    continue;
+                }
+
+                if let Some(change) = diff_frames(reference, &frame.1, frame.0)
+                    && change.changed_fraction >= threshold
+                {
+                    debug!(changed_fraction = change.changed_fraction, "Visual change detected");
+                    if sender.send(change).is_err() {
+                        return;
+                    }
+                    baseline = Some(frame);
+                }
+            }
+        });
+
+        Ok(Self { receiver, task })
+    }
+
+    /// Wait for the next change. Returns `None` if watching stopped.
+    pub async fn next(&mut self) -> Option<VisualChange> {
+        self.receiver.recv().await
+    }
+
+    /// Return a pending change without waiting, if there is one
+    pub fn try_next(&mut self) -> Option<VisualChange> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for VisualChangeWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+type Bounds = (f64, f64, f64, f64);
+
+fn capture(element: &UIElement) -> Result<(Bounds, RgbaImage), AutomationError> {
+    let bounds = element.bounds()?;
+    let (x, y, width, height) = bounds;
+    let image = crate::screen::capture_region(
+        x.round() as i32,
+        y.round() as i32,
+        width.round() as u32,
+        height.round() as u32,
+    )?;
+    Ok((bounds, image))
+}
+
+/// Compare two same-sized frames of an element at `bounds`; `None` if nothing changed
+fn diff_frames(before: &RgbaImage, after: &RgbaImage, bounds: Bounds) -> Option<VisualChange> {
+    let (width, height) = after.dimensions();
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+    let mut changed = 0u64;
+
+    for (x, y, pixel) in after.enumerate_pixels() {
+        let previous = before.get_pixel(x, y);
+        let differs = pixel.0[..3]
+            .iter()
+            .zip(&previous.0[..3])
+            .any(|(a, b)| a.abs_diff(*b) > PIXEL_TOLERANCE);
+        if differs {
+            changed += 1;
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+    }
+    if changed == 0 {
+        return None;
+    }
+
+    // Captures may be in physical pixels while bounds are logical
+    let scale = width as f64 / bounds.2.max(1.0);
+    Some(VisualChange {
+        changed_fraction: (changed as f64 / (width as u64 * height as u64) as f64) as f32,
+        region: (
+            bounds.0 + min_x as f64 / scale,
+            bounds.1 + min_y as f64 / scale,
+            (max_x - min_x + 1) as f64 / scale,
+            (max_y - min_y + 1) as f64 / scale,
+        ),
+        detected_at: SystemTime::now(),
+    })
+}
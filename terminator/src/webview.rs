@@ -0,0 +1,187 @@
+//! Web content embedded in native windows
+//!
+//! Apps built on WebView2, WKWebView, CEF or Electron render part of their UI
+//! as a web page. The page's elements do reach the accessibility tree, but the
+//! web engine builds that tree lazily (on macOS only once asked to), so at
+//! first the region is an opaque pane with no children. [`WebView`] finds
+//! those regions, turns their accessibility on and scopes locators to them.
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, Locator, Selector, UIElement};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+// How long to search a window for embedded web content
+const WEBVIEW_SEARCH_TIMEOUT: Duration = Duration::from_millis(500);
+// How often the page is checked while waiting for its tree to be built
+const WEBVIEW_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[cfg(target_os = "windows")]
+mod platform {
+    /// Window class of the surface Chromium renders page content into
+    pub const WEB_CONTENT_CLASS: &str = "Chrome_RenderWidgetHostHWND";
+    /// Window class CEF hosts its browsers in
+    pub const CEF_HOST_CLASS: &str = "CefBrowserWindow";
+    /// Process that hosts every WebView2 instance
+    pub const WEBVIEW2_PROCESS: &str = "msedgewebview2.exe";
+    /// How far up to look for the CEF host window
+    pub const HOST_SEARCH_DEPTH: usize = 10;
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    pub const WEB_AREA_ROLE: &str = "AXWebArea";
+    /// Attribute Chromium sets on its accessibility nodes and WebKit doesn't
+    pub const CHROMIUM_NODE_ATTRIBUTE: &str = "ChromeAXNodeId";
+}
+
+/// The engine rendering a [`WebView`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WebViewKind {
+    /// Microsoft Edge WebView2 (Windows)
+    WebView2,
+    /// WebKit's WKWebView, including Safari (macOS)
+    WkWebView,
+    /// The Chromium Embedded Framework (Windows)
+    Cef,
+    /// Other Chromium-based content: Electron apps and Chromium browsers
+    Chromium,
+}
+
+/// A region of a native window rendered by a web engine, see [`crate::Desktop::webviews`]
+#[derive(Clone)]
+pub struct WebView {
+    kind: WebViewKind,
+    element: UIElement,
+    engine: Arc<dyn AccessibilityEngine>,
+}
+
+impl fmt::Debug for WebView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebView")
+            .field("kind", &self.kind)
+            .field("element", &self.element)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WebView {
+    pub fn kind(&self) -> WebViewKind {
+        self.kind
+    }
+
+    /// The web document element; page content is below it
+    pub fn element(&self) -> &UIElement {
+        &self.element
+    }
+
+    /// Find elements on the page only, e.g. `webview.locator("role:button|Sign in")`.
+    /// Call [`WebView::wait_until_ready`] first so the page's tree exists.
+    pub fn locator(&self, selector: impl Into<Selector>) -> Locator {
+        Locator::new(self.engine.clone(), selector.into()).within(self.element.clone())
+    }
+
+    /// Turn on the web engine's accessibility if needed and wait until the page
+    /// exposes content, up to `timeout`
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<(), AutomationError> {
+        if let Err(e) = self.engine.enable_web_accessibility(&self.element)
+            && !matches!(e.inner(), AutomationError::UnsupportedOperation(_))
+        {
+            return Err(e);
+        }
+
+        let start = Instant::now();
+        loop {
+            if self.element.children().is_ok_and(|children| !children.is_empty()) {
+                return Ok(());
+            }
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "{:?} web view still has no content after {:?}",
+                    self.kind, timeout
+                )));
+            }
+            crate::abort::check()?;
+            tokio::time::sleep(WEBVIEW_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Every region of `window` rendered by a web engine, in tree order
+pub(crate) fn find_webviews(
+    engine: &Arc<dyn AccessibilityEngine>,
+    window: &UIElement,
+) -> Result<Vec<WebView>, AutomationError> {
+    let candidates = match engine.find_elements(&web_content_selector(), Some(window), Some(WEBVIEW_SEARCH_TIMEOUT), None) {
+        Ok(candidates) => candidates,
+        Err(e) if e.is_retryable() => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    let webviews: Vec<WebView> = candidates
+        .into_iter()
+        .map(|element| WebView {
+            kind: classify(engine.as_ref(), &element),
+            element: element.with_engine(engine.clone()),
+            engine: engine.clone(),
+        })
+        .collect();
+    debug!(count = webviews.len(), "Web views found");
+    Ok(webviews)
+}
+
+#[cfg(target_os = "windows")]
+fn web_content_selector() -> Selector {
+    Selector::ClassName(platform::WEB_CONTENT_CLASS.to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn web_content_selector() -> Selector {
+    Selector::Role {
+        role: platform::WEB_AREA_ROLE.to_string(),
+        name: None,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn classify(engine: &dyn AccessibilityEngine, element: &UIElement) -> WebViewKind {
+    let hosted_by_cef = std::iter::successors(element.parent().ok().flatten(), |e| e.parent().ok().flatten())
+        .take(platform::HOST_SEARCH_DEPTH)
+        .any(|ancestor| {
+            engine
+                .get_attribute(&ancestor, "ClassName")
+                .ok()
+                .flatten()
+                .is_some_and(|class| class.starts_with(platform::CEF_HOST_CLASS))
+        });
+    if hosted_by_cef {
+        return WebViewKind::Cef;
+    }
+
+    let pid = engine
+        .get_attribute(element, "ProcessId")
+        .ok()
+        .flatten()
+        .and_then(|pid| pid.parse::<u32>().ok());
+    let in_webview2 = pid.is_some_and(|pid| {
+        engine.list_processes().is_ok_and(|processes| {
+            processes
+                .iter()
+                .any(|p| p.pid == pid && p.name.eq_ignore_ascii_case(platform::WEBVIEW2_PROCESS))
+        })
+    });
+    if in_webview2 {
+        WebViewKind::WebView2
+    } else {
+        WebViewKind::Chromium
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn classify(engine: &dyn AccessibilityEngine, element: &UIElement) -> WebViewKind {
+    match engine.get_attribute(element, platform::CHROMIUM_NODE_ATTRIBUTE) {
+        Ok(Some(_)) => WebViewKind::Chromium,
+        _ => WebViewKind::WkWebView,
+    }
+}
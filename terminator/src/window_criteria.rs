@@ -0,0 +1,78 @@
+//! Conditions for finding top-level windows
+//!
+//! A title substring is rarely enough on a busy desktop (several "Untitled"
+//! editors, a browser per profile), so windows can also be told apart by a
+//! title pattern, their window class, the process that owns them and whether
+//! they are on screen.
+
+use crate::{AutomationError, SelectorRegex};
+
+/// Conditions a top-level window must meet, see [`crate::Desktop::find_windows_by_criteria`].
+/// Conditions left unset match every window.
+#[derive(Debug, Clone, Default)]
+pub struct WindowCriteria {
+    /// Title contains this text, ignoring case
+    pub title_contains: Option<String>,
+    /// Title matches this regular expression
+    pub title_regex: Option<SelectorRegex>,
+    /// Window class equals this, ignoring case. On macOS this is the window's
+    /// subrole, e.g. `AXStandardWindow` or `AXDialog`.
+    pub class_name: Option<String>,
+    /// Executable name of the owning process, ignoring case and any `.exe` suffix
+    pub process_name: Option<String>,
+    /// Skip windows that are minimized or otherwise off screen
+    pub visible_only: bool,
+}
+
+impl WindowCriteria {
+    /// Check that the criteria can be evaluated, i.e. the title regex compiles
+    pub(crate) fn validate(&self) -> Result<(), AutomationError> {
+        if let Some(regex) = &self.title_regex {
+            regex.regex()?;
+        }
+        Ok(())
+    }
+
+    /// Whether a window matches. The process name and visibility are only looked
+    /// up when a condition needs them, as they cost an extra call per window.
+    pub(crate) fn matches(
+        &self,
+        title: &str,
+        class_name: Option<&str>,
+        process_name: impl FnOnce() -> Option<String>,
+        is_visible: impl FnOnce() -> bool,
+    ) -> bool {
+        if let Some(wanted) = &self.title_contains
+            && !title.to_lowercase().contains(&wanted.to_lowercase())
+        {
+            return false;
+        }
+        if let Some(regex) = &self.title_regex
+            && !regex.regex().is_ok_and(|regex| regex.is_match(title))
+        {
+            return false;
+        }
+        if let Some(wanted) = &self.class_name
+            && !class_name.is_some_and(|class| class.eq_ignore_ascii_case(wanted))
+        {
+            return false;
+        }
+        if let Some(wanted) = &self.process_name
+            && !process_name().is_some_and(|name| strip_exe(&name).eq_ignore_ascii_case(strip_exe(wanted)))
+        {
+            return false;
+        }
+        !self.visible_only || is_visible()
+    }
+}
+
+/// `name` without surrounding whitespace or a trailing `.exe`
+pub(crate) fn strip_exe(name: &str) -> &str {
+    let name = name.trim();
+    match name.len().checked_sub(4) {
+        Some(stem) if name.is_char_boundary(stem) && name[stem..].eq_ignore_ascii_case(".exe") => {
+            &name[..stem]
+        }
+        _ => name,
+    }
+}
@@ -16,12 +16,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Press Ctrl+C to stop recording");
     
     // Create a configuration with enhanced metadata capture
-    let config = WorkflowRecorderConfig {
-        record_mouse: true,
-        record_keyboard: true,
-        record_window: true,
-        capture_ui_elements: true,
-    };
+    let mut config = WorkflowRecorderConfig::default();
+    config.capture_ui_elements_on_wheel = true;
+    config.checkpoint_hotkey = Some(0x78); // F9
     
     // Create a recorder
     let mut recorder = WorkflowRecorder::new("Example Workflow".to_string(), config);
@@ -0,0 +1,278 @@
+use crate::{CheckpointAssertion, CheckpointEvent, Rect, Result, UiElement, WorkflowRecorderError};
+
+#[cfg(target_os = "windows")]
+use {
+    uiautomation::{controls::ControlType, UIAutomation, UIElement as WinUIElement},
+    windows::Win32::Graphics::Gdi::{
+        BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC,
+        GetDIBits, ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
+        DIB_RGB_COLORS, SRCCOPY,
+    },
+};
+
+/// Width and height of screenshot fingerprints
+pub const FINGERPRINT_SIZE: usize = 16;
+
+/// Default mean per-pixel difference tolerated by screenshot checkpoints
+pub const DEFAULT_SCREENSHOT_TOLERANCE: u8 = 12;
+
+/// How long to look for a checkpoint's element (milliseconds)
+#[cfg(target_os = "windows")]
+const ELEMENT_SEARCH_TIMEOUT: u64 = 3000;
+
+/// The result of verifying a checkpoint
+#[derive(Debug, Clone)]
+pub struct CheckpointOutcome {
+    /// Whether the assertion held
+    pub passed: bool,
+
+    /// What was found, for reporting failures
+    pub message: String,
+}
+
+impl CheckpointOutcome {
+    fn pass(message: impl Into<String>) -> Self {
+        Self {
+            passed: true,
+            message: message.into(),
+        }
+    }
+
+    fn fail(message: impl Into<String>) -> Self {
+        Self {
+            passed: false,
+            message: message.into(),
+        }
+    }
+}
+
+/// Check a checkpoint against the current state of the screen
+pub fn verify_checkpoint(checkpoint: &CheckpointEvent) -> Result<CheckpointOutcome> {
+    #[cfg(target_os = "windows")]
+    {
+        let automation = UIAutomation::new().map_err(|e| {
+            WorkflowRecorderError::InitializationError(format!(
+                "Failed to initialize UI Automation: {}",
+                e
+            ))
+        })?;
+
+        let outcome = match &checkpoint.assertion {
+            CheckpointAssertion::ScreenshotMatches {
+                region,
+                fingerprint,
+                tolerance,
+            } => {
+                let current = capture_fingerprint(region)?;
+                let difference = fingerprint_difference(fingerprint, &current);
+                if difference <= *tolerance {
+                    CheckpointOutcome::pass(format!("Screen region matches (difference {})", difference))
+                } else {
+                    CheckpointOutcome::fail(format!(
+                        "Screen region changed (difference {}, tolerance {})",
+                        difference, tolerance
+                    ))
+                }
+            }
+            CheckpointAssertion::ElementExists | CheckpointAssertion::TextEquals { .. } => {
+                let recorded = checkpoint.ui_element.as_ref().ok_or_else(|| {
+                    WorkflowRecorderError::RecordingError(
+                        "Element checkpoint has no recorded element".to_string(),
+                    )
+                })?;
+                match find_recorded_element(&automation, recorded) {
+                    None => CheckpointOutcome::fail(format!(
+                        "Element not found: {}",
+                        recorded.hierarchy_path.as_deref().unwrap_or("unknown")
+                    )),
+                    Some(element) => match &checkpoint.assertion {
+                        CheckpointAssertion::TextEquals { expected } => {
+                            let actual = element_text(&element);
+                            if &actual == expected {
+                                CheckpointOutcome::pass(format!("Text is '{}'", actual))
+                            } else {
+                                CheckpointOutcome::fail(format!(
+                                    "Expected text '{}', found '{}'",
+                                    expected, actual
+                                ))
+                            }
+                        }
+                        _ => CheckpointOutcome::pass("Element exists"),
+                    },
+                }
+            }
+        };
+        Ok(outcome)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = checkpoint;
+        Err(WorkflowRecorderError::InitializationError(
+            "Checkpoint verification is only supported on Windows".to_string(),
+        ))
+    }
+}
+
+/// Build the checkpoint recorded for an element: its text if it has any, else its existence
+pub fn element_checkpoint(element: UiElement) -> CheckpointEvent {
+    let text = element
+        .value
+        .clone()
+        .filter(|value| !value.is_empty())
+        .or_else(|| element.name.clone().filter(|name| !name.is_empty()));
+    let assertion = match text {
+        Some(expected) => CheckpointAssertion::TextEquals { expected },
+        None => CheckpointAssertion::ElementExists,
+    };
+    CheckpointEvent {
+        assertion,
+        ui_element: Some(element),
+        description: None,
+    }
+}
+
+/// Mean absolute difference between two fingerprints, 255 if they can't be compared
+pub fn fingerprint_difference(a: &[u8], b: &[u8]) -> u8 {
+    if a.len() != b.len() || a.is_empty() {
+        return u8::MAX;
+    }
+    let total: u64 = a
+        .iter()
+        .zip(b)
+        .map(|(x, y)| x.abs_diff(*y) as u64)
+        .sum();
+    (total / a.len() as u64) as u8
+}
+
+/// Capture a screen region as a grayscale thumbnail for screenshot checkpoints
+#[cfg(target_os = "windows")]
+pub fn capture_fingerprint(region: &Rect) -> Result<Vec<u8>> {
+    let (width, height) = (region.width, region.height);
+    if width <= 0 || height <= 0 {
+        return Err(WorkflowRecorderError::RecordingError(
+            "Cannot capture an empty region".to_string(),
+        ));
+    }
+
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+    unsafe {
+        let screen = GetDC(None);
+        let memory = CreateCompatibleDC(Some(screen));
+        let bitmap = CreateCompatibleBitmap(screen, width, height);
+        let previous = SelectObject(memory, bitmap.into());
+
+        let copied = BitBlt(memory, 0, 0, width, height, Some(screen), region.x, region.y, SRCCOPY);
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                biHeight: -height, // top-down rows
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let lines = GetDIBits(
+            memory,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr() as *mut _),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+
+        SelectObject(memory, previous);
+        let _ = DeleteObject(bitmap.into());
+        let _ = DeleteDC(memory);
+        ReleaseDC(None, screen);
+
+        if copied.is_err() || lines == 0 {
+            return Err(WorkflowRecorderError::WindowsError(
+                "Failed to capture screen region".to_string(),
+            ));
+        }
+    }
+
+    // Average each cell of a FINGERPRINT_SIZE grid down to one gray value
+    let (width, height) = (width as usize, height as usize);
+    let mut fingerprint = Vec::with_capacity(FINGERPRINT_SIZE * FINGERPRINT_SIZE);
+    for cell_y in 0..FINGERPRINT_SIZE {
+        for cell_x in 0..FINGERPRINT_SIZE {
+            let (x0, x1) = cell_bounds(cell_x, width);
+            let (y0, y1) = cell_bounds(cell_y, height);
+            let mut sum = 0u64;
+            let mut count = 0u64;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let i = (y * width + x) * 4;
+                    // BGRA; integer luma approximation
+                    let (b, g, r) = (pixels[i] as u64, pixels[i + 1] as u64, pixels[i + 2] as u64);
+                    sum += (r * 299 + g * 587 + b * 114) / 1000;
+                    count += 1;
+                }
+            }
+            fingerprint.push((sum / count.max(1)) as u8);
+        }
+    }
+    Ok(fingerprint)
+}
+
+/// Pixel range covered by one fingerprint cell along an axis of `len` pixels
+#[cfg(target_os = "windows")]
+fn cell_bounds(cell: usize, len: usize) -> (usize, usize) {
+    let start = cell * len / FINGERPRINT_SIZE;
+    let end = ((cell + 1) * len / FINGERPRINT_SIZE).max(start + 1).min(len);
+    (start, end)
+}
+
+/// Find the live element matching a recorded one, searching its recorded window first
+#[cfg(target_os = "windows")]
+fn find_recorded_element(automation: &UIAutomation, recorded: &UiElement) -> Option<WinUIElement> {
+    let root = automation.get_root_element().ok()?;
+    let window = recorded.window_title.clone().and_then(|title| {
+        automation
+            .create_matcher()
+            .from_ref(&root)
+            .control_type(ControlType::Window)
+            .filter_fn(Box::new(move |e: &WinUIElement| {
+                Ok(e.get_name().unwrap_or_default() == title)
+            }))
+            .depth(2)
+            .timeout(ELEMENT_SEARCH_TIMEOUT)
+            .find_first()
+            .ok()
+    });
+
+    let automation_id = recorded.automation_id.clone().filter(|id| !id.is_empty());
+    let name = recorded.name.clone().unwrap_or_default();
+    let control_type = recorded.control_type.clone().unwrap_or_default();
+    automation
+        .create_matcher()
+        .from_ref(window.as_ref().unwrap_or(&root))
+        .filter_fn(Box::new(move |e: &WinUIElement| {
+            if let Some(id) = &automation_id {
+                return Ok(e.get_automation_id().unwrap_or_default() == *id);
+            }
+            Ok(e.get_name().unwrap_or_default() == name
+                && e.get_control_type_name().unwrap_or_default() == control_type)
+        }))
+        .depth(50)
+        .timeout(ELEMENT_SEARCH_TIMEOUT)
+        .find_first()
+        .ok()
+}
+
+/// The text a `TextEquals` checkpoint compares: the value, or the name without one
+#[cfg(target_os = "windows")]
+fn element_text(element: &WinUIElement) -> String {
+    element
+        .get_value()
+        .ok()
+        .filter(|value| !value.is_empty())
+        .or_else(|| element.get_name().ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,217 @@
+use crate::{RecordedWorkflow, Result, WorkflowRecorderError};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt;
+use std::path::Path;
+
+/// Marks files written with an [`EncryptionKey`]; also authenticated as associated data
+const MAGIC: &[u8; 8] = b"WFREC\x00\x01\x00";
+
+/// A 256-bit AES-GCM key for encrypting recordings at rest
+#[derive(Clone)]
+pub struct EncryptionKey {
+    bytes: [u8; 32],
+}
+
+impl EncryptionKey {
+    /// Use an existing 32-byte key
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self { bytes }
+    }
+
+    /// Parse a key written as 64 hex digits
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let bytes = decode_hex(hex.trim())
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| {
+                WorkflowRecorderError::EncryptionError("Key must be 64 hex digits".to_string())
+            })?;
+        Ok(Self { bytes })
+    }
+
+    /// Generate a random key
+    pub fn generate() -> Result<Self> {
+        let mut bytes = [0u8; 32];
+        SystemRandom::new().fill(&mut bytes).map_err(|_| {
+            WorkflowRecorderError::EncryptionError("Failed to generate key".to_string())
+        })?;
+        Ok(Self { bytes })
+    }
+
+    /// Encrypt `plaintext` into the recorder's file format: header, nonce, ciphertext and tag
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| {
+            WorkflowRecorderError::EncryptionError("Failed to generate nonce".to_string())
+        })?;
+
+        let mut in_out = plaintext.to_vec();
+        self.key()?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(MAGIC),
+                &mut in_out,
+            )
+            .map_err(|_| WorkflowRecorderError::EncryptionError("Encryption failed".to_string()))?;
+
+        let mut output = Vec::with_capacity(MAGIC.len() + NONCE_LEN + in_out.len());
+        output.extend_from_slice(MAGIC);
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&in_out);
+        Ok(output)
+    }
+
+    /// Decrypt data produced by [`EncryptionKey::encrypt`]
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !is_encrypted(data) || data.len() < MAGIC.len() + NONCE_LEN {
+            return Err(WorkflowRecorderError::EncryptionError(
+                "Data is not an encrypted recording".to_string(),
+            ));
+        }
+        let (nonce, ciphertext) = data[MAGIC.len()..].split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| WorkflowRecorderError::EncryptionError("Invalid nonce".to_string()))?;
+
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .key()?
+            .open_in_place(nonce, Aad::from(MAGIC), &mut in_out)
+            .map_err(|_| {
+                WorkflowRecorderError::EncryptionError(
+                    "Decryption failed: wrong key or corrupted file".to_string(),
+                )
+            })?;
+        Ok(plaintext.to_vec())
+    }
+
+    fn key(&self) -> Result<LessSafeKey> {
+        let key = UnboundKey::new(&AES_256_GCM, &self.bytes)
+            .map_err(|_| WorkflowRecorderError::EncryptionError("Invalid key".to_string()))?;
+        Ok(LessSafeKey::new(key))
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(<redacted>)")
+    }
+}
+
+/// Whether `data` starts with the encrypted recording header
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Lowercase hex digits for `data`
+pub(crate) fn encode_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The bytes written as hex digits in `hex`, or `None` if it is not valid hex
+pub(crate) fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Write `data` to `path`, encrypted when a key is given
+pub(crate) fn write_file<P: AsRef<Path>>(
+    path: P,
+    data: &[u8],
+    key: Option<&EncryptionKey>,
+) -> Result<()> {
+    match key {
+        Some(key) => std::fs::write(path, key.encrypt(data)?)?,
+        None => std::fs::write(path, data)?,
+    }
+    Ok(())
+}
+
+/// Load a recording saved by [`crate::WorkflowRecorder::save`], decrypting it if needed
+pub fn load_workflow<P: AsRef<Path>>(
+    path: P,
+    key: Option<&EncryptionKey>,
+) -> Result<RecordedWorkflow> {
+    let data = std::fs::read(path)?;
+    let json = match (is_encrypted(&data), key) {
+        (true, Some(key)) => key.decrypt(&data)?,
+        (true, None) => {
+            return Err(WorkflowRecorderError::EncryptionError(
+                "Recording is encrypted but no key was given".to_string(),
+            ));
+        }
+        (false, _) => data,
+    };
+    Ok(serde_json::from_slice(&json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &[u8] = br#"{"name":"Invoice run","events":[]}"#;
+
+    fn key(byte: u8) -> EncryptionKey {
+        EncryptionKey::from_bytes([byte; 32])
+    }
+
+    fn is_encryption_error(result: Result<Vec<u8>>) -> bool {
+        matches!(result, Err(WorkflowRecorderError::EncryptionError(_)))
+    }
+
+    #[test]
+    fn round_trips() {
+        let key = key(7);
+        let encrypted = key.encrypt(PLAINTEXT).unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.windows(PLAINTEXT.len()).any(|window| window == PLAINTEXT));
+        assert_eq!(key.decrypt(&encrypted).unwrap(), PLAINTEXT);
+
+        // A fresh nonce every time, so equal recordings don't give equal files
+        assert_ne!(key.encrypt(PLAINTEXT).unwrap(), encrypted);
+        assert_eq!(key.decrypt(&key.encrypt(b"").unwrap()).unwrap(), b"");
+    }
+
+    #[test]
+    fn wrong_key_fails() {
+        let encrypted = key(7).encrypt(PLAINTEXT).unwrap();
+        assert!(is_encryption_error(key(8).decrypt(&encrypted)));
+        assert!(is_encryption_error(EncryptionKey::generate().unwrap().decrypt(&encrypted)));
+    }
+
+    #[test]
+    fn tampered_data_is_rejected() {
+        let key = key(7);
+        let encrypted = key.encrypt(PLAINTEXT).unwrap();
+        // Every byte is covered: the header as associated data, the nonce, the
+        // ciphertext and the tag
+        for i in 0..encrypted.len() {
+            let mut tampered = encrypted.clone();
+            tampered[i] ^= 0x01;
+            assert!(is_encryption_error(key.decrypt(&tampered)), "flipped byte {}", i);
+        }
+        assert!(is_encryption_error(key.decrypt(&encrypted[..encrypted.len() - 1])));
+        assert!(is_encryption_error(key.decrypt(&encrypted[..MAGIC.len() + NONCE_LEN - 1])));
+        assert!(is_encryption_error(key.decrypt(PLAINTEXT)));
+    }
+
+    #[test]
+    fn parses_hex_keys() {
+        let hex = "00112233445566778899aabbccddeeff00112233445566778899AABBCCDDEEFF";
+        let encrypted = EncryptionKey::from_hex(hex).unwrap().encrypt(PLAINTEXT).unwrap();
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = ((i % 16) as u8) * 0x11;
+        }
+        assert_eq!(EncryptionKey::from_bytes(bytes).decrypt(&encrypted).unwrap(), PLAINTEXT);
+
+        assert!(EncryptionKey::from_hex(&hex[1..]).is_err());
+        assert!(EncryptionKey::from_hex(&hex.replace('f', "g")).is_err());
+        assert!(EncryptionKey::from_hex(&format!("+{}", &hex[1..])).is_err());
+        assert_eq!(decode_hex(&encode_hex(&bytes)).unwrap(), bytes);
+    }
+}
@@ -25,6 +25,14 @@ pub enum WorkflowRecorderError {
     #[error("Windows API error: {0}")]
     WindowsError(String),
 
+    /// Error when encrypting or decrypting a recording
+    #[error("Encryption error: {0}")]
+    EncryptionError(String),
+
+    /// Error when substituting parameters into a recording
+    #[error("Parameter error: {0}")]
+    ParameterError(String),
+
     /// Error when serializing or deserializing JSON
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
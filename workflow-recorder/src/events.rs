@@ -1,3 +1,4 @@
+use crate::{VideoPosition, VideoSync};
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
 
@@ -58,7 +59,7 @@ pub struct Rect {
 }
 
 /// Represents the type of mouse button
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MouseButton {
     Left,
     Right,
@@ -66,7 +67,7 @@ pub enum MouseButton {
 }
 
 /// Represents the type of mouse event
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MouseEventType {
     Click,
     DoubleClick,
@@ -97,6 +98,14 @@ pub struct KeyboardEvent {
     
     /// Whether the Win key was pressed
     pub win_pressed: bool,
+    
+    /// The text the key produced in the active keyboard layout, with modifiers applied
+    #[serde(default)]
+    pub character: Option<String>,
+    
+    /// The layout's name for the key, e.g. `Enter` or `Num 7`
+    #[serde(default)]
+    pub key_name: Option<String>,
 }
 
 /// Represents a mouse event
@@ -113,6 +122,49 @@ pub struct MouseEvent {
     
     /// The UI element under the mouse
     pub ui_element: Option<UiElement>,
+    
+    /// The monitor the mouse is on
+    #[serde(default)]
+    pub monitor: Option<MonitorInfo>,
+    
+    /// The top-level window under the mouse
+    #[serde(default)]
+    pub window: Option<TopLevelWindow>,
+}
+
+/// Represents a display monitor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorInfo {
+    /// The monitor's device name, e.g. `\\.\DISPLAY1`
+    pub id: String,
+    
+    /// The monitor's area in virtual-screen coordinates
+    pub bounds: Rect,
+    
+    /// The monitor's area excluding the taskbar and docked toolbars
+    pub work_area: Rect,
+    
+    /// Whether this is the primary monitor
+    pub is_primary: bool,
+}
+
+/// Represents a top-level window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopLevelWindow {
+    /// The window handle, only meaningful during the recording session
+    pub handle: isize,
+    
+    /// The window title
+    pub title: Option<String>,
+    
+    /// The process ID of the application that owns the window
+    pub process_id: Option<u32>,
+    
+    /// The application name
+    pub application_name: Option<String>,
+    
+    /// The window's area in screen coordinates
+    pub bounds: Option<Rect>,
 }
 
 /// Represents a window event
@@ -131,6 +183,43 @@ pub struct WindowEvent {
     pub application_name: Option<String>,
 }
 
+/// What a checkpoint asserts about the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CheckpointAssertion {
+    /// The element exists
+    ElementExists,
+    
+    /// The element's value (or its name, if it has no value) equals `expected`
+    TextEquals {
+        expected: String,
+    },
+    
+    /// A screen region looks as it did when recorded
+    ScreenshotMatches {
+        /// The region, in screen coordinates
+        region: Rect,
+        
+        /// Grayscale 16x16 thumbnail of the region, row by row
+        fingerprint: Vec<u8>,
+        
+        /// The largest mean difference per pixel (0-255) still counted as a match
+        tolerance: u8,
+    },
+}
+
+/// Represents a verification checkpoint inserted into a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointEvent {
+    /// What the checkpoint asserts
+    pub assertion: CheckpointAssertion,
+    
+    /// The UI element the assertion is about
+    pub ui_element: Option<UiElement>,
+    
+    /// A description shown when the checkpoint fails
+    pub description: Option<String>,
+}
+
 /// Represents a workflow event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WorkflowEvent {
@@ -148,6 +237,9 @@ pub enum WorkflowEvent {
     
     /// A window closed event
     WindowClosed(WindowEvent),
+    
+    /// A verification checkpoint, enforced on playback
+    Checkpoint(CheckpointEvent),
 }
 
 /// Represents a recorded event with timestamp
@@ -156,10 +248,26 @@ pub struct RecordedEvent {
     /// The timestamp of the event (milliseconds since epoch)
     pub timestamp: u64,
     
+    /// Where the event is in the screen recording, when one is synced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video: Option<VideoPosition>,
+    
     /// The event
     pub event: WorkflowEvent,
 }
 
+/// Information about the machine and session a workflow was recorded in
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionMetadata {
+    /// The input locale identifier of the keyboard layout active when recording
+    /// started, as hex (e.g. `04090409` for US English)
+    pub keyboard_layout: Option<String>,
+    
+    /// The screen recording made alongside, if any
+    #[serde(default)]
+    pub video: Option<VideoSync>,
+}
+
 /// Represents a recorded workflow
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordedWorkflow {
@@ -174,6 +282,10 @@ pub struct RecordedWorkflow {
     
     /// The recorded events
     pub events: Vec<RecordedEvent>,
+    
+    /// Information about the recording session
+    #[serde(default)]
+    pub metadata: SessionMetadata,
 }
 
 impl RecordedWorkflow {
@@ -189,6 +301,7 @@ impl RecordedWorkflow {
             start_time: now,
             end_time: None,
             events: Vec::new(),
+            metadata: SessionMetadata::default(),
         }
     }
     
@@ -201,10 +314,20 @@ impl RecordedWorkflow {
         
         self.events.push(RecordedEvent {
             timestamp: now,
+            video: self.metadata.video.as_ref().and_then(|video| video.position(now)),
             event,
         });
     }
     
+    /// Line the events up with a screen recording, or with `None` remove the sync.
+    /// Events recorded so far get their position too.
+    pub fn sync_video(&mut self, video: Option<VideoSync>) {
+        for event in &mut self.events {
+            event.video = video.as_ref().and_then(|video| video.position(event.timestamp));
+        }
+        self.metadata.video = video;
+    }
+    
     /// Finish the recording
     pub fn finish(&mut self) {
         let now = SystemTime::now()
@@ -0,0 +1,333 @@
+use crate::{MouseButton, MouseEventType, RecordedWorkflow, UiElement, WorkflowEvent};
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+/// One row of a process-mining event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLogEntry {
+    /// The case (process instance) the event belongs to
+    pub case_id: String,
+
+    /// What was done, e.g. `Click Button 'Save'`
+    pub activity: String,
+
+    /// The timestamp of the event (milliseconds since epoch)
+    pub timestamp: u64,
+
+    /// The application the activity happened in
+    pub resource: Option<String>,
+}
+
+/// Convert a recorded workflow into an event log with one case.
+///
+/// Low-level events are reduced to activities: mouse presses become clicks,
+/// runs of keystrokes become a single `Type` activity, and mouse moves and
+/// releases are dropped.
+pub fn to_event_log(workflow: &RecordedWorkflow, case_id: &str) -> Vec<EventLogEntry> {
+    let mut entries: Vec<EventLogEntry> = Vec::new();
+    let mut current_app: Option<String> = None;
+    let mut typing = false;
+
+    for event in &workflow.events {
+        let (activity, app) = match &event.event {
+            WorkflowEvent::Mouse(mouse_event) => {
+                let verb = match (mouse_event.event_type, mouse_event.button) {
+                    (MouseEventType::Wheel, _) => "Scroll",
+                    (MouseEventType::DoubleClick, _) => "Double-click",
+                    (MouseEventType::RightClick, _)
+                    | (MouseEventType::Down, MouseButton::Right) => "Right-click",
+                    (MouseEventType::Click, _) | (MouseEventType::Down, _) => "Click",
+                    (MouseEventType::Up, _) | (MouseEventType::Move, _) => continue,
+                };
+                let element = mouse_event.ui_element.as_ref();
+                (
+                    describe(verb, element),
+                    element.and_then(|element| element.application_name.clone()),
+                )
+            }
+            WorkflowEvent::Keyboard(keyboard_event) => {
+                if !keyboard_event.is_key_down {
+                    continue;
+                }
+                if typing {
+                    continue; // part of the current run of typing
+                }
+                typing = true;
+                entries.push(EventLogEntry {
+                    case_id: case_id.to_string(),
+                    activity: "Type".to_string(),
+                    timestamp: event.timestamp,
+                    resource: current_app.clone(),
+                });
+                continue;
+            }
+            WorkflowEvent::WindowFocusChanged(window_event) => {
+                ("Switch window".to_string(), window_event.application_name.clone())
+            }
+            WorkflowEvent::WindowCreated(window_event) => {
+                ("Open window".to_string(), window_event.application_name.clone())
+            }
+            WorkflowEvent::WindowClosed(window_event) => {
+                ("Close window".to_string(), window_event.application_name.clone())
+            }
+            WorkflowEvent::Checkpoint(checkpoint) => (
+                describe("Verify", checkpoint.ui_element.as_ref()),
+                checkpoint
+                    .ui_element
+                    .as_ref()
+                    .and_then(|element| element.application_name.clone()),
+            ),
+        };
+
+        typing = false;
+        if app.is_some() {
+            current_app = app;
+        }
+        entries.push(EventLogEntry {
+            case_id: case_id.to_string(),
+            activity,
+            timestamp: event.timestamp,
+            resource: current_app.clone(),
+        });
+    }
+
+    entries
+}
+
+/// Write an event log as CSV with `case_id,activity,timestamp,resource` columns
+pub fn write_csv<W: Write>(entries: &[EventLogEntry], mut writer: W) -> io::Result<()> {
+    writeln!(writer, "case_id,activity,timestamp,resource")?;
+    for entry in entries {
+        writeln!(
+            writer,
+            "{},{},{},{}",
+            csv_field(&entry.case_id),
+            csv_field(&entry.activity),
+            format_timestamp(entry.timestamp),
+            csv_field(entry.resource.as_deref().unwrap_or_default()),
+        )?;
+    }
+    Ok(())
+}
+
+/// Write an event log in the IEEE XES format read by ProM, Celonis and similar tools
+pub fn write_xes<W: Write>(entries: &[EventLogEntry], mut writer: W) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<log xes.version="1.0" xmlns="http://www.xes-standard.org/">"#)?;
+    writeln!(writer, r#"  <extension name="Concept" prefix="concept" uri="http://www.xes-standard.org/concept.xesext"/>"#)?;
+    writeln!(writer, r#"  <extension name="Time" prefix="time" uri="http://www.xes-standard.org/time.xesext"/>"#)?;
+    writeln!(writer, r#"  <extension name="Organizational" prefix="org" uri="http://www.xes-standard.org/org.xesext"/>"#)?;
+
+    let mut open_case: Option<&str> = None;
+    for entry in entries {
+        if open_case != Some(entry.case_id.as_str()) {
+            if open_case.is_some() {
+                writeln!(writer, "  </trace>")?;
+            }
+            writeln!(writer, "  <trace>")?;
+            writeln!(
+                writer,
+                r#"    <string key="concept:name" value="{}"/>"#,
+                xml_escape(&entry.case_id)
+            )?;
+            open_case = Some(&entry.case_id);
+        }
+        writeln!(writer, "    <event>")?;
+        writeln!(
+            writer,
+            r#"      <string key="concept:name" value="{}"/>"#,
+            xml_escape(&entry.activity)
+        )?;
+        writeln!(
+            writer,
+            r#"      <date key="time:timestamp" value="{}"/>"#,
+            format_timestamp(entry.timestamp)
+        )?;
+        if let Some(resource) = &entry.resource {
+            writeln!(
+                writer,
+                r#"      <string key="org:resource" value="{}"/>"#,
+                xml_escape(resource)
+            )?;
+        }
+        writeln!(writer, "    </event>")?;
+    }
+    if open_case.is_some() {
+        writeln!(writer, "  </trace>")?;
+    }
+
+    writeln!(writer, "</log>")
+}
+
+/// Activity name for an action on an element, e.g. `Click Button 'Save'`
+fn describe(verb: &str, element: Option<&UiElement>) -> String {
+    let Some(element) = element else {
+        return verb.to_string();
+    };
+    let mut activity = verb.to_string();
+    if let Some(control_type) = element.control_type.as_deref().filter(|t| !t.is_empty()) {
+        activity.push(' ');
+        activity.push_str(control_type);
+    }
+    if let Some(name) = element.name.as_deref().filter(|name| !name.is_empty()) {
+        activity.push_str(&format!(" '{}'", name));
+    }
+    activity
+}
+
+/// ISO 8601 timestamp in UTC with millisecond precision
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::from_timestamp_millis(timestamp as i64)
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Millis, true))
+        .unwrap_or_default()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape `value` for an attribute. Line breaks and tabs become character references,
+/// since parsers turn them into spaces when they appear literally in attributes.
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+        .replace('\n', "&#10;")
+        .replace('\r', "&#13;")
+        .replace('\t', "&#9;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<EventLogEntry> {
+        vec![
+            EventLogEntry {
+                case_id: "case <1> & \"2\"".to_string(),
+                activity: "Click Button 'Save, then close'".to_string(),
+                timestamp: 1_700_000_000_123,
+                resource: Some("Tom & Jerry's <App>".to_string()),
+            },
+            EventLogEntry {
+                case_id: "case <1> & \"2\"".to_string(),
+                activity: "Type \"quoted\"\nacross lines,\r\nwith commas\tand tabs".to_string(),
+                timestamp: 1_700_000_001_000,
+                resource: None,
+            },
+            EventLogEntry {
+                case_id: "plain".to_string(),
+                activity: "Switch window".to_string(),
+                timestamp: 1_700_000_002_000,
+                resource: Some("Excel".to_string()),
+            },
+        ]
+    }
+
+    /// Read CSV as RFC 4180 describes it: quoted fields may hold commas, newlines and
+    /// doubled quotes
+    fn read_csv(text: &str) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (in_quotes, c) {
+                (true, '"') if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                (true, '"') => in_quotes = false,
+                (true, c) => field.push(c),
+                (false, '"') => in_quotes = true,
+                (false, ',') => row.push(std::mem::take(&mut field)),
+                (false, '\n') => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                (false, c) => field.push(c),
+            }
+        }
+        assert!(!in_quotes, "unterminated quoted field");
+        assert!(field.is_empty() && row.is_empty(), "missing final newline");
+        rows
+    }
+
+    /// The unescaped values of every `<string key="{key}" value="..."/>` in `xml`
+    fn xes_strings(xml: &str, key: &str) -> Vec<String> {
+        let prefix = format!(r#"<string key="{}" value=""#, key);
+        xml.lines()
+            .filter_map(|line| line.trim().strip_prefix(prefix.as_str()))
+            .map(|rest| {
+                let value = rest.strip_suffix(r#""/>"#).expect("attribute value ends the element");
+                assert!(!value.contains(['"', '<', '>', '\'', '\n', '\r']), "unescaped markup in {}", value);
+                value
+                    .replace("&#10;", "\n")
+                    .replace("&#13;", "\r")
+                    .replace("&#9;", "\t")
+                    .replace("&lt;", "<")
+                    .replace("&gt;", ">")
+                    .replace("&quot;", "\"")
+                    .replace("&apos;", "'")
+                    .replace("&amp;", "&")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn csv_round_trips_commas_quotes_and_newlines() {
+        let entries = entries();
+        let mut csv = Vec::new();
+        write_csv(&entries, &mut csv).unwrap();
+        let rows = read_csv(&String::from_utf8(csv).unwrap());
+
+        assert_eq!(rows[0], ["case_id", "activity", "timestamp", "resource"]);
+        assert_eq!(rows.len(), entries.len() + 1);
+        for (row, entry) in rows[1..].iter().zip(&entries) {
+            assert_eq!(row.len(), 4);
+            assert_eq!(row[0], entry.case_id);
+            assert_eq!(row[1], entry.activity);
+            assert_eq!(row[2], format_timestamp(entry.timestamp));
+            assert_eq!(row[3], entry.resource.clone().unwrap_or_default());
+        }
+        assert_eq!(rows[1][2], "2023-11-14T22:13:20.123Z");
+    }
+
+    #[test]
+    fn xes_round_trips_xml_special_characters() {
+        let entries = entries();
+        let mut xes = Vec::new();
+        write_xes(&entries, &mut xes).unwrap();
+        let xes = String::from_utf8(xes).unwrap();
+
+        assert_eq!(xes.matches("<trace>").count(), 2);
+        assert_eq!(xes.matches("</trace>").count(), 2);
+        assert_eq!(xes.matches("<event>").count(), entries.len());
+
+        // Trace names and event names share the concept:name key, in document order
+        let names = xes_strings(&xes, "concept:name");
+        assert_eq!(
+            names,
+            [
+                entries[0].case_id.as_str(),
+                &entries[0].activity,
+                &entries[1].activity,
+                &entries[2].case_id,
+                &entries[2].activity,
+            ]
+        );
+        assert_eq!(
+            xes_strings(&xes, "org:resource"),
+            ["Tom & Jerry's <App>", "Excel"]
+        );
+    }
+}
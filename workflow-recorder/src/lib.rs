@@ -10,11 +10,27 @@ mod events;
 mod recorder;
 mod error;
 mod intent;
+mod summary;
+mod export;
+mod semantic;
+mod encryption;
+mod parameters;
+mod checkpoint;
+mod sinks;
+mod video;
 
 pub use events::*;
 pub use recorder::*;
 pub use error::*;
 pub use intent::*;
+pub use summary::*;
+pub use export::*;
+pub use semantic::*;
+pub use encryption::*;
+pub use parameters::*;
+pub use checkpoint::*;
+pub use sinks::*;
+pub use video::*;
 
 #[cfg(test)]
 mod tests {
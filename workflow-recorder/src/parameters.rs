@@ -0,0 +1,455 @@
+use crate::semantic::{is_modifier, key_for_char, typed_char};
+use crate::{KeyboardEvent, RecordedEvent, RecordedWorkflow, Result, WorkflowEvent, WorkflowRecorderError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+/// Virtual-key code of the Shift key
+const VK_SHIFT: u32 = 16;
+
+/// Maps parameter names to the values typed while recording.
+///
+/// Saved as a mapping file such as
+/// `{"parameters": {"invoice_number": "INV-1001"}}`: every place the recording
+/// typed `INV-1001` is retyped with the row's `invoice_number` value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParameterMapping {
+    /// Parameter name to recorded value
+    pub parameters: HashMap<String, String>,
+}
+
+impl ParameterMapping {
+    /// Load a mapping file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// Rows of parameter values, one replay of the workflow per row
+#[derive(Debug, Clone, Default)]
+pub struct Dataset {
+    /// Each row maps parameter names (the CSV header) to values
+    pub rows: Vec<HashMap<String, String>>,
+}
+
+impl Dataset {
+    /// Load a CSV file whose header row names the parameters
+    pub fn from_csv<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse_csv(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse CSV text whose header row names the parameters
+    pub fn parse_csv(text: &str) -> Result<Self> {
+        let mut records = parse_csv_records(text).into_iter();
+        let header = records.next().ok_or_else(|| {
+            WorkflowRecorderError::ParameterError("CSV has no header row".to_string())
+        })?;
+
+        let mut rows = Vec::new();
+        for (line, record) in records.enumerate() {
+            if record.len() == 1 && record[0].is_empty() {
+                continue; // blank line
+            }
+            if record.len() != header.len() {
+                return Err(WorkflowRecorderError::ParameterError(format!(
+                    "CSV row {} has {} fields, expected {}",
+                    line + 2,
+                    record.len(),
+                    header.len()
+                )));
+            }
+            rows.push(header.iter().cloned().zip(record).collect());
+        }
+        Ok(Self { rows })
+    }
+}
+
+/// Substitute one row of values into a recorded workflow.
+///
+/// Each run of typing whose text contains a mapped value is replaced by
+/// keystrokes typing the text with the row's value instead, spread over the
+/// time the original run took. Fails if a mapped value was never typed, or
+/// the row lacks a value for a mapped parameter.
+pub fn parameterize(
+    workflow: &RecordedWorkflow,
+    mapping: &ParameterMapping,
+    values: &HashMap<String, String>,
+) -> Result<RecordedWorkflow> {
+    // Longest value first, so a value that contains another wins where both match;
+    // equal lengths go by name so the result never depends on map order
+    let mut mapped: Vec<(&String, &String)> = mapping.parameters.iter().collect();
+    mapped.sort_by(|(a_name, a), (b_name, b)| b.len().cmp(&a.len()).then(a_name.cmp(b_name)));
+    let parameters = mapped
+        .into_iter()
+        .map(|(name, recorded)| {
+            let value = values.get(name).ok_or_else(|| {
+                WorkflowRecorderError::ParameterError(format!("No value for parameter '{}'", name))
+            })?;
+            Ok((name.as_str(), recorded.as_str(), value.as_str()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut typed = vec![false; parameters.len()];
+    let mut replacements: Vec<(Range<usize>, String)> = Vec::new();
+    for (range, text) in typing_runs(&workflow.events) {
+        if let Some(new_text) = substitute(&text, &parameters, &mut typed) {
+            replacements.push((range, new_text));
+        }
+    }
+    if let Some(index) = typed.iter().position(|typed| !typed) {
+        return Err(WorkflowRecorderError::ParameterError(format!(
+            "Recorded value for parameter '{}' was never typed",
+            parameters[index].0
+        )));
+    }
+
+    let mut events = Vec::with_capacity(workflow.events.len());
+    let mut next = 0;
+    for (range, text) in replacements {
+        events.extend_from_slice(&workflow.events[next..range.start]);
+        let start_time = workflow.events[range.start].timestamp;
+        let end_time = workflow.events[range.end - 1].timestamp;
+        events.extend(type_text(&text, start_time, end_time)?);
+        next = range.end;
+    }
+    events.extend_from_slice(&workflow.events[next..]);
+
+    let mut parameterized = RecordedWorkflow {
+        name: workflow.name.clone(),
+        start_time: workflow.start_time,
+        end_time: workflow.end_time,
+        events,
+        metadata: workflow.metadata.clone(),
+    };
+    // Place the retyped keystrokes in the video too
+    parameterized.sync_video(workflow.metadata.video.clone());
+    Ok(parameterized)
+}
+
+/// Substitute every row of a dataset, giving one workflow per row
+pub fn parameterize_dataset(
+    workflow: &RecordedWorkflow,
+    mapping: &ParameterMapping,
+    dataset: &Dataset,
+) -> Result<Vec<RecordedWorkflow>> {
+    dataset
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut parameterized = parameterize(workflow, mapping, row)?;
+            parameterized.name = format!("{} #{}", workflow.name, i + 1);
+            Ok(parameterized)
+        })
+        .collect()
+}
+
+/// `text` with every recorded value replaced by its new value, in one pass over
+/// the original text so substituted values are never matched again. `parameters`
+/// are `(name, recorded, value)`, tried in order at each position; `typed` marks
+/// the ones found. `None` if nothing matched.
+fn substitute(text: &str, parameters: &[(&str, &str, &str)], typed: &mut [bool]) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut matched = false;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let found = parameters
+            .iter()
+            .position(|(_, recorded, _)| !recorded.is_empty() && rest.starts_with(recorded));
+        match found {
+            Some(index) => {
+                let (_, recorded, value) = parameters[index];
+                result.push_str(value);
+                rest = &rest[recorded.len()..];
+                typed[index] = true;
+                matched = true;
+            }
+            None => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    matched.then_some(result)
+}
+
+/// Runs of consecutive typing keystrokes: the event range and the text it typed
+fn typing_runs(events: &[RecordedEvent]) -> Vec<(Range<usize>, String)> {
+    let mut runs = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    // A Shift press just before a run's first character belongs to the run
+    let mut shift_down: Option<usize> = None;
+
+    for (index, event) in events.iter().enumerate() {
+        let typed = match &event.event {
+            WorkflowEvent::Keyboard(key) if !key.is_key_down || is_modifier(key.key_code) => {
+                // Releases and Shift presses belong to the run they occur in
+                if current.is_some() {
+                    continue;
+                }
+                if key.is_key_down && matches!(key.key_code, VK_SHIFT | 160 | 161) {
+                    shift_down.get_or_insert(index);
+                    continue;
+                }
+                None
+            }
+            WorkflowEvent::Keyboard(key) if !(key.ctrl_pressed || key.alt_pressed || key.win_pressed) => {
+                typed_char(key)
+            }
+            _ => None,
+        };
+
+        match typed {
+            Some(c) => {
+                let (_, text) =
+                    current.get_or_insert_with(|| (shift_down.take().unwrap_or(index), String::new()));
+                if c == '\u{8}' {
+                    text.pop();
+                } else {
+                    text.push(c);
+                }
+            }
+            None => {
+                shift_down = None;
+                if let Some((start, text)) = current.take() {
+                    runs.push((start..index, text));
+                }
+            }
+        }
+    }
+    if let Some((start, text)) = current {
+        runs.push((start..events.len(), text));
+    }
+    runs
+}
+
+/// Keystrokes typing `text`, spread evenly between two timestamps
+fn type_text(text: &str, start_time: u64, end_time: u64) -> Result<Vec<RecordedEvent>> {
+    let mut keys = Vec::new();
+    for c in text.chars() {
+        let (key_code, shift) = key_for_char(c).ok_or_else(|| {
+            WorkflowRecorderError::ParameterError(format!("Cannot type character {:?}", c))
+        })?;
+        if shift {
+            keys.push((VK_SHIFT, true, true, None));
+        }
+        keys.push((key_code, true, shift, Some(c.to_string())));
+        keys.push((key_code, false, shift, Some(c.to_string())));
+        if shift {
+            keys.push((VK_SHIFT, false, false, None));
+        }
+    }
+
+    let step = end_time.saturating_sub(start_time) / keys.len().max(1) as u64;
+    Ok(keys
+        .into_iter()
+        .enumerate()
+        .map(|(i, (key_code, is_key_down, shift_pressed, character))| RecordedEvent {
+            timestamp: start_time + step * i as u64,
+            video: None,
+            event: WorkflowEvent::Keyboard(KeyboardEvent {
+                key_code,
+                is_key_down,
+                ctrl_pressed: false,
+                alt_pressed: false,
+                shift_pressed,
+                win_pressed: false,
+                character,
+                key_name: None,
+            }),
+        })
+        .collect())
+}
+
+/// Split CSV text into records, honouring quoted fields
+fn parse_csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WindowEvent;
+
+    fn key(key_code: u32, is_key_down: bool, shift_pressed: bool) -> RecordedEvent {
+        RecordedEvent {
+            timestamp: 1_000,
+            video: None,
+            event: WorkflowEvent::Keyboard(KeyboardEvent {
+                key_code,
+                is_key_down,
+                ctrl_pressed: false,
+                alt_pressed: false,
+                shift_pressed,
+                win_pressed: false,
+                character: None,
+                key_name: None,
+            }),
+        }
+    }
+
+    fn focus() -> RecordedEvent {
+        RecordedEvent {
+            timestamp: 1_000,
+            video: None,
+            event: WorkflowEvent::WindowFocusChanged(WindowEvent {
+                title: Some("Invoices".to_string()),
+                class_name: None,
+                process_id: None,
+                application_name: None,
+            }),
+        }
+    }
+
+    fn typed(text: &str) -> Vec<RecordedEvent> {
+        type_text(text, 1_000, 2_000).unwrap()
+    }
+
+    fn workflow(events: Vec<RecordedEvent>) -> RecordedWorkflow {
+        let mut workflow = RecordedWorkflow::new("Invoice".to_string());
+        workflow.events = events;
+        workflow
+    }
+
+    fn texts(events: &[RecordedEvent]) -> Vec<String> {
+        typing_runs(events).into_iter().map(|(_, text)| text).collect()
+    }
+
+    fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn shift_presses(events: &[RecordedEvent]) -> (usize, usize) {
+        let shift = |down: bool| {
+            events
+                .iter()
+                .filter(|event| {
+                    matches!(&event.event, WorkflowEvent::Keyboard(key)
+                        if key.key_code == VK_SHIFT && key.is_key_down == down)
+                })
+                .count()
+        };
+        (shift(true), shift(false))
+    }
+
+    #[test]
+    fn csv_records_honour_quotes() {
+        let text = "name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\r\nthen left\"\r\nplain,\r\n";
+        assert_eq!(
+            parse_csv_records(text),
+            [
+                vec!["name", "note"],
+                vec!["Smith, J", "said \"hi\"\r\nthen left"],
+                vec!["plain", ""],
+            ]
+        );
+        assert_eq!(parse_csv_records("a,b"), [vec!["a", "b"]]);
+        assert!(parse_csv_records("").is_empty());
+    }
+
+    #[test]
+    fn dataset_skips_blank_lines_and_checks_widths() {
+        let dataset = Dataset::parse_csv("invoice,customer\n\nINV-1,\"ACME, Inc\"\n").unwrap();
+        assert_eq!(dataset.rows, [map(&[("invoice", "INV-1"), ("customer", "ACME, Inc")])]);
+
+        assert!(Dataset::parse_csv("invoice,customer\nINV-1\n").is_err());
+        assert!(Dataset::parse_csv("").is_err());
+    }
+
+    #[test]
+    fn typing_runs_apply_backspace_and_shift() {
+        let mut events = typed("Abx");
+        events.push(key(0x08, true, false));
+        events.push(key(0x08, false, false));
+        events.extend(typed("C"));
+        let first_run = events.len();
+        events.push(focus());
+        events.extend(typed("d"));
+
+        let runs = typing_runs(&events);
+        assert_eq!(runs.len(), 2);
+        // The Shift press before 'A' starts the run, so replacing it leaves no Shift held
+        assert_eq!(runs[0], (0..first_run, "AbC".to_string()));
+        assert_eq!(runs[1], (first_run + 1..events.len(), "d".to_string()));
+
+        // Backspace with nothing typed yet, and Shift released without typing
+        let events = vec![
+            key(VK_SHIFT, true, true),
+            key(VK_SHIFT, false, false),
+            key(0x08, true, false),
+            key(0x41, true, false),
+        ];
+        assert_eq!(typing_runs(&events), [(2..4, "a".to_string())]);
+    }
+
+    #[test]
+    fn parameterize_substitutes_in_one_pass() {
+        let mut events = vec![focus()];
+        events.extend(typed("INV-1001 for ACME, 1001"));
+        events.push(focus());
+        events.extend(typed("ACME"));
+        let recorded = workflow(events);
+        let mapping = ParameterMapping {
+            parameters: map(&[("invoice", "INV-1001"), ("number", "1001"), ("customer", "ACME")]),
+        };
+        // The new invoice contains the recorded customer, which must not be replaced again
+        let values = map(&[("invoice", "ACME-7"), ("number", "2002"), ("customer", "Globex")]);
+
+        let parameterized = parameterize(&recorded, &mapping, &values).unwrap();
+        assert_eq!(texts(&parameterized.events), ["ACME-7 for Globex, 2002", "Globex"]);
+        assert!(matches!(parameterized.events[0].event, WorkflowEvent::WindowFocusChanged(_)));
+        let (down, up) = shift_presses(&parameterized.events);
+        assert_eq!(down, up);
+
+        // Same row, same result, whatever order the maps iterate in
+        for _ in 0..5 {
+            let again = parameterize(&recorded, &mapping, &values).unwrap();
+            assert_eq!(texts(&again.events), texts(&parameterized.events));
+        }
+    }
+
+    #[test]
+    fn parameterize_rejects_missing_and_untyped_values() {
+        let recorded = workflow(typed("INV-1001"));
+        let mapping = ParameterMapping {
+            parameters: map(&[("invoice", "INV-1001"), ("customer", "ACME")]),
+        };
+        let is_parameter_error =
+            |result: Result<RecordedWorkflow>| matches!(result, Err(WorkflowRecorderError::ParameterError(_)));
+
+        assert!(is_parameter_error(parameterize(&recorded, &mapping, &map(&[("invoice", "INV-2")]))));
+        assert!(is_parameter_error(parameterize(
+            &recorded,
+            &mapping,
+            &map(&[("invoice", "INV-2"), ("customer", "Globex")])
+        )));
+    }
+}
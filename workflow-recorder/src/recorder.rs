@@ -1,12 +1,17 @@
 use crate::{
     MouseButton, MouseEvent, MouseEventType, Position, RecordedWorkflow, UiElement, WindowEvent,
-    WorkflowEvent, WorkflowRecorderError, Result, IntentGroup, IntentGroupingConfig, extract_intent_groups
+    WorkflowEvent, WorkflowRecorderError, Result, IntentGroup, IntentGroupingConfig, extract_intent_groups, RecordingSummary,
+    SummaryConfig, to_event_log, write_csv, write_xes, SemanticAction, infer_semantic_actions,
+    EncryptionKey, encryption::write_file, CheckpointEvent,
+    EventSink, JsonlFileSink, SinkId, VideoSync
 };
 use std::{
-    fs::File,
-    io::Write,
     path::Path,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tracing::{debug, error, info, warn};
@@ -17,8 +22,10 @@ mod windows;
 #[cfg(target_os = "windows")]
 pub use self::windows::*;
 
-/// Configuration for the workflow recorder
+/// Configuration for the workflow recorder. Start from [`Default::default`] and set
+/// the fields to change, so new options don't break existing code.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct WorkflowRecorderConfig {
     /// Whether to record mouse events
     pub record_mouse: bool,
@@ -31,6 +38,28 @@ pub struct WorkflowRecorderConfig {
     
     /// Whether to capture UI element information
     pub capture_ui_elements: bool,
+
+    /// Whether to record keystrokes injected by software (e.g. automation typing a
+    /// password) rather than typed on a physical keyboard. Off by default, so
+    /// on-screen keyboards, remote-control tools and text expanders go unrecorded
+    /// too; turn it on to capture them.
+    pub record_injected_keyboard: bool,
+
+    /// Whether to capture the UI element under the cursor for mouse wheel events,
+    /// so the scrolled list or document is known
+    pub capture_ui_elements_on_wheel: bool,
+
+    /// Capture the UI element under the cursor for mouse move events, at most once
+    /// per this interval; `None` records moves without element information
+    pub move_ui_element_interval: Option<Duration>,
+
+    /// Encrypt every file the recorder saves with this key (AES-256-GCM);
+    /// read them back with [`crate::load_workflow`]
+    pub encryption_key: Option<EncryptionKey>,
+
+    /// Virtual-key code that, pressed with Ctrl+Shift, inserts a checkpoint asserting the
+    /// text of the element under the cursor (with Ctrl+Shift+Alt, its appearance)
+    pub checkpoint_hotkey: Option<u32>,
 }
 
 impl Default for WorkflowRecorderConfig {
@@ -40,10 +69,18 @@ impl Default for WorkflowRecorderConfig {
             record_keyboard: true,
             record_window: true,
             capture_ui_elements: true,
+            record_injected_keyboard: false,
+            capture_ui_elements_on_wheel: false,
+            move_ui_element_interval: None,
+            encryption_key: None,
+            checkpoint_hotkey: None,
         }
     }
 }
 
+/// Sinks attached to a recorder
+type Sinks = Arc<Mutex<Vec<(SinkId, Box<dyn EventSink>)>>>;
+
 /// The workflow recorder
 pub struct WorkflowRecorder {
     /// The recorded workflow
@@ -55,6 +92,12 @@ pub struct WorkflowRecorder {
     /// The configuration
     config: WorkflowRecorderConfig,
     
+    /// The attached event sinks
+    sinks: Sinks,
+    
+    /// The id given to the next attached sink
+    next_sink_id: AtomicU64,
+    
     /// The platform-specific recorder
     #[cfg(target_os = "windows")]
     windows_recorder: Option<WindowsRecorder>,
@@ -70,18 +113,62 @@ impl WorkflowRecorder {
             workflow,
             event_tx,
             config,
+            sinks: Arc::new(Mutex::new(Vec::new())),
+            next_sink_id: AtomicU64::new(0),
             #[cfg(target_os = "windows")]
             windows_recorder: None,
         }
     }
     
+    /// Attach a sink that receives every event recorded from now on.
+    /// Works while recording; the hooks keep running.
+    pub fn add_sink(&self, sink: Box<dyn EventSink>) -> Result<SinkId> {
+        let id = SinkId(self.next_sink_id.fetch_add(1, Ordering::Relaxed));
+        self.sinks
+            .lock()
+            .map_err(|e| WorkflowRecorderError::RecordingError(format!("Failed to lock sinks: {}", e)))?
+            .push((id, sink));
+        debug!("Attached event sink {:?}", id);
+        Ok(id)
+    }
+    
+    /// Attach a [`JsonlFileSink`] appending to `path`, encrypted with
+    /// [`WorkflowRecorderConfig::encryption_key`] when one is set
+    pub fn add_file_sink<P: AsRef<Path>>(&self, path: P) -> Result<SinkId> {
+        let sink = JsonlFileSink::open(path, self.config.encryption_key.clone())?;
+        self.add_sink(Box::new(sink))
+    }
+    
+    /// Detach a sink, flushing it first. Returns `None` if it was not attached
+    /// (or was detached after failing).
+    pub fn remove_sink(&self, id: SinkId) -> Result<Option<Box<dyn EventSink>>> {
+        let mut sinks = self
+            .sinks
+            .lock()
+            .map_err(|e| WorkflowRecorderError::RecordingError(format!("Failed to lock sinks: {}", e)))?;
+        let Some(index) = sinks.iter().position(|(sink_id, _)| *sink_id == id) else {
+            return Ok(None);
+        };
+        let (_, mut sink) = sinks.remove(index);
+        if let Err(e) = sink.flush() {
+            warn!("Failed to flush event sink {:?}: {}", id, e);
+        }
+        debug!("Detached event sink {:?}", id);
+        Ok(Some(sink))
+    }
+    
     /// Start recording
     pub async fn start(&mut self) -> Result<()> {
         info!("Starting workflow recording");
         
         #[cfg(target_os = "windows")]
         {
+            if let Ok(mut workflow) = self.workflow.lock() {
+                workflow.metadata.keyboard_layout = active_keyboard_layout();
+            }
+            
             let workflow = Arc::clone(&self.workflow);
+            let sinks = Arc::clone(&self.sinks);
             let (event_tx, mut event_rx) = mpsc::unbounded_channel();
             self.event_tx = event_tx.clone();
             
@@ -91,7 +178,7 @@ impl WorkflowRecorder {
             
             // Start the event processing task
             tokio::spawn(async move {
-                Self::process_events(workflow, &mut event_rx).await;
+                Self::process_events(workflow, sinks, &mut event_rx).await;
             });
             
             Ok(())
@@ -111,7 +198,7 @@ impl WorkflowRecorder {
         
         #[cfg(target_os = "windows")]
         {
-            if let Some(windows_recorder) = self.windows_recorder.take() {
+            if let Some(mut windows_recorder) = self.windows_recorder.take() {
                 windows_recorder.stop()?;
             }
         }
@@ -124,6 +211,27 @@ impl WorkflowRecorder {
         Ok(())
     }
     
+    /// Line the recording up with a screen recording made alongside it, e.g. started
+    /// with [`VideoSync::starting_now`] when the screen recorder starts. From then on
+    /// every event carries its offset and frame number in the video, also in the
+    /// events sinks receive; events recorded earlier get theirs in the saved workflow.
+    pub fn sync_video(&self, video: VideoSync) -> Result<()> {
+        info!("Syncing recording with video starting at {}", video.started_at);
+        
+        let mut workflow = self.workflow.lock().map_err(|e| {
+            WorkflowRecorderError::RecordingError(format!("Failed to lock workflow: {}", e))
+        })?;
+        workflow.sync_video(Some(video));
+        
+        Ok(())
+    }
+    
+    /// Insert a verification checkpoint at the current point of the recording
+    pub fn add_checkpoint(&self, checkpoint: CheckpointEvent) -> Result<()> {
+        Self::record_event(&self.workflow, &self.sinks, WorkflowEvent::Checkpoint(checkpoint));
+        Ok(())
+    }
+    
     /// Save the recorded workflow to a file
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         info!("Saving workflow recording to {:?}", path.as_ref());
@@ -133,8 +241,7 @@ impl WorkflowRecorder {
         })?;
         
         let json = serde_json::to_string_pretty(&*workflow)?;
-        let mut file = File::create(path)?;
-        file.write_all(json.as_bytes())?;
+        write_file(path, json.as_bytes(), self.config.encryption_key.as_ref())?;
         
         Ok(())
     }
@@ -142,12 +249,33 @@ impl WorkflowRecorder {
     /// Process events from the event receiver
     async fn process_events(
         workflow: Arc<Mutex<RecordedWorkflow>>,
+        sinks: Sinks,
         event_rx: &mut UnboundedReceiver<WorkflowEvent>,
     ) {
         while let Some(event) = event_rx.recv().await {
-            if let Ok(mut workflow) = workflow.lock() {
+            Self::record_event(&workflow, &sinks, event);
+        }
+    }
+    
+    /// Add an event to the workflow and hand it to the attached sinks
+    fn record_event(workflow: &Mutex<RecordedWorkflow>, sinks: &Sinks, event: WorkflowEvent) {
+        let recorded = match workflow.lock() {
+            Ok(mut workflow) => {
                 workflow.add_event(event);
+                workflow.events.last().cloned()
             }
+            Err(_) => None,
+        };
+        
+        if let (Some(recorded), Ok(mut sinks)) = (recorded, sinks.lock()) {
+            // A failing sink (closed connection, full disk) is detached rather than retried
+            sinks.retain_mut(|(id, sink)| match sink.handle(&recorded) {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Detaching event sink {:?} after error: {}", id, e);
+                    false
+                }
+            });
         }
     }
     
@@ -169,6 +297,66 @@ impl WorkflowRecorder {
         Ok(crate::intent::group_events(&workflow, &config))
     }
     
+    /// Compute activity analytics for the recorded workflow
+    pub fn summary(&self, config: &SummaryConfig) -> Result<RecordingSummary> {
+        let workflow = self.workflow.lock().map_err(|e| {
+            WorkflowRecorderError::SaveError(format!("Failed to lock workflow: {}", e))
+        })?;
+        
+        Ok(RecordingSummary::from_workflow(&workflow, config))
+    }
+    
+    /// Save the recorded workflow as a CSV event log for process-mining tools
+    pub fn save_event_log_csv<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        info!("Saving CSV event log to {:?}", path.as_ref());
+        
+        let workflow = self.workflow.lock().map_err(|e| {
+            WorkflowRecorderError::SaveError(format!("Failed to lock workflow: {}", e))
+        })?;
+        
+        let entries = to_event_log(&workflow, &workflow.name);
+        let mut log = Vec::new();
+        write_csv(&entries, &mut log)?;
+        write_file(path, &log, self.config.encryption_key.as_ref())?;
+        
+        Ok(())
+    }
+    
+    /// Save the recorded workflow as an XES event log for process-mining tools
+    pub fn save_event_log_xes<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        info!("Saving XES event log to {:?}", path.as_ref());
+        
+        let workflow = self.workflow.lock().map_err(|e| {
+            WorkflowRecorderError::SaveError(format!("Failed to lock workflow: {}", e))
+        })?;
+        
+        let entries = to_event_log(&workflow, &workflow.name);
+        let mut log = Vec::new();
+        write_xes(&entries, &mut log)?;
+        write_file(path, &log, self.config.encryption_key.as_ref())?;
+        
+        Ok(())
+    }
+    
+    /// Infer human-readable actions ("clicked button 'Save'") from the recorded events
+    pub fn semantic_actions(&self) -> Result<Vec<SemanticAction>> {
+        let workflow = self.workflow.lock().map_err(|e| {
+            WorkflowRecorderError::SaveError(format!("Failed to lock workflow: {}", e))
+        })?;
+        
+        Ok(infer_semantic_actions(&workflow))
+    }
+    
+    /// Save the inferred semantic actions to a file
+    pub fn save_semantic_actions<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let actions = self.semantic_actions()?;
+        
+        let json = serde_json::to_string_pretty(&actions)?;
+        write_file(path, json.as_bytes(), self.config.encryption_key.as_ref())?;
+        
+        Ok(())
+    }
+    
     /// Save the extracted intent groups to a file
     pub fn save_intent_groups<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let groups = self.extract_intent_groups()?;
@@ -207,9 +395,141 @@ impl WorkflowRecorder {
             .collect();
         
         let json = serde_json::to_string_pretty(&serializable_groups)?;
-        let mut file = File::create(path)?;
-        file.write_all(json.as_bytes())?;
+        write_file(path, json.as_bytes(), self.config.encryption_key.as_ref())?;
         
         Ok(())
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_jsonl, MemorySink, RecordedEvent};
+    use std::sync::atomic::AtomicUsize;
+
+    fn focus(title: &str) -> WorkflowEvent {
+        WorkflowEvent::WindowFocusChanged(WindowEvent {
+            title: Some(title.to_string()),
+            class_name: None,
+            process_id: None,
+            application_name: None,
+        })
+    }
+
+    fn record(recorder: &WorkflowRecorder, title: &str) {
+        WorkflowRecorder::record_event(&recorder.workflow, &recorder.sinks, focus(title));
+    }
+
+    fn sink_count(recorder: &WorkflowRecorder) -> usize {
+        recorder.sinks.lock().unwrap().len()
+    }
+
+    /// Fails on the `fail_on`th event it is handed, counting events and flushes
+    #[derive(Clone, Default)]
+    struct FlakySink {
+        fail_on: Option<usize>,
+        handled: Arc<AtomicUsize>,
+        flushed: Arc<AtomicUsize>,
+    }
+
+    impl EventSink for FlakySink {
+        fn handle(&mut self, _event: &RecordedEvent) -> Result<()> {
+            let handled = self.handled.fetch_add(1, Ordering::SeqCst) + 1;
+            if Some(handled) == self.fail_on {
+                return Err(WorkflowRecorderError::RecordingError("Disk full".to_string()));
+            }
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            self.flushed.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sinks_receive_events_recorded_while_attached() {
+        let recorder = WorkflowRecorder::new("Sinks".to_string(), WorkflowRecorderConfig::default());
+        record(&recorder, "Before");
+
+        let buffer = MemorySink::new();
+        let id = recorder.add_sink(Box::new(buffer.clone())).unwrap();
+        record(&recorder, "During");
+        assert!(recorder.remove_sink(id).unwrap().is_some());
+        record(&recorder, "After");
+
+        let events = buffer.events();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0].event,
+            WorkflowEvent::WindowFocusChanged(window) if window.title.as_deref() == Some("During")
+        ));
+        // The workflow itself keeps everything
+        assert_eq!(recorder.workflow.lock().unwrap().events.len(), 3);
+        assert!(recorder.remove_sink(id).unwrap().is_none());
+    }
+
+    #[test]
+    fn removing_a_sink_flushes_it_and_leaves_the_others() {
+        let recorder = WorkflowRecorder::new("Sinks".to_string(), WorkflowRecorderConfig::default());
+        let first = FlakySink::default();
+        let second = FlakySink::default();
+        let first_id = recorder.add_sink(Box::new(first.clone())).unwrap();
+        let second_id = recorder.add_sink(Box::new(second.clone())).unwrap();
+        assert_ne!(first_id, second_id);
+
+        record(&recorder, "One");
+        recorder.remove_sink(first_id).unwrap();
+        record(&recorder, "Two");
+
+        assert_eq!(first.flushed.load(Ordering::SeqCst), 1);
+        assert_eq!(first.handled.load(Ordering::SeqCst), 1);
+        assert_eq!(second.flushed.load(Ordering::SeqCst), 0);
+        assert_eq!(second.handled.load(Ordering::SeqCst), 2);
+        assert_eq!(sink_count(&recorder), 1);
+    }
+
+    #[test]
+    fn a_failing_sink_is_detached() {
+        let recorder = WorkflowRecorder::new("Sinks".to_string(), WorkflowRecorderConfig::default());
+        let failing = FlakySink {
+            fail_on: Some(2),
+            ..FlakySink::default()
+        };
+        let failing_id = recorder.add_sink(Box::new(failing.clone())).unwrap();
+        let buffer = MemorySink::new();
+        recorder.add_sink(Box::new(buffer.clone())).unwrap();
+
+        for title in ["One", "Two", "Three"] {
+            record(&recorder, title);
+        }
+
+        // Handed the second event, failed, and was never retried
+        assert_eq!(failing.handled.load(Ordering::SeqCst), 2);
+        assert_eq!(buffer.events().len(), 3);
+        assert_eq!(sink_count(&recorder), 1);
+        assert!(recorder.remove_sink(failing_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn file_sinks_use_the_configured_key() {
+        let path = std::env::temp_dir()
+            .join(format!("workflow-recorder-{}-file-sink.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let key = EncryptionKey::from_bytes([3; 32]);
+        let config = WorkflowRecorderConfig {
+            encryption_key: Some(key.clone()),
+            ..WorkflowRecorderConfig::default()
+        };
+        let recorder = WorkflowRecorder::new("Sinks".to_string(), config);
+
+        let id = recorder.add_file_sink(&path).unwrap();
+        record(&recorder, "Payroll");
+        recorder.remove_sink(id).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let events = read_jsonl(&path, Some(&key));
+        let _ = std::fs::remove_file(&path);
+        assert!(!text.contains("Payroll"));
+        assert_eq!(events.unwrap().len(), 1);
+    }
+}
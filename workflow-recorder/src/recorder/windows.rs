@@ -1,10 +1,15 @@
 use crate::{
     KeyboardEvent, MouseButton, MouseEvent, MouseEventType, Position, UiElement, WindowEvent,
-    WorkflowEvent, WorkflowRecorderError, Result, WorkflowRecorderConfig
+    MonitorInfo, Rect, TopLevelWindow,
+    WorkflowEvent, WorkflowRecorderError, Result, WorkflowRecorderConfig, CheckpointAssertion,
+    CheckpointEvent, capture_fingerprint, element_checkpoint, DEFAULT_SCREENSHOT_TOLERANCE
 };
 use std::{
-    sync::{Arc, Mutex},
-    time::Duration,
+    cell::RefCell,
+    collections::HashMap,
+    sync::{mpsc as std_mpsc, Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::Instant,
 };
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::{debug, error, info, warn};
@@ -16,13 +21,25 @@ use {
     std::path::Path,
     uiautomation::{UIAutomation, UIElement as WinUIElement},
     windows::{
-        Win32::Foundation::{HWND, LPARAM, POINT, WPARAM},
+        Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, RECT, WPARAM},
+        Win32::Graphics::Gdi::{
+            GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITORINFOEXW, MONITOR_DEFAULTTONEAREST,
+        },
         Win32::UI::WindowsAndMessaging::{
             GetWindowTextW, GetWindowThreadProcessId, SetWindowsHookExW, UnhookWindowsHookEx,
-            CallNextHookEx, HC_ACTION, WH_KEYBOARD_LL, WH_MOUSE_LL, KBDLLHOOKSTRUCT,
-            MSLLHOOKSTRUCT, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_RBUTTONDOWN,
-            WM_RBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL,
-            EnumWindows, IsWindowVisible, GetWindow, GW_OWNER,
+            CallNextHookEx, HC_ACTION, HHOOK, LLKHF_INJECTED, WH_KEYBOARD_LL, WH_MOUSE_LL,
+            KBDLLHOOKSTRUCT, MSLLHOOKSTRUCT, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN, WM_LBUTTONUP,
+            WM_RBUTTONDOWN, WM_RBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL,
+            WM_SYSKEYDOWN, WM_SYSKEYUP, LLKHF_EXTENDED, GetForegroundWindow,
+            EnumWindows, IsWindowVisible, GetWindow, GW_OWNER, GetCursorPos,
+            DispatchMessageW, GetMessageW, PeekMessageW, PostThreadMessageW, TranslateMessage, MSG,
+            PM_NOREMOVE, WM_QUIT, WM_USER, WindowFromPoint, GetAncestor, GetWindowRect, GA_ROOT,
+            MONITORINFOF_PRIMARY,
+        },
+        Win32::UI::Input::KeyboardAndMouse::{
+            GetAsyncKeyState, GetKeyNameTextW, GetKeyState, GetKeyboardLayout, ToUnicodeEx, HKL,
+            VK_CAPITAL, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MENU,
+            VK_RCONTROL, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT,
         },
         Win32::System::Threading::{
             GetCurrentProcessId, GetCurrentThreadId, OpenProcess, PROCESS_QUERY_INFORMATION,
@@ -35,17 +52,11 @@ use {
     },
 };
 
-/// The Windows-specific recorder
-pub struct WindowsRecorder {
-    /// The UI Automation instance
-    automation: Arc<UIAutomation>,
-    
-    /// The keyboard hook handle
-    keyboard_hook: Option<isize>,
-    
-    /// The mouse hook handle
-    mouse_hook: Option<isize>,
-    
+/// Only every this many mouse moves is recorded, to reduce noise
+const MOVE_SAMPLE_RATE: u32 = 10;
+
+/// State shared between the recorder and its hook thread
+struct HookShared {
     /// The event sender
     event_tx: UnboundedSender<WorkflowEvent>,
     
@@ -53,278 +64,562 @@ pub struct WindowsRecorder {
     config: WorkflowRecorderConfig,
     
     /// The last mouse position
-    last_mouse_pos: Arc<Mutex<Option<POINT>>>,
+    last_mouse_pos: Mutex<Option<POINT>>,
+}
+
+/// State of the hook thread, reached from the hook procedures
+struct HookContext {
+    /// State shared with the recorder
+    shared: Arc<HookShared>,
+    
+    /// The UI Automation instance, created on and only used from the hook thread
+    automation: UIAutomation,
+    
+    /// Mouse moves seen so far, for sampling
+    move_counter: u32,
+    
+    /// When the element under the cursor was last captured for a mouse move
+    last_move_capture: Option<Instant>,
+    
+    /// Application names by process ID, looked up once per process
+    process_names: HashMap<u32, Option<String>>,
+    
+    /// Whether Caps Lock is on. The hook thread's own key state never sees the
+    /// toggle, so it is read once at startup and then followed from the hook.
+    caps_lock: bool,
+    
+    /// Whether the Caps Lock key is held, so auto-repeat doesn't toggle it again
+    caps_lock_down: bool,
+}
+
+#[cfg(target_os = "windows")]
+thread_local! {
+    // Hook procedures take no context argument. Windows calls low-level hooks on the
+    // thread that installed them, so the hook thread keeps its context here.
+    static HOOK_CONTEXT: RefCell<Option<HookContext>> = const { RefCell::new(None) };
+}
+
+/// The Windows-specific recorder.
+///
+/// Hooks are installed on a dedicated thread that pumps messages for them and
+/// owns all hook state; stopping the recorder ends that thread.
+pub struct WindowsRecorder {
+    /// State shared with the hook thread
+    shared: Arc<HookShared>,
+    
+    /// The hook thread
+    hook_thread: Option<JoinHandle<()>>,
+    
+    /// The Win32 thread ID of the hook thread, for posting it WM_QUIT
+    hook_thread_id: u32,
 }
 
 #[cfg(target_os = "windows")]
 impl WindowsRecorder {
-    /// Create a new Windows recorder
+    /// Create a new Windows recorder and start its hook thread
     pub fn new(
         config: WorkflowRecorderConfig,
         event_tx: UnboundedSender<WorkflowEvent>,
     ) -> Result<Self> {
-        // Create UI Automation instance
-        let automation = Arc::new(
-            UIAutomation::new().map_err(|e| {
+        let shared = Arc::new(HookShared {
+            event_tx,
+            config,
+            last_mouse_pos: Mutex::new(None),
+        });
+        
+        let (ready_tx, ready_rx) = std_mpsc::channel();
+        let thread_shared = Arc::clone(&shared);
+        let hook_thread = thread::Builder::new()
+            .name("workflow-recorder-hooks".to_string())
+            .spawn(move || run_hook_thread(thread_shared, ready_tx))
+            .map_err(|e| {
                 WorkflowRecorderError::InitializationError(format!(
-                    "Failed to initialize UI Automation: {}",
+                    "Failed to start hook thread: {}",
                     e
                 ))
-            })?,
-        );
-        
-        let last_mouse_pos = Arc::new(Mutex::new(None));
+            })?;
         
-        let mut recorder = Self {
-            automation,
-            keyboard_hook: None,
-            mouse_hook: None,
-            event_tx,
-            config,
-            last_mouse_pos,
+        // Wait until the hooks are installed so setup errors reach the caller
+        let hook_thread_id = match ready_rx.recv() {
+            Ok(Ok(thread_id)) => thread_id,
+            Ok(Err(e)) => {
+                let _ = hook_thread.join();
+                return Err(e);
+            }
+            Err(_) => {
+                let _ = hook_thread.join();
+                return Err(WorkflowRecorderError::InitializationError(
+                    "Hook thread exited during startup".to_string(),
+                ));
+            }
         };
         
-        // Set up hooks
-        recorder.setup_hooks()?;
-        
-        Ok(recorder)
+        Ok(Self {
+            shared,
+            hook_thread: Some(hook_thread),
+            hook_thread_id,
+        })
     }
     
-    /// Set up the Windows hooks
-    fn setup_hooks(&mut self) -> Result<()> {
-        // Set up keyboard hook if enabled
-        if self.config.record_keyboard {
-            self.setup_keyboard_hook()?;
-        }
+    /// The last mouse position seen by the mouse hook
+    pub fn last_mouse_position(&self) -> Option<Position> {
+        self.shared
+            .last_mouse_pos
+            .lock()
+            .ok()
+            .and_then(|pos| pos.map(|pos| Position { x: pos.x, y: pos.y }))
+    }
+    
+    /// Stop recording: remove the hooks and end the hook thread
+    pub fn stop(&mut self) -> Result<()> {
+        let Some(hook_thread) = self.hook_thread.take() else {
+            return Ok(());
+        };
         
-        // Set up mouse hook if enabled
-        if self.config.record_mouse {
-            self.setup_mouse_hook()?;
-        }
+        unsafe { PostThreadMessageW(self.hook_thread_id, WM_QUIT, WPARAM(0), LPARAM(0)) }.map_err(
+            |e| WorkflowRecorderError::WindowsError(format!("Failed to stop hook thread: {}", e)),
+        )?;
+        hook_thread.join().map_err(|_| {
+            WorkflowRecorderError::WindowsError("Hook thread panicked".to_string())
+        })?;
         
         Ok(())
     }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for WindowsRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.stop() {
+            warn!("Failed to stop Windows recorder: {}", e);
+        }
+    }
+}
+
+/// Body of the hook thread: install the hooks, then pump messages until WM_QUIT
+#[cfg(target_os = "windows")]
+fn run_hook_thread(shared: Arc<HookShared>, ready_tx: std_mpsc::Sender<Result<u32>>) {
+    let automation = match UIAutomation::new() {
+        Ok(automation) => automation,
+        Err(e) => {
+            let _ = ready_tx.send(Err(WorkflowRecorderError::InitializationError(format!(
+                "Failed to initialize UI Automation: {}",
+                e
+            ))));
+            return;
+        }
+    };
     
-    /// Set up the keyboard hook
-    fn setup_keyboard_hook(&mut self) -> Result<()> {
-        let event_tx = self.event_tx.clone();
-        
-        // Define the keyboard hook procedure
-        unsafe extern "system" fn keyboard_hook_proc(
-            code: i32,
-            wparam: WPARAM,
-            lparam: LPARAM,
-        ) -> isize {
-            if code < 0 || code != HC_ACTION {
-                return CallNextHookEx(None, code, wparam, lparam);
-            }
-            
-            let hook_struct = *(lparam.0 as *const KBDLLHOOKSTRUCT);
-            let key_code = hook_struct.vkCode;
-            
-            // Check if key down or up
-            let is_key_down = wparam.0 == WM_KEYDOWN as usize;
-            let is_key_up = wparam.0 == WM_KEYUP as usize;
-            
-            if is_key_down || is_key_up {
-                // Get modifier key states
-                let ctrl_pressed = (hook_struct.flags & 0x8) != 0 || key_code == 17;
-                let alt_pressed = (hook_struct.flags & 0x20) != 0 || key_code == 18;
-                let shift_pressed = (hook_struct.flags & 0x1) != 0 || key_code == 16;
-                let win_pressed = key_code == 91 || key_code == 92;
-                
-                // Create keyboard event
-                let keyboard_event = KeyboardEvent {
-                    key_code,
-                    is_key_down,
-                    ctrl_pressed,
-                    alt_pressed,
-                    shift_pressed,
-                    win_pressed,
-                };
-                
-                // Send event
-                let _ = EVENT_TX.as_ref().unwrap().send(WorkflowEvent::Keyboard(keyboard_event));
+    let record_keyboard = shared.config.record_keyboard;
+    let record_mouse = shared.config.record_mouse;
+    HOOK_CONTEXT.with(|context| {
+        *context.borrow_mut() = Some(HookContext {
+            shared,
+            automation,
+            move_counter: 0,
+            last_move_capture: None,
+            process_names: HashMap::new(),
+            caps_lock: unsafe { GetKeyState(VK_CAPITAL.0 as i32) } & 1 != 0,
+            caps_lock_down: false,
+        });
+    });
+    
+    unsafe {
+        let hooks = match install_hooks(record_keyboard, record_mouse) {
+            Ok(hooks) => hooks,
+            Err(e) => {
+                HOOK_CONTEXT.with(|context| context.borrow_mut().take());
+                let _ = ready_tx.send(Err(e));
+                return;
             }
-            
-            CallNextHookEx(None, code, wparam, lparam)
-        }
+        };
         
-        // Store the event sender in a thread-local static
-        thread_local! {
-            static EVENT_TX: std::cell::RefCell<Option<UnboundedSender<WorkflowEvent>>> = std::cell::RefCell::new(None);
-        }
+        // Make sure this thread has a message queue before anyone posts WM_QUIT to it
+        let mut msg = MSG::default();
+        let _ = PeekMessageW(&mut msg, None, WM_USER, WM_USER, PM_NOREMOVE);
+        let _ = ready_tx.send(Ok(GetCurrentThreadId()));
+        debug!("Hook thread started");
         
-        EVENT_TX.with(|tx| {
-            *tx.borrow_mut() = Some(event_tx);
-        });
+        // Low-level hooks are only called while this thread pumps messages
+        while GetMessageW(&mut msg, None, 0, 0).0 > 0 {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
         
-        // Set the keyboard hook
-        unsafe {
-            let hook = SetWindowsHookExW(
-                WH_KEYBOARD_LL,
-                Some(keyboard_hook_proc),
-                None,
-                0,
-            );
-            
-            if hook.is_null() {
-                return Err(WorkflowRecorderError::InitializationError(
-                    "Failed to set keyboard hook".to_string(),
-                ));
+        for hook in hooks {
+            if UnhookWindowsHookEx(hook).is_err() {
+                warn!("Failed to unhook hook");
             }
-            
-            self.keyboard_hook = Some(hook.0);
         }
-        
-        Ok(())
     }
     
-    /// Set up the mouse hook
-    fn setup_mouse_hook(&mut self) -> Result<()> {
-        let event_tx = self.event_tx.clone();
-        let automation = Arc::clone(&self.automation);
-        let last_mouse_pos = Arc::clone(&self.last_mouse_pos);
-        let capture_ui_elements = self.config.capture_ui_elements;
-        
-        // Define the mouse hook procedure
-        unsafe extern "system" fn mouse_hook_proc(
-            code: i32,
-            wparam: WPARAM,
-            lparam: LPARAM,
-        ) -> isize {
-            if code < 0 || code != HC_ACTION {
-                return CallNextHookEx(None, code, wparam, lparam);
-            }
-            
-            let hook_struct = *(lparam.0 as *const MSLLHOOKSTRUCT);
-            let x = hook_struct.pt.x;
-            let y = hook_struct.pt.y;
-            
-            // Store the current mouse position
-            if let Some(last_pos) = LAST_MOUSE_POS.as_ref() {
-                if let Ok(mut last_pos) = last_pos.lock() {
-                    *last_pos = Some(POINT { x, y });
-                }
-            }
-            
-            // Determine the mouse event type and button
-            let (event_type, button) = match wparam.0 as u32 {
-                WM_LBUTTONDOWN => (MouseEventType::Down, MouseButton::Left),
-                WM_LBUTTONUP => (MouseEventType::Up, MouseButton::Left),
-                WM_RBUTTONDOWN => (MouseEventType::Down, MouseButton::Right),
-                WM_RBUTTONUP => (MouseEventType::Up, MouseButton::Right),
-                WM_MBUTTONDOWN => (MouseEventType::Down, MouseButton::Middle),
-                WM_MBUTTONUP => (MouseEventType::Up, MouseButton::Middle),
-                WM_MOUSEMOVE => (MouseEventType::Move, MouseButton::Left),
-                WM_MOUSEWHEEL => (MouseEventType::Wheel, MouseButton::Middle),
-                _ => return CallNextHookEx(None, code, wparam, lparam),
-            };
-            
-            // Skip mouse move events unless it's a significant movement
-            if event_type == MouseEventType::Move {
-                // Only process every 10th mouse move event to reduce noise
-                static mut MOVE_COUNTER: u32 = 0;
-                MOVE_COUNTER += 1;
-                if MOVE_COUNTER % 10 != 0 {
-                    return CallNextHookEx(None, code, wparam, lparam);
-                }
-            }
-            
-            // Create position
-            let position = Position { x, y };
-            
-            // Get UI element under mouse if needed
-            let mut ui_element = None;
-            if CAPTURE_UI_ELEMENTS && (event_type == MouseEventType::Down || event_type == MouseEventType::Up) {
-                if let Some(automation) = AUTOMATION.as_ref() {
-                    ui_element = get_ui_element_at_point(automation, x, y);
+    HOOK_CONTEXT.with(|context| context.borrow_mut().take());
+    debug!("Hook thread stopped");
+}
+
+/// Install the enabled hooks on the current thread
+#[cfg(target_os = "windows")]
+unsafe fn install_hooks(keyboard: bool, mouse: bool) -> Result<Vec<HHOOK>> {
+    let mut hooks = Vec::new();
+    type HookProc = unsafe extern "system" fn(i32, WPARAM, LPARAM) -> LRESULT;
+    let wanted: [(bool, _, HookProc, &str); 2] = [
+        (keyboard, WH_KEYBOARD_LL, keyboard_hook_proc, "keyboard"),
+        (mouse, WH_MOUSE_LL, mouse_hook_proc, "mouse"),
+    ];
+    for (enabled, id, proc, kind) in wanted {
+        if !enabled {
+            continue;
+        }
+        match SetWindowsHookExW(id, Some(proc), None, 0) {
+            Ok(hook) => hooks.push(hook),
+            Err(e) => {
+                for hook in hooks {
+                    let _ = UnhookWindowsHookEx(hook);
                 }
+                return Err(WorkflowRecorderError::InitializationError(format!(
+                    "Failed to set {} hook: {}",
+                    kind, e
+                )));
             }
-            
-            // Create mouse event
-            let mouse_event = MouseEvent {
-                event_type,
-                button,
-                position,
-                ui_element,
-            };
-            
-            // Send event
-            if let Some(tx) = EVENT_TX.as_ref() {
-                let _ = tx.send(WorkflowEvent::Mouse(mouse_event));
+        }
+    }
+    Ok(hooks)
+}
+
+/// Run `f` with the hook thread's context. Skips the event if the context is
+/// missing or already in use by an outer hook call.
+#[cfg(target_os = "windows")]
+fn with_hook_context(f: impl FnOnce(&mut HookContext)) {
+    HOOK_CONTEXT.with(|context| {
+        if let Ok(mut context) = context.try_borrow_mut() {
+            if let Some(context) = context.as_mut() {
+                f(context);
             }
-            
-            CallNextHookEx(None, code, wparam, lparam)
         }
-        
-        // Store the necessary data in thread-local statics
-        thread_local! {
-            static EVENT_TX: std::cell::RefCell<Option<UnboundedSender<WorkflowEvent>>> = std::cell::RefCell::new(None);
-            static AUTOMATION: std::cell::RefCell<Option<Arc<UIAutomation>>> = std::cell::RefCell::new(None);
-            static LAST_MOUSE_POS: std::cell::RefCell<Option<Arc<Mutex<Option<POINT>>>>> = std::cell::RefCell::new(None);
-            static CAPTURE_UI_ELEMENTS: bool = false;
+    });
+}
+
+/// The keyboard hook procedure
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let hook_struct = *(lparam.0 as *const KBDLLHOOKSTRUCT);
+        with_hook_context(|context| handle_keyboard(context, wparam, &hook_struct));
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// The mouse hook procedure
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let hook_struct = *(lparam.0 as *const MSLLHOOKSTRUCT);
+        with_hook_context(|context| handle_mouse(context, wparam, &hook_struct));
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// Record a keyboard event, or insert a checkpoint for the checkpoint hotkey
+#[cfg(target_os = "windows")]
+unsafe fn handle_keyboard(context: &mut HookContext, wparam: WPARAM, hook_struct: &KBDLLHOOKSTRUCT) {
+    let key_code = hook_struct.vkCode;
+    
+    // Check if key down or up; keys pressed with Alt arrive as system keys
+    let is_key_down = wparam.0 == WM_KEYDOWN as usize || wparam.0 == WM_SYSKEYDOWN as usize;
+    let is_key_up = wparam.0 == WM_KEYUP as usize || wparam.0 == WM_SYSKEYUP as usize;
+    
+    // Injected Caps Lock presses toggle it too, so follow them before filtering
+    if key_code == VK_CAPITAL.0 as u32 && (is_key_down || is_key_up) {
+        if is_key_down && !context.caps_lock_down {
+            context.caps_lock = !context.caps_lock;
         }
-        
-        EVENT_TX.with(|tx| {
-            *tx.borrow_mut() = Some(event_tx);
-        });
-        
-        AUTOMATION.with(|auto| {
-            *auto.borrow_mut() = Some(automation);
-        });
-        
-        LAST_MOUSE_POS.with(|pos| {
-            *pos.borrow_mut() = Some(last_mouse_pos);
-        });
-        
-        CAPTURE_UI_ELEMENTS.with(|capture| {
-            *capture = capture_ui_elements;
-        });
-        
-        // Set the mouse hook
-        unsafe {
-            let hook = SetWindowsHookExW(
-                WH_MOUSE_LL,
-                Some(mouse_hook_proc),
-                None,
-                0,
-            );
-            
-            if hook.is_null() {
-                return Err(WorkflowRecorderError::InitializationError(
-                    "Failed to set mouse hook".to_string(),
-                ));
+        context.caps_lock_down = is_key_down;
+    }
+    
+    let config = &context.shared.config;
+    
+    // Synthesized keystrokes come from automation, which may be typing secrets
+    let injected = (hook_struct.flags.0 & LLKHF_INJECTED.0) != 0;
+    if injected && !config.record_injected_keyboard {
+        return;
+    }
+    
+    // Ctrl+Shift+hotkey checks the element under the cursor; with Alt, how it looks
+    if config.checkpoint_hotkey == Some(key_code)
+        && GetAsyncKeyState(VK_CONTROL.0 as i32) < 0
+        && GetAsyncKeyState(VK_SHIFT.0 as i32) < 0
+    {
+        if is_key_down {
+            let screenshot = GetAsyncKeyState(VK_MENU.0 as i32) < 0;
+            if let Some(checkpoint) = checkpoint_at_cursor(&context.automation, screenshot) {
+                let _ = context.shared.event_tx.send(WorkflowEvent::Checkpoint(checkpoint));
             }
-            
-            self.mouse_hook = Some(hook.0);
         }
+        return;
+    }
+    
+    if is_key_down || is_key_up {
+        // Get modifier key states
+        let ctrl_pressed = GetAsyncKeyState(VK_CONTROL.0 as i32) < 0 || key_code == 17;
+        let alt_pressed = GetAsyncKeyState(VK_MENU.0 as i32) < 0 || key_code == 18;
+        let shift_pressed = GetAsyncKeyState(VK_SHIFT.0 as i32) < 0 || key_code == 16;
+        let win_pressed = GetAsyncKeyState(VK_LWIN.0 as i32) < 0
+            || GetAsyncKeyState(VK_RWIN.0 as i32) < 0
+            || key_code == 91
+            || key_code == 92;
         
-        Ok(())
+        let (character, key_name) = decode_key(hook_struct, context.caps_lock);
+        
+        // Create keyboard event
+        let keyboard_event = KeyboardEvent {
+            key_code,
+            is_key_down,
+            ctrl_pressed,
+            alt_pressed,
+            shift_pressed,
+            win_pressed,
+            character,
+            key_name,
+        };
+        
+        // Send event
+        let _ = context.shared.event_tx.send(WorkflowEvent::Keyboard(keyboard_event));
     }
+}
+
+/// Record a mouse event, with the element under the cursor where configured
+#[cfg(target_os = "windows")]
+unsafe fn handle_mouse(context: &mut HookContext, wparam: WPARAM, hook_struct: &MSLLHOOKSTRUCT) {
+    let x = hook_struct.pt.x;
+    let y = hook_struct.pt.y;
     
-    /// Stop recording
-    pub fn stop(&self) -> Result<()> {
-        // Unhook the keyboard hook
-        if let Some(hook) = self.keyboard_hook {
-            unsafe {
-                if UnhookWindowsHookEx(HWND(hook)).is_err() {
-                    warn!("Failed to unhook keyboard hook");
-                }
-            }
+    // Store the current mouse position
+    if let Ok(mut last_pos) = context.shared.last_mouse_pos.lock() {
+        *last_pos = Some(POINT { x, y });
+    }
+    
+    // Determine the mouse event type and button
+    let (event_type, button) = match wparam.0 as u32 {
+        WM_LBUTTONDOWN => (MouseEventType::Down, MouseButton::Left),
+        WM_LBUTTONUP => (MouseEventType::Up, MouseButton::Left),
+        WM_RBUTTONDOWN => (MouseEventType::Down, MouseButton::Right),
+        WM_RBUTTONUP => (MouseEventType::Up, MouseButton::Right),
+        WM_MBUTTONDOWN => (MouseEventType::Down, MouseButton::Middle),
+        WM_MBUTTONUP => (MouseEventType::Up, MouseButton::Middle),
+        WM_MOUSEMOVE => (MouseEventType::Move, MouseButton::Left),
+        WM_MOUSEWHEEL => (MouseEventType::Wheel, MouseButton::Middle),
+        _ => return,
+    };
+    
+    // Skip mouse move events unless it's a significant movement
+    if event_type == MouseEventType::Move {
+        context.move_counter = context.move_counter.wrapping_add(1);
+        if context.move_counter % MOVE_SAMPLE_RATE != 0 {
+            return;
         }
-        
-        // Unhook the mouse hook
-        if let Some(hook) = self.mouse_hook {
-            unsafe {
-                if UnhookWindowsHookEx(HWND(hook)).is_err() {
-                    warn!("Failed to unhook mouse hook");
-                }
+    }
+    
+    // Create position
+    let position = Position { x, y };
+    
+    // Clicks always carry their element; wheel and move events only when configured,
+    // and moves at most once per interval since the lookup is slow
+    let config = &context.shared.config;
+    let wants_element = match event_type {
+        MouseEventType::Down | MouseEventType::Up => true,
+        MouseEventType::Wheel => config.capture_ui_elements_on_wheel,
+        MouseEventType::Move => config.move_ui_element_interval.is_some_and(|interval| {
+            let due = context
+                .last_move_capture
+                .map_or(true, |at| at.elapsed() >= interval);
+            if due {
+                context.last_move_capture = Some(Instant::now());
             }
+            due
+        }),
+        _ => false,
+    };
+    
+    // Get UI element under mouse if needed
+    let ui_element = if config.capture_ui_elements && wants_element {
+        get_ui_element_at_point(&context.automation, x, y)
+    } else {
+        None
+    };
+    
+    // Where the cursor is, so playback can map the position onto another screen layout
+    let point = POINT { x, y };
+    let monitor = get_monitor_at_point(point);
+    let window = get_top_level_window_at_point(point, &mut context.process_names);
+    
+    // Create mouse event
+    let mouse_event = MouseEvent {
+        event_type,
+        button,
+        position,
+        ui_element,
+        monitor,
+        window,
+    };
+    
+    // Send event
+    let _ = context.shared.event_tx.send(WorkflowEvent::Mouse(mouse_event));
+}
+
+/// Build a checkpoint for the element under the cursor
+#[cfg(target_os = "windows")]
+unsafe fn checkpoint_at_cursor(automation: &UIAutomation, screenshot: bool) -> Option<CheckpointEvent> {
+    let mut point = POINT::default();
+    GetCursorPos(&mut point).ok()?;
+    let element = get_ui_element_at_point(automation, point.x, point.y);
+    if !screenshot {
+        return element.map(element_checkpoint);
+    }
+    let region = element.as_ref()?.bounding_rect.clone()?;
+    let fingerprint = capture_fingerprint(&region).ok()?;
+    Some(CheckpointEvent {
+        assertion: CheckpointAssertion::ScreenshotMatches {
+            region,
+            fingerprint,
+            tolerance: DEFAULT_SCREENSHOT_TOLERANCE,
+        },
+        ui_element: element,
+        description: None,
+    })
+}
+
+/// Get the monitor containing the given point, or the nearest one
+#[cfg(target_os = "windows")]
+unsafe fn get_monitor_at_point(point: POINT) -> Option<MonitorInfo> {
+    let monitor = MonitorFromPoint(point, MONITOR_DEFAULTTONEAREST);
+    if monitor.is_invalid() {
+        return None;
+    }
+    
+    let mut info = MONITORINFOEXW::default();
+    info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+    if !GetMonitorInfoW(monitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO).as_bool() {
+        return None;
+    }
+    
+    let len = info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len());
+    Some(MonitorInfo {
+        id: String::from_utf16_lossy(&info.szDevice[..len]),
+        bounds: rect_from_win(&info.monitorInfo.rcMonitor),
+        work_area: rect_from_win(&info.monitorInfo.rcWork),
+        is_primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+    })
+}
+
+/// Get the top-level window under the given point
+#[cfg(target_os = "windows")]
+unsafe fn get_top_level_window_at_point(
+    point: POINT,
+    process_names: &mut HashMap<u32, Option<String>>,
+) -> Option<TopLevelWindow> {
+    let hwnd = WindowFromPoint(point);
+    if hwnd.is_invalid() {
+        return None;
+    }
+    let hwnd = GetAncestor(hwnd, GA_ROOT);
+    if hwnd.is_invalid() {
+        return None;
+    }
+    
+    let mut buffer = [0u16; 512];
+    let len = GetWindowTextW(hwnd, &mut buffer);
+    let title = (len > 0).then(|| String::from_utf16_lossy(&buffer[..len as usize]));
+    
+    let mut pid = 0u32;
+    GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    let process_id = (pid != 0).then_some(pid);
+    let application_name = process_id.and_then(|pid| {
+        process_names
+            .entry(pid)
+            .or_insert_with(|| get_process_name(pid))
+            .clone()
+    });
+    
+    let mut rect = RECT::default();
+    let bounds = GetWindowRect(hwnd, &mut rect).ok().map(|_| rect_from_win(&rect));
+    
+    Some(TopLevelWindow {
+        handle: hwnd.0 as isize,
+        title,
+        process_id,
+        application_name,
+        bounds,
+    })
+}
+
+/// Convert a Win32 rectangle
+#[cfg(target_os = "windows")]
+fn rect_from_win(rect: &RECT) -> Rect {
+    Rect {
+        x: rect.left,
+        y: rect.top,
+        width: rect.right - rect.left,
+        height: rect.bottom - rect.top,
+    }
+}
+
+/// Resolve the text a key produces in the foreground window's keyboard layout, and the
+/// layout's name for the key
+#[cfg(target_os = "windows")]
+unsafe fn decode_key(hook_struct: &KBDLLHOOKSTRUCT, caps_lock: bool) -> (Option<String>, Option<String>) {
+    // The hook thread's own keyboard state is stale, so rebuild the modifiers from the
+    // physical key state
+    let mut key_state = [0u8; 256];
+    for key in [
+        VK_SHIFT, VK_LSHIFT, VK_RSHIFT, VK_CONTROL, VK_LCONTROL, VK_RCONTROL, VK_MENU, VK_LMENU,
+        VK_RMENU,
+    ] {
+        if GetAsyncKeyState(key.0 as i32) < 0 {
+            key_state[key.0 as usize] = 0x80;
         }
-        
-        Ok(())
     }
+    if caps_lock {
+        key_state[VK_CAPITAL.0 as usize] = 0x01;
+    }
+
+    let mut buffer = [0u16; 8];
+    // Flag 0x4 leaves the keyboard state untouched, so dead keys still compose for the user
+    let len = ToUnicodeEx(
+        hook_struct.vkCode,
+        hook_struct.scanCode,
+        &key_state,
+        &mut buffer,
+        0x4,
+        Some(foreground_keyboard_layout()),
+    );
+    let character = (len > 0).then(|| String::from_utf16_lossy(&buffer[..len as usize]));
+
+    let mut lparam = (hook_struct.scanCode << 16) as i32;
+    if hook_struct.flags.contains(LLKHF_EXTENDED) {
+        lparam |= 1 << 24;
+    }
+    let mut name = [0u16; 64];
+    let len = GetKeyNameTextW(lparam, &mut name);
+    let key_name = (len > 0).then(|| String::from_utf16_lossy(&name[..len as usize]));
+
+    (character, key_name)
+}
+
+/// The keyboard layout of the thread owning the foreground window
+#[cfg(target_os = "windows")]
+unsafe fn foreground_keyboard_layout() -> HKL {
+    let thread_id = GetWindowThreadProcessId(GetForegroundWindow(), None);
+    GetKeyboardLayout(thread_id)
+}
+
+/// The active keyboard layout as a hex input locale identifier, e.g. `04090409`
+#[cfg(target_os = "windows")]
+pub fn active_keyboard_layout() -> Option<String> {
+    let layout = unsafe { foreground_keyboard_layout() };
+    if layout.is_invalid() {
+        return None;
+    }
+    Some(format!("{:08X}", layout.0 as usize & 0xFFFF_FFFF))
 }
 
 /// Get the UI element at the given point
@@ -421,10 +716,9 @@ fn get_element_hierarchy_path(element: &WinUIElement) -> Option<String> {
     }
 }
 
-/// Get window information for the given process ID
+/// Get the executable name of the given process
 #[cfg(target_os = "windows")]
-fn get_window_info_for_process(process_id: u32) -> (Option<String>, Option<String>) {
-    let mut window_title = None;
+fn get_process_name(process_id: u32) -> Option<String> {
     let mut application_name = None;
 
     unsafe {
@@ -454,7 +748,18 @@ fn get_window_info_for_process(process_id: u32) -> (Option<String>, Option<Strin
             // Close the process handle
             process_handle.close();
         }
+    }
+
+    application_name
+}
 
+/// Get window information for the given process ID
+#[cfg(target_os = "windows")]
+fn get_window_info_for_process(process_id: u32) -> (Option<String>, Option<String>) {
+    let application_name = get_process_name(process_id);
+    let mut window_title = None;
+
+    unsafe {
         // Find the main window for this process
         // Use EnumWindows to find windows belonging to the process
         use windows::Win32::UI::WindowsAndMessaging::{EnumWindows, GetWindowThreadProcessId};
@@ -0,0 +1,663 @@
+use crate::{
+    KeyboardEvent, MouseButton, MouseEventType, Position, RecordedWorkflow, UiElement,
+    WorkflowEvent,
+};
+use serde::{Deserialize, Serialize};
+
+/// Presses of the same button closer together than this form a double click (milliseconds)
+const DOUBLE_CLICK_TIME: u64 = 500;
+
+/// Presses further apart than this are separate clicks (pixels)
+const DOUBLE_CLICK_DISTANCE: i32 = 4;
+
+/// What a semantic action did
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SemanticActionKind {
+    /// A left click
+    Click,
+
+    /// A left double click
+    DoubleClick,
+
+    /// A right click
+    RightClick,
+
+    /// Text typed into a field
+    Type {
+        /// The typed text, with backspaces applied
+        text: String,
+    },
+
+    /// A key combination with Ctrl, Alt or Win, or a lone navigation key
+    KeyPress {
+        /// The keys, e.g. `Ctrl+S`
+        keys: String,
+    },
+
+    /// One or more consecutive mouse wheel events
+    Scroll,
+
+    /// The user switched to another window
+    SwitchWindow,
+}
+
+/// A user-level action inferred from low-level events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticAction {
+    /// The timestamp of the first event of the action (milliseconds since epoch)
+    pub start_time: u64,
+
+    /// The timestamp of the last event of the action (milliseconds since epoch)
+    pub end_time: u64,
+
+    /// What the action did
+    pub kind: SemanticActionKind,
+
+    /// The element acted on, when known
+    pub target: Option<UiElement>,
+
+    /// The application name
+    pub application_name: Option<String>,
+
+    /// The window title
+    pub window_title: Option<String>,
+
+    /// Human-readable description, e.g. `clicked button 'Save' in 'Invoice — SAP'`
+    pub description: String,
+}
+
+/// Convert the low-level events of a workflow into semantic actions.
+///
+/// Mouse presses become clicks (two quick presses in place become a double
+/// click), consecutive wheel events become one scroll, and keystrokes become
+/// typed text attributed to the element last clicked, or key combinations.
+pub fn infer_semantic_actions(workflow: &RecordedWorkflow) -> Vec<SemanticAction> {
+    let mut actions: Vec<SemanticAction> = Vec::new();
+    let mut window_title: Option<String> = None;
+    let mut application_name: Option<String> = None;
+    // The element keystrokes go to: the one last clicked
+    let mut focused: Option<UiElement> = None;
+    let mut last_press: Option<(u64, Position)> = None;
+
+    for event in &workflow.events {
+        let timestamp = event.timestamp;
+        match &event.event {
+            WorkflowEvent::Mouse(mouse_event) => {
+                if let Some(element) = &mouse_event.ui_element {
+                    if element.window_title.is_some() {
+                        window_title = element.window_title.clone();
+                    }
+                    if element.application_name.is_some() {
+                        application_name = element.application_name.clone();
+                    }
+                }
+
+                match (mouse_event.event_type, mouse_event.button) {
+                    (MouseEventType::Wheel, _) => {
+                        if let Some(last) = actions.last_mut() {
+                            if last.kind == SemanticActionKind::Scroll {
+                                last.end_time = timestamp;
+                                continue;
+                            }
+                        }
+                        actions.push(SemanticAction {
+                            start_time: timestamp,
+                            end_time: timestamp,
+                            kind: SemanticActionKind::Scroll,
+                            target: mouse_event.ui_element.clone(),
+                            application_name: application_name.clone(),
+                            window_title: window_title.clone(),
+                            description: String::new(),
+                        });
+                    }
+                    (MouseEventType::RightClick, _) | (MouseEventType::Down, MouseButton::Right) => {
+                        focused = mouse_event.ui_element.clone();
+                        last_press = None;
+                        actions.push(SemanticAction {
+                            start_time: timestamp,
+                            end_time: timestamp,
+                            kind: SemanticActionKind::RightClick,
+                            target: mouse_event.ui_element.clone(),
+                            application_name: application_name.clone(),
+                            window_title: window_title.clone(),
+                            description: String::new(),
+                        });
+                    }
+                    (MouseEventType::Down | MouseEventType::Click, MouseButton::Left)
+                    | (MouseEventType::DoubleClick, _) => {
+                        focused = mouse_event.ui_element.clone();
+                        let position = mouse_event.position.clone();
+                        let is_second_press = last_press.as_ref().is_some_and(|(time, at)| {
+                            timestamp.saturating_sub(*time) <= DOUBLE_CLICK_TIME
+                                && (at.x - position.x).abs() <= DOUBLE_CLICK_DISTANCE
+                                && (at.y - position.y).abs() <= DOUBLE_CLICK_DISTANCE
+                        });
+                        let last_is_click = actions
+                            .last()
+                            .is_some_and(|last| last.kind == SemanticActionKind::Click);
+
+                        if mouse_event.event_type == MouseEventType::DoubleClick
+                            || (is_second_press && last_is_click)
+                        {
+                            if is_second_press && last_is_click {
+                                actions.pop();
+                            }
+                            last_press = None;
+                            actions.push(SemanticAction {
+                                start_time: timestamp,
+                                end_time: timestamp,
+                                kind: SemanticActionKind::DoubleClick,
+                                target: mouse_event.ui_element.clone(),
+                                application_name: application_name.clone(),
+                                window_title: window_title.clone(),
+                                description: String::new(),
+                            });
+                        } else {
+                            last_press = Some((timestamp, position));
+                            actions.push(SemanticAction {
+                                start_time: timestamp,
+                                end_time: timestamp,
+                                kind: SemanticActionKind::Click,
+                                target: mouse_event.ui_element.clone(),
+                                application_name: application_name.clone(),
+                                window_title: window_title.clone(),
+                                description: String::new(),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            WorkflowEvent::Keyboard(keyboard_event) => {
+                if !keyboard_event.is_key_down || is_modifier(keyboard_event.key_code) {
+                    continue;
+                }
+
+                let has_command_modifier = keyboard_event.ctrl_pressed
+                    || keyboard_event.alt_pressed
+                    || keyboard_event.win_pressed;
+                let typed = if has_command_modifier {
+                    None
+                } else {
+                    typed_char(keyboard_event)
+                };
+
+                match typed {
+                    Some(c) => {
+                        if let Some(last) = actions.last_mut() {
+                            if let SemanticActionKind::Type { text } = &mut last.kind {
+                                if c == '\u{8}' {
+                                    text.pop();
+                                } else {
+                                    text.push(c);
+                                }
+                                last.end_time = timestamp;
+                                continue;
+                            }
+                        }
+                        if c == '\u{8}' {
+                            continue; // deleting text typed before this run is not recoverable
+                        }
+                        actions.push(SemanticAction {
+                            start_time: timestamp,
+                            end_time: timestamp,
+                            kind: SemanticActionKind::Type {
+                                text: c.to_string(),
+                            },
+                            target: focused.clone(),
+                            application_name: application_name.clone(),
+                            window_title: window_title.clone(),
+                            description: String::new(),
+                        });
+                    }
+                    None => {
+                        actions.push(SemanticAction {
+                            start_time: timestamp,
+                            end_time: timestamp,
+                            kind: SemanticActionKind::KeyPress {
+                                keys: key_combination(keyboard_event),
+                            },
+                            target: focused.clone(),
+                            application_name: application_name.clone(),
+                            window_title: window_title.clone(),
+                            description: String::new(),
+                        });
+                    }
+                }
+            }
+            WorkflowEvent::WindowFocusChanged(window_event) => {
+                window_title = window_event.title.clone();
+                application_name = window_event.application_name.clone();
+                focused = None;
+                last_press = None;
+                actions.push(SemanticAction {
+                    start_time: timestamp,
+                    end_time: timestamp,
+                    kind: SemanticActionKind::SwitchWindow,
+                    target: None,
+                    application_name: application_name.clone(),
+                    window_title: window_title.clone(),
+                    description: String::new(),
+                });
+            }
+            WorkflowEvent::WindowCreated(_)
+            | WorkflowEvent::WindowClosed(_)
+            | WorkflowEvent::Checkpoint(_) => {}
+        }
+    }
+
+    for action in &mut actions {
+        action.description = describe(action);
+    }
+    actions
+}
+
+/// Human-readable description of an action
+fn describe(action: &SemanticAction) -> String {
+    let target = action.target.as_ref().map(describe_element);
+    let mut description = match (&action.kind, target) {
+        (SemanticActionKind::Click, Some(target)) => format!("clicked {}", target),
+        (SemanticActionKind::Click, None) => "clicked".to_string(),
+        (SemanticActionKind::DoubleClick, Some(target)) => format!("double-clicked {}", target),
+        (SemanticActionKind::DoubleClick, None) => "double-clicked".to_string(),
+        (SemanticActionKind::RightClick, Some(target)) => format!("right-clicked {}", target),
+        (SemanticActionKind::RightClick, None) => "right-clicked".to_string(),
+        (SemanticActionKind::Type { text }, Some(target)) => {
+            format!("typed '{}' into {}", text, target)
+        }
+        (SemanticActionKind::Type { text }, None) => format!("typed '{}'", text),
+        (SemanticActionKind::KeyPress { keys }, _) => format!("pressed {}", keys),
+        (SemanticActionKind::Scroll, Some(target)) => format!("scrolled {}", target),
+        (SemanticActionKind::Scroll, None) => "scrolled".to_string(),
+        (SemanticActionKind::SwitchWindow, _) => {
+            return match (&action.window_title, &action.application_name) {
+                (Some(title), Some(app)) => format!("switched to '{}' ({})", title, app),
+                (Some(title), None) => format!("switched to '{}'", title),
+                (None, Some(app)) => format!("switched to {}", app),
+                (None, None) => "switched window".to_string(),
+            };
+        }
+    };
+    if let Some(title) = action.window_title.as_deref().filter(|t| !t.is_empty()) {
+        description.push_str(&format!(" in '{}'", title));
+    }
+    description
+}
+
+/// Describe an element as `button 'Save'`, falling back to its type or name alone
+fn describe_element(element: &UiElement) -> String {
+    let control_type = element
+        .control_type
+        .as_deref()
+        .map(|t| t.trim_end_matches("Control").to_lowercase())
+        .filter(|t| !t.is_empty());
+    let kind = match control_type.as_deref() {
+        Some("edit") | Some("combobox") | Some("document") => Some("field".to_string()),
+        _ => control_type,
+    };
+    match (kind, element.name.as_deref().filter(|n| !n.is_empty())) {
+        (Some(kind), Some(name)) => format!("{} '{}'", kind, name),
+        (Some(kind), None) => kind,
+        (None, Some(name)) => format!("'{}'", name),
+        (None, None) => "element".to_string(),
+    }
+}
+
+pub(crate) fn is_modifier(key_code: u32) -> bool {
+    // Shift, Ctrl, Alt, left/right Win, and their left/right variants
+    matches!(key_code, 16 | 17 | 18 | 91 | 92 | 160..=165)
+}
+
+/// The character a keystroke types, `'\u{8}'` for backspace. Uses the character
+/// recorded with the event, or a US layout for recordings without one.
+pub(crate) fn typed_char(event: &KeyboardEvent) -> Option<char> {
+    if event.key_code == 0x08 {
+        return Some('\u{8}');
+    }
+    if let Some(character) = &event.character {
+        let mut chars = character.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) if !c.is_control() => Some(c),
+            _ => None,
+        };
+    }
+    let shift = event.shift_pressed;
+    let c = match event.key_code {
+        0x08 => '\u{8}',
+        0x20 => ' ',
+        0x30..=0x39 if shift => b")!@#$%^&*("[(event.key_code - 0x30) as usize] as char,
+        0x30..=0x39 => char::from_digit(event.key_code - 0x30, 10)?,
+        0x41..=0x5A if shift => char::from_u32(event.key_code)?,
+        0x41..=0x5A => char::from_u32(event.key_code)?.to_ascii_lowercase(),
+        0x60..=0x69 => char::from_digit(event.key_code - 0x60, 10)?,
+        0x6A => '*',
+        0x6B => '+',
+        0x6D => '-',
+        0x6E => '.',
+        0x6F => '/',
+        0xBA => if shift { ':' } else { ';' },
+        0xBB => if shift { '+' } else { '=' },
+        0xBC => if shift { '<' } else { ',' },
+        0xBD => if shift { '_' } else { '-' },
+        0xBE => if shift { '>' } else { '.' },
+        0xBF => if shift { '?' } else { '/' },
+        0xC0 => if shift { '~' } else { '`' },
+        0xDB => if shift { '{' } else { '[' },
+        0xDC => if shift { '|' } else { '\\' },
+        0xDD => if shift { '}' } else { ']' },
+        0xDE => if shift { '"' } else { '\'' },
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// The virtual-key code and Shift state that type `c` on a US layout; the inverse of [`typed_char`]
+pub(crate) fn key_for_char(c: char) -> Option<(u32, bool)> {
+    (0x08..=0xDE).find_map(|key_code| {
+        [false, true].into_iter().find_map(|shift| {
+            let event = KeyboardEvent {
+                key_code,
+                is_key_down: true,
+                ctrl_pressed: false,
+                alt_pressed: false,
+                shift_pressed: shift,
+                win_pressed: false,
+                character: None,
+                key_name: None,
+            };
+            (typed_char(&event) == Some(c)).then_some((key_code, shift))
+        })
+    })
+}
+
+/// A key combination such as `Ctrl+Shift+S` or `Enter`
+fn key_combination(event: &KeyboardEvent) -> String {
+    let mut keys = Vec::new();
+    if event.ctrl_pressed {
+        keys.push("Ctrl".to_string());
+    }
+    if event.alt_pressed {
+        keys.push("Alt".to_string());
+    }
+    if event.shift_pressed {
+        keys.push("Shift".to_string());
+    }
+    if event.win_pressed {
+        keys.push("Win".to_string());
+    }
+    keys.push(
+        event
+            .key_name
+            .clone()
+            .unwrap_or_else(|| key_name(event.key_code)),
+    );
+    keys.join("+")
+}
+
+/// Name of a virtual-key code
+fn key_name(key_code: u32) -> String {
+    let name = match key_code {
+        0x08 => "Backspace",
+        0x09 => "Tab",
+        0x0D => "Enter",
+        0x1B => "Escape",
+        0x20 => "Space",
+        0x21 => "PageUp",
+        0x22 => "PageDown",
+        0x23 => "End",
+        0x24 => "Home",
+        0x25 => "Left",
+        0x26 => "Up",
+        0x27 => "Right",
+        0x28 => "Down",
+        0x2D => "Insert",
+        0x2E => "Delete",
+        0x30..=0x39 | 0x41..=0x5A => {
+            return char::from_u32(key_code).map_or_else(String::new, |c| c.to_string());
+        }
+        0x70..=0x87 => return format!("F{}", key_code - 0x6F),
+        _ => return format!("Key{}", key_code),
+    };
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MouseEvent, RecordedEvent, WindowEvent};
+
+    fn element(control_type: &str, name: &str) -> UiElement {
+        UiElement {
+            name: Some(name.to_string()),
+            automation_id: None,
+            class_name: None,
+            control_type: Some(control_type.to_string()),
+            process_id: None,
+            application_name: Some("Outlook".to_string()),
+            window_title: Some("New message".to_string()),
+            bounding_rect: None,
+            is_enabled: None,
+            has_keyboard_focus: None,
+            hierarchy_path: None,
+            value: None,
+        }
+    }
+
+    fn mouse(
+        timestamp: u64,
+        event_type: MouseEventType,
+        button: MouseButton,
+        (x, y): (i32, i32),
+    ) -> RecordedEvent {
+        RecordedEvent {
+            timestamp,
+            video: None,
+            event: WorkflowEvent::Mouse(MouseEvent {
+                event_type,
+                button,
+                position: Position { x, y },
+                ui_element: Some(element("EditControl", "Subject")),
+                monitor: None,
+                window: None,
+            }),
+        }
+    }
+
+    fn press(timestamp: u64, at: (i32, i32)) -> RecordedEvent {
+        mouse(timestamp, MouseEventType::Down, MouseButton::Left, at)
+    }
+
+    fn wheel(timestamp: u64) -> RecordedEvent {
+        mouse(timestamp, MouseEventType::Wheel, MouseButton::Middle, (10, 10))
+    }
+
+    fn key_event(key_code: u32, shift: bool) -> KeyboardEvent {
+        KeyboardEvent {
+            key_code,
+            is_key_down: true,
+            ctrl_pressed: false,
+            alt_pressed: false,
+            shift_pressed: shift,
+            win_pressed: false,
+            character: None,
+            key_name: None,
+        }
+    }
+
+    fn key(timestamp: u64, event: KeyboardEvent) -> RecordedEvent {
+        RecordedEvent {
+            timestamp,
+            video: None,
+            event: WorkflowEvent::Keyboard(event),
+        }
+    }
+
+    /// Key down and up for each character of `text`, on a US layout
+    fn typing(timestamp: u64, text: &str) -> Vec<RecordedEvent> {
+        text.chars()
+            .flat_map(|c| {
+                let (key_code, shift) = key_for_char(c).unwrap();
+                let up = KeyboardEvent {
+                    is_key_down: false,
+                    ..key_event(key_code, shift)
+                };
+                [key(timestamp, key_event(key_code, shift)), key(timestamp, up)]
+            })
+            .collect()
+    }
+
+    fn backspace(timestamp: u64) -> RecordedEvent {
+        key(timestamp, key_event(0x08, false))
+    }
+
+    fn kinds(events: Vec<RecordedEvent>) -> Vec<SemanticActionKind> {
+        infer(events).into_iter().map(|action| action.kind).collect()
+    }
+
+    fn infer(events: Vec<RecordedEvent>) -> Vec<SemanticAction> {
+        let mut workflow = RecordedWorkflow::new("Semantic".to_string());
+        workflow.events = events;
+        infer_semantic_actions(&workflow)
+    }
+
+    fn typed(text: &str) -> SemanticActionKind {
+        SemanticActionKind::Type {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn quick_presses_in_place_merge_into_a_double_click() {
+        let actions = infer(vec![
+            press(1_000, (100, 100)),
+            mouse(1_050, MouseEventType::Up, MouseButton::Left, (100, 100)),
+            press(1_300, (102, 99)),
+        ]);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].kind, SemanticActionKind::DoubleClick);
+        assert_eq!(actions[0].description, "double-clicked field 'Subject' in 'New message'");
+
+        // A third press starts over
+        assert_eq!(
+            kinds(vec![press(1_000, (100, 100)), press(1_200, (100, 100)), press(1_400, (100, 100))]),
+            [SemanticActionKind::DoubleClick, SemanticActionKind::Click]
+        );
+        assert_eq!(
+            kinds(vec![mouse(1_000, MouseEventType::DoubleClick, MouseButton::Left, (5, 5))]),
+            [SemanticActionKind::DoubleClick]
+        );
+    }
+
+    #[test]
+    fn slow_or_distant_presses_stay_separate_clicks() {
+        let two_clicks = [SemanticActionKind::Click, SemanticActionKind::Click];
+        assert_eq!(kinds(vec![press(1_000, (100, 100)), press(1_600, (100, 100))]), two_clicks);
+        assert_eq!(kinds(vec![press(1_000, (100, 100)), press(1_100, (110, 100))]), two_clicks);
+        // Something in between breaks the pair
+        assert_eq!(
+            kinds(vec![press(1_000, (100, 100)), wheel(1_100), press(1_200, (100, 100))]),
+            [SemanticActionKind::Click, SemanticActionKind::Scroll, SemanticActionKind::Click]
+        );
+    }
+
+    #[test]
+    fn consecutive_wheel_events_coalesce_into_one_scroll() {
+        let actions = infer(vec![wheel(1_000), wheel(1_100), wheel(1_250)]);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].kind, SemanticActionKind::Scroll);
+        assert_eq!((actions[0].start_time, actions[0].end_time), (1_000, 1_250));
+
+        assert_eq!(
+            kinds(vec![wheel(1_000), press(1_100, (1, 1)), wheel(1_200), wheel(1_300)]),
+            [SemanticActionKind::Scroll, SemanticActionKind::Click, SemanticActionKind::Scroll]
+        );
+    }
+
+    #[test]
+    fn backspace_edits_the_text_being_typed() {
+        let mut events = vec![press(1_000, (100, 100))];
+        events.extend(typing(2_000, "Helo"));
+        events.push(backspace(3_000));
+        events.extend(typing(4_000, "lo!"));
+        let actions = infer(events);
+
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[1].kind, typed("Hello!"));
+        assert_eq!((actions[1].start_time, actions[1].end_time), (2_000, 4_000));
+        assert_eq!(actions[1].description, "typed 'Hello!' into field 'Subject' in 'New message'");
+
+        // Backspace before anything was typed, or after a shortcut, deletes what we never saw
+        let ctrl_a = KeyboardEvent {
+            ctrl_pressed: true,
+            ..key_event(0x41, false)
+        };
+        let mut events = vec![backspace(1_000)];
+        events.extend(typing(2_000, "ab"));
+        events.push(key(3_000, ctrl_a));
+        events.push(backspace(3_100));
+        events.extend(typing(4_000, "c"));
+        assert_eq!(
+            kinds(events),
+            [
+                typed("ab"),
+                SemanticActionKind::KeyPress {
+                    keys: "Ctrl+A".to_string()
+                },
+                typed("c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn switching_windows_ends_typing() {
+        let mut events = typing(1_000, "one");
+        events.push(RecordedEvent {
+            timestamp: 2_000,
+            video: None,
+            event: WorkflowEvent::WindowFocusChanged(WindowEvent {
+                title: Some("Inbox".to_string()),
+                class_name: None,
+                process_id: None,
+                application_name: Some("Outlook".to_string()),
+            }),
+        });
+        events.extend(typing(3_000, "two"));
+        let actions = infer(events);
+        assert_eq!(
+            actions.iter().map(|action| action.kind.clone()).collect::<Vec<_>>(),
+            [typed("one"), SemanticActionKind::SwitchWindow, typed("two")]
+        );
+        assert_eq!(actions[1].description, "switched to 'Inbox' (Outlook)");
+        assert_eq!(actions[2].description, "typed 'two' in 'Inbox'");
+    }
+
+    #[test]
+    fn key_for_char_inverts_typed_char() {
+        for c in (' '..='~').chain(['\u{8}']) {
+            let (key_code, shift) = key_for_char(c).unwrap_or_else(|| panic!("no key types {:?}", c));
+            assert_eq!(typed_char(&key_event(key_code, shift)), Some(c), "{:?}", c);
+        }
+        for key_code in 0..=0xFF {
+            for shift in [false, true] {
+                if let Some(c) = typed_char(&key_event(key_code, shift)) {
+                    let (code, shift) = key_for_char(c).unwrap();
+                    assert_eq!(typed_char(&key_event(code, shift)), Some(c));
+                }
+            }
+        }
+        assert_eq!(key_for_char('é'), None);
+        assert_eq!(key_for_char('\n'), None);
+    }
+
+    #[test]
+    fn typed_char_prefers_the_recorded_character() {
+        let recorded = |character: &str| KeyboardEvent {
+            character: Some(character.to_string()),
+            ..key_event(0x32, false)
+        };
+        assert_eq!(typed_char(&recorded("é")), Some('é'));
+        assert_eq!(typed_char(&recorded("\r")), None);
+        assert_eq!(typed_char(&recorded("ab")), None);
+        assert_eq!(typed_char(&key_event(0x32, true)), Some('@'));
+    }
+}
@@ -0,0 +1,318 @@
+use crate::{
+    encryption::{decode_hex, encode_hex, is_encrypted},
+    EncryptionKey, RecordedEvent, Result, WorkflowRecorderError,
+};
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, Write},
+    net::{TcpStream, ToSocketAddrs},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Receives recorded events as they happen.
+///
+/// Sinks can be attached to and detached from a running
+/// [`crate::WorkflowRecorder`] without restarting its hooks.
+pub trait EventSink: Send {
+    /// Handle one recorded event. A sink that returns an error is detached.
+    fn handle(&mut self, event: &RecordedEvent) -> Result<()>;
+
+    /// Flush buffered output; called when the sink is detached
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Identifies an attached sink, for detaching it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SinkId(pub(crate) u64);
+
+/// Appends each event to a file as one line of JSON, or with a key as one
+/// AES-256-GCM sealed record per line, hex-encoded. Read either back with [`read_jsonl`].
+pub struct JsonlFileSink {
+    writer: BufWriter<File>,
+    key: Option<EncryptionKey>,
+}
+
+impl JsonlFileSink {
+    /// Append to `path` in plaintext, creating it if needed. When the recorder
+    /// encrypts its files, use [`JsonlFileSink::create_encrypted`] or
+    /// [`crate::WorkflowRecorder::add_file_sink`] instead.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open(path, None)
+    }
+
+    /// Append to `path`, encrypting every line with `key`
+    pub fn create_encrypted<P: AsRef<Path>>(path: P, key: EncryptionKey) -> Result<Self> {
+        Self::open(path, Some(key))
+    }
+
+    pub(crate) fn open<P: AsRef<Path>>(path: P, key: Option<EncryptionKey>) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            key,
+        })
+    }
+}
+
+impl EventSink for JsonlFileSink {
+    fn handle(&mut self, event: &RecordedEvent) -> Result<()> {
+        match &self.key {
+            Some(key) => {
+                let sealed = key.encrypt(&serde_json::to_vec(event)?)?;
+                self.writer.write_all(encode_hex(&sealed).as_bytes())?;
+            }
+            None => serde_json::to_writer(&mut self.writer, event)?,
+        }
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for JsonlFileSink {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+/// Read the events a [`JsonlFileSink`] wrote to `path`, decrypting them with `key`
+/// if the sink was created with one
+pub fn read_jsonl<P: AsRef<Path>>(
+    path: P,
+    key: Option<&EncryptionKey>,
+) -> Result<Vec<RecordedEvent>> {
+    let mut events = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = match key {
+            Some(key) => {
+                let sealed = decode_hex(line.trim()).ok_or_else(|| {
+                    WorkflowRecorderError::EncryptionError(
+                        "Line is not an encrypted event".to_string(),
+                    )
+                })?;
+                serde_json::from_slice(&key.decrypt(&sealed)?)?
+            }
+            None if decode_hex(line.trim()).is_some_and(|data| is_encrypted(&data)) => {
+                return Err(WorkflowRecorderError::EncryptionError(
+                    "Events are encrypted but no key was given".to_string(),
+                ));
+            }
+            None => serde_json::from_str(&line)?,
+        };
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Keeps events in memory; clone the sink to read them while it is attached
+#[derive(Clone, Default)]
+pub struct MemorySink {
+    events: Arc<Mutex<Vec<RecordedEvent>>>,
+}
+
+impl MemorySink {
+    /// Create an empty in-memory sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The events received so far
+    pub fn events(&self) -> Vec<RecordedEvent> {
+        self.events.lock().map(|events| events.clone()).unwrap_or_default()
+    }
+
+    /// Remove and return the events received so far
+    pub fn drain(&self) -> Vec<RecordedEvent> {
+        self.events
+            .lock()
+            .map(|mut events| std::mem::take(&mut *events))
+            .unwrap_or_default()
+    }
+}
+
+impl EventSink for MemorySink {
+    fn handle(&mut self, event: &RecordedEvent) -> Result<()> {
+        self.events
+            .lock()
+            .map_err(|e| WorkflowRecorderError::RecordingError(format!("Failed to lock sink: {}", e)))?
+            .push(event.clone());
+        Ok(())
+    }
+}
+
+/// Forwards events to a channel, e.g. for a monitoring UI in the same process
+pub struct ChannelSink {
+    sender: UnboundedSender<RecordedEvent>,
+}
+
+impl ChannelSink {
+    /// Forward events to `sender`; the sink detaches once the receiver is dropped
+    pub fn new(sender: UnboundedSender<RecordedEvent>) -> Self {
+        Self { sender }
+    }
+}
+
+impl EventSink for ChannelSink {
+    fn handle(&mut self, event: &RecordedEvent) -> Result<()> {
+        self.sender.send(event.clone()).map_err(|_| {
+            WorkflowRecorderError::RecordingError("Channel sink receiver was dropped".to_string())
+        })
+    }
+}
+
+/// Streams events over TCP as lines of JSON
+pub struct TcpSink {
+    stream: BufWriter<TcpStream>,
+}
+
+impl TcpSink {
+    /// Connect to a listener at `addr`
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            stream: BufWriter::new(stream),
+        })
+    }
+}
+
+impl EventSink for TcpSink {
+    fn handle(&mut self, event: &RecordedEvent) -> Result<()> {
+        serde_json::to_writer(&mut self.stream, event)?;
+        self.stream.write_all(b"\n")?;
+        // Monitors want events live, not when the buffer fills
+        self.stream.flush()?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.stream.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{WindowEvent, WorkflowEvent};
+    use std::path::PathBuf;
+
+    fn event(title: &str) -> RecordedEvent {
+        RecordedEvent {
+            timestamp: 1_700_000_000_000,
+            video: None,
+            event: WorkflowEvent::WindowFocusChanged(WindowEvent {
+                title: Some(title.to_string()),
+                class_name: None,
+                process_id: Some(42),
+                application_name: Some("Notepad".to_string()),
+            }),
+        }
+    }
+
+    fn title(event: &RecordedEvent) -> Option<&str> {
+        match &event.event {
+            WorkflowEvent::WindowFocusChanged(window) => window.title.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// A fresh path in the temp directory, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("workflow-recorder-{}-{}.jsonl", std::process::id(), name));
+            let _ = std::fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn memory_sink_clones_share_events() {
+        let sink = MemorySink::new();
+        let mut attached = sink.clone();
+        attached.handle(&event("One")).unwrap();
+        attached.handle(&event("Two")).unwrap();
+
+        assert_eq!(sink.events().len(), 2);
+        let drained = sink.drain();
+        assert_eq!(drained.iter().map(title).collect::<Vec<_>>(), [Some("One"), Some("Two")]);
+        assert!(sink.events().is_empty());
+    }
+
+    #[test]
+    fn jsonl_file_sink_round_trips() {
+        let file = TempFile::new("plain");
+        let mut sink = JsonlFileSink::create(&file.0).unwrap();
+        sink.handle(&event("Invoice 1")).unwrap();
+        sink.handle(&event("Invoice 2")).unwrap();
+        sink.flush().unwrap();
+
+        let text = std::fs::read_to_string(&file.0).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains("Invoice 1"));
+
+        // Reopening appends
+        let mut sink = JsonlFileSink::create(&file.0).unwrap();
+        sink.handle(&event("Invoice 3")).unwrap();
+        drop(sink);
+        let events = read_jsonl(&file.0, None).unwrap();
+        assert_eq!(
+            events.iter().map(title).collect::<Vec<_>>(),
+            [Some("Invoice 1"), Some("Invoice 2"), Some("Invoice 3")]
+        );
+    }
+
+    #[test]
+    fn encrypted_jsonl_file_sink_seals_every_line() {
+        let file = TempFile::new("encrypted");
+        let key = EncryptionKey::from_bytes([7; 32]);
+        let mut sink = JsonlFileSink::create_encrypted(&file.0, key.clone()).unwrap();
+        sink.handle(&event("Salary review")).unwrap();
+        sink.handle(&event("Bonus")).unwrap();
+        drop(sink);
+
+        let text = std::fs::read_to_string(&file.0).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(!text.contains("Salary") && !text.contains("Notepad"));
+
+        let events = read_jsonl(&file.0, Some(&key)).unwrap();
+        assert_eq!(events.iter().map(title).collect::<Vec<_>>(), [Some("Salary review"), Some("Bonus")]);
+
+        let is_encryption_error =
+            |result: Result<Vec<RecordedEvent>>| matches!(result, Err(WorkflowRecorderError::EncryptionError(_)));
+        assert!(is_encryption_error(read_jsonl(&file.0, None)));
+        assert!(is_encryption_error(read_jsonl(&file.0, Some(&EncryptionKey::from_bytes([8; 32])))));
+    }
+
+    #[test]
+    fn channel_sink_fails_once_the_receiver_is_dropped() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut sink = ChannelSink::new(sender);
+        sink.handle(&event("Live")).unwrap();
+        assert_eq!(receiver.try_recv().ok().as_ref().and_then(title), Some("Live"));
+
+        drop(receiver);
+        assert!(sink.handle(&event("Gone")).is_err());
+    }
+}
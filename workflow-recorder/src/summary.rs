@@ -0,0 +1,162 @@
+use crate::{MouseEventType, RecordedWorkflow, WorkflowEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Time spent and events recorded in one application
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationUsage {
+    /// The application name
+    pub application_name: String,
+
+    /// Active time spent in the application (milliseconds)
+    pub active_time: u64,
+
+    /// The number of events recorded while the application was in use
+    pub event_count: usize,
+}
+
+/// Configuration for recording summaries
+#[derive(Debug, Clone)]
+pub struct SummaryConfig {
+    /// Gaps between events longer than this count as idle time (milliseconds)
+    pub idle_threshold: u64,
+
+    /// How many applications to list in `top_applications`
+    pub max_top_applications: usize,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            idle_threshold: 30000, // 30 seconds
+            max_top_applications: 5,
+        }
+    }
+}
+
+/// Activity analytics computed from a recorded workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingSummary {
+    /// Total duration of the recording (milliseconds)
+    pub total_duration: u64,
+
+    /// Time the user was interacting (milliseconds)
+    pub active_time: u64,
+
+    /// Time without any recorded input longer than the idle threshold (milliseconds)
+    pub idle_time: u64,
+
+    /// The number of times the user moved to a different application
+    pub app_switches: usize,
+
+    /// Applications ordered by active time, most used first
+    pub top_applications: Vec<ApplicationUsage>,
+
+    /// The number of mouse button presses
+    pub click_count: usize,
+
+    /// The number of key presses
+    pub keystroke_count: usize,
+}
+
+impl RecordingSummary {
+    /// Compute a summary of a recorded workflow
+    pub fn from_workflow(workflow: &RecordedWorkflow, config: &SummaryConfig) -> Self {
+        let end_time = workflow
+            .end_time
+            .or_else(|| workflow.events.last().map(|event| event.timestamp))
+            .unwrap_or(workflow.start_time);
+        let total_duration = end_time.saturating_sub(workflow.start_time);
+
+        let mut active_time = 0;
+        let mut app_switches = 0;
+        let mut click_count = 0;
+        let mut keystroke_count = 0;
+        let mut usage: HashMap<String, ApplicationUsage> = HashMap::new();
+        let mut current_app: Option<String> = None;
+        let mut last_event_time: Option<u64> = None;
+
+        for event in &workflow.events {
+            // The gap before an event is active time in the application that was in use
+            if let Some(last_time) = last_event_time {
+                let gap = event.timestamp.saturating_sub(last_time);
+                if gap <= config.idle_threshold {
+                    active_time += gap;
+                    if let Some(app) = &current_app {
+                        if let Some(entry) = usage.get_mut(app) {
+                            entry.active_time += gap;
+                        }
+                    }
+                }
+            }
+            last_event_time = Some(event.timestamp);
+
+            match &event.event {
+                WorkflowEvent::Mouse(mouse_event) => {
+                    if matches!(
+                        mouse_event.event_type,
+                        MouseEventType::Down
+                            | MouseEventType::Click
+                            | MouseEventType::DoubleClick
+                            | MouseEventType::RightClick
+                    ) {
+                        click_count += 1;
+                    }
+                }
+                WorkflowEvent::Keyboard(keyboard_event) if keyboard_event.is_key_down => {
+                    keystroke_count += 1;
+                }
+                _ => {}
+            }
+
+            if let Some(app) = application_of(&event.event) {
+                if current_app.as_deref() != Some(app) {
+                    if current_app.is_some() {
+                        app_switches += 1;
+                    }
+                    current_app = Some(app.to_string());
+                }
+            }
+            if let Some(app) = &current_app {
+                usage
+                    .entry(app.clone())
+                    .or_insert_with(|| ApplicationUsage {
+                        application_name: app.clone(),
+                        active_time: 0,
+                        event_count: 0,
+                    })
+                    .event_count += 1;
+            }
+        }
+
+        let mut top_applications: Vec<ApplicationUsage> = usage.into_values().collect();
+        top_applications.sort_by(|a, b| {
+            b.active_time
+                .cmp(&a.active_time)
+                .then(b.event_count.cmp(&a.event_count))
+        });
+        top_applications.truncate(config.max_top_applications);
+
+        Self {
+            total_duration,
+            active_time,
+            idle_time: total_duration.saturating_sub(active_time),
+            app_switches,
+            top_applications,
+            click_count,
+            keystroke_count,
+        }
+    }
+}
+
+/// The application an event happened in, when the event records it
+fn application_of(event: &WorkflowEvent) -> Option<&str> {
+    match event {
+        WorkflowEvent::WindowFocusChanged(window_event) => window_event.application_name.as_deref(),
+        WorkflowEvent::Mouse(mouse_event) => mouse_event
+            .ui_element
+            .as_ref()
+            .and_then(|element| element.application_name.as_deref()),
+        _ => None,
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// A screen recording made alongside the workflow recording.
+///
+/// With a sync set (see [`crate::WorkflowRecorder::sync_video`]), every recorded
+/// event carries its [`VideoPosition`], so a player can seek straight to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoSync {
+    /// Where the video is saved, for tools that open it next to the recording
+    pub path: Option<String>,
+
+    /// When the first frame of the video was captured (milliseconds since epoch)
+    pub started_at: u64,
+
+    /// Frames per second of the video
+    pub frame_rate: f64,
+}
+
+/// Where an event happened in the screen recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VideoPosition {
+    /// Time since the first frame, in milliseconds
+    pub offset_ms: u64,
+
+    /// Number of the frame shown at that time, counting from 0
+    pub frame: u64,
+}
+
+impl VideoSync {
+    /// A video whose capture starts now
+    pub fn starting_now(frame_rate: f64) -> Self {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        Self {
+            path: None,
+            started_at: now,
+            frame_rate,
+        }
+    }
+
+    /// Where an event recorded at `timestamp` is in the video; `None` if it happened
+    /// before the video started
+    pub fn position(&self, timestamp: u64) -> Option<VideoPosition> {
+        let offset_ms = timestamp.checked_sub(self.started_at)?;
+        let frame = if self.frame_rate > 0.0 {
+            (offset_ms as f64 * self.frame_rate / 1000.0) as u64
+        } else {
+            0
+        };
+        Some(VideoPosition { offset_ms, frame })
+    }
+
+    /// The recording timestamp shown `offset_ms` into the video, to find the events
+    /// around a moment seen in the video
+    pub fn timestamp_at(&self, offset_ms: u64) -> u64 {
+        self.started_at + offset_ms
+    }
+}